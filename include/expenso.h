@@ -0,0 +1,40 @@
+/*
+ * C interface to the expenso tracker, built with `cargo build --release --features ffi`.
+ * Link against libexpense_tracker (.so/.dylib/.dll or the static .a/.lib).
+ *
+ * Strings are NUL-terminated UTF-8. Strings returned by the library must be
+ * released with expenso_string_free(). On failure, functions return NULL, 0
+ * or -1 and expenso_last_error() describes what went wrong.
+ */
+#ifndef EXPENSO_H
+#define EXPENSO_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct ExpensoTracker ExpensoTracker;
+
+ExpensoTracker *expenso_tracker_new(void);
+ExpensoTracker *expenso_tracker_load(const char *path);
+int32_t expenso_tracker_save(const ExpensoTracker *tracker, const char *path);
+void expenso_tracker_free(ExpensoTracker *tracker);
+
+/* json: {"description": "Lunch", "amount": 12.5, "transaction_type": "Expense", ...}
+ * Returns the new transaction id, or 0 on error. */
+uint32_t expenso_add_transaction(ExpensoTracker *tracker, const char *json);
+int32_t expenso_delete_transaction(ExpensoTracker *tracker, uint32_t id);
+
+char *expenso_transactions_json(const ExpensoTracker *tracker);
+char *expenso_summary_json(const ExpensoTracker *tracker);
+
+const char *expenso_last_error(void);
+void expenso_string_free(char *text);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* EXPENSO_H */
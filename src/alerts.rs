@@ -0,0 +1,176 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::bills;
+use crate::cards::{CardStatus, REMIND_DAYS};
+use crate::config::Config;
+use crate::money::{self, format_money};
+use crate::{Transaction, TransactionType, dates};
+
+/// Something worth interrupting for: a budget gone over this period, or a
+/// bill or card payment due within [`REMIND_DAYS`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Alert {
+    pub title: String,
+    pub body: String,
+}
+
+/// A category whose expenses this budget period went over its budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverBudget {
+    pub category: String,
+    pub spent: f64,
+    pub limit: f64,
+}
+
+/// The budgeted categories spent past their limit in the budget period
+/// containing `today`, in the order of the budgets.
+pub fn over_budget(
+    transactions: &[&Transaction],
+    config: &Config,
+    today: NaiveDate,
+) -> Vec<OverBudget> {
+    let (first, last) = config.calendar().period(config.budget_period, today);
+    let (first, last) = (
+        first.format(dates::ISO_FORMAT).to_string(),
+        last.format(dates::ISO_FORMAT).to_string(),
+    );
+    config
+        .budgets
+        .iter()
+        .filter_map(|(category, limit)| {
+            let spent = money::sum(
+                transactions
+                    .iter()
+                    .filter(|t| {
+                        t.transaction_type == TransactionType::Expense
+                            && t.date >= first
+                            && t.date <= last
+                            && t.category.eq_ignore_ascii_case(category)
+                    })
+                    .map(|t| t.amount),
+            );
+            (spent > *limit).then(|| OverBudget {
+                category: category.clone(),
+                spent,
+                limit: *limit,
+            })
+        })
+        .collect()
+}
+
+pub fn pending(transactions: &[&Transaction], config: &Config, today: NaiveDate) -> Vec<Alert> {
+    let (first, last) = config.calendar().period(config.budget_period, today);
+    let period = dates::period_label(first, last);
+    let mut alerts: Vec<Alert> = over_budget(transactions, config, today)
+        .into_iter()
+        .map(|over| Alert {
+            title: format!("Over budget: {}", over.category),
+            body: format!(
+                "{} spent in {}, over the {} budget",
+                format_money(over.spent),
+                period,
+                format_money(over.limit)
+            ),
+        })
+        .collect();
+
+    for bill in bills::upcoming(transactions, today) {
+        let days = (bill.next_due - today).num_days();
+        if days > REMIND_DAYS {
+            continue;
+        }
+        let due = match days {
+            0 => String::from("today"),
+            _ => bill.next_due.format(dates::ISO_FORMAT).to_string(),
+        };
+        alerts.push(Alert {
+            title: format!("Bill due: {}", bill.description),
+            body: format!(
+                "{} from {} due {}",
+                format_money(bill.amount),
+                bill.account,
+                due
+            ),
+        });
+    }
+
+    for (account, cycle) in &config.cards {
+        let status = CardStatus::compute(transactions, account, cycle, today);
+        if let Some(reminder) = status.reminder(today) {
+            alerts.push(Alert {
+                title: String::from("Card payment due"),
+                body: reminder,
+            });
+        }
+    }
+    alerts
+}
+
+/// Shows `alert` as a desktop notification.
+#[cfg(feature = "notifications")]
+pub fn notify(alert: &Alert) -> crate::Result<()> {
+    notify_rust::Notification::new()
+        .appname("expenso")
+        .summary(&alert.title)
+        .body(&alert.body)
+        .show()
+        .map_err(|err| {
+            std::io::Error::other(format!("could not show a desktop notification: {}", err))
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_pending_alerts() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, category, is_recurring) in [
+            ("Rent", 1200.0, "2024-03-01", "Housing", true),
+            ("Gym", 40.0, "2024-03-20", "Health", true),
+            ("Groceries", 180.0, "2024-04-02", "Food", false),
+            ("Restaurant", 60.0, "2024-04-05", "Food", false),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from(date),
+                category: String::from(category),
+                is_recurring,
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let mut config = Config::default();
+        config.budgets.insert(String::from("Food"), 200.0);
+        config.budgets.insert(String::from("Housing"), 1500.0);
+        let today = NaiveDate::from_ymd_opt(2024, 4, 15).unwrap();
+
+        let alerts = pending(&all, &config, today);
+        assert_eq!(
+            alerts,
+            vec![
+                Alert {
+                    title: String::from("Over budget: Food"),
+                    body: String::from("$240.00 spent in 2024-04, over the $200.00 budget"),
+                },
+                Alert {
+                    title: String::from("Bill due: Gym"),
+                    body: String::from("$40.00 from Cash due 2024-04-20"),
+                },
+            ]
+        );
+
+        config.month_start = 3;
+        config.budgets.insert(String::from("Food"), 50.0);
+        assert_eq!(
+            pending(&all, &config, today)[0].body,
+            "$60.00 spent in 2024-04-03 to 2024-05-02, over the $50.00 budget"
+        );
+    }
+}
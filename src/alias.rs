@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+
+pub fn split_words(input: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err(format!("Unterminated quote in alias \"{}\"", input));
+    }
+    if in_word {
+        words.push(current);
+    }
+    Ok(words)
+}
+
+fn command_position(args: &[String]) -> Option<usize> {
+    let command = Cli::command();
+    let value_options: Vec<String> = command
+        .get_arguments()
+        .filter(|arg| arg.get_action().takes_values())
+        .filter_map(|arg| arg.get_long())
+        .map(|long| format!("--{}", long))
+        .collect();
+
+    let mut index = 1;
+    while index < args.len() {
+        let arg = &args[index];
+        if value_options.contains(arg) {
+            index += 2;
+        } else if arg.starts_with('-') {
+            index += 1;
+        } else {
+            return Some(index);
+        }
+    }
+    None
+}
+
+pub fn expand(
+    args: Vec<String>,
+    aliases: &BTreeMap<String, String>,
+) -> Result<Vec<String>, String> {
+    let Some(position) = command_position(&args) else {
+        return Ok(args);
+    };
+    let name = &args[position];
+
+    let is_builtin = Cli::command()
+        .get_subcommands()
+        .any(|sub| sub.get_name() == name);
+    let Some(expansion) = aliases.get(name).filter(|_| !is_builtin) else {
+        return Ok(args);
+    };
+
+    let mut expanded = args[..position].to_vec();
+    expanded.extend(split_words(expansion)?);
+    expanded.extend(args[position + 1..].iter().cloned());
+    Ok(expanded)
+}
+
+pub fn config_path_from_args(args: &[String]) -> Option<String> {
+    option_from_args(args, "--config")
+}
+
+pub fn profile_from_args(args: &[String]) -> Option<String> {
+    option_from_args(args, "--profile")
+}
+
+/// Reads a global option before the config, and so the aliases, are loaded.
+fn option_from_args(args: &[String], name: &str) -> Option<String> {
+    args.iter().enumerate().find_map(|(index, arg)| {
+        if arg == name {
+            args.get(index + 1).cloned()
+        } else {
+            arg.strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('='))
+                .map(|value| value.to_string())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    fn aliases() -> BTreeMap<String, String> {
+        let mut aliases = BTreeMap::new();
+        aliases.insert(
+            String::from("coffee"),
+            String::from("add --amount 4.5 --category Food --desc Coffee"),
+        );
+        aliases.insert(
+            String::from("lunch"),
+            String::from("add \"12 lunch #Food\""),
+        );
+        aliases.insert(String::from("list"), String::from("report summary"));
+        aliases
+    }
+
+    #[test]
+    fn test_split_words_handles_quotes() {
+        assert_eq!(
+            split_words(r#"add "12.50 lunch" --desc 'Big "deal"' x"#).unwrap(),
+            args(&["add", "12.50 lunch", "--desc", "Big \"deal\"", "x"])
+        );
+        assert_eq!(
+            split_words("add --desc \"\"").unwrap(),
+            args(&["add", "--desc", ""])
+        );
+        assert!(split_words("add \"oops").is_err());
+    }
+
+    #[test]
+    fn test_expand_alias_with_extra_args() {
+        let expanded = expand(
+            args(&[
+                "expenso",
+                "--data",
+                "f.json",
+                "coffee",
+                "--date",
+                "yesterday",
+            ]),
+            &aliases(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            expanded,
+            args(&[
+                "expenso",
+                "--data",
+                "f.json",
+                "add",
+                "--amount",
+                "4.5",
+                "--category",
+                "Food",
+                "--desc",
+                "Coffee",
+                "--date",
+                "yesterday"
+            ])
+        );
+        assert_eq!(
+            expand(args(&["expenso", "lunch"]), &aliases()).unwrap(),
+            args(&["expenso", "add", "12 lunch #Food"])
+        );
+    }
+
+    #[test]
+    fn test_builtin_commands_are_not_shadowed() {
+        let original = args(&["expenso", "list", "--sort", "date"]);
+        assert_eq!(expand(original.clone(), &aliases()).unwrap(), original);
+
+        let interactive = args(&["expenso", "--tui"]);
+        assert_eq!(
+            expand(interactive.clone(), &aliases()).unwrap(),
+            interactive
+        );
+    }
+
+    #[test]
+    fn test_config_path_from_args() {
+        assert_eq!(
+            config_path_from_args(&args(&["expenso", "--config", "a.toml", "list"])).as_deref(),
+            Some("a.toml")
+        );
+        assert_eq!(
+            config_path_from_args(&args(&["expenso", "--config=b.toml"])).as_deref(),
+            Some("b.toml")
+        );
+        assert_eq!(config_path_from_args(&args(&["expenso", "list"])), None);
+        assert_eq!(
+            profile_from_args(&args(&["expenso", "list", "--profile=business"])).as_deref(),
+            Some("business")
+        );
+        assert_eq!(
+            profile_from_args(&args(&["expenso", "--profile", "personal"])).as_deref(),
+            Some("personal")
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use chrono::{Months, NaiveDate};
+
+use crate::{NewTransaction, Transaction, TransactionType, dates};
+
+/// Months a recurring transaction can go unseen before [`materialize`]
+/// stops adding it.
+pub const CATCH_UP_MONTHS: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bill {
+    pub description: String,
+    pub category: String,
+    pub account: String,
+    pub amount: f64,
+    pub next_due: NaiveDate,
+}
+
+pub fn upcoming(transactions: &[&Transaction], today: NaiveDate) -> Vec<Bill> {
+    let latest = latest_recurring(transactions, today)
+        .into_iter()
+        .filter(|(_, t)| t.transaction_type == TransactionType::Expense);
+
+    let mut bills: Vec<Bill> = latest
+        .filter_map(|(last_paid, t)| {
+            Some(Bill {
+                description: t.description.clone(),
+                category: t.category.to_string(),
+                account: t.account.clone(),
+                amount: t.amount,
+                next_due: next_due(last_paid, today)?,
+            })
+        })
+        .collect();
+    bills.sort_by(|a, b| {
+        a.next_due
+            .cmp(&b.next_due)
+            .then(a.description.cmp(&b.description))
+    });
+    bills
+}
+
+/// Copies of each recurring income and expense for the months since it
+/// last happened, up to `today`, so they don't have to be entered by hand.
+/// One not seen for more than [`CATCH_UP_MONTHS`] is taken to have stopped.
+pub fn materialize(transactions: &[&Transaction], today: NaiveDate) -> Vec<NewTransaction> {
+    let stopped = today - Months::new(CATCH_UP_MONTHS);
+    let mut due = Vec::new();
+    for (last, t) in latest_recurring(transactions, today) {
+        if t.transaction_type == TransactionType::Settlement || last < stopped {
+            continue;
+        }
+        let missed = (1..)
+            .map_while(|months| last.checked_add_months(Months::new(months)))
+            .take_while(|date| *date <= today);
+        for date in missed {
+            due.push(NewTransaction {
+                date: date.format(dates::ISO_FORMAT).to_string(),
+                external_id: None,
+                import: None,
+                receipt: None,
+                ..NewTransaction::from(t.clone())
+            });
+        }
+    }
+    due.sort_by(|a, b| a.date.cmp(&b.date));
+    due
+}
+
+/// The latest of each recurring transaction, told apart by description,
+/// category and account, with its date.
+fn latest_recurring<'a>(
+    transactions: &[&'a Transaction],
+    today: NaiveDate,
+) -> Vec<(NaiveDate, &'a Transaction)> {
+    let mut latest: BTreeMap<(String, String, String), (NaiveDate, &Transaction)> = BTreeMap::new();
+
+    for t in transactions {
+        if !t.is_recurring {
+            continue;
+        }
+        let Some(date) = dates::parse(&t.date, today) else {
+            continue;
+        };
+        let key = (
+            t.description.to_lowercase(),
+            t.category.to_lowercase(),
+            t.account.to_lowercase(),
+        );
+        if latest.get(&key).is_none_or(|(seen, _)| date >= *seen) {
+            latest.insert(key, (date, t));
+        }
+    }
+    latest.into_values().collect()
+}
+
+fn next_due(last_paid: NaiveDate, today: NaiveDate) -> Option<NaiveDate> {
+    (1..)
+        .map(|months| last_paid.checked_add_months(Months::new(months)))
+        .find(|due| due.is_none_or(|due| due >= today))
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn date(input: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(input, dates::ISO_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_next_due_keeps_day_of_month() {
+        let today = date("2024-04-10");
+        assert_eq!(
+            next_due(date("2024-01-15"), today),
+            Some(date("2024-04-15"))
+        );
+        assert_eq!(
+            next_due(date("2024-03-10"), today),
+            Some(date("2024-04-10"))
+        );
+        assert_eq!(
+            next_due(date("2024-01-31"), date("2024-02-01")),
+            Some(date("2024-02-29"))
+        );
+    }
+
+    #[test]
+    fn test_upcoming_uses_latest_recurring_expense() {
+        let mut tracker = FinanceTracker::new();
+        for (description, date, is_recurring, transaction_type) in [
+            ("Rent", "2024-02-01", true, TransactionType::Expense),
+            ("rent", "2024-03-01", true, TransactionType::Expense),
+            ("Netflix", "2024-03-20", true, TransactionType::Expense),
+            ("Salary", "2024-03-25", true, TransactionType::Income),
+            ("Dinner", "2024-03-26", false, TransactionType::Expense),
+        ] {
+            tracker.insert(NewTransaction {
+                description: description.to_string(),
+                amount: 10.0,
+                is_recurring,
+                date: date.to_string(),
+                transaction_type,
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let bills = upcoming(&all, date("2024-03-28"));
+        assert_eq!(bills.len(), 2);
+        assert_eq!(bills[0].description, "rent");
+        assert_eq!(bills[0].next_due, date("2024-04-01"));
+        assert_eq!(bills[1].description, "Netflix");
+        assert_eq!(bills[1].next_due, date("2024-04-20"));
+    }
+
+    #[test]
+    fn test_materialize_catches_up_recurring_transactions() {
+        let mut tracker = FinanceTracker::new();
+        for (description, date, transaction_type) in [
+            ("Rent", "2024-01-31", TransactionType::Expense),
+            ("Salary", "2024-03-25", TransactionType::Income),
+            ("Old gym", "2023-06-01", TransactionType::Expense),
+        ] {
+            tracker.insert(NewTransaction {
+                description: description.to_string(),
+                amount: 10.0,
+                is_recurring: true,
+                date: date.to_string(),
+                transaction_type,
+                external_id: Some(format!("bank-{}", description)),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        // The salary isn't due again until the 25th, and the gym stopped.
+        let due = materialize(&all, date("2024-04-10"));
+        let dates: Vec<(&str, &str)> = due
+            .iter()
+            .map(|t| (t.description.as_str(), t.date.as_str()))
+            .collect();
+        assert_eq!(dates, vec![("Rent", "2024-02-29"), ("Rent", "2024-03-31")]);
+        assert!(
+            due.iter()
+                .all(|t| t.is_recurring && t.external_id.is_none())
+        );
+    }
+}
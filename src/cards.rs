@@ -0,0 +1,239 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{Transaction, dates, money};
+
+/// Days before a payment is due that it starts being mentioned.
+pub const REMIND_DAYS: i64 = 7;
+
+/// When a credit card's statement closes and when its payment is due, as
+/// days of the month. Days past the end of a short month fall on its last
+/// day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StatementCycle {
+    pub closing_day: u32,
+    pub due_day: u32,
+}
+
+impl StatementCycle {
+    /// The last statement closing date on or before `date`.
+    pub fn last_closing(&self, date: NaiveDate) -> NaiveDate {
+        let closing = day_in_month(date, self.closing_day);
+        if closing <= date {
+            closing
+        } else {
+            day_in_month(date - Months::new(1), self.closing_day)
+        }
+    }
+
+    /// The closing date of the cycle `date` falls in.
+    pub fn next_closing(&self, date: NaiveDate) -> NaiveDate {
+        let closing = day_in_month(date, self.closing_day);
+        if closing >= date {
+            closing
+        } else {
+            day_in_month(date + Months::new(1), self.closing_day)
+        }
+    }
+
+    /// When the statement closing on `closing` has to be paid: the first
+    /// due day after it.
+    pub fn due_date(&self, closing: NaiveDate) -> NaiveDate {
+        let due = day_in_month(closing, self.due_day);
+        if due > closing {
+            due
+        } else {
+            day_in_month(closing + Months::new(1), self.due_day)
+        }
+    }
+}
+
+fn day_in_month(date: NaiveDate, day: u32) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    let last = (first + Months::new(1)).pred_opt().unwrap_or(date).day();
+    first.with_day(day.clamp(1, last)).unwrap_or(date)
+}
+
+/// Where a credit card stands: what was charged since the last statement,
+/// and how much of that statement is still to be paid by when.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CardStatus {
+    pub account: String,
+    /// First and last day of the current cycle, `YYYY-MM-DD`.
+    pub cycle_start: String,
+    pub cycle_end: String,
+    /// Transactions in the current cycle, oldest first.
+    pub charges: Vec<Transaction>,
+    /// What the current cycle adds to the balance owed.
+    pub cycle_total: f64,
+    pub last_closing: String,
+    /// Balance owed when the last statement closed.
+    pub statement_balance: f64,
+    pub due_date: String,
+    /// Payments and refunds since the last statement closed.
+    pub paid: f64,
+    /// What is left to pay of the last statement.
+    pub remaining_due: f64,
+}
+
+impl CardStatus {
+    pub fn compute(
+        transactions: &[&Transaction],
+        account: &str,
+        cycle: &StatementCycle,
+        today: NaiveDate,
+    ) -> Self {
+        let last_closing = cycle.last_closing(today);
+        let closed_on = last_closing.format(dates::ISO_FORMAT).to_string();
+        let in_account = transactions
+            .iter()
+            .filter(|t| t.account.eq_ignore_ascii_case(account));
+
+        let mut charges: Vec<Transaction> = in_account
+            .clone()
+            .filter(|t| t.date > closed_on)
+            .map(|t| (*t).clone())
+            .collect();
+        charges.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+        let statement_balance = -money::sum(
+            in_account
+                .filter(|t| t.date <= closed_on)
+                .map(|t| t.signed_amount()),
+        );
+        let paid = money::sum(charges.iter().filter(|t| t.is_inflow()).map(|t| t.amount));
+
+        CardStatus {
+            account: account.to_string(),
+            cycle_start: (last_closing + Days::new(1))
+                .format(dates::ISO_FORMAT)
+                .to_string(),
+            cycle_end: cycle
+                .next_closing(today)
+                .format(dates::ISO_FORMAT)
+                .to_string(),
+            cycle_total: -money::sum(charges.iter().map(|t| t.signed_amount())),
+            charges,
+            due_date: cycle
+                .due_date(last_closing)
+                .format(dates::ISO_FORMAT)
+                .to_string(),
+            last_closing: closed_on,
+            statement_balance: money::round(statement_balance),
+            paid,
+            remaining_due: money::round(statement_balance - paid).max(0.0),
+        }
+    }
+
+    /// A reminder when part of the last statement is unpaid and due within
+    /// [`REMIND_DAYS`], or overdue.
+    pub fn reminder(&self, today: NaiveDate) -> Option<String> {
+        if self.remaining_due <= 0.0 {
+            return None;
+        }
+        let due = NaiveDate::parse_from_str(&self.due_date, dates::ISO_FORMAT).ok()?;
+        let days = (due - today).num_days();
+        let amount = money::format_money(self.remaining_due);
+        match days {
+            ..0 => Some(format!(
+                "{} payment of {} was due {}",
+                self.account, amount, self.due_date
+            )),
+            0 => Some(format!(
+                "{} payment of {} is due today",
+                self.account, amount
+            )),
+            1..=REMIND_DAYS => Some(format!(
+                "{} payment of {} is due {} ({} days)",
+                self.account, amount, self.due_date, days
+            )),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction, TransactionType};
+
+    fn date(input: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(input, dates::ISO_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_card_statement_cycle() {
+        let cycle = StatementCycle {
+            closing_day: 31,
+            due_day: 25,
+        };
+        assert_eq!(cycle.last_closing(date("2024-03-10")), date("2024-02-29"));
+        assert_eq!(cycle.next_closing(date("2024-03-10")), date("2024-03-31"));
+        assert_eq!(cycle.due_date(date("2024-02-29")), date("2024-03-25"));
+
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, transaction_type, account) in [
+            (
+                "Flights",
+                400.0,
+                "2024-02-10",
+                TransactionType::Expense,
+                "Visa",
+            ),
+            (
+                "Refund",
+                50.0,
+                "2024-02-20",
+                TransactionType::Income,
+                "Visa",
+            ),
+            (
+                "Coffee",
+                4.5,
+                "2024-03-02",
+                TransactionType::Expense,
+                "Cash",
+            ),
+            (
+                "Books",
+                30.0,
+                "2024-03-05",
+                TransactionType::Expense,
+                "Visa",
+            ),
+            (
+                "Payment",
+                100.0,
+                "2024-03-08",
+                TransactionType::Income,
+                "Visa",
+            ),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from(date),
+                transaction_type,
+                account: String::from(account),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let today = date("2024-03-20");
+
+        let status = CardStatus::compute(&all, "Visa", &cycle, today);
+        assert_eq!(status.cycle_start, "2024-03-01");
+        assert_eq!(status.cycle_end, "2024-03-31");
+        let ids: Vec<u32> = status.charges.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+        assert_eq!(status.cycle_total, -70.0);
+        assert_eq!(status.statement_balance, 350.0);
+        assert_eq!(status.paid, 100.0);
+        assert_eq!(status.remaining_due, 250.0);
+        assert_eq!(
+            status.reminder(today).as_deref(),
+            Some("Visa payment of $250.00 is due 2024-03-25 (5 days)")
+        );
+        assert_eq!(status.reminder(date("2024-03-10")), None);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use crate::error::{ExpensoError, Result};
+use crate::money::{self, format_money};
+use crate::report::{self, MonthlyReport, Report};
+use crate::{Transaction, TransactionType};
+
+/// Categories drawn as a slice of their own, the rest share an `Other` one.
+const SLICES: usize = 8;
+
+/// Share of the expenses below which a category goes into `Other`, as its
+/// slice would be too thin to label.
+const MIN_SHARE: f64 = 0.03;
+
+const PALETTE: [RGBColor; SLICES] = [
+    RGBColor(31, 119, 180),
+    RGBColor(255, 127, 14),
+    RGBColor(44, 160, 44),
+    RGBColor(214, 39, 40),
+    RGBColor(148, 103, 189),
+    RGBColor(140, 86, 75),
+    RGBColor(227, 119, 194),
+    RGBColor(127, 127, 127),
+];
+
+const INCOME: RGBColor = RGBColor(46, 160, 67);
+const EXPENSE: RGBColor = RGBColor(207, 34, 46);
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ChartKind {
+    /// Pie of the expenses per category
+    Categories,
+    /// Income and expense per month
+    Monthly,
+    /// Both, side by side
+    Both,
+}
+
+/// Draws `kind` over `transactions` to `path`, an SVG when its extension is
+/// `.svg` and a PNG otherwise.
+pub fn render(
+    transactions: &[&Transaction],
+    kind: ChartKind,
+    path: &Path,
+    size: (u32, u32),
+) -> Result<()> {
+    let svg = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    if svg {
+        draw(
+            SVGBackend::new(path, size).into_drawing_area(),
+            transactions,
+            kind,
+        )
+    } else {
+        draw(
+            BitMapBackend::new(path, size).into_drawing_area(),
+            transactions,
+            kind,
+        )
+    }
+}
+
+fn draw<DB: DrawingBackend>(
+    root: DrawingArea<DB, Shift>,
+    transactions: &[&Transaction],
+    kind: ChartKind,
+) -> Result<()> {
+    root.fill(&WHITE).map_err(failed)?;
+    match kind {
+        ChartKind::Categories => draw_categories(&root, transactions)?,
+        ChartKind::Monthly => draw_monthly(&root, transactions)?,
+        ChartKind::Both => {
+            let areas = root.split_evenly((1, 2));
+            draw_categories(&areas[0], transactions)?;
+            draw_monthly(&areas[1], transactions)?;
+        }
+    }
+    root.present().map_err(failed)
+}
+
+fn draw_categories<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    transactions: &[&Transaction],
+) -> Result<()> {
+    let area = area
+        .titled("Expenses by category", ("sans-serif", 24))
+        .map_err(failed)?;
+    let slices = slices(transactions);
+    let (width, height) = area.dim_in_pixel();
+    let center = (width as i32 / 2, height as i32 / 2);
+    if slices.is_empty() {
+        let style = TextStyle::from(("sans-serif", 18).into_font())
+            .pos(Pos::new(HPos::Center, VPos::Center));
+        return area
+            .draw_text("No expenses", &style, center)
+            .map_err(failed);
+    }
+
+    let radius = f64::from(width.min(height)) * 0.3;
+    let sizes: Vec<f64> = slices.iter().map(|(_, total)| *total).collect();
+    let labels: Vec<String> = slices
+        .iter()
+        .map(|(category, total)| format!("{} {}", category, format_money(*total)))
+        .collect();
+    let colors = &PALETTE[..slices.len()];
+    let mut pie = Pie::new(&center, &radius, &sizes, colors, &labels);
+    pie.start_angle(-90.0);
+    pie.label_style(("sans-serif", 15).into_font().color(&BLACK));
+    pie.percentages(("sans-serif", 14).into_font().color(&WHITE));
+    area.draw(&pie).map_err(failed)
+}
+
+fn draw_monthly<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    transactions: &[&Transaction],
+) -> Result<()> {
+    let months = MonthlyReport::compute(transactions).months;
+    let top = months
+        .iter()
+        .map(|month| month.income.max(month.expense))
+        .fold(0.0, f64::max)
+        .max(1.0)
+        * 1.1;
+    let mut chart = ChartBuilder::on(area)
+        .caption("Income and expense per month", ("sans-serif", 24))
+        .margin(20)
+        .margin_right(40)
+        .x_label_area_size(40)
+        .y_label_area_size(90)
+        .build_cartesian_2d(0..months.len().saturating_sub(1).max(1), 0.0..top)
+        .map_err(failed)?;
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(months.len().max(2))
+        .x_label_formatter(&|i| {
+            months
+                .get(*i)
+                .map(|month| month.month.clone())
+                .unwrap_or_default()
+        })
+        .y_label_formatter(&|amount| format_money(*amount))
+        .draw()
+        .map_err(failed)?;
+
+    for (label, color, amount) in [
+        (
+            "Income",
+            INCOME,
+            (|month| month.income) as fn(&report::MonthTotal) -> f64,
+        ),
+        ("Expense", EXPENSE, |month| month.expense),
+    ] {
+        chart
+            .draw_series(
+                LineSeries::new(
+                    months
+                        .iter()
+                        .enumerate()
+                        .map(|(i, month)| (i, amount(month))),
+                    color.stroke_width(2),
+                )
+                .point_size(4),
+            )
+            .map_err(failed)?
+            .label(label)
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+            });
+    }
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(failed)
+}
+
+/// Expense totals per category, largest first, with the thin ones and
+/// everything past the last slice lumped into `Other`.
+fn slices(transactions: &[&Transaction]) -> Vec<(String, f64)> {
+    let expenses: Vec<&Transaction> = transactions
+        .iter()
+        .copied()
+        .filter(|t| t.transaction_type == TransactionType::Expense)
+        .collect();
+    let totals: HashMap<_, f64> = report::category_totals(&expenses);
+    let mut slices: Vec<(String, f64)> = totals
+        .into_iter()
+        .filter(|(_, total)| *total > 0.0)
+        .map(|(category, total)| (category.to_string(), total))
+        .collect();
+    slices.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let total = money::sum(slices.iter().map(|(_, total)| *total));
+    let shown = slices
+        .iter()
+        .take_while(|(_, amount)| *amount >= total * MIN_SHARE)
+        .count()
+        .min(SLICES - 1);
+    if shown + 1 < slices.len() {
+        let other = slices.split_off(shown);
+        slices.push((
+            String::from("Other"),
+            money::sum(other.iter().map(|(_, total)| *total)),
+        ));
+    }
+    slices
+}
+
+fn failed<E: std::error::Error + Send + Sync>(err: DrawingAreaErrorKind<E>) -> ExpensoError {
+    ExpensoError::Chart(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_render_charts() {
+        let mut tracker = FinanceTracker::new();
+        for (amount, date, transaction_type, category) in [
+            (3000.0, "2024-01-01", TransactionType::Income, "Salary"),
+            (1200.0, "2024-01-02", TransactionType::Expense, "Rent"),
+            (80.0, "2024-01-15", TransactionType::Expense, "Food"),
+            (3000.0, "2024-02-01", TransactionType::Income, "Salary"),
+            (95.0, "2024-02-12", TransactionType::Expense, "Food"),
+            (20.0, "2024-02-14", TransactionType::Expense, "Fun"),
+            (10.0, "2024-02-20", TransactionType::Expense, "Transport"),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(category),
+                amount,
+                date: String::from(date),
+                transaction_type,
+                category: String::from(category),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        assert_eq!(
+            slices(&all),
+            vec![
+                (String::from("Rent"), 1200.0),
+                (String::from("Food"), 175.0),
+                (String::from("Other"), 30.0),
+            ]
+        );
+
+        let dir = std::env::temp_dir().join(format!("expenso-chart-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (svg, png) = (dir.join("spending.svg"), dir.join("spending.png"));
+        render(&all, ChartKind::Both, &svg, (800, 400)).unwrap();
+        render(&all, ChartKind::Monthly, &png, (400, 300)).unwrap();
+        let text = std::fs::read_to_string(&svg).unwrap();
+        let image = std::fs::read(&png).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(text.starts_with("<svg"));
+        assert!(text.contains("Expenses by category") && text.contains("2024-02"));
+        assert!(image.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{FinanceTracker, dates};
+
+/// Something wrong with a data set, as found by [`check`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// More than one transaction has this id.
+    DuplicateId { id: u32, count: usize },
+    /// A date that is not YYYY-MM-DD.
+    InvalidDate { id: u32, date: String },
+    /// A transaction changed outside expenso, so it is not what its history
+    /// says.
+    HistoryMismatch(u32),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::DuplicateId { id, count } => {
+                write!(f, "{} transactions share id {}", count, id)
+            }
+            Problem::InvalidDate { id, date } => {
+                write!(f, "transaction {} has invalid date \"{}\"", id, date)
+            }
+            Problem::HistoryMismatch(id) => write!(
+                f,
+                "transaction {} is not what its history says, it was changed outside expenso",
+                id
+            ),
+        }
+    }
+}
+
+/// Scans every loaded transaction and checks them against the history.
+pub fn check(tracker: &FinanceTracker) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    let mut counts: HashMap<u32, usize> = HashMap::new();
+    for transaction in tracker.get_transactions() {
+        *counts.entry(transaction.id).or_default() += 1;
+    }
+    let mut duplicates: Vec<(u32, usize)> =
+        counts.into_iter().filter(|&(_, count)| count > 1).collect();
+    duplicates.sort_unstable();
+    problems.extend(
+        duplicates
+            .into_iter()
+            .map(|(id, count)| Problem::DuplicateId { id, count }),
+    );
+
+    problems.extend(
+        tracker
+            .get_transactions()
+            .iter()
+            .filter(|t| !dates::is_iso_date(&t.date))
+            .map(|t| Problem::InvalidDate {
+                id: t.id,
+                date: t.date.clone(),
+            }),
+    );
+
+    problems.extend(
+        tracker
+            .history_mismatches()
+            .into_iter()
+            .map(Problem::HistoryMismatch),
+    );
+
+    problems
+}
+
+/// Fixes what [`check`] found where that can be done safely, returning a
+/// line per fix. Duplicate ids are renumbered, dates written in
+/// `date_format` or unpadded are rewritten as YYYY-MM-DD and changes made
+/// outside expenso are recorded in the history. Dates that can't be read
+/// are left for the user.
+pub fn repair(tracker: &mut FinanceTracker, date_format: &str) -> Vec<String> {
+    let mut fixed: Vec<String> = tracker
+        .renumber_duplicates()
+        .into_iter()
+        .map(|(old, new)| format!("renumbered a duplicate of transaction {} to {}", old, new))
+        .collect();
+
+    // Ids are unique from here on, so the dates are looked up again rather
+    // than by the ids [`check`] found.
+    let invalid: Vec<(u32, String)> = tracker
+        .get_transactions()
+        .iter()
+        .filter(|t| !dates::is_iso_date(&t.date))
+        .map(|t| (t.id, t.date.clone()))
+        .collect();
+    for (id, date) in invalid {
+        let Some(normalized) = [date_format, dates::ISO_FORMAT]
+            .into_iter()
+            .find_map(|format| NaiveDate::parse_from_str(date.trim(), format).ok())
+            .map(|date| date.format(dates::ISO_FORMAT).to_string())
+        else {
+            continue;
+        };
+        if let Some(mut transaction) = tracker.get_transaction(id).cloned() {
+            transaction.date = normalized.clone();
+            tracker.update_transaction(transaction);
+            fixed.push(format!(
+                "changed the date of transaction {} to {}",
+                id, normalized
+            ));
+        }
+    }
+
+    fixed.extend(tracker.rebaseline_history().into_iter().map(|id| {
+        format!(
+            "recorded the outside change to transaction {} in the history",
+            id
+        )
+    }));
+
+    fixed
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Transaction, TransactionType};
+
+    fn transaction(id: u32, date: &str) -> Transaction {
+        Transaction {
+            id,
+            description: String::from("Coffee"),
+            amount: 4.5,
+            currency: None,
+            is_recurring: false,
+            date: String::from(date),
+            transaction_type: TransactionType::Expense,
+            category: "Food".into(),
+            payee: None,
+            notes: None,
+            tags: Vec::new(),
+            account: String::from("Cash"),
+            external_id: None,
+            import: None,
+            receipt: None,
+            splits: Vec::new(),
+            project: None,
+            created_at: None,
+            updated_at: None,
+            reconciled: false,
+        }
+    }
+
+    #[test]
+    fn test_check_and_repair() {
+        let mut tracker = FinanceTracker::from_transactions(vec![
+            transaction(1, "2024-03-01"),
+            transaction(1, "02/03/2024"),
+            transaction(2, "04/03/2024"),
+            transaction(3, "someday"),
+        ]);
+
+        let problems = check(&tracker);
+        assert_eq!(
+            problems,
+            vec![
+                Problem::DuplicateId { id: 1, count: 2 },
+                Problem::InvalidDate {
+                    id: 1,
+                    date: String::from("02/03/2024")
+                },
+                Problem::InvalidDate {
+                    id: 2,
+                    date: String::from("04/03/2024")
+                },
+                Problem::InvalidDate {
+                    id: 3,
+                    date: String::from("someday")
+                },
+            ]
+        );
+
+        let fixed = repair(&mut tracker, "%d/%m/%Y");
+        assert_eq!(fixed.len(), 3);
+        assert_eq!(tracker.get_transaction(1).unwrap().date, "2024-03-01");
+        assert_eq!(tracker.get_transaction(2).unwrap().date, "2024-03-04");
+        assert_eq!(tracker.get_transaction(4).unwrap().date, "2024-03-02");
+        assert_eq!(
+            check(&tracker),
+            vec![Problem::InvalidDate {
+                id: 3,
+                date: String::from("someday")
+            }]
+        );
+    }
+
+    #[test]
+    fn test_repair_records_outside_changes() {
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(crate::NewTransaction {
+            description: String::from("Coffee"),
+            amount: 4.5,
+            date: String::from("2024-03-01"),
+            ..Default::default()
+        });
+        let mut ledger = crate::storage::Ledger::from(&tracker);
+        ledger.transactions[0].description = String::from("Tea");
+        let mut tracker = FinanceTracker::from(ledger);
+
+        let problems = check(&tracker);
+        assert_eq!(problems, vec![Problem::HistoryMismatch(1)]);
+        assert_eq!(repair(&mut tracker, "%d/%m/%Y").len(), 1);
+        assert!(check(&tracker).is_empty());
+        assert_eq!(tracker.history().len(), 2);
+    }
+
+    #[test]
+    fn test_repair_records_renumbered_duplicates() {
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(crate::NewTransaction {
+            description: String::from("Coffee"),
+            amount: 4.5,
+            date: String::from("2024-03-01"),
+            ..Default::default()
+        });
+        let mut ledger = crate::storage::Ledger::from(&tracker);
+        ledger.transactions.insert(
+            0,
+            Transaction {
+                description: String::from("Tea"),
+                ..ledger.transactions[0].clone()
+            },
+        );
+        let mut tracker = FinanceTracker::from(ledger);
+
+        let problems = check(&tracker);
+        assert_eq!(problems, vec![Problem::DuplicateId { id: 1, count: 2 }]);
+        assert_eq!(repair(&mut tracker, "%d/%m/%Y").len(), 2);
+        assert!(check(&tracker).is_empty());
+        let replayed = FinanceTracker::replay(tracker.history().to_vec());
+        assert_eq!(replayed.get_transactions(), tracker.get_transactions());
+    }
+}
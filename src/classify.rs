@@ -0,0 +1,126 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Symbol, Transaction};
+
+/// Learns which words appear in the descriptions of each category and
+/// suggests a category for a new description: naive Bayes over word counts,
+/// retrained from the transactions whenever it's needed.
+///
+/// Categories are not weighted by how common they are, otherwise a one-word
+/// description would mostly get the busiest category; that only breaks ties.
+#[derive(Debug, Clone, Default)]
+pub struct Classifier {
+    /// How often each word appeared per category.
+    words: HashMap<String, HashMap<Symbol, u32>>,
+    /// Transactions and words seen per category.
+    categories: HashMap<Symbol, (u32, u32)>,
+}
+
+impl Classifier {
+    pub fn train<'a>(transactions: impl IntoIterator<Item = &'a Transaction>) -> Self {
+        let mut classifier = Classifier::default();
+        for transaction in transactions {
+            classifier.learn(&transaction.description, transaction.category);
+        }
+        classifier
+    }
+
+    pub fn learn(&mut self, description: &str, category: Symbol) {
+        let words = tokens(description);
+        let seen = self.categories.entry(category).or_default();
+        seen.0 += 1;
+        seen.1 += words.len() as u32;
+        for word in words {
+            *self
+                .words
+                .entry(word)
+                .or_default()
+                .entry(category)
+                .or_default() += 1;
+        }
+    }
+
+    /// The most likely category for `description`, or `None` when none of
+    /// its words have been seen before.
+    pub fn suggest(&self, description: &str) -> Option<Symbol> {
+        let words: Vec<String> = tokens(description)
+            .into_iter()
+            .filter(|word| self.words.contains_key(word))
+            .collect();
+        if words.is_empty() {
+            return None;
+        }
+
+        let vocabulary = self.words.len() as f64;
+        let score = |category: Symbol, total: u32| -> f64 {
+            words
+                .iter()
+                .map(|word| {
+                    let seen = self.words[word].get(&category).copied().unwrap_or(0);
+                    // Add-one smoothing, so an unseen word lowers the odds
+                    // without ruling the category out.
+                    ((f64::from(seen) + 1.0) / (f64::from(total) + vocabulary)).ln()
+                })
+                .sum()
+        };
+        self.categories
+            .iter()
+            .map(|(category, &(count, total))| (*category, count, score(*category, total)))
+            .max_by(|a, b| {
+                a.2.total_cmp(&b.2)
+                    .then(a.1.cmp(&b.1))
+                    .then_with(|| b.0.cmp(&a.0))
+            })
+            .map(|(category, ..)| category)
+    }
+}
+
+/// Lowercased words of two or more letters; amounts and dates are left out.
+fn tokens(description: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1 && !word.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_suggests_from_past_descriptions() {
+        let mut tracker = FinanceTracker::new();
+        for (description, category) in [
+            ("Coffee at Blue Bottle", "Food"),
+            ("Lunch with team", "Food"),
+            ("Coffee beans", "Food"),
+            ("Uber to airport", "Transport"),
+            ("Uber home", "Transport"),
+            ("Netflix subscription", "Entertainment"),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount: 10.0,
+                category: String::from(category),
+                ..Default::default()
+            });
+        }
+        let classifier = Classifier::train(tracker.get_transactions());
+
+        assert_eq!(classifier.suggest("coffee 4.50"), Some(Symbol::new("Food")));
+        assert_eq!(
+            classifier.suggest("Uber to the office"),
+            Some(Symbol::new("Transport"))
+        );
+        assert_eq!(
+            classifier.suggest("Netflix"),
+            Some(Symbol::new("Entertainment"))
+        );
+        assert_eq!(classifier.suggest("Dentist"), None);
+    }
+}
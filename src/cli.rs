@@ -0,0 +1,1200 @@
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::config::Config;
+use crate::daemon;
+use crate::dates;
+use crate::expense_report::{self, ExpenseReportFormat};
+use crate::export::ExportFormat;
+use crate::filter::TransactionFilter;
+use crate::import::{CsvProfile, ImportFormat, ImportOptions};
+use crate::output::OutputFormat;
+use crate::pager::DEFAULT_PAGE_SIZE;
+use crate::report::{CategoryOrder, ReportFormat};
+use crate::rules::RuleField;
+use crate::whatif::Change;
+use crate::{NewTransaction, SortKey, SortOrder, Split, TransactionType, money, quickadd, rules};
+
+#[derive(Debug, Parser)]
+#[command(name = "expenso", version, about = "Track income and expenses")]
+pub struct Cli {
+    /// Path to the config file
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Use a separate profile, with its own config and data file
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Path to the data file (overrides the config file)
+    #[arg(long, global = true)]
+    pub data: Option<PathBuf>,
+
+    /// Currency symbol (overrides the config file)
+    #[arg(long, global = true)]
+    pub currency: Option<String>,
+
+    /// Default account for new transactions (overrides the config file)
+    #[arg(long, global = true)]
+    pub account: Option<String>,
+
+    /// Use an `expenso serve` instance instead of the local data file
+    #[cfg(feature = "remote")]
+    #[arg(long, global = true, value_name = "URL")]
+    pub remote: Option<String>,
+
+    /// Access token for --remote (overrides EXPENSO_REMOTE_TOKEN and the config file)
+    #[cfg(feature = "remote")]
+    #[arg(long, global = true)]
+    pub token: Option<String>,
+
+    /// Disable colored output
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Start the full-screen terminal UI
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    pub tui: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    pub fn apply_overrides(&self, config: &mut Config) {
+        if let Some(data) = &self.data {
+            config.data_file = data.clone();
+        }
+        if let Some(currency) = &self.currency {
+            config.currency_symbol = currency.clone();
+        }
+        if let Some(account) = &self.account {
+            config.default_account = account.clone();
+        }
+        #[cfg(feature = "remote")]
+        {
+            if let Some(remote) = &self.remote {
+                config.remote = Some(remote.clone());
+            }
+            if let Some(token) = self
+                .token
+                .clone()
+                .or_else(|| std::env::var("EXPENSO_REMOTE_TOKEN").ok())
+            {
+                config.remote_token = Some(token);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Add a transaction, e.g. `expenso add "12.50 lunch #Food @cash yesterday"`
+    Add(AddArgs),
+    /// List transactions
+    List(ListArgs),
+    /// Search descriptions, payees, notes and categories, e.g. `coffee` or `/^(uber|lyft)/`
+    Search {
+        /// Text to look for, or a regex wrapped in /.../
+        query: String,
+    },
+    /// Delete transactions by id
+    Delete {
+        /// Ids of the transactions to delete
+        #[arg(required = true)]
+        ids: Vec<u32>,
+
+        /// Show what would change without writing the data file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Move every transaction in one category to another
+    Recategorize {
+        /// Current category name
+        from: String,
+
+        /// New category name
+        to: String,
+
+        /// Show what would change without writing the data file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Revert the most recent change to the data file
+    Undo {
+        /// Show what would change without writing the data file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Look for duplicate ids, invalid dates and out-of-date totals
+    Check {
+        /// Fix what can be fixed and write the data file
+        #[arg(long)]
+        repair: bool,
+    },
+    /// Record a payment that settles up with someone you share expenses with
+    Settle {
+        /// Who you are settling with
+        person: String,
+
+        /// Amount paid (the whole balance by default)
+        #[arg(long)]
+        amount: Option<f64>,
+
+        /// Show what would change without writing the data file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Tick off transactions against a bank statement and lock the matched ones
+    Reconcile {
+        /// Account the statement is for (the default account by default)
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Ending balance on the statement (asked for when left out)
+        #[arg(long, allow_hyphen_values = true)]
+        balance: Option<f64>,
+
+        /// Statement date (today by default)
+        #[arg(long, value_parser = date_arg)]
+        date: Option<String>,
+
+        /// Unlock reconciled transactions so they can be edited again
+        #[arg(long, value_delimiter = ',', num_args = 1..)]
+        unlock: Vec<u32>,
+    },
+    /// Show the changes made to the data file, newest first
+    History {
+        /// Only show this many changes
+        #[arg(long, short = 'n')]
+        limit: Option<usize>,
+        /// Only show the changes to this transaction, field by field
+        #[arg(long)]
+        id: Option<u32>,
+    },
+    /// Export transactions to another tool's format
+    Export(ExportArgs),
+    /// Collect reimbursable expenses into a report to submit to an employer
+    ExpenseReport(ExpenseReportArgs),
+    /// Import transactions from a bank statement or another app's CSV export
+    Import(ImportArgs),
+    /// Show what changed between two expenso data files, e.g. before and after a sync
+    Diff {
+        /// The earlier data file
+        old: PathBuf,
+        /// The later data file
+        new: PathBuf,
+    },
+    /// Close a month or year so its transactions can't be changed, or list the closed ones
+    Close {
+        /// The month (YYYY-MM) or year (YYYY)
+        period: Option<String>,
+    },
+    /// Reopen a closed month or year so its transactions can be changed again
+    Reopen {
+        /// The month (YYYY-MM) or year (YYYY), as it was closed
+        period: String,
+    },
+    /// Combine another expenso data file, e.g. from another machine, into this one
+    Merge {
+        /// The other data file
+        file: PathBuf,
+        /// Show what would be merged without saving
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Add a transaction from a photo of a receipt, confirming what OCR read
+    #[cfg(feature = "ocr")]
+    Receipt {
+        /// The receipt image
+        image: PathBuf,
+    },
+    /// Serve a web dashboard and JSON API for the data file
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Push transactions and monthly summaries to a Google Sheet, one tab per month
+    #[cfg(feature = "sheets")]
+    Sheets {
+        /// Spreadsheet id (overrides google_sheet_id in the config file)
+        #[arg(long)]
+        spreadsheet: Option<String>,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Run a Telegram bot that records entries and answers /summary
+    #[cfg(feature = "telegram")]
+    Telegram,
+    /// List installed WASM plugins and what they provide
+    #[cfg(feature = "plugins")]
+    Plugins,
+    /// Track investment holdings, their cost and current prices
+    Invest {
+        #[command(subcommand)]
+        invest: InvestCommand,
+    },
+    /// Group transactions into projects such as trips or renovations
+    Project {
+        #[command(subcommand)]
+        project: ProjectCommand,
+    },
+    /// Show credit cards' current cycle, last statement and when it is due
+    Card {
+        /// One of the accounts under [cards] in the config file; all of them by default
+        account: Option<String>,
+    },
+    /// Track loans, their amortization schedules and what is still owed
+    Loan {
+        #[command(subcommand)]
+        loan: LoanCommand,
+    },
+    /// Show budgets gone over this budget period and bills and card payments coming due
+    Alerts {
+        /// Also send them as desktop notifications, e.g. from cron or a systemd timer
+        #[cfg(feature = "notifications")]
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Keep running to add recurring transactions as they come due, report
+    /// alerts and back up the data each day
+    Daemon {
+        /// Minutes between runs
+        #[arg(long, default_value_t = daemon::DEFAULT_INTERVAL, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+
+        /// Run once and exit, e.g. from cron or a systemd timer
+        #[arg(long)]
+        once: bool,
+
+        /// Also send alerts as desktop notifications
+        #[cfg(feature = "notifications")]
+        #[arg(long)]
+        notify: bool,
+    },
+    /// Securely delete the data file, backups, receipts and config file
+    Purge {
+        /// List what would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Project balances with hypothetical changes, without touching the data
+    WhatIf(WhatIfArgs),
+    /// Manage rules that categorize and tag new transactions
+    Rules {
+        #[command(subcommand)]
+        rules: RulesCommand,
+    },
+    /// Show reports
+    Report {
+        #[command(subcommand)]
+        report: ReportCommand,
+    },
+    /// Print a shell completion script
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Debug, Default, Args)]
+pub struct AddArgs {
+    /// Shorthand entry: amount, description, #category, @account and a date
+    pub entry: Option<String>,
+
+    /// Amount
+    #[arg(long)]
+    pub amount: Option<f64>,
+
+    /// Currency the amount is in, e.g. EUR, when it isn't the configured one
+    #[arg(long = "in", value_name = "CODE")]
+    pub currency: Option<String>,
+
+    /// Description
+    #[arg(long = "desc")]
+    pub description: Option<String>,
+
+    /// Category
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// Income or expense
+    #[arg(long = "type", value_enum)]
+    pub transaction_type: Option<TransactionType>,
+
+    /// Date (YYYY-MM-DD, today or yesterday)
+    #[arg(long)]
+    pub date: Option<String>,
+
+    /// Payee
+    #[arg(long)]
+    pub payee: Option<String>,
+
+    /// Notes
+    #[arg(long)]
+    pub notes: Option<String>,
+
+    /// Tag (repeatable)
+    #[arg(long = "tag")]
+    pub tags: Vec<String>,
+
+    /// Mark as recurring
+    #[arg(long)]
+    pub recurring: bool,
+
+    /// Project it belongs to, one defined with `project add`
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Share with someone (repeatable): NAME for an equal share, or
+    /// NAME=AMOUNT for what they owe you
+    #[arg(long = "split", value_name = "NAME[=AMOUNT]")]
+    pub splits: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct WhatIfArgs {
+    /// Months to project
+    #[arg(long, default_value_t = 12, value_parser = clap::value_parser!(u32).range(1..=600))]
+    pub months: u32,
+
+    /// A new monthly expense (repeatable)
+    #[arg(long, value_name = "NAME=AMOUNT", value_parser = named_amount)]
+    pub add: Vec<(String, f64)>,
+
+    /// New monthly income (repeatable)
+    #[arg(long, value_name = "NAME=AMOUNT", value_parser = named_amount)]
+    pub income: Vec<(String, f64)>,
+
+    /// Raise a recurring amount, or lower it with a negative one, e.g. Rent=300 (repeatable)
+    #[arg(long, value_name = "NAME=AMOUNT", value_parser = named_amount)]
+    pub change: Vec<(String, f64)>,
+
+    /// Stop a recurring transaction, e.g. a subscription (repeatable)
+    #[arg(long, value_name = "NAME")]
+    pub drop: Vec<String>,
+
+    /// Spend this much a month on a category instead of its recent average (repeatable)
+    #[arg(long, value_name = "CATEGORY=AMOUNT", value_parser = named_amount)]
+    pub budget: Vec<(String, f64)>,
+}
+
+impl WhatIfArgs {
+    pub fn changes(&self) -> Vec<Change> {
+        let drops = self.drop.iter().map(|description| Change::Drop {
+            description: description.clone(),
+        });
+        let adjustments = self.change.iter().map(|(description, by)| Change::Adjust {
+            description: description.clone(),
+            by: *by,
+        });
+        let budgets = self.budget.iter().map(|(category, amount)| Change::Budget {
+            category: category.clone(),
+            amount: *amount,
+        });
+        let additions = self.add.iter().map(|(description, amount)| Change::Add {
+            description: description.clone(),
+            amount: *amount,
+        });
+        let income = self
+            .income
+            .iter()
+            .map(|(description, amount)| Change::Income {
+                description: description.clone(),
+                amount: *amount,
+            });
+        drops
+            .chain(adjustments)
+            .chain(budgets)
+            .chain(additions)
+            .chain(income)
+            .collect()
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Export format
+    #[arg(long, value_enum)]
+    pub format: ExportFormat,
+
+    /// Write to this file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    /// Hash descriptions, payees and accounts, drop notes and round amounts, to share the data safely
+    #[arg(long)]
+    pub anonymize: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ExpenseReportArgs {
+    /// First day of the period (YYYY-MM-DD, 2024-06, last friday, ...)
+    #[arg(long, value_parser = date_arg)]
+    pub from: Option<String>,
+
+    /// Last day of the period (YYYY-MM-DD, 2024-06, last friday, ...)
+    #[arg(long, value_parser = date_arg)]
+    pub to: Option<String>,
+
+    /// Tag marking the expenses to claim
+    #[arg(long, default_value = expense_report::REIMBURSABLE)]
+    pub tag: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExpenseReportFormat::Plain)]
+    pub format: ExpenseReportFormat,
+
+    /// Write to this file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// File to import
+    pub file: PathBuf,
+
+    /// File format (detected from the extension by default)
+    #[arg(long, value_enum)]
+    pub format: Option<ImportFormat>,
+
+    /// Column layout of a CSV file (detected from the header by default)
+    #[arg(long, value_enum)]
+    pub profile: Option<CsvProfile>,
+
+    /// Date format of a CSV file, e.g. %d/%m/%Y
+    #[arg(long)]
+    pub date_format: Option<String>,
+
+    /// Read the file with an import plugin instead of a built-in format
+    #[cfg(feature = "plugins")]
+    #[arg(long, conflicts_with_all = ["format", "profile", "date_format"])]
+    pub plugin: Option<String>,
+
+    /// Show what would change without writing the data file
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl ImportArgs {
+    pub fn options(&self, config: &Config) -> ImportOptions {
+        ImportOptions {
+            account: config.default_account.clone(),
+            profile: self.profile,
+            date_format: self.date_format.clone(),
+            categories: config.import_categories.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RulesCommand {
+    /// List rules in the order they are tried
+    List,
+    /// Add a rule, e.g. `expenso rules add --field payee --contains starbucks --category Food:Coffee --tag coffee`
+    Add {
+        /// Text to look for (case-insensitive)
+        #[arg(long)]
+        contains: String,
+        /// Field to search
+        #[arg(long, value_enum, default_value = "any")]
+        field: RuleField,
+        /// Category to assign
+        #[arg(long)]
+        category: Option<String>,
+        /// Tag to add (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+    },
+    /// Remove a rule by its number in `rules list`
+    Remove { number: usize },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum InvestCommand {
+    /// List holdings with their value and gain
+    List,
+    /// Record a purchase, e.g. `expenso invest buy VTI 10 --price 231.50`
+    Buy {
+        symbol: String,
+        quantity: f64,
+        /// Price paid per unit
+        #[arg(long)]
+        price: f64,
+    },
+    /// Record a sale at its average cost, showing the realized gain
+    Sell {
+        symbol: String,
+        quantity: f64,
+        /// Price received per unit
+        #[arg(long)]
+        price: f64,
+    },
+    /// Set the current price of a holding, or fetch every price with price_command
+    Price {
+        #[arg(required_unless_present = "fetch")]
+        symbol: Option<String>,
+        #[arg(required_unless_present = "fetch")]
+        price: Option<f64>,
+        /// Run price_command from the config file for each holding
+        #[arg(long, conflicts_with_all = ["symbol", "price"])]
+        fetch: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ProjectCommand {
+    /// List projects with their dates and budget
+    List,
+    /// Define a project, e.g. `expenso project add Berlin --budget 1500 --start 2024-04-01 --end 2024-04-07`
+    Add {
+        name: String,
+        /// Total to spend on it
+        #[arg(long)]
+        budget: Option<f64>,
+        /// First day
+        #[arg(long, value_parser = date_arg)]
+        start: Option<String>,
+        /// Last day
+        #[arg(long, value_parser = date_arg)]
+        end: Option<String>,
+    },
+    /// Put transactions in a project
+    Assign {
+        name: String,
+        /// Ids of the transactions to assign
+        #[arg(required = true)]
+        ids: Vec<u32>,
+    },
+    /// Take transactions out of their project
+    Unassign {
+        /// Ids of the transactions to unassign
+        #[arg(required = true)]
+        ids: Vec<u32>,
+    },
+    /// Remove a project, taking its transactions out of it
+    Remove { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LoanCommand {
+    /// List loans with their monthly payment and remaining principal
+    List,
+    /// Add a loan, e.g. `expenso loan add Car --principal 20000 --rate 6.5 --months 60`
+    Add {
+        name: String,
+        /// Amount borrowed
+        #[arg(long)]
+        principal: f64,
+        /// Yearly interest rate in percent
+        #[arg(long)]
+        rate: f64,
+        /// Number of monthly payments
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        months: u32,
+        /// When the loan started; the first payment is due a month later (defaults to today)
+        #[arg(long, value_parser = date_arg)]
+        start: Option<String>,
+        /// Don't add the monthly payment as a recurring expense
+        #[arg(long)]
+        no_payment: bool,
+    },
+    /// Show every payment with its interest, principal and remaining balance
+    Schedule { name: String },
+    /// Remove a loan
+    Remove { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// Income, expense and balance totals
+    Summary(ReportArgs),
+    /// Totals per category
+    Categories(CategoryReportArgs),
+    /// Income, expense and net per month
+    Monthly(ReportArgs),
+    /// Income, expense and net per fiscal year
+    Yearly(ReportArgs),
+    /// Totals per payee, largest first
+    Payees(ReportArgs),
+    /// What the people you share expenses with owe you, or you owe them
+    Balances(ReportArgs),
+    /// Account balances plus the market value of your investments, less loans
+    NetWorth(ReportArgs),
+    /// Spending per project against its budget
+    Projects(ReportArgs),
+    /// Repeated charges from the same payee for the same amount, with their monthly cost
+    Subscriptions(SubscriptionReportArgs),
+    /// Draw the category pie and monthly trend as a PNG or SVG image
+    #[cfg(feature = "charts")]
+    Chart(ChartArgs),
+    /// Email the weekly digest or monthly report over SMTP
+    #[cfg(feature = "email")]
+    Email(EmailArgs),
+    /// Run a report plugin
+    #[cfg(feature = "plugins")]
+    Plugin {
+        /// Plugin name (its file name without the extension)
+        name: String,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+}
+
+#[cfg(feature = "charts")]
+#[derive(Debug, Args)]
+pub struct ChartArgs {
+    /// Image to write, an SVG when it ends in .svg and a PNG otherwise
+    #[arg(long, short)]
+    pub out: PathBuf,
+
+    /// Charts to draw
+    #[arg(long, value_enum, default_value = "both")]
+    pub kind: crate::chart::ChartKind,
+
+    /// Width in pixels
+    #[arg(long, default_value_t = 1200)]
+    pub width: u32,
+
+    /// Height in pixels
+    #[arg(long, default_value_t = 600)]
+    pub height: u32,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[cfg(feature = "email")]
+#[derive(Debug, Args)]
+pub struct EmailArgs {
+    /// Period to report on
+    #[arg(long, value_enum, default_value = "week")]
+    pub period: crate::email::Period,
+
+    /// Recipient (repeatable, overrides email_to in the config file)
+    #[arg(long)]
+    pub to: Vec<String>,
+
+    /// Print the email instead of sending it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Plain)]
+    pub format: ReportFormat,
+
+    /// Restate amounts in one year's money using the `cpi` table in the config, its latest year by default
+    #[arg(long, value_name = "YEAR")]
+    pub real: Option<Option<i32>>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct CategoryReportArgs {
+    /// Order of the categories, `category_order` in the config by default
+    #[arg(long, value_enum)]
+    pub order: Option<CategoryOrder>,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct SubscriptionReportArgs {
+    /// Mark the detected charges as recurring so the next one shows up as an upcoming bill
+    #[arg(long)]
+    pub mark_recurring: bool,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct FilterArgs {
+    /// Only include this category
+    #[arg(long)]
+    pub category: Option<String>,
+
+    /// Only include income or expense transactions
+    #[arg(long = "type", value_enum)]
+    pub transaction_type: Option<TransactionType>,
+
+    /// Only include transactions on or after this date (YYYY-MM-DD, 2024-06, last friday, ...)
+    #[arg(long, value_parser = date_arg)]
+    pub from: Option<String>,
+
+    /// Only include transactions on or before this date (YYYY-MM-DD, 2024-06, last friday, ...)
+    #[arg(long, value_parser = date_arg)]
+    pub to: Option<String>,
+
+    /// Only include the fiscal year starting in YEAR, set by `fiscal_year_start` in the config
+    #[arg(long, value_name = "YEAR", conflicts_with_all = ["from", "to"])]
+    pub year: Option<i32>,
+
+    /// Minimum amount
+    #[arg(long)]
+    pub min: Option<f64>,
+
+    /// Maximum amount
+    #[arg(long)]
+    pub max: Option<f64>,
+
+    /// Only include transactions with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Only include recurring (or, with `false`, one-off) transactions
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub recurring: Option<bool>,
+
+    /// Only include transactions in this project
+    #[arg(long)]
+    pub project: Option<String>,
+}
+
+impl FilterArgs {
+    pub fn to_filter(&self) -> TransactionFilter {
+        let (from, to) = match self
+            .year
+            .and_then(|year| dates::fiscal_year_range(year, dates::fiscal_year_start()))
+        {
+            Some((from, to)) => (Some(from), Some(to)),
+            None => (self.from.clone(), self.to.clone()),
+        };
+        TransactionFilter {
+            category: self.category.clone(),
+            transaction_type: self.transaction_type.clone(),
+            from,
+            to,
+            min_amount: self.min,
+            max_amount: self.max,
+            tag: self.tag.clone(),
+            recurring: self.recurring,
+            project: self.project.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Field to sort by
+    #[arg(long, value_enum, default_value_t = SortKey::Id)]
+    pub sort: SortKey,
+
+    /// Sort in descending order
+    #[arg(long)]
+    pub desc: bool,
+
+    /// Show only this page of results (starting at 1)
+    #[arg(long, value_parser = positive)]
+    pub page: Option<usize>,
+
+    /// Number of transactions per page
+    #[arg(long, default_value_t = DEFAULT_PAGE_SIZE, value_parser = positive)]
+    pub per_page: usize,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Plain)]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+impl ListArgs {
+    pub fn order(&self) -> SortOrder {
+        if self.desc {
+            SortOrder::Descending
+        } else {
+            SortOrder::Ascending
+        }
+    }
+}
+
+pub fn print_completions(shell: Shell) {
+    let mut command = Cli::command();
+    let name = command.get_name().to_string();
+    clap_complete::generate(shell, &mut command, name, &mut std::io::stdout());
+}
+
+fn date_arg(value: &str) -> Result<String, String> {
+    dates::normalize(value, Local::now().date_naive())
+        .ok_or_else(|| format!("unrecognised date \"{}\"", value))
+}
+
+fn named_amount(value: &str) -> Result<(String, f64), String> {
+    let invalid = || format!("expected NAME=AMOUNT, not \"{}\"", value);
+    let (name, amount) = value.split_once('=').ok_or_else(invalid)?;
+    let amount: f64 = amount.trim().parse().map_err(|_| invalid())?;
+    if name.trim().is_empty() || !amount.is_finite() {
+        return Err(invalid());
+    }
+    Ok((name.trim().to_string(), amount))
+}
+
+fn positive(value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(0) => Err(String::from("must be at least 1")),
+        Ok(n) => Ok(n),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub fn build_new_transaction(
+    args: &AddArgs,
+    config: &Config,
+    today: NaiveDate,
+) -> Result<NewTransaction, String> {
+    let quick = match &args.entry {
+        Some(entry) => quickadd::parse(entry, today)?,
+        None => quickadd::QuickAdd::default(),
+    };
+
+    let amount = args
+        .amount
+        .or(quick.amount)
+        .ok_or_else(|| String::from("An amount is required"))?;
+    let date = match &args.date {
+        Some(date) => dates::normalize_in(date, &config.date_format, today).ok_or_else(|| {
+            format!(
+                "Invalid date \"{}\", expected {}",
+                date,
+                dates::format_hint(&config.date_format)
+            )
+        })?,
+        None => quick
+            .date
+            .unwrap_or_else(|| today.format(dates::ISO_FORMAT).to_string()),
+    };
+    let explicit_category = args.category.clone().or(quick.category);
+    let category = explicit_category
+        .clone()
+        .unwrap_or_else(|| String::from("Uncategorized"));
+    let description = args
+        .description
+        .clone()
+        .or(quick.description)
+        .unwrap_or_else(|| category.clone());
+
+    let mut new = NewTransaction {
+        description,
+        amount,
+        currency: args
+            .currency
+            .clone()
+            .filter(|code| !code.trim().eq_ignore_ascii_case(&config.currency)),
+        is_recurring: args.recurring,
+        date,
+        transaction_type: args
+            .transaction_type
+            .clone()
+            .or(quick.transaction_type)
+            .unwrap_or_else(|| config.default_type.clone()),
+        category,
+        payee: args.payee.clone(),
+        notes: args.notes.clone(),
+        tags: args.tags.clone(),
+        account: quick
+            .account
+            .unwrap_or_else(|| config.default_account.clone()),
+        splits: parse_splits(&args.splits, amount)?,
+        project: args.project.clone(),
+        ..Default::default()
+    };
+    if let Some(rule) = rules::find(&config.rules, &new) {
+        if explicit_category.is_some() {
+            rule.add_tags(&mut new);
+        } else {
+            rule.apply(&mut new);
+        }
+    }
+    Ok(new)
+}
+
+/// Reads `--split` values. People named without an amount share what the
+/// others don't owe equally with you.
+pub fn parse_splits(specs: &[String], amount: f64) -> Result<Vec<Split>, String> {
+    let mut splits = Vec::new();
+    let mut equal = Vec::new();
+    for spec in specs {
+        match spec.split_once('=') {
+            Some((person, share)) => {
+                let share = share
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid split \"{}\", expected NAME=AMOUNT", spec))?;
+                splits.push(Split {
+                    person: person.trim().to_string(),
+                    share,
+                });
+            }
+            None => equal.push(spec.trim().to_string()),
+        }
+    }
+    if !equal.is_empty() {
+        let rest = amount - money::sum(splits.iter().map(|s| s.share));
+        // The first share is yours and takes any odd cent.
+        let shares = money::split(rest, equal.len() + 1);
+        splits.extend(
+            equal
+                .into_iter()
+                .zip(shares.into_iter().skip(1))
+                .map(|(person, share)| Split { person, share }),
+        );
+    }
+    Ok(splits)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_list_with_sort() {
+        let cli = Cli::try_parse_from(["expenso", "list", "--sort", "amount", "--desc"]).unwrap();
+        let Some(Command::List(args)) = cli.command else {
+            panic!("expected list command");
+        };
+        assert_eq!(args.sort, SortKey::Amount);
+        assert_eq!(args.order(), SortOrder::Descending);
+        assert_eq!(args.page, None);
+        assert_eq!(args.per_page, DEFAULT_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_parse_list_filters() {
+        let cli = Cli::try_parse_from([
+            "expenso",
+            "list",
+            "--type",
+            "expense",
+            "--from",
+            "2024-01",
+            "--min",
+            "10",
+            "--recurring",
+        ])
+        .unwrap();
+        let Some(Command::List(args)) = cli.command else {
+            panic!("expected list command");
+        };
+        let filter = args.filter.to_filter();
+        assert_eq!(filter.transaction_type, Some(TransactionType::Expense));
+        assert_eq!(filter.from.as_deref(), Some("2024-01-01"));
+        assert_eq!(filter.min_amount, Some(10.0));
+        assert_eq!(filter.recurring, Some(true));
+        assert_eq!(filter.category, None);
+    }
+
+    #[test]
+    fn test_parse_report_with_filters() {
+        let cli = Cli::try_parse_from([
+            "expenso",
+            "report",
+            "summary",
+            "--recurring",
+            "false",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        let Some(Command::Report {
+            report: ReportCommand::Summary(args),
+        }) = cli.command
+        else {
+            panic!("expected report summary command");
+        };
+        assert_eq!(args.filter.to_filter().recurring, Some(false));
+        assert_eq!(args.format, ReportFormat::Json);
+    }
+
+    #[test]
+    fn test_completions_cover_subcommands() {
+        let mut command = Cli::command();
+        let mut script = Vec::new();
+        clap_complete::generate(Shell::Bash, &mut command, "expenso", &mut script);
+        let script = String::from_utf8(script).unwrap();
+
+        assert!(script.contains("expenso__subcmd__list"));
+        assert!(script.contains("expenso__subcmd__report__subcmd__summary"));
+        assert!(script.contains("--per-page"));
+    }
+
+    #[test]
+    fn test_parse_add_shorthand_and_flags() {
+        let cli = Cli::try_parse_from([
+            "expenso",
+            "add",
+            "12.50 lunch #Food",
+            "--tag",
+            "work",
+            "--tag",
+            "team",
+        ])
+        .unwrap();
+        let Some(Command::Add(args)) = cli.command else {
+            panic!("expected add command");
+        };
+        assert_eq!(args.entry.as_deref(), Some("12.50 lunch #Food"));
+        assert_eq!(args.tags, vec!["work", "team"]);
+        assert!(!args.recurring);
+    }
+
+    #[test]
+    fn test_parse_delete_dry_run() {
+        let cli = Cli::try_parse_from(["expenso", "delete", "3", "4", "--dry-run"]).unwrap();
+        let Some(Command::Delete { ids, dry_run }) = cli.command else {
+            panic!("expected delete command");
+        };
+        assert_eq!(ids, vec![3, 4]);
+        assert!(dry_run);
+        assert!(Cli::try_parse_from(["expenso", "delete"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_list_rejects_page_zero() {
+        assert!(Cli::try_parse_from(["expenso", "list", "--page", "0"]).is_err());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn test_no_subcommand_is_interactive() {
+        let cli = Cli::try_parse_from(["expenso", "--tui"]).unwrap();
+        assert!(cli.command.is_none());
+        assert!(cli.tui);
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let cli =
+            Cli::try_parse_from(["expenso", "--data", "other.json", "--currency", "€"]).unwrap();
+        let mut config = Config::default();
+        cli.apply_overrides(&mut config);
+
+        assert_eq!(config.data_file, PathBuf::from("other.json"));
+        assert_eq!(config.currency_symbol, "€");
+        assert_eq!(config.default_account, Config::default().default_account);
+    }
+
+    #[test]
+    fn test_build_new_transaction_from_shorthand() {
+        let cli =
+            Cli::try_parse_from(["expenso", "add", "12.50 lunch #Food @card yesterday"]).unwrap();
+        let Some(Command::Add(args)) = cli.command else {
+            panic!("expected add command");
+        };
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let new = build_new_transaction(&args, &Config::default(), today).unwrap();
+
+        assert_eq!(new.amount, 12.5);
+        assert_eq!(new.description, "lunch");
+        assert_eq!(new.category, "Food");
+        assert_eq!(new.account, "card");
+        assert_eq!(new.date, "2024-03-09");
+        assert_eq!(new.transaction_type, TransactionType::Expense);
+    }
+
+    #[test]
+    fn test_build_new_transaction_defaults_and_flags() {
+        let cli =
+            Cli::try_parse_from(["expenso", "add", "--amount", "4.5", "--desc", "Coffee"]).unwrap();
+        let Some(Command::Add(args)) = cli.command else {
+            panic!("expected add command");
+        };
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let config = Config {
+            default_account: String::from("Wallet"),
+            ..Config::default()
+        };
+        let new = build_new_transaction(&args, &config, today).unwrap();
+
+        assert_eq!(new.description, "Coffee");
+        assert_eq!(new.category, "Uncategorized");
+        assert_eq!(new.account, "Wallet");
+        assert_eq!(new.date, "2024-03-10");
+    }
+
+    #[test]
+    fn test_build_new_transaction_applies_rules() {
+        let config = Config {
+            rules: vec![rules::Rule {
+                field: rules::RuleField::Description,
+                contains: String::from("starbucks"),
+                category: Some(String::from("Food:Coffee")),
+                tags: vec![String::from("coffee")],
+            }],
+            ..Config::default()
+        };
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+
+        let cli = Cli::try_parse_from(["expenso", "add", "4.50 Starbucks latte"]).unwrap();
+        let Some(Command::Add(args)) = cli.command else {
+            panic!("expected add command");
+        };
+        let new = build_new_transaction(&args, &config, today).unwrap();
+        assert_eq!(new.category, "Food:Coffee");
+        assert_eq!(new.tags, vec!["coffee"]);
+
+        let cli = Cli::try_parse_from(["expenso", "add", "4.50 Starbucks #Work"]).unwrap();
+        let Some(Command::Add(args)) = cli.command else {
+            panic!("expected add command");
+        };
+        let new = build_new_transaction(&args, &config, today).unwrap();
+        assert_eq!(new.category, "Work");
+        assert_eq!(new.tags, vec!["coffee"]);
+    }
+
+    #[test]
+    fn test_split_shares() {
+        let cli = Cli::try_parse_from([
+            "expenso",
+            "add",
+            "100 dinner",
+            "--split",
+            "Alice=40",
+            "--split",
+            "Bob",
+            "--split",
+            "Carol",
+        ])
+        .unwrap();
+        let Some(Command::Add(args)) = cli.command else {
+            panic!("expected add command");
+        };
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let new = build_new_transaction(&args, &Config::default(), today).unwrap();
+        let shares: Vec<(&str, f64)> = new
+            .splits
+            .iter()
+            .map(|s| (s.person.as_str(), s.share))
+            .collect();
+        assert_eq!(
+            shares,
+            vec![("Alice", 40.0), ("Bob", 20.0), ("Carol", 20.0)]
+        );
+
+        assert!(parse_splits(&[String::from("Dan=lots")], 10.0).is_err());
+    }
+}
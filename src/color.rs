@@ -0,0 +1,98 @@
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::TransactionType;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Green,
+    Red,
+    Yellow,
+    /// Reversed, for search matches.
+    Highlight,
+    /// Bold red, for categories over their budget.
+    Alert,
+    /// Faint, for transactions from before the recent years.
+    Dim,
+}
+
+impl Color {
+    fn code(self) -> &'static str {
+        match self {
+            Color::Green => "32",
+            Color::Red => "31",
+            Color::Yellow => "33",
+            Color::Highlight => "7",
+            Color::Alert => "1;31",
+            Color::Dim => "2",
+        }
+    }
+}
+
+pub fn init(no_color_flag: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let enabled = should_color(no_color_flag, no_color_env, std::io::stdout().is_terminal());
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn should_color(no_color_flag: bool, no_color_env: bool, is_terminal: bool) -> bool {
+    !no_color_flag && !no_color_env && is_terminal
+}
+
+pub fn paint(text: &str, color: Color) -> String {
+    if enabled() {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn type_color(transaction_type: &TransactionType) -> Color {
+    match transaction_type {
+        TransactionType::Income => Color::Green,
+        TransactionType::Expense => Color::Red,
+        TransactionType::Settlement => Color::Yellow,
+    }
+}
+
+pub fn balance_color(amount: f64) -> Color {
+    if amount < 0.0 {
+        Color::Red
+    } else {
+        Color::Green
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_should_color() {
+        assert!(should_color(false, false, true));
+        assert!(!should_color(true, false, true));
+        assert!(!should_color(false, true, true));
+        assert!(!should_color(false, false, false));
+    }
+
+    #[test]
+    fn test_type_and_balance_colors() {
+        assert_eq!(type_color(&TransactionType::Income), Color::Green);
+        assert_eq!(type_color(&TransactionType::Expense), Color::Red);
+        assert_eq!(balance_color(-0.01), Color::Red);
+        assert_eq!(balance_color(0.0), Color::Green);
+    }
+
+    #[test]
+    fn test_paint_without_color_is_plain() {
+        ENABLED.store(false, Ordering::Relaxed);
+        assert_eq!(paint("$5.00", Color::Red), "$5.00");
+    }
+}
@@ -0,0 +1,115 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum Completion {
+    Exact(String),
+    Prefix(String),
+    Ambiguous(Vec<String>),
+    Suggestion(String),
+    New,
+}
+
+pub fn complete(input: &str, candidates: &[String]) -> Completion {
+    let needle = input.trim().to_lowercase();
+    if needle.is_empty() {
+        return Completion::New;
+    }
+
+    if let Some(exact) = candidates.iter().find(|c| c.to_lowercase() == needle) {
+        return Completion::Exact(exact.clone());
+    }
+
+    let prefixed: Vec<String> = candidates
+        .iter()
+        .filter(|c| c.to_lowercase().starts_with(&needle))
+        .cloned()
+        .collect();
+    match prefixed.len() {
+        0 => {}
+        1 => return Completion::Prefix(prefixed[0].clone()),
+        _ => return Completion::Ambiguous(prefixed),
+    }
+
+    candidates
+        .iter()
+        .filter_map(|c| {
+            let candidate = c.to_lowercase();
+            let distance = edit_distance(&needle, &candidate);
+            if distance <= 2 || is_subsequence(&needle, &candidate) {
+                Some((distance, c))
+            } else {
+                None
+            }
+        })
+        .min_by_key(|(distance, c)| (*distance, c.len()))
+        .map_or(Completion::New, |(_, c)| Completion::Suggestion(c.clone()))
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|n| haystack.any(|h| h == n))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn categories() -> Vec<String> {
+        ["Food", "Fuel", "Housing", "Work"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_exact_and_prefix() {
+        assert_eq!(
+            complete("food", &categories()),
+            Completion::Exact(String::from("Food"))
+        );
+        assert_eq!(
+            complete("hou", &categories()),
+            Completion::Prefix(String::from("Housing"))
+        );
+        assert_eq!(
+            complete("F", &categories()),
+            Completion::Ambiguous(vec![String::from("Food"), String::from("Fuel")])
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_suggestions() {
+        assert_eq!(
+            complete("Hsng", &categories()),
+            Completion::Suggestion(String::from("Housing"))
+        );
+        assert_eq!(
+            complete("Wrok", &categories()),
+            Completion::Suggestion(String::from("Work"))
+        );
+        assert_eq!(complete("Travel", &categories()), Completion::New);
+        assert_eq!(complete("  ", &categories()), Completion::New);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("food", "food"), 0);
+        assert_eq!(edit_distance("fod", "food"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
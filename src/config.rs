@@ -0,0 +1,352 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::TransactionType;
+use crate::cards::StatementCycle;
+use crate::dates::{BudgetPeriod, Calendar};
+use crate::error::{ExpensoError, Result};
+use crate::report::CategoryOrder;
+use crate::rules::Rule;
+
+pub const DEFAULT_ACCOUNT: &str = "Cash";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub currency: String,
+    pub currency_symbol: String,
+    pub date_format: String,
+    pub default_account: String,
+    pub default_type: TransactionType,
+    pub locale: String,
+    pub data_file: PathBuf,
+    /// Years of transactions and history the menu and TUI load at startup,
+    /// counting the current one. Older ones are read when a view needs them;
+    /// 0 loads everything.
+    pub recent_years: u32,
+    pub aliases: BTreeMap<String, String>,
+    pub autosave: bool,
+    /// Name recorded with each change in the history, the login name by default.
+    pub user: Option<String>,
+    pub import_categories: BTreeMap<String, String>,
+    pub telegram_token: Option<String>,
+    pub telegram_chats: Vec<i64>,
+    pub smtp_host: Option<String>,
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub email_from: Option<String>,
+    pub email_to: Vec<String>,
+    pub google_sheet_id: Option<String>,
+    pub google_access_token: Option<String>,
+    pub remote: Option<String>,
+    pub remote_token: Option<String>,
+    pub server_token: Option<String>,
+    pub rules: Vec<Rule>,
+    /// Money there was before the first transaction, not tied to an account.
+    pub opening_balance: f64,
+    /// What each account held before its first transaction.
+    pub opening_balances: BTreeMap<String, f64>,
+    /// Month (1 to 12) the fiscal year starts in, for `--year` and the
+    /// yearly report.
+    pub fiscal_year_start: u32,
+    /// Day weeks start on, for the weekly digest and weekly budgets.
+    pub week_start: Weekday,
+    /// Day of the month (1 to 28) months start on, e.g. payday, for the
+    /// monthly email report and monthly budgets.
+    pub month_start: u32,
+    /// Whether `budgets` limit spending per `month` or per `week`.
+    pub budget_period: BudgetPeriod,
+    /// Spending limit per category for each budget period.
+    pub budgets: BTreeMap<String, f64>,
+    /// Statement cycle per credit card account.
+    pub cards: BTreeMap<String, StatementCycle>,
+    /// Consumer price index per year, for reports with `--real`.
+    pub cpi: BTreeMap<i32, f64>,
+    /// What one unit of each other currency is worth in `currency`, for
+    /// converting transactions in it.
+    pub rates: BTreeMap<String, f64>,
+    /// Order of the category report, `name` or `total`.
+    pub category_order: CategoryOrder,
+    /// Prints the text of a receipt image whose path is appended, for
+    /// `expenso receipt`; `tesseract <image> stdout` when unset.
+    pub ocr_command: Option<String>,
+    /// Prints the current price of the symbol appended, for `invest price --fetch`.
+    pub price_command: Option<String>,
+    /// Where WASM plugins are loaded from, `plugins` in the config directory by default.
+    pub plugin_dir: Option<PathBuf>,
+    /// Where `expenso daemon` writes a copy of the data each day; no
+    /// backups are made when unset.
+    pub backup_dir: Option<PathBuf>,
+    /// Daily backups kept before the oldest are deleted.
+    pub backup_keep: usize,
+    /// URLs `expenso daemon` posts alerts and added recurring transactions to as JSON.
+    pub webhooks: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            currency: String::from("USD"),
+            currency_symbol: String::from("$"),
+            date_format: String::from("%Y-%m-%d"),
+            default_account: String::from(DEFAULT_ACCOUNT),
+            default_type: TransactionType::Expense,
+            locale: String::from("en-US"),
+            data_file: PathBuf::from("expenso.json"),
+            recent_years: 2,
+            aliases: BTreeMap::new(),
+            autosave: false,
+            user: None,
+            import_categories: BTreeMap::new(),
+            telegram_token: None,
+            telegram_chats: Vec::new(),
+            smtp_host: None,
+            smtp_port: None,
+            smtp_username: None,
+            smtp_password: None,
+            email_from: None,
+            email_to: Vec::new(),
+            google_sheet_id: None,
+            google_access_token: None,
+            remote: None,
+            remote_token: None,
+            server_token: None,
+            rules: Vec::new(),
+            opening_balance: 0.0,
+            opening_balances: BTreeMap::new(),
+            fiscal_year_start: 1,
+            week_start: Weekday::Mon,
+            month_start: 1,
+            budget_period: BudgetPeriod::Month,
+            budgets: BTreeMap::new(),
+            cards: BTreeMap::new(),
+            cpi: BTreeMap::new(),
+            rates: BTreeMap::new(),
+            category_order: CategoryOrder::Name,
+            ocr_command: None,
+            price_command: None,
+            plugin_dir: None,
+            backup_dir: None,
+            backup_keep: 30,
+            webhooks: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("expenso").join("config.toml"))
+    }
+
+    /// Where profiles live, one directory per profile holding its
+    /// `config.toml` and, unless that says otherwise, its data file.
+    pub fn profiles_dir() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("expenso").join("profiles"))
+    }
+
+    pub fn profile_path(name: &str) -> Result<PathBuf> {
+        let valid = !name.starts_with('.')
+            && !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(ExpensoError::InvalidInput(format!(
+                "invalid profile name '{}'",
+                name
+            )));
+        }
+        let dir = Config::profiles_dir().ok_or_else(|| {
+            ExpensoError::InvalidInput(String::from("could not determine the config directory"))
+        })?;
+        Ok(dir.join(name).join("config.toml"))
+    }
+
+    /// Names of the profiles created so far, sorted.
+    pub fn profile_names() -> Vec<String> {
+        let Some(entries) = Config::profiles_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Like [`load`](Config::load), but a relative `data_file` is kept next
+    /// to the profile's config so profiles never share transactions.
+    pub fn load_profile(path: &Path) -> Result<Config> {
+        let mut config = Config::load(path)?;
+        if config.data_file.is_relative()
+            && let Some(dir) = path.parent()
+        {
+            config.data_file = dir.join(&config.data_file);
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Config> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        let config: Config = toml::from_str(&contents)?;
+        if !(1..=12).contains(&config.fiscal_year_start) {
+            return Err(ExpensoError::InvalidInput(format!(
+                "fiscal_year_start must be a month from 1 to 12, not {}",
+                config.fiscal_year_start
+            )));
+        }
+        if !(1..=28).contains(&config.month_start) {
+            return Err(ExpensoError::InvalidInput(format!(
+                "month_start must be a day from 1 to 28, not {}",
+                config.month_start
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Where weeks and months start.
+    pub fn calendar(&self) -> Calendar {
+        Calendar {
+            week_start: self.week_start,
+            month_start: self.month_start,
+        }
+    }
+
+    /// January 1st of the first of the [`recent_years`](Config::recent_years)
+    /// up to `today`, or `None` when every year is loaded. Transactions dated
+    /// before it are the archived ones.
+    pub fn recent_start(&self, today: NaiveDate) -> Option<String> {
+        let first = today.year() - (self.recent_years.checked_sub(1)? as i32);
+        Some(format!("{}-01-01", first))
+    }
+
+    pub fn save_rules(path: &Path, rules: &[Rule]) -> Result<()> {
+        let mut table: toml::Table = if path.exists() {
+            toml::from_str(&fs::read_to_string(path)?)?
+        } else {
+            toml::Table::new()
+        };
+
+        let rules = toml::Value::try_from(rules)?;
+        table.insert(String::from("rules"), rules);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, toml::to_string(&table)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_recent_start() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let mut config = Config::default();
+        assert_eq!(config.recent_start(today).as_deref(), Some("2024-01-01"));
+        config.recent_years = 1;
+        assert_eq!(config.recent_start(today).as_deref(), Some("2025-01-01"));
+        config.recent_years = 0;
+        assert_eq!(config.recent_start(today), None);
+    }
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            currency_symbol = "€"
+            default_type = "Income"
+            data_file = "/tmp/finances.json"
+            week_start = "sunday"
+            budget_period = "week"
+
+            [aliases]
+            coffee = "add --amount 4.5 --category Food --desc Coffee"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.currency_symbol, "€");
+        assert_eq!(config.default_type, TransactionType::Income);
+        assert_eq!(config.data_file, PathBuf::from("/tmp/finances.json"));
+        assert_eq!(config.default_account, DEFAULT_ACCOUNT);
+        assert_eq!(config.date_format, "%Y-%m-%d");
+        assert_eq!(config.calendar().week_start, Weekday::Sun);
+        assert_eq!(config.calendar().month_start, 1);
+        assert_eq!(config.budget_period, BudgetPeriod::Week);
+        assert_eq!(
+            config.aliases.get("coffee").map(String::as_str),
+            Some("add --amount 4.5 --category Food --desc Coffee")
+        );
+    }
+
+    #[test]
+    fn test_missing_file_uses_defaults() {
+        let path = std::env::temp_dir().join("expenso-no-such-config.toml");
+        assert_eq!(Config::load(&path).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_save_rules_keeps_other_settings() {
+        let path = std::env::temp_dir().join(format!("expenso-rules-{}.toml", std::process::id()));
+        fs::write(&path, "currency_symbol = \"€\"\n").unwrap();
+
+        let rules = vec![Rule {
+            field: crate::rules::RuleField::Payee,
+            contains: String::from("uber"),
+            category: Some(String::from("Transport")),
+            tags: Vec::new(),
+        }];
+        Config::save_rules(&path, &rules).unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.currency_symbol, "€");
+        assert_eq!(config.rules, rules);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_profiles_keep_their_own_data_file() {
+        let dir = std::env::temp_dir().join(format!("expenso-profile-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "currency_symbol = \"€\"\n").unwrap();
+        let config = Config::load_profile(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.currency_symbol, "€");
+        assert_eq!(config.data_file, dir.join("expenso.json"));
+
+        assert!(Config::profile_path("../personal").is_err());
+        assert!(Config::profile_path("").is_err());
+        if let Ok(path) = Config::profile_path("business") {
+            assert!(path.ends_with("profiles/business/config.toml"));
+        }
+    }
+
+    #[test]
+    fn test_invalid_config_is_an_error() {
+        let path =
+            std::env::temp_dir().join(format!("expenso-bad-config-{}.toml", std::process::id()));
+        fs::write(&path, "currency_symbol = [").unwrap();
+        let result = Config::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(crate::ExpensoError::Config(_))));
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use crate::error::{ExpensoError, Result};
+use crate::{Split, Transaction, money};
+
+/// Converts transactions in other currencies into the base currency, the
+/// configured `currency`, using a table of what one unit of each is worth
+/// in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rates<'a> {
+    base: &'a str,
+    table: &'a BTreeMap<String, f64>,
+}
+
+impl<'a> Rates<'a> {
+    pub fn new(base: &'a str, table: &'a BTreeMap<String, f64>) -> Result<Self> {
+        if let Some((code, rate)) = table
+            .iter()
+            .find(|(_, rate)| !(rate.is_finite() && **rate > 0.0))
+        {
+            return Err(ExpensoError::InvalidInput(format!(
+                "rate for {} must be a positive number, not {}",
+                code, rate
+            )));
+        }
+        Ok(Rates { base, table })
+    }
+
+    pub fn base(&self) -> &str {
+        self.base
+    }
+
+    /// The currency `transaction` is in, the base currency when it doesn't say.
+    pub fn currency_of<'t>(&self, transaction: &'t Transaction) -> &'t str
+    where
+        'a: 't,
+    {
+        transaction.currency.as_deref().unwrap_or(self.base)
+    }
+
+    /// Whether any of `transactions` is in another currency than the base.
+    pub fn any_foreign(&self, transactions: &[&Transaction]) -> bool {
+        transactions
+            .iter()
+            .any(|t| !self.currency_of(t).eq_ignore_ascii_case(self.base))
+    }
+
+    /// What one unit of `currency` is worth in the base currency.
+    pub fn rate(&self, currency: &str) -> Result<f64> {
+        if currency.eq_ignore_ascii_case(self.base) {
+            return Ok(1.0);
+        }
+        self.table
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(currency))
+            .map(|(_, rate)| *rate)
+            .ok_or_else(|| {
+                ExpensoError::InvalidInput(format!(
+                    "no rate for {} in the config, add it to the [rates] table",
+                    currency
+                ))
+            })
+    }
+
+    /// Copies of `transactions` with their amounts and split shares in the
+    /// base currency.
+    pub fn convert(&self, transactions: &[&Transaction]) -> Result<Vec<Transaction>> {
+        transactions
+            .iter()
+            .map(|t| {
+                let rate = self.rate(self.currency_of(t))?;
+                Ok(Transaction {
+                    amount: money::convert(t.amount, rate),
+                    currency: None,
+                    splits: t
+                        .splits
+                        .iter()
+                        .map(|split| Split {
+                            share: money::convert(split.share, rate),
+                            ..split.clone()
+                        })
+                        .collect(),
+                    ..(*t).clone()
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_rates_convert_into_base_currency() {
+        let mut tracker = FinanceTracker::new();
+        for (amount, currency) in [(10.0, None), (20.0, Some("EUR")), (1000.0, Some("JPY"))] {
+            tracker.insert(NewTransaction {
+                description: String::from("Lunch"),
+                amount,
+                currency: currency.map(String::from),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let table = BTreeMap::from([(String::from("eur"), 1.08)]);
+        let rates = Rates::new("USD", &table).unwrap();
+
+        assert!(rates.any_foreign(&all));
+        assert!(!rates.any_foreign(&all[..1]));
+        assert_eq!(rates.rate("usd").unwrap(), 1.0);
+        let converted = rates.convert(&all[..2]).unwrap();
+        assert_eq!(converted[1].amount, 21.6);
+        assert_eq!(converted[1].currency, None);
+        assert!(rates.convert(&all).is_err());
+
+        let invalid = BTreeMap::from([(String::from("EUR"), 0.0)]);
+        assert!(Rates::new("USD", &invalid).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+
+use crate::alerts::{self, Alert};
+use crate::bills;
+use crate::config::Config;
+use crate::error::Result;
+use crate::money::format_money;
+use crate::storage::{self, Storage};
+use crate::{FinanceTracker, Transaction, dates};
+
+/// Minutes between runs unless `--interval` says otherwise.
+pub const DEFAULT_INTERVAL: u64 = 60;
+
+/// What to do besides adding recurring transactions and backing up.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options {
+    /// Show new alerts as desktop notifications.
+    pub notify: bool,
+}
+
+/// Runs every `interval` until stopped: adds the recurring transactions
+/// that came due, reports new alerts and makes the day's backup. The data
+/// is read afresh each time, so changes made elsewhere meanwhile are kept.
+pub fn run(storage: &Storage, config: &Config, interval: Duration, options: Options) -> Result<()> {
+    println!(
+        "expenso daemon running every {} minute(s) on {}, press Ctrl-C to stop",
+        interval.as_secs() / 60,
+        storage.location()
+    );
+    let mut state = State::default();
+    loop {
+        if let Err(err) = run_once(storage, config, options, &mut state) {
+            log(&format!("Error: {}", err));
+        }
+        thread::sleep(interval);
+    }
+}
+
+/// Alerts already reported today, so each one is only sent once a day.
+#[derive(Debug, Default)]
+pub struct State {
+    day: Option<NaiveDate>,
+    sent: HashSet<Alert>,
+}
+
+#[cfg_attr(not(feature = "notifications"), allow(unused_variables))]
+pub fn run_once(
+    storage: &Storage,
+    config: &Config,
+    options: Options,
+    state: &mut State,
+) -> Result<()> {
+    let today = Local::now().date_naive();
+    if state.day != Some(today) {
+        state.day = Some(today);
+        state.sent.clear();
+    }
+
+    let mut tracker = storage.load()?;
+    tracker.set_user(Some(String::from("expenso daemon")));
+    let added = add_recurring(&mut tracker, today)?;
+    if !added.is_empty() {
+        storage.save(&mut tracker)?;
+        for transaction in &added {
+            log(&format!(
+                "Added recurring {} {} on {}",
+                transaction.description,
+                format_money(transaction.amount),
+                transaction.date
+            ));
+            post(
+                config,
+                serde_json::json!({ "event": "recurring", "transaction": transaction }),
+            );
+        }
+    }
+
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    for alert in alerts::pending(&all, config, today) {
+        if !state.sent.insert(alert.clone()) {
+            continue;
+        }
+        log(&format!("{}: {}", alert.title, alert.body));
+        post(
+            config,
+            serde_json::json!({ "event": "alert", "title": alert.title, "body": alert.body }),
+        );
+        #[cfg(feature = "notifications")]
+        if options.notify
+            && let Err(err) = alerts::notify(&alert)
+        {
+            log(&format!("Error: {}", err));
+        }
+    }
+
+    if let Some(dir) = &config.backup_dir
+        && let Some(path) = backup(&tracker, dir, today, config.backup_keep)?
+    {
+        log(&format!("Backed up to {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Adds what [`bills::materialize`] finds due, returning the new transactions.
+pub fn add_recurring(tracker: &mut FinanceTracker, today: NaiveDate) -> Result<Vec<Transaction>> {
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    bills::materialize(&all, today)
+        .into_iter()
+        .map(|new| tracker.add(new))
+        .collect()
+}
+
+/// Writes `expenso-YYYY-MM-DD.json` to `dir` unless today's is already
+/// there, then deletes all but the newest `keep`. Returns the new file.
+pub fn backup(
+    tracker: &FinanceTracker,
+    dir: &Path,
+    today: NaiveDate,
+    keep: usize,
+) -> Result<Option<PathBuf>> {
+    let path = dir.join(format!("expenso-{}.json", today.format(dates::ISO_FORMAT)));
+    if path.exists() {
+        return Ok(None);
+    }
+    storage::save(tracker, &path)?;
+
+    let backups = backups(dir)?;
+    let stale = backups.len().saturating_sub(keep.max(1));
+    for old in &backups[..stale] {
+        fs::remove_file(old)?;
+    }
+    Ok(Some(path))
+}
+
+/// The backups in `dir`, oldest first.
+pub fn backups(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("expenso-") && name.ends_with(".json"))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
+
+fn log(message: &str) {
+    println!("{} {}", Local::now().format("%Y-%m-%d %H:%M"), message);
+}
+
+/// Posts `body` to each of the config's webhooks. A failed post is logged
+/// and doesn't stop the others.
+#[cfg(feature = "webhooks")]
+fn post(config: &Config, body: serde_json::Value) {
+    for url in &config.webhooks {
+        if let Err(err) = ureq::post(url).send_json(&body) {
+            log(&format!("Error: could not post to {}: {}", url, err));
+        }
+    }
+}
+
+#[cfg(not(feature = "webhooks"))]
+fn post(_config: &Config, _body: serde_json::Value) {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::NewTransaction;
+
+    #[test]
+    fn test_daemon_adds_recurring_and_rotates_backups() {
+        let dir = std::env::temp_dir().join(format!("expenso-backups-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Rent"),
+            amount: 1200.0,
+            date: String::from("2024-03-01"),
+            category: String::from("Housing"),
+            is_recurring: true,
+            ..Default::default()
+        });
+        let day = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+
+        let added = add_recurring(&mut tracker, day(2)).unwrap();
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].date, "2024-04-01");
+        assert!(add_recurring(&mut tracker, day(2)).unwrap().is_empty());
+
+        for today in 1..=4 {
+            backup(&tracker, &dir, day(today), 2).unwrap();
+        }
+        assert_eq!(backup(&tracker, &dir, day(4), 2).unwrap(), None);
+        let mut names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        let restored = storage::load(&dir.join("expenso-2024-04-04.json")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            names,
+            vec!["expenso-2024-04-03.json", "expenso-2024-04-04.json"]
+        );
+        assert_eq!(restored.get_transactions().len(), 2);
+    }
+}
@@ -0,0 +1,309 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use chrono::{Datelike, Days, Months, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+pub const ISO_FORMAT: &str = "%Y-%m-%d";
+
+static FISCAL_YEAR_START: AtomicU32 = AtomicU32::new(1);
+
+/// How long a budget's spending limit lasts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPeriod {
+    #[default]
+    Month,
+    Week,
+}
+
+/// Where weeks and months start, for budgets and digests. Months can
+/// start on a payday instead of the 1st.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calendar {
+    pub week_start: Weekday,
+    /// Day of the month, 1 to 28.
+    pub month_start: u32,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar {
+            week_start: Weekday::Mon,
+            month_start: 1,
+        }
+    }
+}
+
+impl Calendar {
+    /// The first and last day of the week `date` falls in.
+    pub fn week(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let week = date.week(self.week_start);
+        (week.first_day(), week.last_day())
+    }
+
+    /// The first and last day of the month `date` falls in.
+    pub fn month(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let start = self.month_start.clamp(1, 28);
+        let first = if date.day() >= start {
+            date.with_day(start)
+        } else {
+            date.checked_sub_months(Months::new(1))
+                .and_then(|date| date.with_day(start))
+        }
+        .unwrap_or(date);
+        let last = first
+            .checked_add_months(Months::new(1))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(first);
+        (first, last)
+    }
+
+    /// The first and last day of the budget `period` `date` falls in.
+    pub fn period(&self, period: BudgetPeriod, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match period {
+            BudgetPeriod::Month => self.month(date),
+            BudgetPeriod::Week => self.week(date),
+        }
+    }
+}
+
+/// `2024-03` for a calendar month, `2024-03-25 to 2024-04-24` for any
+/// other period.
+pub fn period_label(first: NaiveDate, last: NaiveDate) -> String {
+    let whole_month = first.day() == 1
+        && first.month() == last.month()
+        && last.succ_opt().is_some_and(|next| next.day() == 1);
+    if whole_month {
+        first.format("%Y-%m").to_string()
+    } else {
+        format!(
+            "{} to {}",
+            first.format(ISO_FORMAT),
+            last.format(ISO_FORMAT)
+        )
+    }
+}
+
+pub fn parse(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().collect();
+
+    match words.as_slice() {
+        ["today"] => Some(today),
+        ["yesterday"] => today.checked_sub_days(Days::new(1)),
+        ["tomorrow"] => today.checked_add_days(Days::new(1)),
+        ["last", weekday] => previous_weekday(today, weekday.parse().ok()?, false),
+        [weekday] if weekday.parse::<Weekday>().is_ok() => {
+            previous_weekday(today, weekday.parse().ok()?, true)
+        }
+        [count, unit, "ago"] => ago(today, count.parse().ok()?, unit),
+        [date] => parse_absolute(date),
+        _ => None,
+    }
+}
+
+/// The current time in UTC, as stored in a transaction's `created_at` and
+/// `updated_at`.
+pub fn utc_now() -> String {
+    Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+pub fn normalize(input: &str, today: NaiveDate) -> Option<String> {
+    parse(input, today).map(|date| date.format(ISO_FORMAT).to_string())
+}
+
+/// Like [`parse`], but a date written in `format` (the configured
+/// `date_format`) is tried first.
+pub fn parse_in(input: &str, format: &str, today: NaiveDate) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(input.trim(), format)
+        .ok()
+        .or_else(|| parse(input, today))
+}
+
+/// A date typed by the user as YYYY-MM-DD, the only form kept in storage.
+pub fn normalize_in(input: &str, format: &str, today: NaiveDate) -> Option<String> {
+    parse_in(input, format, today).map(|date| date.format(ISO_FORMAT).to_string())
+}
+
+/// `format` spelled out for prompts, e.g. `DD/MM/YYYY` for `%d/%m/%Y`.
+pub fn format_hint(format: &str) -> String {
+    format
+        .replace("%Y", "YYYY")
+        .replace("%m", "MM")
+        .replace("%d", "DD")
+}
+
+/// Whether `date` is a real calendar date in YYYY-MM-DD form.
+pub fn is_iso_date(date: &str) -> bool {
+    NaiveDate::parse_from_str(date, ISO_FORMAT).is_ok()
+}
+
+/// Sets the month (1 to 12) fiscal years start in from here on; called
+/// again when switching profiles.
+pub fn set_fiscal_year_start(month: u32) {
+    FISCAL_YEAR_START.store(month, Ordering::Relaxed);
+}
+
+pub fn fiscal_year_start() -> u32 {
+    FISCAL_YEAR_START.load(Ordering::Relaxed)
+}
+
+/// The fiscal year `date` falls in, for years starting in month `start`,
+/// named after the calendar year it starts in.
+pub fn fiscal_year(date: NaiveDate, start: u32) -> i32 {
+    if date.month() >= start {
+        date.year()
+    } else {
+        date.year() - 1
+    }
+}
+
+/// The first and last day, `YYYY-MM-DD`, of the fiscal year starting in
+/// month `start` of `year`.
+pub fn fiscal_year_range(year: i32, start: u32) -> Option<(String, String)> {
+    let first = NaiveDate::from_ymd_opt(year, start, 1)?;
+    let last = first.checked_add_months(Months::new(12))?.pred_opt()?;
+    Some((
+        first.format(ISO_FORMAT).to_string(),
+        last.format(ISO_FORMAT).to_string(),
+    ))
+}
+
+/// `2024` for a calendar year, `2024/25` for one starting later than January.
+pub fn fiscal_year_label(year: i32, start: u32) -> String {
+    if start <= 1 {
+        year.to_string()
+    } else {
+        format!("{}/{:02}", year, (year + 1).rem_euclid(100))
+    }
+}
+
+fn parse_absolute(input: &str) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(input, ISO_FORMAT) {
+        return Some(date);
+    }
+    NaiveDate::parse_from_str(&format!("{}-01", input), ISO_FORMAT).ok()
+}
+
+fn previous_weekday(today: NaiveDate, weekday: Weekday, include_today: bool) -> Option<NaiveDate> {
+    let mut days_back =
+        (7 + today.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    if days_back == 0 && !include_today {
+        days_back = 7;
+    }
+    today.checked_sub_days(Days::new(u64::from(days_back)))
+}
+
+fn ago(today: NaiveDate, count: u32, unit: &str) -> Option<NaiveDate> {
+    match unit.trim_end_matches('s') {
+        "day" => today.checked_sub_days(Days::new(u64::from(count))),
+        "week" => today.checked_sub_days(Days::new(u64::from(count) * 7)),
+        "month" => today.checked_sub_months(Months::new(count)),
+        "year" => today.checked_sub_months(Months::new(count.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    // A Wednesday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 6).unwrap()
+    }
+
+    fn parsed(input: &str) -> Option<String> {
+        normalize(input, today())
+    }
+
+    #[test]
+    fn test_relative_words() {
+        assert_eq!(parsed("Today").as_deref(), Some("2024-03-06"));
+        assert_eq!(parsed("yesterday").as_deref(), Some("2024-03-05"));
+        assert_eq!(parsed("tomorrow").as_deref(), Some("2024-03-07"));
+    }
+
+    #[test]
+    fn test_weekdays() {
+        assert_eq!(parsed("last friday").as_deref(), Some("2024-03-01"));
+        assert_eq!(parsed("last wednesday").as_deref(), Some("2024-02-28"));
+        assert_eq!(parsed("wednesday").as_deref(), Some("2024-03-06"));
+        assert_eq!(parsed("mon").as_deref(), Some("2024-03-04"));
+    }
+
+    #[test]
+    fn test_ago() {
+        assert_eq!(parsed("3 days ago").as_deref(), Some("2024-03-03"));
+        assert_eq!(parsed("1 day ago").as_deref(), Some("2024-03-05"));
+        assert_eq!(parsed("2 weeks ago").as_deref(), Some("2024-02-21"));
+        assert_eq!(parsed("1 month ago").as_deref(), Some("2024-02-06"));
+        assert_eq!(parsed("1 year ago").as_deref(), Some("2023-03-06"));
+        assert_eq!(parsed("3 fortnights ago"), None);
+    }
+
+    #[test]
+    fn test_absolute_dates() {
+        assert_eq!(parsed("2024-06-15").as_deref(), Some("2024-06-15"));
+        assert_eq!(parsed("2024-06").as_deref(), Some("2024-06-01"));
+        assert_eq!(parsed("2024-02-31"), None);
+        assert_eq!(parsed("someday"), None);
+    }
+
+    #[test]
+    fn test_configured_format_and_impossible_dates() {
+        let in_format = |input| normalize_in(input, "%d/%m/%Y", today());
+        assert_eq!(in_format("09/03/2024").as_deref(), Some("2024-03-09"));
+        assert_eq!(in_format("2024-03-09").as_deref(), Some("2024-03-09"));
+        assert_eq!(in_format("yesterday").as_deref(), Some("2024-03-05"));
+        assert_eq!(in_format("31/02/2024"), None);
+        assert_eq!(parsed("2024-02-31"), None);
+        assert!(!is_iso_date("2024-02-30"));
+        assert_eq!(format_hint("%d/%m/%Y"), "DD/MM/YYYY");
+    }
+
+    #[test]
+    fn test_fiscal_years() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        assert_eq!(fiscal_year(date(3, 31), 4), 2023);
+        assert_eq!(fiscal_year(date(4, 1), 4), 2024);
+        assert_eq!(fiscal_year(date(3, 31), 1), 2024);
+        assert_eq!(
+            fiscal_year_range(2024, 4),
+            Some((String::from("2024-04-01"), String::from("2025-03-31")))
+        );
+        assert_eq!(
+            fiscal_year_range(2024, 1),
+            Some((String::from("2024-01-01"), String::from("2024-12-31")))
+        );
+        assert_eq!(fiscal_year_range(2024, 13), None);
+        assert_eq!(fiscal_year_label(2024, 1), "2024");
+        assert_eq!(fiscal_year_label(2099, 7), "2099/00");
+    }
+
+    #[test]
+    fn test_calendar_periods() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let calendar = Calendar::default();
+        assert_eq!(calendar.week(date(3, 6)), (date(3, 4), date(3, 10)));
+        assert_eq!(calendar.month(date(2, 10)), (date(2, 1), date(2, 29)));
+        assert_eq!(period_label(date(2, 1), date(2, 29)), "2024-02");
+
+        let payday = Calendar {
+            week_start: Weekday::Sun,
+            month_start: 25,
+        };
+        assert_eq!(payday.week(date(3, 6)), (date(3, 3), date(3, 9)));
+        assert_eq!(payday.week(date(3, 3)), (date(3, 3), date(3, 9)));
+        assert_eq!(payday.month(date(3, 24)), (date(2, 25), date(3, 24)));
+        assert_eq!(
+            payday.period(BudgetPeriod::Month, date(3, 25)),
+            (date(3, 25), date(4, 24))
+        );
+        assert_eq!(
+            period_label(date(3, 25), date(4, 24)),
+            "2024-03-25 to 2024-04-24"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::{FinanceTracker, Transaction, money};
+
+/// A transaction in both snapshots that changed, with the JSON names of the
+/// fields that did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Modified<'a> {
+    pub before: &'a Transaction,
+    pub after: &'a Transaction,
+    pub fields: Vec<String>,
+}
+
+/// How one snapshot of the data differs from another, with transactions
+/// matched by id. Being edited again without changing, which only moves
+/// `updated_at`, doesn't count as a change.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diff<'a> {
+    pub added: Vec<&'a Transaction>,
+    pub removed: Vec<&'a Transaction>,
+    pub modified: Vec<Modified<'a>>,
+    /// What each category added to the balance before and after, for the
+    /// categories where that changed.
+    pub categories: Vec<(String, f64, f64)>,
+}
+
+impl<'a> Diff<'a> {
+    pub fn compute(before: &'a FinanceTracker, after: &'a FinanceTracker) -> Self {
+        let old: HashMap<u32, &Transaction> = before
+            .get_transactions()
+            .iter()
+            .map(|t| (t.id, t))
+            .collect();
+        let new: HashMap<u32, &Transaction> =
+            after.get_transactions().iter().map(|t| (t.id, t)).collect();
+
+        let added = after
+            .get_transactions()
+            .iter()
+            .filter(|t| !old.contains_key(&t.id))
+            .collect();
+        let removed = before
+            .get_transactions()
+            .iter()
+            .filter(|t| !new.contains_key(&t.id))
+            .collect();
+        let modified = before
+            .get_transactions()
+            .iter()
+            .filter_map(|before| {
+                let after = new.get(&before.id)?;
+                let fields = changed_fields(before, after);
+                (!fields.is_empty()).then_some(Modified {
+                    before,
+                    after,
+                    fields,
+                })
+            })
+            .collect();
+
+        let (old_totals, new_totals) = (by_category(before), by_category(after));
+        let names: BTreeSet<&String> = old_totals.keys().chain(new_totals.keys()).collect();
+        let categories = names
+            .into_iter()
+            .map(|name| {
+                let total = |totals: &BTreeMap<String, f64>| totals.get(name).copied();
+                (
+                    name.clone(),
+                    total(&old_totals).unwrap_or_default(),
+                    total(&new_totals).unwrap_or_default(),
+                )
+            })
+            .filter(|(_, old, new)| old != new)
+            .collect();
+
+        Diff {
+            added,
+            removed,
+            modified,
+            categories,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+fn changed_fields(before: &Transaction, after: &Transaction) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| *name != "updated_at" && old.get(*name) != new.get(*name))
+        .cloned()
+        .collect()
+}
+
+fn by_category(tracker: &FinanceTracker) -> BTreeMap<String, f64> {
+    let mut totals: BTreeMap<String, f64> = BTreeMap::new();
+    for t in tracker.get_transactions() {
+        let total = totals.entry(t.category.to_string()).or_default();
+        *total = money::sum([*total, t.signed_amount()]);
+    }
+    totals
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::NewTransaction;
+
+    #[test]
+    fn test_diff_between_snapshots() {
+        let mut before = FinanceTracker::new();
+        for (description, amount, category) in [
+            ("Coffee", 4.5, "Food"),
+            ("Rent", 1200.0, "Housing"),
+            ("Books", 30.0, "Fun"),
+        ] {
+            before.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from("2024-03-01"),
+                category: String::from(category),
+                ..Default::default()
+            });
+        }
+        let mut after = before.clone();
+        after.delete_transaction(3);
+        let mut rent = after.get_transaction(2).unwrap().clone();
+        rent.amount = 1250.0;
+        rent.notes = Some(String::from("New lease"));
+        after.update_transaction(rent);
+        let coffee = after.get_transaction(1).unwrap().clone();
+        after.update_transaction(coffee);
+        after.insert(NewTransaction {
+            description: String::from("Lunch"),
+            amount: 12.0,
+            date: String::from("2024-03-02"),
+            category: String::from("Food"),
+            ..Default::default()
+        });
+
+        let diff = Diff::compute(&before, &after);
+        assert_eq!(diff.added.iter().map(|t| t.id).collect::<Vec<_>>(), vec![4]);
+        assert_eq!(
+            diff.removed.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].fields, vec!["amount", "notes"]);
+        assert_eq!(
+            diff.categories,
+            vec![
+                (String::from("Food"), -4.5, -16.5),
+                (String::from("Fun"), -30.0, 0.0),
+                (String::from("Housing"), -1200.0, -1250.0),
+            ]
+        );
+        assert!(Diff::compute(&before, &before).is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+use std::io;
+
+use chrono::{Duration, NaiveDate};
+use lettre::message::{Mailbox, header::ContentType};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+
+use crate::config::Config;
+use crate::dates::{self, Calendar};
+use crate::money::MoneyFormat;
+use crate::report::{self, Summary};
+use crate::table::{Align, Table};
+use crate::{FinanceTracker, Symbol, Transaction, TransactionType};
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Period {
+    /// The last complete week, starting on `week_start` in the config
+    Week,
+    /// The last complete month, starting on `month_start` in the config
+    Month,
+}
+
+impl Period {
+    pub fn range(self, today: NaiveDate, calendar: Calendar) -> (NaiveDate, NaiveDate) {
+        match self {
+            Period::Week => calendar.week(calendar.week(today).0 - Duration::days(1)),
+            Period::Month => calendar.month(calendar.month(today).0 - Duration::days(1)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Email {
+    pub subject: String,
+    pub body: String,
+}
+
+pub fn compose(
+    tracker: &FinanceTracker,
+    config: &Config,
+    period: Period,
+    today: NaiveDate,
+) -> Email {
+    let money = MoneyFormat::from_config(config);
+    let (from, to) = period.range(today, config.calendar());
+    let from = from.format(dates::ISO_FORMAT).to_string();
+    let to = to.format(dates::ISO_FORMAT).to_string();
+
+    let transactions: Vec<&Transaction> = tracker
+        .get_transactions()
+        .iter()
+        .filter(|t| t.date.as_str() >= from.as_str() && t.date.as_str() <= to.as_str())
+        .collect();
+    let summary = Summary::of(&transactions);
+
+    let title = match period {
+        Period::Week => "Weekly digest",
+        Period::Month => "Monthly report",
+    };
+    let mut body = format!(
+        "{}: {} to {}\n\nIncome:  {}\nExpense: {}\nBalance: {}\n",
+        title,
+        from,
+        to,
+        money.format(summary.total_income),
+        money.format(summary.total_expense),
+        money.format(summary.net_balance)
+    );
+
+    let expenses: Vec<&Transaction> = transactions
+        .iter()
+        .copied()
+        .filter(|t| t.transaction_type == TransactionType::Expense)
+        .collect();
+    let mut categories: Vec<(Symbol, f64)> =
+        report::category_totals(&expenses).into_iter().collect();
+    categories.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    if !categories.is_empty() {
+        let mut table = Table::new()
+            .column("Category", Align::Left)
+            .column("Spent", Align::Right);
+        for (category, total) in categories {
+            table.add_row(vec![category.as_str().into(), money.format(total).into()]);
+        }
+        body.push_str(&format!("\nSpending by category\n{}\n", table.render()));
+    }
+
+    let mut largest = expenses;
+    largest.sort_by(|a, b| b.amount.total_cmp(&a.amount));
+    if !largest.is_empty() {
+        let mut table = Table::new()
+            .column("Date", Align::Left)
+            .truncated_column("Description", Align::Left, 30)
+            .column("Amount", Align::Right);
+        for t in largest.into_iter().take(5) {
+            table.add_row(vec![
+                t.date.as_str().into(),
+                t.description.as_str().into(),
+                money.format(t.amount).into(),
+            ]);
+        }
+        body.push_str(&format!("\nLargest expenses\n{}\n", table.render()));
+    }
+
+    Email {
+        subject: format!("expenso {}: {} to {}", title.to_lowercase(), from, to),
+        body,
+    }
+}
+
+pub fn send(email: Email, config: &Config, recipients: &[String]) -> io::Result<()> {
+    let invalid = |err: lettre::address::AddressError| {
+        io::Error::new(io::ErrorKind::InvalidInput, err.to_string())
+    };
+    let host = config.smtp_host.as_deref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Set smtp_host in the config file",
+        )
+    })?;
+    let from = config.email_from.as_deref().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Set email_from in the config file",
+        )
+    })?;
+    if recipients.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "No recipients: pass --to or set email_to in the config file",
+        ));
+    }
+
+    let mut builder = Message::builder()
+        .from(from.parse::<Mailbox>().map_err(invalid)?)
+        .subject(email.subject)
+        .header(ContentType::TEXT_PLAIN);
+    for recipient in recipients {
+        builder = builder.to(recipient.parse::<Mailbox>().map_err(invalid)?);
+    }
+    let message = builder.body(email.body).map_err(io::Error::other)?;
+
+    let mut transport = SmtpTransport::starttls_relay(host).map_err(io::Error::other)?;
+    if let Some(port) = config.smtp_port {
+        transport = transport.port(port);
+    }
+    if let Some(username) = &config.smtp_username {
+        let password = std::env::var("EXPENSO_SMTP_PASSWORD")
+            .ok()
+            .or_else(|| config.smtp_password.clone())
+            .unwrap_or_default();
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+
+    transport
+        .build()
+        .send(&message)
+        .map(|_| ())
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::NewTransaction;
+
+    fn date(input: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(input, dates::ISO_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_period_ranges() {
+        let today = date("2024-03-11");
+        let calendar = Calendar::default();
+        assert_eq!(
+            Period::Week.range(today, calendar),
+            (date("2024-03-04"), date("2024-03-10"))
+        );
+        assert_eq!(
+            Period::Month.range(today, calendar),
+            (date("2024-02-01"), date("2024-02-29"))
+        );
+        assert_eq!(
+            Period::Month.range(date("2024-01-01"), calendar),
+            (date("2023-12-01"), date("2023-12-31"))
+        );
+
+        let payday = Calendar {
+            week_start: chrono::Weekday::Sun,
+            month_start: 25,
+        };
+        assert_eq!(
+            Period::Week.range(today, payday),
+            (date("2024-03-03"), date("2024-03-09"))
+        );
+        assert_eq!(
+            Period::Month.range(today, payday),
+            (date("2024-01-25"), date("2024-02-24"))
+        );
+    }
+
+    #[test]
+    fn test_compose_weekly_digest() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, category, transaction_type) in [
+            (
+                "Groceries",
+                80.0,
+                "2024-03-05",
+                "Food",
+                TransactionType::Expense,
+            ),
+            (
+                "Dinner",
+                40.0,
+                "2024-03-08",
+                "Food",
+                TransactionType::Expense,
+            ),
+            (
+                "Train",
+                12.0,
+                "2024-03-06",
+                "Transport",
+                TransactionType::Expense,
+            ),
+            (
+                "Salary",
+                500.0,
+                "2024-03-04",
+                "Work",
+                TransactionType::Income,
+            ),
+            ("Old", 999.0, "2024-03-01", "Food", TransactionType::Expense),
+            ("Today", 5.0, "2024-03-11", "Food", TransactionType::Expense),
+        ] {
+            tracker.insert(NewTransaction {
+                description: description.to_string(),
+                amount,
+                date: date.to_string(),
+                category: category.to_string(),
+                transaction_type,
+                ..Default::default()
+            });
+        }
+
+        let email = compose(
+            &tracker,
+            &Config::default(),
+            Period::Week,
+            date("2024-03-11"),
+        );
+        assert_eq!(
+            email.subject,
+            "expenso weekly digest: 2024-03-04 to 2024-03-10"
+        );
+
+        let expected = "\
+Weekly digest: 2024-03-04 to 2024-03-10
+
+Income:  $500.00
+Expense: $132.00
+Balance: $368.00
+
+Spending by category
+Category     Spent
+---------  -------
+Food       $120.00
+Transport   $12.00
+
+Largest expenses
+Date        Description  Amount
+----------  -----------  ------
+2024-03-05  Groceries    $80.00
+2024-03-08  Dinner       $40.00
+2024-03-06  Train        $12.00
+";
+        assert_eq!(email.body, expected);
+    }
+}
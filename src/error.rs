@@ -0,0 +1,43 @@
+use std::io;
+
+use thiserror::Error;
+
+pub type Result<T, E = ExpensoError> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum ExpensoError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("invalid data file: {0}")]
+    DataFile(#[from] serde_json::Error),
+    #[error(
+        "data file version {found} is newer than this expenso supports ({supported}), please upgrade"
+    )]
+    UnsupportedVersion { found: u32, supported: u32 },
+    #[error("invalid config file: {0}")]
+    Config(#[from] toml::de::Error),
+    #[error("could not write config file: {0}")]
+    ConfigWrite(#[from] toml::ser::Error),
+    #[error("invalid transaction type '{0}', expected income, expense or settlement")]
+    InvalidTransactionType(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    #[error("no transaction with id {0}")]
+    NotFound(u32),
+    #[error(
+        "transaction {0} is reconciled, unlock it before changing its amount, date, type or account or deleting it"
+    )]
+    Reconciled(u32),
+    #[error("{0} is closed, reopen it before changing its transactions")]
+    Closed(String),
+    #[error("{0}")]
+    Import(String),
+    #[error("{0}")]
+    Remote(String),
+    #[error("plugin {0}")]
+    Plugin(String),
+    #[error("could not read the receipt: {0}")]
+    Ocr(String),
+    #[error("could not draw the chart: {0}")]
+    Chart(String),
+}
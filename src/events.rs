@@ -0,0 +1,49 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::Transaction;
+
+/// Something that changed in a [`FinanceTracker`](crate::FinanceTracker).
+///
+/// Loading a data file does not fire events; only mutations made afterwards do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    TransactionAdded(Transaction),
+    TransactionUpdated {
+        before: Box<Transaction>,
+        after: Box<Transaction>,
+    },
+    TransactionDeleted(Transaction),
+    /// An expense took a category's spending over its budget for `period`,
+    /// `YYYY-MM` for a calendar month or `YYYY-MM-DD to YYYY-MM-DD` otherwise.
+    BudgetExceeded {
+        category: String,
+        period: String,
+        limit: f64,
+        spent: f64,
+    },
+}
+
+type Hook = Arc<dyn Fn(&Event) + Send + Sync>;
+
+/// The callbacks registered on a tracker. Clones share the same callbacks.
+#[derive(Clone, Default)]
+pub struct Hooks(Vec<Hook>);
+
+impl Hooks {
+    pub fn add(&mut self, hook: impl Fn(&Event) + Send + Sync + 'static) {
+        self.0.push(Arc::new(hook));
+    }
+
+    pub fn emit(&self, event: Event) {
+        for hook in &self.0 {
+            hook(&event);
+        }
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hooks({})", self.0.len())
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::io;
+
+use serde::Serialize;
+
+use crate::money::{self, format_money};
+use crate::output::csv_cell;
+use crate::table::{Align, Table};
+use crate::{Transaction, TransactionType};
+
+#[cfg(feature = "pdf")]
+mod pdf;
+
+/// The tag that marks an expense as one to claim back, as the Expensify
+/// import sets it.
+pub const REIMBURSABLE: &str = "reimbursable";
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExpenseReportFormat {
+    Plain,
+    Csv,
+    /// A printable document, with the receipts listed at the end
+    #[cfg(feature = "pdf")]
+    Pdf,
+}
+
+impl ExpenseReportFormat {
+    pub fn is_binary(self) -> bool {
+        #[cfg(feature = "pdf")]
+        if self == ExpenseReportFormat::Pdf {
+            return true;
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpenseItem {
+    pub id: u32,
+    pub date: String,
+    pub description: String,
+    pub payee: Option<String>,
+    pub category: String,
+    pub amount: f64,
+    /// Link or path to the receipt, see [`Transaction::receipt`].
+    pub receipt: Option<String>,
+}
+
+/// Expenses to claim back from an employer: every expense with the tag in
+/// the period, oldest first, with totals per category.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpenseReport {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub items: Vec<ExpenseItem>,
+    pub categories: BTreeMap<String, f64>,
+    pub total: f64,
+}
+
+impl ExpenseReport {
+    pub fn collect(
+        transactions: &[&Transaction],
+        tag: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> Self {
+        let mut claimed: Vec<&Transaction> = transactions
+            .iter()
+            .copied()
+            .filter(|t| t.transaction_type == TransactionType::Expense)
+            .filter(|t| t.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            .filter(|t| from.is_none_or(|from| t.date.as_str() >= from))
+            .filter(|t| to.is_none_or(|to| t.date.as_str() <= to))
+            .collect();
+        claimed.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+
+        let mut categories: BTreeMap<String, f64> = BTreeMap::new();
+        for t in &claimed {
+            let total = categories.entry(t.category.to_string()).or_default();
+            *total = money::sum([*total, t.amount]);
+        }
+        ExpenseReport {
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+            total: money::sum(claimed.iter().map(|t| t.amount)),
+            items: claimed
+                .into_iter()
+                .map(|t| ExpenseItem {
+                    id: t.id,
+                    date: t.date.clone(),
+                    description: t.description.clone(),
+                    payee: t.payee.map(String::from),
+                    category: t.category.to_string(),
+                    amount: t.amount,
+                    receipt: t.receipt.clone(),
+                })
+                .collect(),
+            categories,
+        }
+    }
+
+    pub fn missing_receipts(&self) -> usize {
+        self.items.iter().filter(|i| i.receipt.is_none()).count()
+    }
+
+    pub fn period(&self) -> String {
+        match (&self.from, &self.to) {
+            (Some(from), Some(to)) => format!("{} to {}", from, to),
+            (Some(from), None) => format!("from {}", from),
+            (None, Some(to)) => format!("up to {}", to),
+            (None, None) => String::from("all dates"),
+        }
+    }
+
+    pub fn render(
+        &self,
+        format: ExpenseReportFormat,
+        prepared_by: Option<&str>,
+    ) -> io::Result<Vec<u8>> {
+        Ok(match format {
+            ExpenseReportFormat::Plain => self.text(prepared_by).into_bytes(),
+            ExpenseReportFormat::Csv => self.csv()?.into_bytes(),
+            #[cfg(feature = "pdf")]
+            ExpenseReportFormat::Pdf => pdf::document(self, prepared_by),
+        })
+    }
+
+    fn text(&self, prepared_by: Option<&str>) -> String {
+        let mut text = vec![String::from("=== Expense Report ===")];
+        text.push(format!("Period: {}", self.period()));
+        text.extend(prepared_by.map(|name| format!("Prepared by: {}", name)));
+        text.push(String::new());
+
+        let mut table = Table::new()
+            .column("Date", Align::Left)
+            .column("Description", Align::Left)
+            .column("Category", Align::Left)
+            .column("Amount", Align::Right)
+            .column("Receipt", Align::Left);
+        for item in &self.items {
+            table.add_row(vec![
+                item.date.as_str().into(),
+                item.description.as_str().into(),
+                item.category.as_str().into(),
+                format_money(item.amount).into(),
+                item.receipt.as_deref().unwrap_or("missing").into(),
+            ]);
+        }
+        text.push(table.render());
+        text.push(String::new());
+        for (category, total) in &self.categories {
+            text.push(format!("{}: {}", category, format_money(*total)));
+        }
+        text.push(format!("Total: {}", format_money(self.total)));
+        if self.missing_receipts() > 0 {
+            text.push(format!("Receipts missing: {}", self.missing_receipts()));
+        }
+        text.push(String::new());
+        text.join("\n")
+    }
+
+    /// The expenses, then the totals per category below a blank line.
+    fn csv(&self) -> io::Result<String> {
+        let mut writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(Vec::new());
+        writer.write_record([
+            "date",
+            "description",
+            "payee",
+            "category",
+            "amount",
+            "receipt",
+        ])?;
+        for item in &self.items {
+            writer.write_record([
+                item.date.clone(),
+                csv_cell(&item.description).into_owned(),
+                csv_cell(item.payee.as_deref().unwrap_or_default()).into_owned(),
+                csv_cell(&item.category).into_owned(),
+                item.amount.to_string(),
+                csv_cell(item.receipt.as_deref().unwrap_or_default()).into_owned(),
+            ])?;
+        }
+        writer.write_record([""])?;
+        writer.write_record(["category", "total"])?;
+        for (category, total) in &self.categories {
+            writer.write_record([csv_cell(category).into_owned(), total.to_string()])?;
+        }
+        writer.write_record([String::from("Total"), self.total.to_string()])?;
+        let bytes = writer.into_inner().map_err(|err| err.into_error())?;
+        String::from_utf8(bytes).map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_expense_report_collects_reimbursable_expenses() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, category, tags, receipt) in [
+            (
+                "Hotel",
+                312.4,
+                "2024-04-02",
+                "Travel",
+                "reimbursable",
+                Some("hotel.jpg"),
+            ),
+            ("Taxi", 23.5, "2024-04-01", "Travel", "Reimbursable", None),
+            (
+                "Client lunch",
+                64.0,
+                "2024-04-03",
+                "Meals",
+                "reimbursable",
+                None,
+            ),
+            ("Groceries", 80.0, "2024-04-03", "Food", "", None),
+            (
+                "Conference",
+                400.0,
+                "2024-05-10",
+                "Travel",
+                "reimbursable",
+                None,
+            ),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from(date),
+                category: String::from(category),
+                tags: tags.split_terminator(',').map(String::from).collect(),
+                receipt: receipt.map(String::from),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let report =
+            ExpenseReport::collect(&all, REIMBURSABLE, Some("2024-04-01"), Some("2024-04-30"));
+        let ids: Vec<u32> = report.items.iter().map(|i| i.id).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        assert_eq!(report.total, 399.9);
+        assert_eq!(report.categories["Travel"], 335.9);
+        assert_eq!(report.missing_receipts(), 2);
+        assert_eq!(
+            report.csv().unwrap(),
+            "date,description,payee,category,amount,receipt
+2024-04-01,Taxi,,Travel,23.5,
+2024-04-02,Hotel,,Travel,312.4,hotel.jpg
+2024-04-03,Client lunch,,Meals,64,
+\"\"
+category,total
+Meals,64
+Travel,335.9
+Total,399.9
+"
+        );
+    }
+}
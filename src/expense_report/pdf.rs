@@ -0,0 +1,205 @@
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+
+use super::ExpenseReport;
+use crate::money::format_money;
+
+/// A4 in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const LINE: f32 = 15.0;
+const SIZE: f32 = 10.0;
+
+const REGULAR: Name = Name(b"F1");
+const BOLD: Name = Name(b"F2");
+
+/// Left edges of the date, description, category and receipt columns; the
+/// amount is right-aligned to `AMOUNT_RIGHT`.
+const DATE_X: f32 = MARGIN;
+const DESCRIPTION_X: f32 = 115.0;
+const CATEGORY_X: f32 = 315.0;
+const AMOUNT_RIGHT: f32 = 480.0;
+const RECEIPT_X: f32 = 495.0;
+
+/// Text laid out top to bottom, starting a new page when one fills up.
+struct Layout {
+    pages: Vec<Content>,
+    y: f32,
+}
+
+impl Layout {
+    fn new() -> Self {
+        Layout {
+            pages: vec![Content::new()],
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn next_line(&mut self) {
+        self.y -= LINE;
+        if self.y < MARGIN {
+            self.pages.push(Content::new());
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text(&mut self, x: f32, font: Name, size: f32, text: &str) {
+        let y = self.y;
+        let page = self.pages.last_mut().expect("layout starts with a page");
+        page.begin_text();
+        page.set_font(font, size);
+        page.next_line(x, y);
+        page.show(Str(&win_ansi(text)));
+        page.end_text();
+    }
+
+    fn right(&mut self, right: f32, font: Name, size: f32, text: &str) {
+        self.text(right - width(text, size), font, size, text);
+    }
+}
+
+pub fn document(report: &ExpenseReport, prepared_by: Option<&str>) -> Vec<u8> {
+    let mut layout = Layout::new();
+    layout.text(MARGIN, BOLD, 16.0, "Expense Report");
+    layout.next_line();
+    layout.next_line();
+    layout.text(
+        MARGIN,
+        REGULAR,
+        SIZE,
+        &format!("Period: {}", report.period()),
+    );
+    if let Some(name) = prepared_by {
+        layout.next_line();
+        layout.text(MARGIN, REGULAR, SIZE, &format!("Prepared by: {}", name));
+    }
+    layout.next_line();
+    layout.next_line();
+
+    layout.text(DATE_X, BOLD, SIZE, "Date");
+    layout.text(DESCRIPTION_X, BOLD, SIZE, "Description");
+    layout.text(CATEGORY_X, BOLD, SIZE, "Category");
+    layout.right(AMOUNT_RIGHT, BOLD, SIZE, "Amount");
+    layout.text(RECEIPT_X, BOLD, SIZE, "Receipt");
+    layout.next_line();
+
+    // Receipts are numbered here and listed in full at the end.
+    let mut receipts = Vec::new();
+    for item in &report.items {
+        let reference = match &item.receipt {
+            Some(receipt) => {
+                receipts.push(receipt.as_str());
+                format!("R{}", receipts.len())
+            }
+            None => String::from("missing"),
+        };
+        layout.text(DATE_X, REGULAR, SIZE, &item.date);
+        layout.text(
+            DESCRIPTION_X,
+            REGULAR,
+            SIZE,
+            &truncate(&item.description, 36),
+        );
+        layout.text(CATEGORY_X, REGULAR, SIZE, &truncate(&item.category, 24));
+        layout.right(AMOUNT_RIGHT, REGULAR, SIZE, &format_money(item.amount));
+        layout.text(RECEIPT_X, REGULAR, SIZE, &reference);
+        layout.next_line();
+    }
+
+    layout.next_line();
+    for (category, total) in &report.categories {
+        layout.text(CATEGORY_X, REGULAR, SIZE, &truncate(category, 24));
+        layout.right(AMOUNT_RIGHT, REGULAR, SIZE, &format_money(*total));
+        layout.next_line();
+    }
+    layout.text(CATEGORY_X, BOLD, SIZE, "Total");
+    layout.right(AMOUNT_RIGHT, BOLD, SIZE, &format_money(report.total));
+    layout.next_line();
+
+    if !receipts.is_empty() {
+        layout.next_line();
+        layout.text(MARGIN, BOLD, SIZE, "Receipts");
+        layout.next_line();
+        for (index, receipt) in receipts.iter().enumerate() {
+            layout.text(MARGIN, REGULAR, SIZE, &format!("R{}", index + 1));
+            layout.text(DESCRIPTION_X - 30.0, REGULAR, SIZE, receipt);
+            layout.next_line();
+        }
+    }
+
+    write(layout.pages)
+}
+
+fn write(pages: Vec<Content>) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let regular_id = Ref::new(3);
+    let bold_id = Ref::new(4);
+    // Each page and its content stream.
+    let ids: Vec<(Ref, Ref)> = (0..pages.len() as i32)
+        .map(|index| (Ref::new(5 + 2 * index), Ref::new(6 + 2 * index)))
+        .collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id)
+        .kids(ids.iter().map(|(page, _)| *page))
+        .count(ids.len() as i32);
+    pdf.type1_font(regular_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.type1_font(bold_id)
+        .base_font(Name(b"Helvetica-Bold"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+
+    for ((page_id, content_id), content) in ids.into_iter().zip(pages) {
+        let mut page = pdf.page(page_id);
+        page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT))
+            .parent(page_tree_id)
+            .contents(content_id);
+        page.resources()
+            .fonts()
+            .pair(REGULAR, regular_id)
+            .pair(BOLD, bold_id);
+        page.finish();
+        pdf.stream(content_id, &content.finish());
+    }
+    pdf.finish()
+}
+
+fn truncate(text: &str, chars: usize) -> String {
+    if text.chars().count() <= chars {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(chars - 1).collect();
+    short.push('…');
+    short
+}
+
+/// Roughly how wide `text` is in Helvetica, enough to right-align amounts:
+/// its digits are all the same width.
+fn width(text: &str, size: f32) -> f32 {
+    let units: u32 = text
+        .chars()
+        .map(|c| match c {
+            '.' | ',' | ' ' => 278,
+            '-' => 333,
+            _ => 556,
+        })
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// The standard fonts only cover Windows-1252; anything else becomes `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '€' => 0x80,
+            '…' => 0x85,
+            '–' => 0x96,
+            '—' => 0x97,
+            c if (c as u32) < 0x80 || (0xA0..=0xFF).contains(&(c as u32)) => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
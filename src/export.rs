@@ -0,0 +1,96 @@
+use std::io::{self, Write};
+
+use chrono::Local;
+
+use crate::Transaction;
+use crate::config::Config;
+
+mod anonymize;
+mod ical;
+mod ledger;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+
+pub use anonymize::anonymize;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// ledger-cli/hledger journal
+    Ledger,
+    /// Upcoming bills as an iCalendar (.ics) file
+    Ical,
+    /// Excel workbook with transactions, monthly summary and category sheets
+    #[cfg(feature = "xlsx")]
+    Xlsx,
+    /// One JSON transaction per line
+    Jsonl,
+}
+
+impl ExportFormat {
+    pub fn is_binary(self) -> bool {
+        #[cfg(feature = "xlsx")]
+        if self == ExportFormat::Xlsx {
+            return true;
+        }
+        false
+    }
+}
+
+pub fn export(
+    transactions: &[&Transaction],
+    format: ExportFormat,
+    config: &Config,
+) -> io::Result<Vec<u8>> {
+    Ok(match format {
+        ExportFormat::Ledger => ledger::ledger(transactions, &config.currency).into_bytes(),
+        ExportFormat::Ical => {
+            ical::bills(transactions, config, Local::now().date_naive()).into_bytes()
+        }
+        #[cfg(feature = "xlsx")]
+        ExportFormat::Xlsx => {
+            xlsx::workbook(transactions, &config.currency).map_err(io::Error::other)?
+        }
+        ExportFormat::Jsonl => {
+            let mut out = Vec::new();
+            jsonl(transactions, &mut out)?;
+            out
+        }
+    })
+}
+
+pub fn jsonl(transactions: &[&Transaction], mut out: impl Write) -> io::Result<()> {
+    for transaction in transactions {
+        serde_json::to_writer(&mut out, transaction)?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_jsonl_one_transaction_per_line() {
+        let mut tracker = FinanceTracker::new();
+        for description in ["Rent", "Coffee\nbeans"] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount: 10.0,
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let mut out = Vec::new();
+        jsonl(&all, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        let parsed: Transaction = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(&parsed, all[1]);
+    }
+}
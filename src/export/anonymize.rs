@@ -0,0 +1,107 @@
+use std::hash::{BuildHasher, RandomState};
+
+use crate::{Split, Symbol, Transaction, money};
+
+/// Copies of `transactions` safe to share in a bug report or demo.
+///
+/// Descriptions, payees, accounts, tags, projects and the people expenses
+/// are split with become short hashes, the same text always getting the
+/// same one within an export but a different one in the next. Notes,
+/// receipts and import ids are dropped and amounts are rounded to two
+/// significant digits. Dates, types and categories are kept so reports
+/// over the data still look real.
+pub fn anonymize(transactions: &[&Transaction]) -> Vec<Transaction> {
+    let hasher = RandomState::new();
+    let hash = |prefix: &str, text: &str| {
+        format!(
+            "{}-{:08x}",
+            prefix,
+            hasher.hash_one(text.to_lowercase()) as u32
+        )
+    };
+    transactions
+        .iter()
+        .map(|t| {
+            let amount = bucket(t.amount);
+            let scale = if t.amount == 0.0 {
+                0.0
+            } else {
+                amount / t.amount
+            };
+            Transaction {
+                description: hash("item", &t.description),
+                amount,
+                payee: t.payee.map(|payee| Symbol::from(hash("payee", &payee))),
+                notes: None,
+                tags: t.tags.iter().map(|tag| hash("tag", tag)).collect(),
+                account: hash("account", &t.account),
+                external_id: None,
+                import: None,
+                receipt: None,
+                splits: t
+                    .splits
+                    .iter()
+                    .map(|split| Split {
+                        person: hash("person", &split.person),
+                        share: money::round(split.share * scale),
+                    })
+                    .collect(),
+                project: t.project.as_deref().map(|project| hash("project", project)),
+                ..(*t).clone()
+            }
+        })
+        .collect()
+}
+
+/// `amount` rounded to two significant digits, or to a whole number below 10.
+fn bucket(amount: f64) -> f64 {
+    if amount.abs() < 10.0 {
+        return amount.round();
+    }
+    let step = 10f64.powi(amount.abs().log10().floor() as i32 - 1);
+    money::round((amount / step).round() * step)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_anonymize_hides_text_and_buckets_amounts() {
+        assert_eq!(bucket(4.35), 4.0);
+        assert_eq!(bucket(87.4), 87.0);
+        assert_eq!(bucket(1234.56), 1200.0);
+
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, payee) in [
+            ("Dinner at Luigi's", 87.4, "Luigi's"),
+            ("dinner at luigi's", 23.0, "Luigi's"),
+            ("Rent", 1234.56, "Landlord"),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from("2024-03-01"),
+                category: String::from("Food"),
+                payee: Some(String::from(payee)),
+                notes: Some(String::from("Anna's birthday")),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let shared = anonymize(&all);
+        assert_eq!(shared[0].description, shared[1].description);
+        assert_ne!(shared[0].description, shared[2].description);
+        assert!(shared[0].description.starts_with("item-"));
+        assert_eq!(shared[0].payee, shared[1].payee);
+        assert_eq!(shared[2].amount, 1200.0);
+        assert_eq!(shared[0].category, all[0].category);
+        assert_eq!(shared[0].date, all[0].date);
+        assert!(shared.iter().all(|t| t.notes.is_none()));
+        let text = serde_json::to_string(&shared).unwrap();
+        assert!(!text.contains("Luigi") && !text.contains("Landlord"));
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::NaiveDate;
+
+use crate::Transaction;
+use crate::bills::{self, Bill};
+use crate::config::Config;
+use crate::money::MoneyFormat;
+
+pub fn bills(transactions: &[&Transaction], config: &Config, today: NaiveDate) -> String {
+    let money = MoneyFormat::from_config(config);
+    let stamp = today.format("%Y%m%dT000000Z").to_string();
+
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//expenso//bills//EN"),
+        String::from("CALSCALE:GREGORIAN"),
+        String::from("X-WR-CALNAME:expenso bills"),
+    ];
+
+    for bill in bills::upcoming(transactions, today) {
+        lines.extend([
+            String::from("BEGIN:VEVENT"),
+            format!("UID:{}@expenso", uid(&bill)),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", bill.next_due.format("%Y%m%d")),
+            String::from("RRULE:FREQ=MONTHLY"),
+            format!(
+                "SUMMARY:{}",
+                escape(&format!(
+                    "{} due ({})",
+                    bill.description,
+                    money.format(bill.amount)
+                ))
+            ),
+            format!(
+                "DESCRIPTION:{}",
+                escape(&format!(
+                    "Category: {}\nAccount: {}",
+                    bill.category, bill.account
+                ))
+            ),
+            String::from("TRANSP:TRANSPARENT"),
+            String::from("END:VEVENT"),
+        ]);
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn uid(bill: &Bill) -> String {
+    [&bill.description, &bill.category, &bill.account]
+        .iter()
+        .map(|part| {
+            part.to_lowercase()
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_bills_calendar() {
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Rent, flat 2"),
+            amount: 1200.0,
+            is_recurring: true,
+            date: String::from("2024-03-01"),
+            category: String::from("Housing"),
+            ..Default::default()
+        });
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let today = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+
+        let calendar = bills(&all, &Config::default(), today);
+        let lines: Vec<&str> = calendar.split("\r\n").collect();
+
+        assert_eq!(lines[0], "BEGIN:VCALENDAR");
+        assert!(lines.contains(&"UID:rent--flat-2.housing.cash@expenso"));
+        assert!(lines.contains(&"DTSTART;VALUE=DATE:20240401"));
+        assert!(lines.contains(&"SUMMARY:Rent\\, flat 2 due ($1\\,200.00)"));
+        assert!(lines.contains(&"DESCRIPTION:Category: Housing\\nAccount: Cash"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_fold_long_lines() {
+        let line = format!("SUMMARY:{}", "x".repeat(100));
+        let folded = fold(&line);
+        let parts: Vec<&str> = folded.split("\r\n").collect();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].len(), 75);
+        assert!(parts[1].starts_with(' '));
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Write;
+
+use crate::money;
+use crate::{Transaction, TransactionType};
+
+pub fn ledger(transactions: &[&Transaction], currency: &str) -> String {
+    let mut journal = String::new();
+    let places = money::decimal_places(currency);
+
+    for transaction in transactions {
+        let asset = account_name("Assets", &transaction.account);
+        let (debit, credit) = match transaction.transaction_type {
+            TransactionType::Expense => (account_name("Expenses", &transaction.category), asset),
+            TransactionType::Income => (asset, account_name("Income", &transaction.category)),
+            TransactionType::Settlement => {
+                let person = transaction.splits.first().map_or("", |s| s.person.as_str());
+                let owed = account_name("Assets:Receivable", person);
+                if transaction.is_inflow() {
+                    (asset, owed)
+                } else {
+                    (owed, asset)
+                }
+            }
+        };
+        let amount = format!("{} {:.*}", currency, places, transaction.amount);
+
+        let _ = writeln!(
+            journal,
+            "{} {}",
+            transaction.date,
+            single_line(&transaction.description)
+        );
+        if let Some(payee) = transaction
+            .payee
+            .as_ref()
+            .filter(|payee| **payee != transaction.description)
+        {
+            let _ = writeln!(journal, "    ; payee: {}", single_line(payee));
+        }
+        if let Some(notes) = &transaction.notes {
+            let _ = writeln!(journal, "    ; {}", single_line(notes));
+        }
+        if !transaction.tags.is_empty() {
+            let _ = writeln!(journal, "    ; :{}:", transaction.tags.join(":"));
+        }
+        let _ = writeln!(journal, "    {:<40}  {}", debit, amount);
+        let _ = writeln!(journal, "    {}", credit);
+        journal.push('\n');
+    }
+
+    journal
+}
+
+fn account_name(root: &str, name: &str) -> String {
+    let segments: Vec<String> = name
+        .split(':')
+        .map(single_line)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() {
+        format!("{}:Unknown", root)
+    } else {
+        format!("{}:{}", root, segments.join(":"))
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Weekly  shop"),
+            amount: 42.5,
+            date: String::from("2024-03-05"),
+            category: String::from("Food:Groceries"),
+            payee: Some(String::from("Corner Market")),
+            tags: vec![String::from("home")],
+            account: String::from("Checking"),
+            ..Default::default()
+        });
+        tracker.insert(NewTransaction {
+            description: String::from("March salary"),
+            amount: 2500.0,
+            date: String::from("2024-03-01"),
+            transaction_type: TransactionType::Income,
+            category: String::from("Salary"),
+            account: String::from("Checking"),
+            ..Default::default()
+        });
+        tracker
+    }
+
+    #[test]
+    fn test_ledger_journal() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let expected = "\
+2024-03-05 Weekly shop
+    ; payee: Corner Market
+    ; :home:
+    Expenses:Food:Groceries                   USD 42.50
+    Assets:Checking
+
+2024-03-01 March salary
+    Assets:Checking                           USD 2500.00
+    Income:Salary
+
+";
+        assert_eq!(ledger(&all, "USD"), expected);
+    }
+
+    #[test]
+    fn test_account_names() {
+        assert_eq!(
+            account_name("Expenses", "Eating  out"),
+            "Expenses:Eating out"
+        );
+        assert_eq!(account_name("Expenses", ""), "Expenses:Unknown");
+        assert_eq!(
+            account_name("Assets", "Bank: Savings"),
+            "Assets:Bank:Savings"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use rust_xlsxwriter::{ExcelDateTime, Format, Workbook, Worksheet, XlsxError};
+
+use crate::report::Summary;
+use crate::{Transaction, TransactionType};
+
+pub fn workbook(transactions: &[&Transaction], currency: &str) -> Result<Vec<u8>, XlsxError> {
+    let bold = Format::new().set_bold();
+    let money = Format::new().set_num_format(money_format(currency));
+    let date = Format::new().set_num_format("yyyy-mm-dd");
+
+    let mut workbook = Workbook::new();
+
+    let sheet = workbook.add_worksheet().set_name("Transactions")?;
+    write_header(
+        sheet,
+        &[
+            "ID",
+            "Date",
+            "Description",
+            "Type",
+            "Category",
+            "Account",
+            "Amount",
+            "Recurring",
+            "Payee",
+            "Notes",
+            "Tags",
+        ],
+        &bold,
+    )?;
+    for (index, t) in transactions.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_number(row, 0, t.id)?;
+        match ExcelDateTime::parse_from_str(&t.date) {
+            Ok(parsed) => sheet.write_datetime_with_format(row, 1, &parsed, &date)?,
+            Err(_) => sheet.write_string(row, 1, &t.date)?,
+        };
+        sheet.write_string(row, 2, &t.description)?;
+        sheet.write_string(row, 3, t.transaction_type.to_string())?;
+        sheet.write_string(row, 4, t.category.as_str())?;
+        sheet.write_string(row, 5, &t.account)?;
+        sheet.write_number_with_format(row, 6, t.signed_amount(), &money)?;
+        sheet.write_boolean(row, 7, t.is_recurring)?;
+        sheet.write_string(row, 8, t.payee.as_deref().unwrap_or_default())?;
+        sheet.write_string(row, 9, t.notes.as_deref().unwrap_or_default())?;
+        sheet.write_string(row, 10, t.tags.join(", "))?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+
+    let months = by_month(transactions);
+
+    let sheet = workbook.add_worksheet().set_name("Monthly Summary")?;
+    write_header(sheet, &["Month", "Income", "Expense", "Net"], &bold)?;
+    for (index, (month, transactions)) in months.iter().enumerate() {
+        let row = index as u32 + 1;
+        let summary = Summary::of(transactions);
+        sheet.write_string(row, 0, month)?;
+        sheet.write_number_with_format(row, 1, summary.total_income, &money)?;
+        sheet.write_number_with_format(row, 2, summary.total_expense, &money)?;
+        sheet.write_number_with_format(row, 3, summary.net_balance, &money)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+    sheet.autofit();
+
+    let pivot = category_pivot(transactions);
+    let columns: Vec<&String> = months.keys().collect();
+    let sheet = workbook.add_worksheet().set_name("Categories")?;
+    sheet.write_string_with_format(0, 0, "Category", &bold)?;
+    for (index, month) in columns.iter().enumerate() {
+        sheet.write_string_with_format(0, index as u16 + 1, *month, &bold)?;
+    }
+    let total_column = columns.len() as u16 + 1;
+    sheet.write_string_with_format(0, total_column, "Total", &bold)?;
+    for (index, (category, totals)) in pivot.iter().enumerate() {
+        let row = index as u32 + 1;
+        sheet.write_string(row, 0, category)?;
+        for (column, month) in columns.iter().enumerate() {
+            if let Some(total) = totals.get(*month) {
+                sheet.write_number_with_format(row, column as u16 + 1, *total, &money)?;
+            }
+        }
+        sheet.write_number_with_format(row, total_column, totals.values().sum::<f64>(), &money)?;
+    }
+    sheet.set_freeze_panes(1, 1)?;
+    sheet.autofit();
+
+    workbook.save_to_buffer()
+}
+
+fn write_header(sheet: &mut Worksheet, headers: &[&str], bold: &Format) -> Result<(), XlsxError> {
+    for (column, header) in headers.iter().enumerate() {
+        sheet.write_string_with_format(0, column as u16, *header, bold)?;
+    }
+    Ok(())
+}
+
+fn money_format(currency: &str) -> String {
+    match crate::money::decimal_places(currency) {
+        0 => String::from("#,##0;[Red]-#,##0"),
+        places => {
+            let zeros = "0".repeat(places);
+            format!("#,##0.{zeros};[Red]-#,##0.{zeros}")
+        }
+    }
+}
+
+fn by_month<'a>(transactions: &[&'a Transaction]) -> BTreeMap<String, Vec<&'a Transaction>> {
+    let mut months: BTreeMap<String, Vec<&Transaction>> = BTreeMap::new();
+    for t in transactions {
+        let month = t.date.get(..7).unwrap_or(&t.date).to_string();
+        months.entry(month).or_default().push(t);
+    }
+    months
+}
+
+fn category_pivot(transactions: &[&Transaction]) -> BTreeMap<String, BTreeMap<String, f64>> {
+    let mut pivot: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+    let expenses = transactions
+        .iter()
+        .filter(|t| t.transaction_type == TransactionType::Expense);
+    for t in expenses {
+        let month = t.date.get(..7).unwrap_or(&t.date).to_string();
+        *pivot
+            .entry(t.category.to_string())
+            .or_default()
+            .entry(month)
+            .or_default() += t.amount;
+    }
+    pivot
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+        for (amount, date, category, transaction_type) in [
+            (80.0, "2024-01-05", "Food", TransactionType::Expense),
+            (1200.0, "2024-01-01", "Housing", TransactionType::Expense),
+            (95.0, "2024-02-03", "Food", TransactionType::Expense),
+            (3000.0, "2024-02-25", "Salary", TransactionType::Income),
+        ] {
+            tracker.insert(NewTransaction {
+                description: category.to_string(),
+                amount,
+                date: date.to_string(),
+                category: category.to_string(),
+                transaction_type,
+                ..Default::default()
+            });
+        }
+        tracker
+    }
+
+    #[test]
+    fn test_category_pivot_by_month() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let pivot = category_pivot(&all);
+        assert_eq!(pivot.len(), 2);
+        assert_eq!(pivot["Food"]["2024-01"], 80.0);
+        assert_eq!(pivot["Food"]["2024-02"], 95.0);
+        assert!(!pivot.contains_key("Salary"));
+    }
+
+    #[test]
+    fn test_workbook_is_a_zip_archive() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let bytes = workbook(&all, "USD").unwrap();
+        assert_eq!(&bytes[..2], b"PK");
+        assert_eq!(money_format("JPY"), "#,##0;[Red]-#,##0");
+        assert_eq!(money_format("USD"), "#,##0.00;[Red]-#,##0.00");
+    }
+}
@@ -0,0 +1,292 @@
+//! C ABI for embedding the tracker in apps written in other languages.
+//!
+//! Every function is declared in `include/expenso.h`. Strings go in and come
+//! out as NUL-terminated UTF-8; transactions and summaries are JSON using the
+//! same field names as the data file. Strings returned by this library must be
+//! released with [`expenso_string_free`] and trackers with
+//! [`expenso_tracker_free`].
+//!
+//! Functions that can fail return `NULL`, `0` or `-1`, and the message is then
+//! available from [`expenso_last_error`] on the same thread.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::path::Path;
+use std::ptr;
+
+use crate::error::{ExpensoError, Result};
+use crate::report::{Report, Summary};
+use crate::{FinanceTracker, NewTransaction, storage};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(err: ExpensoError) {
+    let message = CString::new(err.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+fn ok_or_set<T>(result: Result<T>, fallback: T) -> T {
+    result.unwrap_or_else(|err| {
+        set_error(err);
+        fallback
+    })
+}
+
+/// # Safety
+///
+/// `text` must be NULL or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(text: *const c_char, name: &str) -> Result<&'a str> {
+    if text.is_null() {
+        return Err(ExpensoError::InvalidInput(format!("{} is NULL", name)));
+    }
+    // SAFETY: checked for NULL above; the caller guarantees NUL termination.
+    unsafe { CStr::from_ptr(text) }
+        .to_str()
+        .map_err(|_| ExpensoError::InvalidInput(format!("{} is not valid UTF-8", name)))
+}
+
+fn into_c_string(text: String) -> *mut c_char {
+    match CString::new(text) {
+        Ok(text) => text.into_raw(),
+        Err(err) => {
+            set_error(ExpensoError::InvalidInput(err.to_string()));
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+///
+/// `tracker` must be NULL or a pointer returned by this library and not yet freed.
+unsafe fn tracker_ref<'a>(tracker: *const FinanceTracker) -> Result<&'a FinanceTracker> {
+    // SAFETY: the caller guarantees the pointer is NULL or live.
+    unsafe { tracker.as_ref() }
+        .ok_or_else(|| ExpensoError::InvalidInput(String::from("tracker is NULL")))
+}
+
+/// # Safety
+///
+/// As for [`tracker_ref`], and no other reference to the tracker may be in use.
+unsafe fn tracker_mut<'a>(tracker: *mut FinanceTracker) -> Result<&'a mut FinanceTracker> {
+    // SAFETY: the caller guarantees the pointer is NULL or live and unaliased.
+    unsafe { tracker.as_mut() }
+        .ok_or_else(|| ExpensoError::InvalidInput(String::from("tracker is NULL")))
+}
+
+/// Creates an empty tracker.
+#[unsafe(no_mangle)]
+pub extern "C" fn expenso_tracker_new() -> *mut FinanceTracker {
+    Box::into_raw(Box::new(FinanceTracker::new()))
+}
+
+/// Loads a tracker from a data file, or returns NULL.
+///
+/// # Safety
+///
+/// `path` must point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_tracker_load(path: *const c_char) -> *mut FinanceTracker {
+    // SAFETY: forwarded from the caller.
+    let result = unsafe { str_arg(path, "path") }.and_then(|path| storage::load(Path::new(path)));
+    match result {
+        Ok(tracker) => Box::into_raw(Box::new(tracker)),
+        Err(err) => {
+            set_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Writes the tracker to a data file. Returns 0, or -1 on error.
+///
+/// # Safety
+///
+/// `tracker` must come from this library and `path` must be NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_tracker_save(
+    tracker: *const FinanceTracker,
+    path: *const c_char,
+) -> i32 {
+    // SAFETY: forwarded from the caller.
+    let result = unsafe { tracker_ref(tracker) }.and_then(|tracker| {
+        // SAFETY: forwarded from the caller.
+        let path = unsafe { str_arg(path, "path") }?;
+        storage::save(tracker, Path::new(path))
+    });
+    ok_or_set(result.map(|()| 0), -1)
+}
+
+/// Frees a tracker. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `tracker` must be NULL or come from this library, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_tracker_free(tracker: *mut FinanceTracker) {
+    if !tracker.is_null() {
+        // SAFETY: the caller hands back ownership of a pointer from Box::into_raw.
+        drop(unsafe { Box::from_raw(tracker) });
+    }
+}
+
+/// Adds a transaction given as JSON, e.g.
+/// `{"description": "Lunch", "amount": 12.5, "category": "Food"}`.
+/// Returns the new id, or 0 on error.
+///
+/// # Safety
+///
+/// `tracker` must come from this library and `json` must be NUL-terminated.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_add_transaction(
+    tracker: *mut FinanceTracker,
+    json: *const c_char,
+) -> u32 {
+    // SAFETY: forwarded from the caller.
+    let result = unsafe { tracker_mut(tracker) }.and_then(|tracker| {
+        // SAFETY: forwarded from the caller.
+        let json = unsafe { str_arg(json, "json") }?;
+        let new: NewTransaction = serde_json::from_str(json)?;
+        Ok(tracker.add(new)?.id)
+    });
+    ok_or_set(result, 0)
+}
+
+/// Deletes a transaction. Returns 0, or -1 when there is no such id.
+///
+/// # Safety
+///
+/// `tracker` must come from this library.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_delete_transaction(tracker: *mut FinanceTracker, id: u32) -> i32 {
+    // SAFETY: forwarded from the caller.
+    let result = unsafe { tracker_mut(tracker) }.and_then(|tracker| tracker.remove(id).map(|_| 0));
+    ok_or_set(result, -1)
+}
+
+/// Returns every transaction as a JSON array, or NULL.
+///
+/// # Safety
+///
+/// `tracker` must come from this library.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_transactions_json(tracker: *const FinanceTracker) -> *mut c_char {
+    // SAFETY: forwarded from the caller.
+    let result = unsafe { tracker_ref(tracker) }
+        .and_then(|tracker| Ok(serde_json::to_string(tracker.get_transactions())?));
+    ok_or_set(result.map(into_c_string), ptr::null_mut())
+}
+
+/// Returns the income, expense and balance totals as a JSON object, or NULL.
+///
+/// # Safety
+///
+/// `tracker` must come from this library.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_summary_json(tracker: *const FinanceTracker) -> *mut c_char {
+    // SAFETY: forwarded from the caller.
+    let result = unsafe { tracker_ref(tracker) }
+        .and_then(|tracker| Ok(Summary::from_tracker(tracker).json()?));
+    ok_or_set(result.map(into_c_string), ptr::null_mut())
+}
+
+/// Returns the message of the last error on this thread, or NULL. The string
+/// stays owned by the library and is valid until the next failing call.
+#[unsafe(no_mangle)]
+pub extern "C" fn expenso_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Frees a string returned by this library. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `text` must be NULL or come from this library, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn expenso_string_free(text: *mut c_char) {
+    if !text.is_null() {
+        // SAFETY: the caller hands back ownership of a pointer from CString::into_raw.
+        drop(unsafe { CString::from_raw(text) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn take_string(text: *mut c_char) -> String {
+        assert!(!text.is_null());
+        let owned = unsafe { CStr::from_ptr(text) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { expenso_string_free(text) };
+        owned
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(expenso_last_error()) }
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_add_and_summarize() {
+        let tracker = expenso_tracker_new();
+        let json = c"{\"description\":\"Lunch\",\"amount\":12.5,\"date\":\"2024-03-01\"}";
+        let id = unsafe { expenso_add_transaction(tracker, json.as_ptr()) };
+        assert_eq!(id, 1);
+
+        let summary: serde_json::Value =
+            serde_json::from_str(&take_string(unsafe { expenso_summary_json(tracker) })).unwrap();
+        assert_eq!(summary["total_expense"], 12.5);
+
+        let transactions: serde_json::Value =
+            serde_json::from_str(&take_string(unsafe { expenso_transactions_json(tracker) }))
+                .unwrap();
+        assert_eq!(transactions[0]["category"], "Uncategorized");
+
+        assert_eq!(unsafe { expenso_delete_transaction(tracker, 1) }, 0);
+        assert_eq!(unsafe { expenso_delete_transaction(tracker, 1) }, -1);
+        assert_eq!(last_error(), "no transaction with id 1");
+        unsafe { expenso_tracker_free(tracker) };
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        let tracker = expenso_tracker_new();
+        let id = unsafe { expenso_add_transaction(tracker, c"{\"amount\":-3}".as_ptr()) };
+        assert_eq!(id, 0);
+        assert_eq!(last_error(), "amount must be a positive number, got -3");
+
+        assert_eq!(
+            unsafe { expenso_add_transaction(ptr::null_mut(), c"{}".as_ptr()) },
+            0
+        );
+        assert_eq!(last_error(), "tracker is NULL");
+        unsafe { expenso_tracker_free(tracker) };
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("expenso-ffi-{}.json", std::process::id()));
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        let tracker = expenso_tracker_new();
+        unsafe { expenso_add_transaction(tracker, c"{\"amount\":5}".as_ptr()) };
+        assert_eq!(unsafe { expenso_tracker_save(tracker, c_path.as_ptr()) }, 0);
+        unsafe { expenso_tracker_free(tracker) };
+
+        let loaded = unsafe { expenso_tracker_load(c_path.as_ptr()) };
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(unsafe { &*loaded }.get_transactions().len(), 1);
+        unsafe { expenso_tracker_free(loaded) };
+    }
+}
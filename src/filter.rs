@@ -0,0 +1,168 @@
+use crate::{Transaction, TransactionType};
+
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    pub category: Option<String>,
+    pub transaction_type: Option<TransactionType>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub tag: Option<String>,
+    pub recurring: Option<bool>,
+    pub project: Option<String>,
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        if let Some(category) = &self.category
+            && !transaction.category.eq_ignore_ascii_case(category)
+        {
+            return false;
+        }
+        if let Some(transaction_type) = &self.transaction_type
+            && transaction.transaction_type != *transaction_type
+        {
+            return false;
+        }
+        if let Some(from) = &self.from
+            && transaction.date.as_str() < from.as_str()
+        {
+            return false;
+        }
+        if let Some(to) = &self.to
+            && transaction.date.as_str() > to.as_str()
+        {
+            return false;
+        }
+        if self.min_amount.is_some_and(|min| transaction.amount < min) {
+            return false;
+        }
+        if self.max_amount.is_some_and(|max| transaction.amount > max) {
+            return false;
+        }
+        if let Some(tag) = &self.tag
+            && !transaction.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+        {
+            return false;
+        }
+        if let Some(recurring) = self.recurring
+            && transaction.is_recurring != recurring
+        {
+            return false;
+        }
+        if let Some(project) = &self.project
+            && !transaction
+                .project
+                .as_deref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(project))
+        {
+            return false;
+        }
+        true
+    }
+
+    /// Whether it keeps every transaction up to its `to` date, so the
+    /// opening balances still count towards what it selects.
+    pub fn starts_at_beginning(&self) -> bool {
+        self.category.is_none()
+            && self.transaction_type.is_none()
+            && self.from.is_none()
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+            && self.tag.is_none()
+            && self.recurring.is_none()
+            && self.project.is_none()
+    }
+
+    pub fn apply<'a>(&self, transactions: Vec<&'a Transaction>) -> Vec<&'a Transaction> {
+        transactions
+            .into_iter()
+            .filter(|t| self.matches(t))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Rent"),
+            amount: 2000.0,
+            is_recurring: true,
+            date: String::from("2024-01-01"),
+            category: String::from("Housing"),
+            ..Default::default()
+        });
+        tracker.insert(NewTransaction {
+            description: String::from("Flight"),
+            amount: 350.0,
+            date: String::from("2024-02-14"),
+            category: String::from("Travel"),
+            tags: vec![String::from("vacation")],
+            ..Default::default()
+        });
+        tracker.insert(NewTransaction {
+            description: String::from("Salary"),
+            amount: 5000.0,
+            is_recurring: true,
+            date: String::from("2024-02-28"),
+            transaction_type: TransactionType::Income,
+            category: String::from("Work"),
+            ..Default::default()
+        });
+        tracker
+    }
+
+    fn ids(tracker: &FinanceTracker, filter: &TransactionFilter) -> Vec<u32> {
+        let all = tracker.get_transactions().iter().collect();
+        filter.apply(all).iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let tracker = create_test_tracker();
+        assert_eq!(ids(&tracker, &TransactionFilter::default()), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let tracker = create_test_tracker();
+
+        let filter = TransactionFilter {
+            transaction_type: Some(TransactionType::Expense),
+            from: Some(String::from("2024-02-01")),
+            ..Default::default()
+        };
+        assert_eq!(ids(&tracker, &filter), vec![2]);
+
+        let filter = TransactionFilter {
+            recurring: Some(true),
+            max_amount: Some(2500.0),
+            ..Default::default()
+        };
+        assert_eq!(ids(&tracker, &filter), vec![1]);
+    }
+
+    #[test]
+    fn test_category_and_tag_ignore_case() {
+        let tracker = create_test_tracker();
+
+        let filter = TransactionFilter {
+            category: Some(String::from("housing")),
+            ..Default::default()
+        };
+        assert_eq!(ids(&tracker, &filter), vec![1]);
+
+        let filter = TransactionFilter {
+            tag: Some(String::from("Vacation")),
+            ..Default::default()
+        };
+        assert_eq!(ids(&tracker, &filter), vec![2]);
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Enum, InputObject, Object, Result, Schema,
+    SimpleObject,
+};
+use axum::Json;
+use axum::extract::State;
+use chrono::Local;
+
+use crate::filter::TransactionFilter;
+use crate::report::{self, Summary};
+use crate::server::AppState;
+use crate::{SharedTracker, SortKey, SortOrder, Symbol, Transaction, TransactionType, dates};
+
+pub type ExpensoSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema(tracker: SharedTracker) -> ExpensoSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(tracker)
+        .finish()
+}
+
+pub async fn handler(
+    State(state): State<AppState>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(state.schema().execute(request).await)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Kind {
+    Income,
+    Expense,
+    Settlement,
+}
+
+impl From<&TransactionType> for Kind {
+    fn from(transaction_type: &TransactionType) -> Self {
+        match transaction_type {
+            TransactionType::Income => Kind::Income,
+            TransactionType::Expense => Kind::Expense,
+            TransactionType::Settlement => Kind::Settlement,
+        }
+    }
+}
+
+impl From<Kind> for TransactionType {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Income => TransactionType::Income,
+            Kind::Expense => TransactionType::Expense,
+            Kind::Settlement => TransactionType::Settlement,
+        }
+    }
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct TransactionNode {
+    id: u32,
+    description: String,
+    amount: f64,
+    is_recurring: bool,
+    date: String,
+    #[graphql(name = "type")]
+    kind: Kind,
+    category: String,
+    payee: Option<String>,
+    notes: Option<String>,
+    tags: Vec<String>,
+    account: String,
+    project: Option<String>,
+}
+
+impl From<&Transaction> for TransactionNode {
+    fn from(t: &Transaction) -> Self {
+        TransactionNode {
+            id: t.id,
+            description: t.description.clone(),
+            amount: t.amount,
+            is_recurring: t.is_recurring,
+            date: t.date.clone(),
+            kind: Kind::from(&t.transaction_type),
+            category: t.category.to_string(),
+            payee: t.payee.map(String::from),
+            notes: t.notes.clone(),
+            tags: t.tags.clone(),
+            account: t.account.clone(),
+            project: t.project.clone(),
+        }
+    }
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct CategoryTotal {
+    category: String,
+    total: f64,
+}
+
+#[derive(Debug, SimpleObject)]
+pub struct MonthlyTotal {
+    month: String,
+    category: String,
+    income: f64,
+    expense: f64,
+    net: f64,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct FilterInput {
+    category: Option<String>,
+    #[graphql(name = "type")]
+    kind: Option<Kind>,
+    from: Option<String>,
+    to: Option<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+    tag: Option<String>,
+    recurring: Option<bool>,
+    project: Option<String>,
+}
+
+impl FilterInput {
+    fn to_filter(&self) -> Result<TransactionFilter> {
+        let today = Local::now().date_naive();
+        let date = |value: &Option<String>| -> Result<Option<String>> {
+            match value {
+                Some(value) => dates::normalize(value, today)
+                    .map(Some)
+                    .ok_or_else(|| format!("Invalid date '{}'", value).into()),
+                None => Ok(None),
+            }
+        };
+
+        Ok(TransactionFilter {
+            category: self.category.clone(),
+            transaction_type: self.kind.map(TransactionType::from),
+            from: date(&self.from)?,
+            to: date(&self.to)?,
+            min_amount: self.min,
+            max_amount: self.max,
+            tag: self.tag.clone(),
+            recurring: self.recurring,
+            project: self.project.clone(),
+        })
+    }
+}
+
+fn with_filtered<T>(
+    ctx: &Context<'_>,
+    filter: Option<FilterInput>,
+    f: impl FnOnce(Vec<&Transaction>) -> T,
+) -> Result<T> {
+    let filter = filter.unwrap_or_default().to_filter()?;
+    let tracker = ctx.data::<SharedTracker>()?.read();
+    let transactions = tracker.filtered(&filter, SortKey::Id, SortOrder::Ascending);
+    Ok(f(transactions))
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<FilterInput>,
+    ) -> Result<Vec<TransactionNode>> {
+        with_filtered(ctx, filter, |transactions| {
+            transactions
+                .into_iter()
+                .map(TransactionNode::from)
+                .collect()
+        })
+    }
+
+    async fn transaction(&self, ctx: &Context<'_>, id: u32) -> Result<Option<TransactionNode>> {
+        with_filtered(ctx, None, |transactions| {
+            transactions
+                .into_iter()
+                .find(|t| t.id == id)
+                .map(TransactionNode::from)
+        })
+    }
+
+    async fn summary(&self, ctx: &Context<'_>, filter: Option<FilterInput>) -> Result<Summary> {
+        with_filtered(ctx, filter, |transactions| Summary::of(&transactions))
+    }
+
+    async fn categories(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<FilterInput>,
+    ) -> Result<Vec<CategoryTotal>> {
+        with_filtered(ctx, filter, |transactions| {
+            let totals: BTreeMap<Symbol, f64> =
+                report::category_totals(&transactions).into_iter().collect();
+            totals
+                .into_iter()
+                .map(|(category, total)| CategoryTotal {
+                    category: category.to_string(),
+                    total,
+                })
+                .collect()
+        })
+    }
+
+    /// Income and expense per month and category, optionally limited to some categories
+    async fn monthly_totals(
+        &self,
+        ctx: &Context<'_>,
+        categories: Option<Vec<String>>,
+        filter: Option<FilterInput>,
+    ) -> Result<Vec<MonthlyTotal>> {
+        with_filtered(ctx, filter, |transactions| {
+            monthly_totals(&transactions, categories.as_deref())
+        })
+    }
+}
+
+fn monthly_totals(
+    transactions: &[&Transaction],
+    categories: Option<&[String]>,
+) -> Vec<MonthlyTotal> {
+    let mut totals: BTreeMap<(String, Symbol), (f64, f64)> = BTreeMap::new();
+
+    for t in transactions {
+        if let Some(categories) = categories
+            && !categories
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(&t.category))
+        {
+            continue;
+        }
+        let month = t.date.get(..7).unwrap_or(&t.date).to_string();
+        let entry = totals.entry((month, t.category)).or_default();
+        match t.transaction_type {
+            TransactionType::Income => entry.0 += t.amount,
+            TransactionType::Expense => entry.1 += t.amount,
+            TransactionType::Settlement => {}
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|((month, category), (income, expense))| MonthlyTotal {
+            month,
+            category: category.to_string(),
+            income,
+            expense,
+            net: income - expense,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn create_test_schema() -> ExpensoSchema {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, category) in [
+            ("Groceries", 80.0, "2024-01-05", "Food"),
+            ("Dinner", 40.0, "2024-01-20", "Food"),
+            ("Rent", 1200.0, "2024-01-01", "Housing"),
+            ("Groceries", 95.0, "2024-02-03", "Food"),
+            ("Cinema", 15.0, "2024-02-10", "Fun"),
+        ] {
+            tracker.insert(NewTransaction {
+                description: description.to_string(),
+                amount,
+                date: date.to_string(),
+                category: category.to_string(),
+                ..Default::default()
+            });
+        }
+        schema(SharedTracker::new(tracker))
+    }
+
+    async fn query(schema: &ExpensoSchema, query: &str) -> serde_json::Value {
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        response.data.into_json().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_monthly_totals_for_selected_categories() {
+        let schema = create_test_schema();
+        let data = query(
+            &schema,
+            r#"{ monthlyTotals(categories: ["food", "Housing"]) { month category expense } }"#,
+        )
+        .await;
+
+        assert_eq!(
+            data["monthlyTotals"],
+            serde_json::json!([
+                { "month": "2024-01", "category": "Food", "expense": 120.0 },
+                { "month": "2024-01", "category": "Housing", "expense": 1200.0 },
+                { "month": "2024-02", "category": "Food", "expense": 95.0 },
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_filtered_transactions_and_summary() {
+        let schema = create_test_schema();
+        let data = query(
+            &schema,
+            r#"{
+                transactions(filter: { from: "2024-02-01", type: EXPENSE }) { id description }
+                summary(filter: { category: "Food" }) { totalExpense }
+            }"#,
+        )
+        .await;
+
+        assert_eq!(data["transactions"].as_array().unwrap().len(), 2);
+        assert_eq!(data["transactions"][0]["id"], 4);
+        assert_eq!(data["summary"]["totalExpense"], 215.0);
+
+        let response = schema
+            .execute(r#"{ transactions(filter: { from: "someday" }) { id } }"#)
+            .await;
+        assert_eq!(response.errors[0].message, "Invalid date 'someday'");
+    }
+}
@@ -0,0 +1,355 @@
+use std::fmt;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::Transaction;
+use crate::dates;
+use crate::money::format_money;
+
+/// A change to a [`FinanceTracker`](crate::FinanceTracker). Every mutation is
+/// recorded as one, and replaying them in order rebuilds the tracker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum Change {
+    TransactionAdded {
+        transaction: Box<Transaction>,
+    },
+    TransactionEdited {
+        before: Box<Transaction>,
+        after: Box<Transaction>,
+    },
+    TransactionDeleted {
+        transaction: Box<Transaction>,
+    },
+    /// `limit` is `None` when the budget was removed.
+    BudgetSet {
+        category: String,
+        limit: Option<f64>,
+        previous: Option<f64>,
+    },
+}
+
+impl Change {
+    /// The change that reverts this one.
+    pub fn inverse(&self) -> Change {
+        match self {
+            Change::TransactionAdded { transaction } => Change::TransactionDeleted {
+                transaction: transaction.clone(),
+            },
+            Change::TransactionEdited { before, after } => Change::TransactionEdited {
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Change::TransactionDeleted { transaction } => Change::TransactionAdded {
+                transaction: transaction.clone(),
+            },
+            Change::BudgetSet {
+                category,
+                limit,
+                previous,
+            } => Change::BudgetSet {
+                category: category.clone(),
+                limit: *previous,
+                previous: *limit,
+            },
+        }
+    }
+
+    /// The id of the transaction this change touches, if any.
+    pub fn transaction_id(&self) -> Option<u32> {
+        match self {
+            Change::TransactionAdded { transaction }
+            | Change::TransactionDeleted { transaction } => Some(transaction.id),
+            Change::TransactionEdited { after, .. } => Some(after.id),
+            Change::BudgetSet { .. } => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Change::TransactionAdded { .. } => "added",
+            Change::TransactionEdited { .. } => "edited",
+            Change::TransactionDeleted { .. } => "deleted",
+            Change::BudgetSet { .. } => "budget",
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let limit = |limit: &Option<f64>| limit.map_or(String::from("none"), format_money);
+        match self {
+            Change::TransactionAdded { transaction }
+            | Change::TransactionDeleted { transaction } => {
+                write!(
+                    f,
+                    "{} #{} {} ({})",
+                    self.name(),
+                    transaction.id,
+                    transaction.description,
+                    format_money(transaction.amount)
+                )
+            }
+            Change::TransactionEdited { before, after } => {
+                write!(f, "edited #{} {}", after.id, after.description)?;
+                if before.amount != after.amount {
+                    write!(
+                        f,
+                        " ({} -> {})",
+                        format_money(before.amount),
+                        format_money(after.amount)
+                    )?;
+                }
+                Ok(())
+            }
+            Change::BudgetSet {
+                category,
+                limit: new,
+                previous,
+            } => write!(
+                f,
+                "budget for {} {} -> {}",
+                category,
+                limit(previous),
+                limit(new)
+            ),
+        }
+    }
+}
+
+/// A [`Change`], when it was made and by whom, as kept in the data file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// UTC, e.g. `2024-03-01T09:30:00Z`. Entries recorded before that are
+    /// local time with no offset.
+    #[serde(rename = "at")]
+    pub at: String,
+    /// Missing on changes recorded before users were.
+    #[serde(rename = "by", default, skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+    /// Whether this reverts the latest change not undone before it.
+    #[serde(rename = "undo", default, skip_serializing_if = "std::ops::Not::not")]
+    pub undo: bool,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+impl Entry {
+    pub fn now(change: Change) -> Self {
+        Entry {
+            at: dates::utc_now(),
+            by: None,
+            undo: false,
+            change,
+        }
+    }
+
+    /// When the change was made in local time, e.g. `2024-03-01 09:30:00`.
+    pub fn local_time(&self) -> String {
+        match DateTime::parse_from_rfc3339(&self.at) {
+            Ok(at) => at
+                .with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string(),
+            Err(_) => self.at.replace('T', " "),
+        }
+    }
+
+    /// What the change did to its transaction, one line per field for an
+    /// edit, e.g. `amount: $4.50 -> $5.00`.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = match &self.change {
+            Change::TransactionEdited { before, after } => {
+                let fields = field_changes(before, after);
+                if fields.is_empty() {
+                    vec![String::from("edited, nothing changed")]
+                } else {
+                    fields.iter().map(FieldChange::to_string).collect()
+                }
+            }
+            change => vec![change.to_string()],
+        };
+        if self.undo {
+            lines[0].insert_str(0, "undo: ");
+        }
+        lines
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.undo {
+            write!(f, "undo: ")?;
+        }
+        write!(f, "{}", self.change)
+    }
+}
+
+/// One field of a transaction before and after an edit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub before: String,
+    pub after: String,
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.field, self.before, self.after)
+    }
+}
+
+/// The fields that differ between two versions of a transaction, leaving
+/// out the timestamps every edit changes.
+pub fn field_changes(before: &Transaction, after: &Transaction) -> Vec<FieldChange> {
+    let optional = |value: Option<&str>| value.unwrap_or("none").to_string();
+    let splits = |transaction: &Transaction| {
+        let splits: Vec<String> = transaction
+            .splits
+            .iter()
+            .map(|split| format!("{}={}", split.person, format_money(split.share)))
+            .collect();
+        if splits.is_empty() {
+            String::from("none")
+        } else {
+            splits.join(", ")
+        }
+    };
+    let fields = [
+        ("id", before.id.to_string(), after.id.to_string()),
+        (
+            "description",
+            before.description.clone(),
+            after.description.clone(),
+        ),
+        (
+            "amount",
+            format_money(before.amount),
+            format_money(after.amount),
+        ),
+        ("date", before.date.clone(), after.date.clone()),
+        (
+            "type",
+            before.transaction_type.to_string(),
+            after.transaction_type.to_string(),
+        ),
+        (
+            "category",
+            before.category.to_string(),
+            after.category.to_string(),
+        ),
+        (
+            "payee",
+            optional(before.payee.as_ref().map(|payee| payee.as_str())),
+            optional(after.payee.as_ref().map(|payee| payee.as_str())),
+        ),
+        ("account", before.account.clone(), after.account.clone()),
+        (
+            "recurring",
+            before.is_recurring.to_string(),
+            after.is_recurring.to_string(),
+        ),
+        ("tags", before.tags.join(", "), after.tags.join(", ")),
+        (
+            "notes",
+            optional(before.notes.as_deref()),
+            optional(after.notes.as_deref()),
+        ),
+        (
+            "receipt",
+            optional(before.receipt.as_deref()),
+            optional(after.receipt.as_deref()),
+        ),
+        ("splits", splits(before), splits(after)),
+        (
+            "project",
+            optional(before.project.as_deref()),
+            optional(after.project.as_deref()),
+        ),
+        (
+            "reconciled",
+            before.reconciled.to_string(),
+            after.reconciled.to_string(),
+        ),
+    ];
+    fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange {
+            field,
+            before,
+            after,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_entry_json_is_flat() {
+        let entry = Entry {
+            at: String::from("2024-03-01T09:30:00"),
+            by: Some(String::from("alex")),
+            undo: false,
+            change: Change::BudgetSet {
+                category: String::from("Food"),
+                limit: Some(300.0),
+                previous: None,
+            },
+        };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["event"], "BudgetSet");
+        assert_eq!(json["at"], "2024-03-01T09:30:00");
+        assert_eq!(json["by"], "alex");
+        assert_eq!(json["limit"], 300.0);
+        assert!(json.get("undo").is_none());
+        assert_eq!(serde_json::from_value::<Entry>(json).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_entry_time_is_utc_and_old_entries_still_read() {
+        let budget = || Change::BudgetSet {
+            category: String::from("Food"),
+            limit: Some(300.0),
+            previous: None,
+        };
+        let entry = Entry::now(budget());
+        assert!(entry.at.ends_with('Z'));
+        let at = DateTime::parse_from_rfc3339(&entry.at).unwrap();
+        assert_eq!(
+            entry.local_time(),
+            at.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        );
+
+        let old: Entry = serde_json::from_value(serde_json::json!({
+            "at": "2024-03-01T09:30:00",
+            "event": "BudgetSet",
+            "category": "Food",
+            "limit": 300.0,
+            "previous": null,
+        }))
+        .unwrap();
+        assert_eq!(old.change, budget());
+        assert_eq!(old.local_time(), "2024-03-01 09:30:00");
+    }
+
+    #[test]
+    fn test_inverse_round_trips() {
+        let change = Change::BudgetSet {
+            category: String::from("Food"),
+            limit: Some(300.0),
+            previous: Some(250.0),
+        };
+        assert_eq!(change.inverse().inverse(), change);
+        assert_eq!(
+            change.inverse().to_string(),
+            "budget for Food $300.00 -> $250.00"
+        );
+    }
+}
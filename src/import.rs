@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use crate::error::{ExpensoError, Result};
+use crate::{Fingerprint, NewTransaction};
+
+mod csv;
+#[cfg(feature = "gnucash")]
+mod gnucash;
+mod ofx;
+mod qif;
+
+pub use self::csv::CsvProfile;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ImportFormat {
+    Csv,
+    Ofx,
+    Qif,
+    /// Uncompressed or gzipped GnuCash XML book
+    #[cfg(feature = "gnucash")]
+    #[value(name = "gnucash")]
+    GnuCash,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    pub account: String,
+    pub profile: Option<CsvProfile>,
+    pub date_format: Option<String>,
+    pub categories: BTreeMap<String, String>,
+}
+
+impl ImportFormat {
+    pub fn detect(path: &Path) -> Option<ImportFormat> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(ImportFormat::Csv),
+            "ofx" | "qfx" => Some(ImportFormat::Ofx),
+            "qif" => Some(ImportFormat::Qif),
+            #[cfg(feature = "gnucash")]
+            "gnucash" => Some(ImportFormat::GnuCash),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse(
+    contents: &str,
+    format: ImportFormat,
+    options: &ImportOptions,
+) -> Result<Vec<NewTransaction>> {
+    match format {
+        ImportFormat::Csv => csv::parse(contents, options),
+        ImportFormat::Ofx => ofx::parse(contents, &options.account).map_err(ExpensoError::Import),
+        ImportFormat::Qif => qif::parse(contents, &options.account).map_err(ExpensoError::Import),
+        #[cfg(feature = "gnucash")]
+        ImportFormat::GnuCash => {
+            gnucash::parse(contents, &options.account).map_err(ExpensoError::Import)
+        }
+    }
+}
+
+pub fn read(
+    path: &Path,
+    format: Option<ImportFormat>,
+    options: &ImportOptions,
+) -> Result<Vec<NewTransaction>> {
+    let format = format
+        .or_else(|| ImportFormat::detect(path))
+        .ok_or_else(|| {
+            ExpensoError::InvalidInput(format!(
+                "Cannot tell the format of {}, pass --format",
+                path.display()
+            ))
+        })?;
+    let mut contents = fs::read(path)?;
+    if contents.starts_with(&[0x1f, 0x8b]) {
+        let mut decoded = Vec::new();
+        GzDecoder::new(contents.as_slice()).read_to_end(&mut decoded)?;
+        contents = decoded;
+    }
+    let contents =
+        String::from_utf8(contents).map_err(|err| ExpensoError::Import(err.to_string()))?;
+    let mut transactions = parse(&contents, format, options)?;
+    let source = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    fingerprint(&source, &mut transactions);
+    Ok(transactions)
+}
+
+/// Gives each transaction read from `source` its [`Fingerprint`]. The hash
+/// only depends on the row, not the file, so the same row in an overlapping
+/// statement gets the same one; identical rows within a file are told apart
+/// by how many came before.
+pub fn fingerprint(source: &str, transactions: &mut [NewTransaction]) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for transaction in transactions {
+        let content = format!(
+            "{}\u{1f}{:.2}\u{1f}{:?}\u{1f}{}\u{1f}{}",
+            transaction.date,
+            transaction.amount,
+            transaction.transaction_type,
+            transaction.description.trim().to_lowercase(),
+            transaction.account.trim().to_lowercase()
+        );
+        let count = counts.entry(content.clone()).or_default();
+        let hash = fnv1a(format!("{}\u{1f}{}", content, count).as_bytes());
+        *count += 1;
+        transaction.import = Some(Fingerprint {
+            source: source.to_string(),
+            hash: format!("{:016x}", hash),
+        });
+    }
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hashers gives the
+/// same hash in every build, as fingerprints saved in the data file need.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::FinanceTracker;
+
+    fn imported(external_id: Option<&str>, amount: f64) -> NewTransaction {
+        NewTransaction {
+            description: String::from("Imported"),
+            amount,
+            external_id: external_id.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_detect_format_from_extension() {
+        assert_eq!(
+            ImportFormat::detect(Path::new("statement.QFX")),
+            Some(ImportFormat::Ofx)
+        );
+        assert_eq!(
+            ImportFormat::detect(Path::new("bank/2024.ofx")),
+            Some(ImportFormat::Ofx)
+        );
+        assert_eq!(
+            ImportFormat::detect(Path::new("old.qif")),
+            Some(ImportFormat::Qif)
+        );
+        assert_eq!(
+            ImportFormat::detect(Path::new("ynab.csv")),
+            Some(ImportFormat::Csv)
+        );
+        assert_eq!(ImportFormat::detect(Path::new("statement")), None);
+    }
+
+    #[test]
+    fn test_add_transactions_skips_known_external_ids() {
+        let mut tracker = FinanceTracker::new();
+        tracker.add_transactions(vec![imported(Some("A1"), 10.0)]);
+
+        let report = tracker.add_transactions(vec![
+            imported(Some("A1"), 10.0),
+            imported(Some("B2"), 20.0),
+            imported(Some("B2"), 20.0),
+            imported(None, 5.0),
+            imported(None, -1.0),
+        ]);
+
+        assert_eq!(report.added, vec![2, 3]);
+        assert_eq!(report.duplicates, vec![0, 2]);
+        assert_eq!(report.invalid.len(), 1);
+        assert_eq!(report.invalid[0].0, 4);
+        assert_eq!(tracker.total_expense(), 35.0);
+        assert_eq!(tracker.history().len(), 3);
+    }
+
+    #[test]
+    fn test_reimporting_overlapping_statement_skips_known_rows() {
+        let row = |date: &str, description: &str, amount: f64| NewTransaction {
+            description: String::from(description),
+            amount,
+            date: String::from(date),
+            ..Default::default()
+        };
+        let mut march = vec![
+            row("2024-03-02", "Coffee", 4.5),
+            row("2024-03-02", "Coffee", 4.5),
+            row("2024-03-30", "Groceries", 80.0),
+        ];
+        let mut overlap = vec![
+            row("2024-03-02", "COFFEE", 4.5),
+            row("2024-03-02", "Coffee", 4.5),
+            row("2024-03-02", "Coffee", 4.5),
+            row("2024-03-30", "Groceries", 80.0),
+            row("2024-04-01", "Rent", 1200.0),
+        ];
+        fingerprint("march.csv", &mut march);
+        fingerprint("march-april.csv", &mut overlap);
+        assert_eq!(
+            march[0].import,
+            overlap[0].import.clone().map(|import| Fingerprint {
+                source: String::from("march.csv"),
+                ..import
+            })
+        );
+        assert_ne!(march[0].import, march[1].import);
+
+        let mut tracker = FinanceTracker::new();
+        assert_eq!(tracker.add_transactions(march).added.len(), 3);
+        let report = tracker.add_transactions(overlap);
+        assert_eq!(report.duplicates, vec![0, 1, 3]);
+        assert_eq!(report.added.len(), 2);
+        let rent = tracker.get_transaction(report.added[1]).unwrap();
+        assert_eq!(rent.import.as_ref().unwrap().source, "march-april.csv");
+    }
+}
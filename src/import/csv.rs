@@ -0,0 +1,576 @@
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+
+use crate::error::{ExpensoError, Result};
+use crate::{NewTransaction, TransactionType, dates};
+
+use super::ImportOptions;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum CsvProfile {
+    /// expenso's own CSV export (date, description, type, category, account, amount, ...)
+    Expenso,
+    /// YNAB register export
+    Ynab,
+    /// Mint transactions export
+    Mint,
+    /// Personal Capital (Empower) transactions export
+    PersonalCapital,
+    /// Expensify expense or report export
+    Expensify,
+}
+
+#[derive(Debug)]
+enum Amount {
+    Signed(&'static str),
+    Outflow(&'static str),
+    /// The type in a column of its own, `kinds` mapping the profile's own
+    /// spellings to [`TransactionType`] names.
+    Typed {
+        amount: &'static str,
+        kind: &'static str,
+        kinds: &'static [(&'static str, &'static str)],
+    },
+    Split {
+        outflow: &'static str,
+        inflow: &'static str,
+    },
+}
+
+#[derive(Debug)]
+struct Profile {
+    date: &'static str,
+    date_formats: &'static [&'static str],
+    amount: Amount,
+    description: &'static [&'static str],
+    payee: Option<&'static str>,
+    notes: Option<&'static str>,
+    category: Option<&'static str>,
+    account: Option<&'static str>,
+    tags: Option<&'static str>,
+    report: Option<&'static str>,
+    receipt: Option<&'static str>,
+    reimbursable: Option<&'static str>,
+    transfer_prefix: Option<&'static str>,
+    categories: &'static [(&'static str, &'static str)],
+}
+
+const EXPENSO: Profile = Profile {
+    date: "date",
+    date_formats: &[dates::ISO_FORMAT],
+    amount: Amount::Typed {
+        amount: "amount",
+        kind: "type",
+        kinds: &[],
+    },
+    description: &["description"],
+    payee: Some("payee"),
+    notes: Some("notes"),
+    category: Some("category"),
+    account: Some("account"),
+    tags: Some("tags"),
+    report: None,
+    receipt: None,
+    reimbursable: None,
+    transfer_prefix: None,
+    categories: &[],
+};
+
+const YNAB: Profile = Profile {
+    date: "Date",
+    date_formats: &["%m/%d/%Y", dates::ISO_FORMAT, "%d/%m/%Y"],
+    amount: Amount::Split {
+        outflow: "Outflow",
+        inflow: "Inflow",
+    },
+    description: &["Memo", "Payee"],
+    payee: Some("Payee"),
+    notes: None,
+    category: Some("Category"),
+    account: Some("Account"),
+    tags: None,
+    report: None,
+    receipt: None,
+    reimbursable: None,
+    transfer_prefix: Some("Transfer : "),
+    categories: &[],
+};
+
+const MINT: Profile = Profile {
+    date: "Date",
+    date_formats: &["%m/%d/%Y", dates::ISO_FORMAT],
+    amount: Amount::Typed {
+        amount: "Amount",
+        kind: "Transaction Type",
+        kinds: &[("credit", "income"), ("debit", "expense")],
+    },
+    description: &["Description", "Original Description"],
+    payee: None,
+    notes: Some("Notes"),
+    category: Some("Category"),
+    account: Some("Account Name"),
+    tags: Some("Labels"),
+    report: None,
+    receipt: None,
+    reimbursable: None,
+    transfer_prefix: None,
+    categories: &[
+        ("Groceries", "Food"),
+        ("Restaurants", "Food"),
+        ("Fast Food", "Food"),
+        ("Coffee Shops", "Food"),
+        ("Food & Dining", "Food"),
+        ("Gas & Fuel", "Transport"),
+        ("Public Transportation", "Transport"),
+        ("Auto & Transport", "Transport"),
+        ("Mortgage & Rent", "Housing"),
+        ("Utilities", "Bills"),
+        ("Mobile Phone", "Bills"),
+        ("Internet", "Bills"),
+        ("Paycheck", "Salary"),
+        ("Income", "Salary"),
+        ("Transfer", "Transfer"),
+        ("Credit Card Payment", "Transfer"),
+    ],
+};
+
+const PERSONAL_CAPITAL: Profile = Profile {
+    date: "Date",
+    date_formats: &[dates::ISO_FORMAT, "%m/%d/%Y"],
+    amount: Amount::Signed("Amount"),
+    description: &["Description"],
+    payee: None,
+    notes: None,
+    category: Some("Category"),
+    account: Some("Account"),
+    tags: Some("Tags"),
+    report: None,
+    receipt: None,
+    reimbursable: None,
+    transfer_prefix: None,
+    categories: &[
+        ("Groceries", "Food"),
+        ("Restaurants", "Food"),
+        ("Gasoline/Fuel", "Transport"),
+        ("Automotive", "Transport"),
+        ("Mortgages", "Housing"),
+        ("Rent", "Housing"),
+        ("Utilities", "Bills"),
+        ("Telephone", "Bills"),
+        ("Paychecks/Salary", "Salary"),
+        ("Transfers", "Transfer"),
+        ("Credit Card Payments", "Transfer"),
+    ],
+};
+
+const EXPENSIFY: Profile = Profile {
+    date: "Timestamp",
+    date_formats: &["%Y-%m-%d %H:%M:%S", dates::ISO_FORMAT, "%m/%d/%Y"],
+    amount: Amount::Outflow("Amount"),
+    description: &["Merchant"],
+    payee: Some("Merchant"),
+    notes: Some("Comment"),
+    category: Some("Category"),
+    account: None,
+    tags: Some("Tag"),
+    report: Some("Report Name"),
+    receipt: Some("Receipt"),
+    reimbursable: Some("Reimbursable"),
+    transfer_prefix: None,
+    categories: &[],
+};
+
+impl CsvProfile {
+    const ALL: [CsvProfile; 5] = [
+        CsvProfile::Ynab,
+        CsvProfile::Mint,
+        CsvProfile::Expenso,
+        CsvProfile::PersonalCapital,
+        CsvProfile::Expensify,
+    ];
+
+    fn profile(self) -> &'static Profile {
+        match self {
+            CsvProfile::Expenso => &EXPENSO,
+            CsvProfile::Ynab => &YNAB,
+            CsvProfile::Mint => &MINT,
+            CsvProfile::PersonalCapital => &PERSONAL_CAPITAL,
+            CsvProfile::Expensify => &EXPENSIFY,
+        }
+    }
+
+    fn detect(headers: &Headers) -> Option<CsvProfile> {
+        CsvProfile::ALL
+            .into_iter()
+            .find(|profile| profile.profile().required().all(|name| headers.has(name)))
+    }
+}
+
+impl Profile {
+    fn required(&self) -> impl Iterator<Item = &'static str> {
+        let amount = match self.amount {
+            Amount::Signed(amount) | Amount::Outflow(amount) => vec![amount],
+            Amount::Typed { amount, kind, .. } => vec![amount, kind],
+            Amount::Split { outflow, inflow } => vec![outflow, inflow],
+        };
+        std::iter::once(self.date).chain(amount)
+    }
+
+    fn map_category(&self, category: &str, overrides: &BTreeMap<String, String>) -> String {
+        overrides
+            .iter()
+            .map(|(from, to)| (from.as_str(), to.as_str()))
+            .chain(self.categories.iter().copied())
+            .find(|(from, _)| from.eq_ignore_ascii_case(category))
+            .map_or(category, |(_, to)| to)
+            .to_string()
+    }
+}
+
+struct Headers(HashMap<String, usize>);
+
+impl Headers {
+    fn new(record: &csv::StringRecord) -> Self {
+        Headers(
+            record
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.trim().to_lowercase(), index))
+                .collect(),
+        )
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.0.contains_key(&name.to_lowercase())
+    }
+
+    fn get<'r>(&self, record: &'r csv::StringRecord, name: &str) -> Option<&'r str> {
+        let index = self.0.get(&name.to_lowercase())?;
+        record
+            .get(*index)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+pub fn parse(contents: &str, options: &ImportOptions) -> Result<Vec<NewTransaction>> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(contents.as_bytes());
+
+    let headers = Headers::new(reader.headers().map_err(invalid)?);
+    let profile = match options.profile {
+        Some(profile) => profile,
+        None => CsvProfile::detect(&headers)
+            .ok_or_else(|| invalid("Unrecognised CSV columns, pass --profile"))?,
+    }
+    .profile();
+    if let Some(missing) = profile.required().find(|name| !headers.has(name)) {
+        return Err(invalid(format!("Missing column '{}'", missing)));
+    }
+
+    reader
+        .records()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = record.map_err(invalid)?;
+            to_transaction(profile, &headers, &record, options)
+                .map_err(|err| invalid(format!("Row {}: {}", index + 2, err)))
+        })
+        .collect()
+}
+
+fn to_transaction(
+    profile: &Profile,
+    headers: &Headers,
+    record: &csv::StringRecord,
+    options: &ImportOptions,
+) -> Result<NewTransaction> {
+    let field = |name: Option<&str>| name.and_then(|name| headers.get(record, name));
+    let required = |name: &str, what: &str| {
+        field(Some(name)).ok_or_else(|| invalid(format!("missing {}", what)))
+    };
+
+    let raw_date = required(profile.date, "date")?;
+    let date = parse_date(raw_date, profile, options.date_format.as_deref())
+        .ok_or_else(|| invalid(format!("invalid date '{}'", raw_date)))?;
+
+    let (amount, transaction_type) = match profile.amount {
+        Amount::Signed(amount) => by_sign(parse_amount(required(amount, "amount")?)?),
+        Amount::Outflow(amount) => by_sign(-parse_amount(required(amount, "amount")?)?),
+        Amount::Typed {
+            amount,
+            kind,
+            kinds,
+        } => {
+            let value = parse_amount(required(amount, "amount")?)?;
+            let kind = required(kind, "type")?;
+            let kind = kinds
+                .iter()
+                .find(|(from, _)| from.eq_ignore_ascii_case(kind))
+                .map_or(kind, |(_, to)| to);
+            (value.abs(), TransactionType::from_str(kind)?)
+        }
+        Amount::Split { outflow, inflow } => {
+            let outflow = field(Some(outflow)).map(parse_amount).transpose()?;
+            let inflow = field(Some(inflow)).map(parse_amount).transpose()?;
+            by_sign(inflow.unwrap_or(0.0) - outflow.unwrap_or(0.0))
+        }
+    };
+
+    let payee = field(profile.payee).map(str::to_string);
+    let notes = field(profile.notes).map(str::to_string);
+    let description = profile
+        .description
+        .iter()
+        .find_map(|name| field(Some(name)))
+        .unwrap_or("Imported transaction")
+        .to_string();
+
+    let is_transfer = profile
+        .transfer_prefix
+        .zip(payee.as_deref())
+        .is_some_and(|(prefix, payee)| payee.starts_with(prefix));
+    let category = if is_transfer {
+        String::from("Transfer")
+    } else {
+        field(profile.category).map_or_else(
+            || String::from("Uncategorized"),
+            |category| profile.map_category(category, &options.categories),
+        )
+    };
+
+    let mut tags: Vec<String> = field(profile.tags)
+        .map(|tags| {
+            tags.split([';', ','])
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    tags.extend(field(profile.report).map(str::to_string));
+    if field(profile.reimbursable)
+        .is_some_and(|value| matches!(value.to_lowercase().as_str(), "yes" | "y" | "true"))
+    {
+        tags.push(String::from("reimbursable"));
+    }
+
+    Ok(NewTransaction {
+        description,
+        amount,
+        date: date.format(dates::ISO_FORMAT).to_string(),
+        transaction_type,
+        category,
+        payee,
+        notes,
+        tags,
+        account: field(profile.account)
+            .unwrap_or(&options.account)
+            .to_string(),
+        receipt: field(profile.receipt).map(str::to_string),
+        ..Default::default()
+    })
+}
+
+fn parse_date(input: &str, profile: &Profile, date_format: Option<&str>) -> Option<NaiveDate> {
+    match date_format {
+        Some(format) => NaiveDate::parse_from_str(input, format).ok(),
+        None => profile
+            .date_formats
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(input, format).ok()),
+    }
+}
+
+/// Splits a signed `value` into its amount and type, money in being income.
+fn by_sign(value: f64) -> (f64, TransactionType) {
+    let transaction_type = if value > 0.0 {
+        TransactionType::Income
+    } else {
+        TransactionType::Expense
+    };
+    (value.abs(), transaction_type)
+}
+
+fn parse_amount(input: &str) -> Result<f64> {
+    let negative = input.starts_with('(') && input.ends_with(')') || input.contains('-');
+    let digits: String = input
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let value: f64 = digits
+        .parse()
+        .map_err(|_| invalid(format!("invalid amount '{}'", input)))?;
+    Ok(if negative { -value } else { value })
+}
+
+fn invalid(message: impl ToString) -> ExpensoError {
+    ExpensoError::Import(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn options() -> ImportOptions {
+        ImportOptions {
+            account: String::from("Cash"),
+            ..Default::default()
+        }
+    }
+
+    const YNAB_EXPORT: &str = "\u{feff}\"Account\",\"Flag\",\"Date\",\"Payee\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Memo\",\"Outflow\",\"Inflow\",\"Cleared\"
+\"Checking\",\"\",\"03/05/2024\",\"Corner Market\",\"Everyday: Groceries\",\"Everyday\",\"Groceries\",\"Weekly shop\",\"$1,042.17\",\"$0.00\",\"Cleared\"
+\"Checking\",\"\",\"03/01/2024\",\"Acme Payroll\",\"Inflow: Ready to Assign\",\"Inflow\",\"Ready to Assign\",\"\",\"$0.00\",\"$2,500.00\",\"Cleared\"
+\"Checking\",\"\",\"03/02/2024\",\"Transfer : Savings\",\"\",\"\",\"\",\"\",\"$200.00\",\"$0.00\",\"Cleared\"
+";
+
+    #[test]
+    fn test_ynab_profile() {
+        let transactions = parse(YNAB_EXPORT, &options()).unwrap();
+        assert_eq!(transactions.len(), 3);
+
+        let shop = &transactions[0];
+        assert_eq!(shop.description, "Weekly shop");
+        assert_eq!(shop.payee.as_deref(), Some("Corner Market"));
+        assert_eq!(shop.amount, 1042.17);
+        assert_eq!(shop.transaction_type, TransactionType::Expense);
+        assert_eq!(shop.date, "2024-03-05");
+        assert_eq!(shop.category, "Groceries");
+        assert_eq!(shop.account, "Checking");
+
+        let salary = &transactions[1];
+        assert_eq!(salary.description, "Acme Payroll");
+        assert_eq!(salary.transaction_type, TransactionType::Income);
+        assert_eq!(salary.amount, 2500.0);
+
+        assert_eq!(transactions[2].category, "Transfer");
+    }
+
+    #[test]
+    fn test_expenso_profile_round_trip() {
+        let csv = "id,date,description,type,category,account,amount,recurring,payee,notes,tags
+1,2024-03-01,Lunch,Expense,Food,,12.50,no,Cafe,,work;team
+2,2024-03-02,Refund,Income,Shopping,Card,5,no,,,
+3,2024-03-03,Paid back Sam,Settlement,Shared,,20,no,Sam,,
+";
+        let transactions = parse(csv, &options()).unwrap();
+        assert_eq!(transactions[0].account, "Cash");
+        assert_eq!(transactions[0].tags, vec!["work", "team"]);
+        assert_eq!(transactions[1].transaction_type, TransactionType::Income);
+        assert_eq!(transactions[1].account, "Card");
+        assert_eq!(
+            transactions[2].transaction_type,
+            TransactionType::Settlement
+        );
+        assert_eq!(transactions[2].amount, 20.0);
+    }
+
+    #[test]
+    fn test_errors_and_date_override() {
+        assert_eq!(
+            parse("when,what\n2024-01-01,x\n", &options())
+                .unwrap_err()
+                .to_string(),
+            "Unrecognised CSV columns, pass --profile"
+        );
+
+        let bad = "Date,Payee,Outflow,Inflow\n2024-13-40,x,1,\n";
+        assert_eq!(
+            parse(bad, &options()).unwrap_err().to_string(),
+            "Row 2: invalid date '2024-13-40'"
+        );
+
+        let typo = "date,description,type,amount\n2024-01-01,x,incme,5\n";
+        assert_eq!(
+            parse(typo, &options()).unwrap_err().to_string(),
+            "Row 2: invalid transaction type 'incme', expected income, expense or settlement"
+        );
+
+        let european = ImportOptions {
+            date_format: Some(String::from("%d/%m/%Y")),
+            ..options()
+        };
+        let transactions =
+            parse("Date,Payee,Outflow,Inflow\n05/03/2024,x,1,\n", &european).unwrap();
+        assert_eq!(transactions[0].date, "2024-03-05");
+    }
+
+    #[test]
+    fn test_mint_profile_maps_categories() {
+        let csv = "\"Date\",\"Description\",\"Original Description\",\"Amount\",\"Transaction Type\",\"Category\",\"Account Name\",\"Labels\",\"Notes\"
+\"1/15/2024\",\"Starbucks\",\"STARBUCKS #123\",\"4.50\",\"debit\",\"Coffee Shops\",\"Visa\",\"\",\"\"
+\"1/31/2024\",\"Acme\",\"ACME PAYROLL\",\"2500.00\",\"credit\",\"Paycheck\",\"Checking\",\"\",\"January\"
+\"2/01/2024\",\"Gym\",\"GYM\",\"40.00\",\"debit\",\"Gym\",\"Visa\",\"health\",\"\"
+";
+        let mut categories = BTreeMap::new();
+        categories.insert(String::from("coffee shops"), String::from("Coffee"));
+        let options = ImportOptions {
+            categories,
+            ..options()
+        };
+
+        let transactions = parse(csv, &options).unwrap();
+        assert_eq!(transactions[0].category, "Coffee");
+        assert_eq!(transactions[0].date, "2024-01-15");
+        assert_eq!(transactions[0].account, "Visa");
+        assert_eq!(transactions[1].category, "Salary");
+        assert_eq!(transactions[1].transaction_type, TransactionType::Income);
+        assert_eq!(transactions[1].notes.as_deref(), Some("January"));
+        assert_eq!(transactions[2].category, "Gym");
+        assert_eq!(transactions[2].tags, vec!["health"]);
+    }
+
+    #[test]
+    fn test_personal_capital_profile() {
+        let csv = "Date,Account,Description,Category,Tags,Amount
+2024-02-03,Chase Checking,Whole Foods,Groceries,,-85.20
+2024-02-15,Chase Checking,Acme Corp,Paychecks/Salary,,3000.00
+";
+        let transactions = parse(csv, &options()).unwrap();
+        assert_eq!(transactions[0].category, "Food");
+        assert_eq!(transactions[0].amount, 85.2);
+        assert_eq!(transactions[0].transaction_type, TransactionType::Expense);
+        assert_eq!(transactions[1].category, "Salary");
+        assert_eq!(transactions[1].transaction_type, TransactionType::Income);
+    }
+
+    #[test]
+    fn test_expensify_profile() {
+        let csv = "\"Timestamp\",\"Merchant\",\"Amount\",\"MCC\",\"Category\",\"Tag\",\"Comment\",\"Reimbursable\",\"Report Name\",\"Receipt\"
+\"2024-04-02 00:00:00\",\"Hilton\",\"312.40\",\"7011\",\"Travel\",\"Client A\",\"2 nights\",\"yes\",\"Berlin trip\",\"https://www.expensify.com/receipts/w_1.jpg\"
+\"2024-04-03 00:00:00\",\"Deutsche Bahn\",\"-20.00\",\"4112\",\"Travel\",\"\",\"\",\"no\",\"Berlin trip\",\"\"
+";
+        let transactions = parse(csv, &options()).unwrap();
+
+        let hotel = &transactions[0];
+        assert_eq!(hotel.date, "2024-04-02");
+        assert_eq!(hotel.amount, 312.4);
+        assert_eq!(hotel.transaction_type, TransactionType::Expense);
+        assert_eq!(hotel.payee.as_deref(), Some("Hilton"));
+        assert_eq!(hotel.notes.as_deref(), Some("2 nights"));
+        assert_eq!(hotel.tags, vec!["Client A", "Berlin trip", "reimbursable"]);
+        assert_eq!(
+            hotel.receipt.as_deref(),
+            Some("https://www.expensify.com/receipts/w_1.jpg")
+        );
+
+        let refund = &transactions[1];
+        assert_eq!(refund.transaction_type, TransactionType::Income);
+        assert_eq!(refund.tags, vec!["Berlin trip"]);
+        assert_eq!(refund.receipt, None);
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("$1,234.50").ok(), Some(1234.5));
+        assert_eq!(parse_amount("(12.00)").ok(), Some(-12.0));
+        assert_eq!(parse_amount("-3").ok(), Some(-3.0));
+        assert!(parse_amount("n/a").is_err());
+    }
+}
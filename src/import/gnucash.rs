@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use roxmltree::{Document, Node};
+
+use crate::{NewTransaction, TransactionType};
+
+const GNC_NS: &str = "http://www.gnucash.org/XML/gnc";
+const CATEGORY_TYPES: [&str; 3] = ["INCOME", "EXPENSE", "EQUITY"];
+
+#[derive(Debug)]
+struct Account {
+    name: String,
+    kind: String,
+    parent: Option<String>,
+}
+
+#[derive(Debug)]
+struct Split<'a> {
+    id: &'a str,
+    account: &'a Account,
+    value: f64,
+    memo: Option<&'a str>,
+}
+
+pub fn parse(contents: &str, account: &str) -> Result<Vec<NewTransaction>, String> {
+    let document =
+        Document::parse(contents).map_err(|err| format!("Not a GnuCash XML file: {}", err))?;
+    let book = document
+        .descendants()
+        .find(|node| node.has_tag_name((GNC_NS, "book")))
+        .ok_or("Not a GnuCash XML file")?;
+
+    let accounts: HashMap<String, Account> = book
+        .children()
+        .filter(|node| node.has_tag_name((GNC_NS, "account")))
+        .filter_map(|node| {
+            let id = child_text(node, "id")?;
+            let account = Account {
+                name: child_text(node, "name").unwrap_or_default(),
+                kind: child_text(node, "type").unwrap_or_default(),
+                parent: child_text(node, "parent"),
+            };
+            Some((id, account))
+        })
+        .collect();
+
+    let mut transactions = Vec::new();
+    for node in book
+        .children()
+        .filter(|node| node.has_tag_name((GNC_NS, "transaction")))
+    {
+        let description = child_text(node, "description").unwrap_or_default();
+        let date = child(node, "date-posted")
+            .and_then(|posted| child_text(posted, "date"))
+            .and_then(|date| date.get(..10).map(str::to_string))
+            .ok_or_else(|| format!("Transaction '{}': missing date", description))?;
+
+        let splits = child(node, "splits")
+            .into_iter()
+            .flat_map(|splits| splits.children().filter(|n| is_element(*n, "split")))
+            .map(|split| to_split(split, &accounts))
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(|message| format!("Transaction '{}': {}", description, message))?;
+
+        let funding = splits
+            .iter()
+            .filter(|split| !is_category(split.account))
+            .min_by(|a, b| a.value.total_cmp(&b.value))
+            .map_or_else(|| account.to_string(), |split| split.account.name.clone());
+
+        let categories: Vec<&Split> = splits
+            .iter()
+            .filter(|split| is_category(split.account))
+            .collect();
+        if categories.is_empty() {
+            // A transfer between asset or liability accounts, recorded from the paying side.
+            if let Some(from) = splits
+                .iter()
+                .filter(|split| split.value < 0.0)
+                .min_by(|a, b| a.value.total_cmp(&b.value))
+            {
+                transactions.push(NewTransaction {
+                    description: description.clone(),
+                    amount: -from.value,
+                    date: date.clone(),
+                    transaction_type: TransactionType::Expense,
+                    category: String::from("Transfer"),
+                    notes: from.memo.map(str::to_string),
+                    account: from.account.name.clone(),
+                    external_id: Some(format!("gnucash:{}", from.id)),
+                    ..Default::default()
+                });
+            }
+            continue;
+        }
+
+        for split in categories {
+            transactions.push(NewTransaction {
+                description: description.clone(),
+                amount: split.value.abs(),
+                date: date.clone(),
+                transaction_type: if split.value > 0.0 {
+                    TransactionType::Expense
+                } else {
+                    TransactionType::Income
+                },
+                category: category_path(split.account, &accounts),
+                notes: split.memo.map(str::to_string),
+                account: funding.clone(),
+                external_id: Some(format!("gnucash:{}", split.id)),
+                ..Default::default()
+            });
+        }
+    }
+
+    Ok(transactions)
+}
+
+fn to_split<'a>(
+    node: Node<'a, '_>,
+    accounts: &'a HashMap<String, Account>,
+) -> Result<Split<'a>, String> {
+    let id = child(node, "id")
+        .and_then(|n| n.text())
+        .ok_or("split without id")?;
+    let account_id = child_text(node, "account").ok_or("split without account")?;
+    let account = accounts
+        .get(&account_id)
+        .ok_or_else(|| format!("unknown account {}", account_id))?;
+    let value = child_text(node, "value").ok_or("split without value")?;
+    Ok(Split {
+        id,
+        account,
+        value: parse_value(&value)?,
+        memo: child(node, "memo")
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .filter(|memo| !memo.is_empty()),
+    })
+}
+
+fn is_category(account: &Account) -> bool {
+    CATEGORY_TYPES.contains(&account.kind.as_str())
+}
+
+fn category_path(account: &Account, accounts: &HashMap<String, Account>) -> String {
+    let mut names = vec![account.name.as_str()];
+    let mut parent = account.parent.as_ref().and_then(|id| accounts.get(id));
+    while let Some(current) = parent {
+        if current.kind == "ROOT" {
+            break;
+        }
+        names.push(&current.name);
+        parent = current.parent.as_ref().and_then(|id| accounts.get(id));
+    }
+    // Drop the top-level "Expenses"/"Income" account unless it is the only one.
+    if names.len() > 1 {
+        names.pop();
+    }
+    names.reverse();
+    names.join(":")
+}
+
+fn parse_value(value: &str) -> Result<f64, String> {
+    let invalid = || format!("invalid amount '{}'", value);
+    let (numerator, denominator) = value.trim().split_once('/').unwrap_or((value.trim(), "1"));
+    let numerator: f64 = numerator.parse().map_err(|_| invalid())?;
+    let denominator: f64 = denominator.parse().map_err(|_| invalid())?;
+    if denominator == 0.0 {
+        return Err(invalid());
+    }
+    Ok(numerator / denominator)
+}
+
+fn is_element(node: Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| is_element(*child, name))
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn account(id: &str, name: &str, kind: &str, parent: Option<&str>) -> String {
+        let parent = parent.map_or_else(String::new, |parent| {
+            format!("<act:parent type=\"guid\">{}</act:parent>", parent)
+        });
+        format!(
+            "<gnc:account version=\"2.0.0\"><act:name>{}</act:name><act:id type=\"guid\">{}</act:id><act:type>{}</act:type>{}</gnc:account>",
+            name, id, kind, parent
+        )
+    }
+
+    fn transaction(description: &str, date: &str, splits: &[(&str, &str, &str)]) -> String {
+        let splits: String = splits
+            .iter()
+            .map(|(id, account, value)| {
+                format!(
+                    "<trn:split><split:id type=\"guid\">{}</split:id><split:memo></split:memo><split:value>{}</split:value><split:quantity>{}</split:quantity><split:account type=\"guid\">{}</split:account></trn:split>",
+                    id, value, value, account
+                )
+            })
+            .collect();
+        format!(
+            "<gnc:transaction version=\"2.0.0\"><trn:description>{}</trn:description><trn:date-posted><ts:date>{} 10:59:00 +0000</ts:date></trn:date-posted><trn:splits>{}</trn:splits></gnc:transaction>",
+            description, date, splits
+        )
+    }
+
+    fn book() -> String {
+        let accounts = [
+            account("root", "Root Account", "ROOT", None),
+            account("assets", "Assets", "ASSET", Some("root")),
+            account("checking", "Checking Account", "BANK", Some("assets")),
+            account("savings", "Savings Account", "BANK", Some("assets")),
+            account("expenses", "Expenses", "EXPENSE", Some("root")),
+            account("food", "Food", "EXPENSE", Some("expenses")),
+            account("groceries", "Groceries", "EXPENSE", Some("food")),
+            account("income", "Income", "INCOME", Some("root")),
+            account("salary", "Salary", "INCOME", Some("income")),
+        ]
+        .concat();
+        let transactions = [
+            transaction(
+                "Corner Market",
+                "2024-03-05",
+                &[
+                    ("s1", "checking", "-4217/100"),
+                    ("s2", "groceries", "4217/100"),
+                ],
+            ),
+            transaction(
+                "Acme Payroll",
+                "2024-03-01",
+                &[
+                    ("s3", "checking", "250000/100"),
+                    ("s4", "salary", "-250000/100"),
+                ],
+            ),
+            transaction(
+                "Move to savings",
+                "2024-03-02",
+                &[
+                    ("s5", "checking", "-20000/100"),
+                    ("s6", "savings", "20000/100"),
+                ],
+            ),
+        ]
+        .concat();
+        format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\" ?>
+<gnc-v2 xmlns:gnc=\"http://www.gnucash.org/XML/gnc\" xmlns:act=\"http://www.gnucash.org/XML/act\" xmlns:trn=\"http://www.gnucash.org/XML/trn\" xmlns:split=\"http://www.gnucash.org/XML/split\" xmlns:ts=\"http://www.gnucash.org/XML/ts\">
+<gnc:book version=\"2.0.0\">{}{}</gnc:book></gnc-v2>",
+            accounts, transactions
+        )
+    }
+
+    #[test]
+    fn test_parse_book_maps_account_tree() {
+        let transactions = parse(&book(), "Cash").unwrap();
+        assert_eq!(transactions.len(), 3);
+
+        let groceries = &transactions[0];
+        assert_eq!(groceries.description, "Corner Market");
+        assert_eq!(groceries.date, "2024-03-05");
+        assert_eq!(groceries.amount, 42.17);
+        assert_eq!(groceries.transaction_type, TransactionType::Expense);
+        assert_eq!(groceries.category, "Food:Groceries");
+        assert_eq!(groceries.account, "Checking Account");
+        assert_eq!(groceries.external_id.as_deref(), Some("gnucash:s2"));
+        assert_eq!(groceries.notes, None);
+
+        let salary = &transactions[1];
+        assert_eq!(salary.transaction_type, TransactionType::Income);
+        assert_eq!(salary.category, "Salary");
+        assert_eq!(salary.amount, 2500.0);
+
+        let transfer = &transactions[2];
+        assert_eq!(transfer.category, "Transfer");
+        assert_eq!(transfer.account, "Checking Account");
+        assert_eq!(transfer.amount, 200.0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(
+            parse("not xml", "Cash")
+                .unwrap_err()
+                .starts_with("Not a GnuCash XML file")
+        );
+        assert_eq!(
+            parse("<gnc-v2/>", "Cash").unwrap_err(),
+            "Not a GnuCash XML file"
+        );
+        assert_eq!(parse_value("-4217/100"), Ok(-42.17));
+        assert!(parse_value("1/0").is_err());
+    }
+}
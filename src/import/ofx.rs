@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::{NewTransaction, TransactionType, dates};
+
+pub fn parse(contents: &str, account: &str) -> Result<Vec<NewTransaction>, String> {
+    if !contents.to_uppercase().contains("<OFX") {
+        return Err(String::from("Not an OFX/QFX file"));
+    }
+
+    let mut transactions = Vec::new();
+    let mut current: Option<HashMap<String, String>> = None;
+
+    for (tag, value) in elements(contents) {
+        match tag.as_str() {
+            "STMTTRN" => current = Some(HashMap::new()),
+            "/STMTTRN" => {
+                if let Some(fields) = current.take() {
+                    let number = transactions.len() + 1;
+                    let transaction = to_transaction(&fields, account)
+                        .map_err(|message| format!("Transaction {}: {}", number, message))?;
+                    transactions.push(transaction);
+                }
+            }
+            _ => {
+                if let Some(fields) = current.as_mut()
+                    && !value.is_empty()
+                {
+                    fields.entry(tag).or_insert(value);
+                }
+            }
+        }
+    }
+
+    Ok(transactions)
+}
+
+fn elements(contents: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    contents.split('<').skip(1).filter_map(|piece| {
+        let (tag, rest) = piece.split_once('>')?;
+        let tag = tag.trim().to_uppercase();
+        let value = unescape(rest.trim());
+        Some((tag, value))
+    })
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn to_transaction(
+    fields: &HashMap<String, String>,
+    account: &str,
+) -> Result<NewTransaction, String> {
+    let field = |name: &str| fields.get(name).cloned();
+
+    let raw_amount = field("TRNAMT").ok_or("missing TRNAMT")?;
+    let amount: f64 = raw_amount
+        .replace(',', ".")
+        .parse()
+        .map_err(|_| format!("invalid amount '{}'", raw_amount))?;
+
+    let posted = field("DTPOSTED").ok_or("missing DTPOSTED")?;
+    let date = posted
+        .get(..8)
+        .and_then(|day| NaiveDate::parse_from_str(day, "%Y%m%d").ok())
+        .ok_or_else(|| format!("invalid date '{}'", posted))?;
+
+    let name = field("NAME");
+    let memo = field("MEMO").filter(|memo| Some(memo) != name.as_ref());
+    let description = name
+        .clone()
+        .or_else(|| memo.clone())
+        .unwrap_or_else(|| String::from("Imported transaction"));
+
+    Ok(NewTransaction {
+        description,
+        amount: amount.abs(),
+        date: date.format(dates::ISO_FORMAT).to_string(),
+        transaction_type: if amount > 0.0 {
+            TransactionType::Income
+        } else {
+            TransactionType::Expense
+        },
+        category: String::from("Uncategorized"),
+        payee: name,
+        notes: memo,
+        account: account.to_string(),
+        external_id: field("FITID"),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const SGML: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+
+<OFX>
+<BANKMSGSRSV1><STMTTRNRS><STMTRS>
+<BANKTRANLIST>
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20240305120000[-5:EST]
+<TRNAMT>-42.17
+<FITID>2024030501
+<NAME>CORNER MARKET
+<MEMO>Card purchase
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20240301
+<TRNAMT>2500.00
+<FITID>2024030102
+<NAME>ACME PAYROLL &amp; CO
+</STMTTRN>
+</BANKTRANLIST>
+</STMTRS></STMTTRNRS></BANKMSGSRSV1>
+</OFX>";
+
+    #[test]
+    fn test_parse_sgml_statement() {
+        let transactions = parse(SGML, "Checking").unwrap();
+        assert_eq!(transactions.len(), 2);
+
+        let purchase = &transactions[0];
+        assert_eq!(purchase.description, "CORNER MARKET");
+        assert_eq!(purchase.amount, 42.17);
+        assert_eq!(purchase.transaction_type, TransactionType::Expense);
+        assert_eq!(purchase.date, "2024-03-05");
+        assert_eq!(purchase.notes.as_deref(), Some("Card purchase"));
+        assert_eq!(purchase.external_id.as_deref(), Some("2024030501"));
+        assert_eq!(purchase.account, "Checking");
+
+        let salary = &transactions[1];
+        assert_eq!(salary.payee.as_deref(), Some("ACME PAYROLL & CO"));
+        assert_eq!(salary.transaction_type, TransactionType::Income);
+    }
+
+    #[test]
+    fn test_parse_xml_statement() {
+        let xml = r#"<?xml version="1.0"?><?OFX OFXHEADER="200"?>
+<OFX><BANKTRANLIST>
+<STMTTRN><DTPOSTED>20240102</DTPOSTED><TRNAMT>-3.50</TRNAMT><FITID>X9</FITID><MEMO>Coffee</MEMO></STMTTRN>
+</BANKTRANLIST></OFX>"#;
+
+        let transactions = parse(xml, "Cash").unwrap();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].description, "Coffee");
+        assert_eq!(transactions[0].payee, None);
+        assert_eq!(transactions[0].date, "2024-01-02");
+    }
+
+    #[test]
+    fn test_parse_reports_bad_transactions() {
+        let bad = "<OFX><STMTTRN><DTPOSTED>20240102<TRNAMT>abc</STMTTRN></OFX>";
+        assert_eq!(
+            parse(bad, "Cash").unwrap_err(),
+            "Transaction 1: invalid amount 'abc'"
+        );
+        assert!(parse("date,amount\n", "Cash").is_err());
+    }
+}
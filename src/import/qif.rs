@@ -0,0 +1,188 @@
+use chrono::NaiveDate;
+
+use crate::{NewTransaction, TransactionType, dates};
+
+#[derive(Debug, Default)]
+struct Record {
+    date: Option<String>,
+    amount: Option<String>,
+    payee: Option<String>,
+    memo: Option<String>,
+    category: Option<String>,
+}
+
+pub fn parse(contents: &str, account: &str) -> Result<Vec<NewTransaction>, String> {
+    let mut transactions = Vec::new();
+    let mut record = Record::default();
+
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('!') {
+            continue;
+        }
+        if line.starts_with('^') {
+            let finished = std::mem::take(&mut record);
+            if finished.date.is_some() || finished.amount.is_some() {
+                let number = transactions.len() + 1;
+                let transaction = to_transaction(finished, account)
+                    .map_err(|message| format!("Transaction {}: {}", number, message))?;
+                transactions.push(transaction);
+            }
+            continue;
+        }
+
+        let (code, value) = line.split_at(1);
+        let value = value.trim().to_string();
+        match code {
+            "D" => record.date = Some(value),
+            "T" | "U" => record.amount = Some(value),
+            "P" => record.payee = Some(value),
+            "M" => record.memo = Some(value),
+            "L" => record.category = Some(value),
+            _ => {}
+        }
+    }
+
+    if transactions.is_empty() && !contents.trim_start().starts_with('!') {
+        return Err(String::from("Not a QIF file"));
+    }
+    Ok(transactions)
+}
+
+fn to_transaction(record: Record, account: &str) -> Result<NewTransaction, String> {
+    let raw_amount = record.amount.ok_or("missing amount")?;
+    let amount: f64 = raw_amount
+        .replace(',', "")
+        .parse()
+        .map_err(|_| format!("invalid amount '{}'", raw_amount))?;
+
+    let raw_date = record.date.ok_or("missing date")?;
+    let date = parse_date(&raw_date).ok_or_else(|| format!("invalid date '{}'", raw_date))?;
+
+    let payee = record.payee.filter(|payee| !payee.is_empty());
+    let memo = record.memo.filter(|memo| !memo.is_empty());
+    let description = payee
+        .clone()
+        .or_else(|| memo.clone())
+        .unwrap_or_else(|| String::from("Imported transaction"));
+
+    Ok(NewTransaction {
+        description,
+        amount: amount.abs(),
+        date: date.format(dates::ISO_FORMAT).to_string(),
+        transaction_type: if amount > 0.0 {
+            TransactionType::Income
+        } else {
+            TransactionType::Expense
+        },
+        category: record
+            .category
+            .as_deref()
+            .map(category)
+            .unwrap_or_else(|| String::from("Uncategorized")),
+        payee,
+        notes: memo,
+        account: account.to_string(),
+        ..Default::default()
+    })
+}
+
+fn category(value: &str) -> String {
+    if value.starts_with('[') && value.ends_with(']') {
+        return String::from("Transfer");
+    }
+    match value.trim() {
+        "" => String::from("Uncategorized"),
+        category => category.to_string(),
+    }
+}
+
+fn parse_date(input: &str) -> Option<NaiveDate> {
+    let apostrophe = input.contains('\'');
+    let cleaned: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            if c == '\'' || c == '-' || c == '.' {
+                '/'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let parts: Vec<u32> = cleaned
+        .split('/')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+
+    let (year, month, day) = match parts.as_slice() {
+        [year, month, day] if *year > 31 => (*year as i32, *month, *day),
+        [month, day, year] if *year >= 100 => (*year as i32, *month, *day),
+        [month, day, year] if apostrophe || *year < 70 => (2000 + *year as i32, *month, *day),
+        [month, day, year] => (1900 + *year as i32, *month, *day),
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const STATEMENT: &str = "!Type:Bank
+D03/05/2024
+T-1,042.17
+PCORNER MARKET
+MWeekly shop
+LFood:Groceries
+^
+D3/ 1'24
+T2,500.00
+PACME Payroll
+LSalary
+^
+D02/28/99
+T-100.00
+L[Savings]
+^
+";
+
+    #[test]
+    fn test_parse_bank_statement() {
+        let transactions = parse(STATEMENT, "Checking").unwrap();
+        assert_eq!(transactions.len(), 3);
+
+        let shop = &transactions[0];
+        assert_eq!(shop.description, "CORNER MARKET");
+        assert_eq!(shop.amount, 1042.17);
+        assert_eq!(shop.transaction_type, TransactionType::Expense);
+        assert_eq!(shop.date, "2024-03-05");
+        assert_eq!(shop.category, "Food:Groceries");
+        assert_eq!(shop.notes.as_deref(), Some("Weekly shop"));
+        assert_eq!(shop.account, "Checking");
+
+        assert_eq!(transactions[1].transaction_type, TransactionType::Income);
+        assert_eq!(transactions[1].date, "2024-03-01");
+        assert_eq!(transactions[2].category, "Transfer");
+        assert_eq!(transactions[2].date, "1999-02-28");
+        assert_eq!(transactions[2].description, "Imported transaction");
+    }
+
+    #[test]
+    fn test_parse_date_formats() {
+        let expected = NaiveDate::from_ymd_opt(2024, 1, 9);
+        assert_eq!(parse_date("01/09/2024"), expected);
+        assert_eq!(parse_date("1/9'24"), expected);
+        assert_eq!(parse_date("2024-01-09"), expected);
+        assert_eq!(parse_date("13/40/2024"), None);
+    }
+
+    #[test]
+    fn test_parse_reports_bad_records() {
+        assert_eq!(
+            parse("!Type:Bank\nD01/01/2024\nTabc\n^\n", "Cash").unwrap_err(),
+            "Transaction 1: invalid amount 'abc'"
+        );
+        assert!(parse("date,amount\n", "Cash").is_err());
+    }
+}
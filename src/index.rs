@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// Positions in a tracker's transaction list grouped by a key, ascending
+/// within each key, so lookups only touch the matching transactions.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PositionIndex(BTreeMap<String, Vec<usize>>);
+
+impl PositionIndex {
+    pub(crate) fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Adds a position larger than any already indexed.
+    pub(crate) fn push(&mut self, key: String, position: usize) {
+        self.0.entry(key).or_default().push(position);
+    }
+
+    pub(crate) fn insert(&mut self, key: String, position: usize) {
+        let positions = self.0.entry(key).or_default();
+        let at = positions.partition_point(|&p| p < position);
+        positions.insert(at, position);
+    }
+
+    pub(crate) fn remove(&mut self, key: &str, position: usize) {
+        if let Some(positions) = self.0.get_mut(key) {
+            positions.retain(|&p| p != position);
+            if positions.is_empty() {
+                self.0.remove(key);
+            }
+        }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> &[usize] {
+        self.0.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Positions for the keys from `from` to `to` inclusive, in key order.
+    pub(crate) fn range(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> impl Iterator<Item = usize> + use<'_> {
+        self.0
+            .range::<str, _>(bounds(from, to))
+            .flat_map(|(_, positions)| positions.iter().copied())
+    }
+
+    /// Positions for every key containing `needle`, in key order, repeated
+    /// when a position is under several of them. Only the keys are scanned.
+    pub(crate) fn containing<'a>(&'a self, needle: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.0
+            .iter()
+            .filter(move |(key, _)| key.contains(needle))
+            .flat_map(|(_, positions)| positions.iter().copied())
+    }
+
+    /// Positions for every key starting with `prefix`, in key order.
+    pub(crate) fn with_prefix<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = usize> + 'a {
+        self.0
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(key, _)| key.starts_with(prefix))
+            .flat_map(|(_, positions)| positions.iter().copied())
+    }
+}
+
+/// The lowercased words in `text`, the keys of the search index.
+pub(crate) fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn bounds<'a>(from: Option<&'a str>, to: Option<&'a str>) -> (Bound<&'a str>, Bound<&'a str>) {
+    match (from, to) {
+        // BTreeMap::range panics on a reversed range; this one is just empty.
+        (Some(from), Some(to)) if from > to => (Bound::Excluded(to), Bound::Included(to)),
+        _ => (
+            from.map_or(Bound::Unbounded, Bound::Included),
+            to.map_or(Bound::Unbounded, Bound::Included),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_positions_stay_sorted() {
+        let mut index = PositionIndex::default();
+        index.push(String::from("2024-01-02"), 0);
+        index.push(String::from("2024-01-01"), 1);
+        index.insert(String::from("2024-01-02"), 2);
+        index.insert(String::from("2024-01-02"), 1);
+        index.remove("2024-01-01", 1);
+
+        assert_eq!(index.get("2024-01-02"), &[0, 1, 2]);
+        assert!(index.get("2024-01-01").is_empty());
+        assert_eq!(
+            index.range(Some("2024-01-01"), None).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            index.range(Some("2024-02-01"), Some("2024-01-01")).count(),
+            0
+        );
+
+        index.push(String::from("food"), 3);
+        index.push(String::from("food:coffee"), 4);
+        index.push(String::from("foodstuff"), 5);
+        assert_eq!(index.with_prefix("food:").collect::<Vec<_>>(), vec![4]);
+        assert_eq!(index.containing("stuf").collect::<Vec<_>>(), vec![5]);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words("Lunch @ Café-Nord, 2x"),
+            vec!["lunch", "café", "nord", "2x"]
+        );
+        assert!(words(" $ ").is_empty());
+    }
+}
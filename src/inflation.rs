@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use crate::error::{ExpensoError, Result};
+use crate::{Transaction, money};
+
+/// Restates amounts in one year's money using a consumer price index per
+/// year, so totals from different years compare in purchasing power.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceIndex<'a> {
+    table: &'a BTreeMap<i32, f64>,
+    year: i32,
+    base: f64,
+}
+
+impl<'a> PriceIndex<'a> {
+    /// Amounts are restated in `year`'s money, the latest year in `table`
+    /// when `None`.
+    pub fn new(table: &'a BTreeMap<i32, f64>, year: Option<i32>) -> Result<Self> {
+        if let Some((year, index)) = table
+            .iter()
+            .find(|(_, index)| !(index.is_finite() && **index > 0.0))
+        {
+            return Err(ExpensoError::InvalidInput(format!(
+                "CPI for {} must be a positive number, not {}",
+                year, index
+            )));
+        }
+        let (year, base) = match year {
+            Some(year) => table.get_key_value(&year),
+            None => table.iter().next_back(),
+        }
+        .map(|(year, base)| (*year, *base))
+        .ok_or_else(|| {
+            ExpensoError::InvalidInput(match year {
+                Some(year) => format!("no CPI for {} in the config", year),
+                None => String::from("add a [cpi] table of index per year to the config"),
+            })
+        })?;
+        Ok(PriceIndex { table, year, base })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// What one unit of money on `date` is worth in the base year. Years
+    /// missing from the table use the closest earlier one, or the first.
+    pub fn factor(&self, date: &str) -> f64 {
+        let Some(year) = date.get(..4).and_then(|year| year.parse::<i32>().ok()) else {
+            return 1.0;
+        };
+        let index = self
+            .table
+            .range(..=year)
+            .next_back()
+            .or_else(|| self.table.iter().next())
+            .map_or(self.base, |(_, index)| *index);
+        self.base / index
+    }
+
+    pub fn adjust(&self, transactions: &[&Transaction]) -> Vec<Transaction> {
+        transactions
+            .iter()
+            .map(|t| Transaction {
+                amount: money::round(t.amount * self.factor(&t.date)),
+                ..(*t).clone()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_price_index_restates_amounts() {
+        let table = BTreeMap::from([(2020, 100.0), (2022, 110.0), (2024, 125.0)]);
+        let index = PriceIndex::new(&table, None).unwrap();
+        assert_eq!(index.factor("2024-06-01"), 1.0);
+        assert_eq!(index.factor("2020-03-15"), 1.25);
+        // 2021 isn't listed, so 2020's index applies; before 2020, the first.
+        assert_eq!(index.factor("2021-12-31"), 1.25);
+        assert_eq!(index.factor("2018-01-01"), 1.25);
+        assert_eq!(index.factor("2030-01-01"), 1.0);
+
+        assert_eq!(index.year(), 2024);
+        let in_2020 = PriceIndex::new(&table, Some(2020)).unwrap();
+        assert_eq!(in_2020.factor("2024-01-01"), 0.8);
+
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Rent"),
+            amount: 880.0,
+            date: String::from("2022-05-01"),
+            ..Default::default()
+        });
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let adjusted = index.adjust(&all);
+        assert_eq!(adjusted[0].amount, 1000.0);
+        assert_eq!(adjusted[0].description, "Rent");
+
+        assert!(PriceIndex::new(&table, Some(2019)).is_err());
+        assert!(PriceIndex::new(&BTreeMap::new(), None).is_err());
+        assert!(PriceIndex::new(&BTreeMap::from([(2020, 0.0)]), None).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use std::cell::RefCell;
+use std::io;
+
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+
+thread_local! {
+    static EDITOR: RefCell<Option<DefaultEditor>> = const { RefCell::new(None) };
+}
+
+pub fn read_line(prompt: &str) -> io::Result<Option<String>> {
+    EDITOR.with_borrow_mut(|editor| {
+        let editor = match editor {
+            Some(editor) => editor,
+            None => editor.insert(DefaultEditor::new().map_err(io::Error::other)?),
+        };
+
+        match editor.readline(prompt) {
+            Ok(line) => {
+                let line = line.trim().to_string();
+                if !line.is_empty() {
+                    let _ = editor.add_history_entry(line.as_str());
+                }
+                Ok(Some(line))
+            }
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(None),
+            Err(ReadlineError::Io(err)) => Err(err),
+            Err(err) => Err(io::Error::other(err)),
+        }
+    })
+}
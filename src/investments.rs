@@ -0,0 +1,215 @@
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExpensoError, Result};
+use crate::money;
+
+/// Quantities below this are what's left of selling everything.
+const DUST: f64 = 1e-9;
+
+/// Shares, units or coins of one security.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    #[serde(rename = "symbol")]
+    pub symbol: String,
+    #[serde(rename = "quantity")]
+    pub quantity: f64,
+    /// What was paid for the quantity still held.
+    #[serde(rename = "cost_basis")]
+    pub cost_basis: f64,
+    /// Last known price per unit and the date it was set.
+    #[serde(rename = "price", default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(rename = "priced_on", default, skip_serializing_if = "Option::is_none")]
+    pub priced_on: Option<String>,
+}
+
+impl Holding {
+    pub fn average_cost(&self) -> f64 {
+        if self.quantity > DUST {
+            self.cost_basis / self.quantity
+        } else {
+            0.0
+        }
+    }
+
+    /// The quantity at the last known price, or at cost before one is set.
+    pub fn market_value(&self) -> f64 {
+        money::round(self.quantity * self.price.unwrap_or_else(|| self.average_cost()))
+    }
+
+    pub fn gain(&self) -> f64 {
+        money::round(self.market_value() - self.cost_basis)
+    }
+}
+
+/// Every holding, kept in the data file next to the transactions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Portfolio {
+    holdings: Vec<Holding>,
+}
+
+impl Portfolio {
+    pub fn holdings(&self) -> &[Holding] {
+        &self.holdings
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.holdings.is_empty()
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&Holding> {
+        self.holdings
+            .iter()
+            .find(|h| h.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    fn get_mut(&mut self, symbol: &str) -> Result<&mut Holding> {
+        self.holdings
+            .iter_mut()
+            .find(|h| h.symbol.eq_ignore_ascii_case(symbol))
+            .ok_or_else(|| ExpensoError::InvalidInput(format!("no holding of {}", symbol)))
+    }
+
+    /// Adds `quantity` bought at `price` each to the holding of `symbol`,
+    /// starting one if needed.
+    pub fn buy(&mut self, symbol: &str, quantity: f64, price: f64, date: &str) -> Result<&Holding> {
+        check_trade(quantity, price)?;
+        let index = match self
+            .holdings
+            .iter()
+            .position(|h| h.symbol.eq_ignore_ascii_case(symbol))
+        {
+            Some(index) => index,
+            None => {
+                self.holdings.push(Holding {
+                    symbol: symbol.to_uppercase(),
+                    quantity: 0.0,
+                    cost_basis: 0.0,
+                    price: None,
+                    priced_on: None,
+                });
+                self.holdings.len() - 1
+            }
+        };
+        let holding = &mut self.holdings[index];
+        holding.quantity += quantity;
+        holding.cost_basis = money::sum([holding.cost_basis, quantity * price]);
+        holding.price = Some(price);
+        holding.priced_on = Some(date.to_string());
+        Ok(holding)
+    }
+
+    /// Takes `quantity` sold at `price` each out of the holding at its
+    /// average cost, returning the realized gain. A holding sold off
+    /// entirely is removed.
+    pub fn sell(&mut self, symbol: &str, quantity: f64, price: f64, date: &str) -> Result<f64> {
+        check_trade(quantity, price)?;
+        let holding = self.get_mut(symbol)?;
+        if quantity > holding.quantity + DUST {
+            return Err(ExpensoError::InvalidInput(format!(
+                "only {} of {} held",
+                holding.quantity, holding.symbol
+            )));
+        }
+        let cost = money::round(holding.average_cost() * quantity);
+        holding.quantity -= quantity;
+        holding.cost_basis = money::round(holding.cost_basis - cost);
+        holding.price = Some(price);
+        holding.priced_on = Some(date.to_string());
+        if holding.quantity <= DUST {
+            self.holdings
+                .retain(|h| !h.symbol.eq_ignore_ascii_case(symbol));
+        }
+        Ok(money::round(quantity * price - cost))
+    }
+
+    pub fn set_price(&mut self, symbol: &str, price: f64, date: &str) -> Result<()> {
+        check_trade(1.0, price)?;
+        let holding = self.get_mut(symbol)?;
+        holding.price = Some(price);
+        holding.priced_on = Some(date.to_string());
+        Ok(())
+    }
+
+    pub fn market_value(&self) -> f64 {
+        money::sum(self.holdings.iter().map(Holding::market_value))
+    }
+
+    pub fn cost_basis(&self) -> f64 {
+        money::sum(self.holdings.iter().map(|h| h.cost_basis))
+    }
+}
+
+/// Runs `command` with `symbol` appended; it should print just the price.
+pub fn fetch_price(command: &str, symbol: &str) -> Result<f64> {
+    let mut words = command.split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| ExpensoError::InvalidInput(String::from("price_command is empty")))?;
+    let output = Command::new(program)
+        .args(words)
+        .arg(symbol)
+        .output()
+        .map_err(|err| ExpensoError::InvalidInput(format!("could not run {}: {}", program, err)))?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    match text.trim().parse::<f64>() {
+        Ok(price) if output.status.success() && price.is_finite() => Ok(price),
+        _ => Err(ExpensoError::InvalidInput(format!(
+            "{} gave no price for {}",
+            program, symbol
+        ))),
+    }
+}
+
+fn check_trade(quantity: f64, price: f64) -> Result<()> {
+    if !(quantity.is_finite() && quantity > 0.0) {
+        return Err(ExpensoError::InvalidInput(String::from(
+            "quantity must be a positive number",
+        )));
+    }
+    if !(price.is_finite() && price >= 0.0) {
+        return Err(ExpensoError::InvalidInput(String::from(
+            "price must be zero or more",
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_buy_sell_and_value_holdings() {
+        let mut portfolio = Portfolio::default();
+        portfolio.buy("vti", 10.0, 200.0, "2024-01-02").unwrap();
+        portfolio.buy("VTI", 10.0, 220.0, "2024-02-01").unwrap();
+        portfolio.buy("BTC", 0.5, 40000.0, "2024-02-01").unwrap();
+
+        let vti = portfolio.get("vti").unwrap();
+        assert_eq!(vti.symbol, "VTI");
+        assert_eq!(vti.cost_basis, 4200.0);
+        assert_eq!(vti.average_cost(), 210.0);
+
+        assert_eq!(
+            portfolio.sell("VTI", 5.0, 230.0, "2024-03-01").unwrap(),
+            100.0
+        );
+        portfolio.set_price("VTI", 250.0, "2024-04-01").unwrap();
+        let vti = portfolio.get("VTI").unwrap();
+        assert_eq!(vti.cost_basis, 3150.0);
+        assert_eq!(vti.market_value(), 3750.0);
+        assert_eq!(vti.gain(), 600.0);
+        assert_eq!(portfolio.market_value(), 23750.0);
+
+        assert!(portfolio.sell("VTI", 20.0, 250.0, "2024-04-01").is_err());
+        portfolio.sell("BTC", 0.5, 60000.0, "2024-04-02").unwrap();
+        assert!(portfolio.get("BTC").is_none());
+        assert!(portfolio.set_price("ETH", 3000.0, "2024-04-02").is_err());
+        assert!(portfolio.buy("VTI", -1.0, 250.0, "2024-04-02").is_err());
+    }
+}
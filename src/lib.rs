@@ -0,0 +1,89 @@
+//! Personal finance tracking: the transaction model, the tracker, reports,
+//! storage and the import/export formats behind the `expenso` command.
+//!
+//! The code is split so every interface shares the same rules:
+//!
+//! - The domain ([`model`], [`tracker`], [`history`], [`query`], [`report`])
+//!   holds the data and every operation on it, from validation through
+//!   [`FinanceTracker::add`], [`FinanceTracker::edit`] and
+//!   [`FinanceTracker::remove`] to undo. It never prints, prompts or does IO.
+//! - [`storage`] is the repository: a [`storage::Backend`] loads and saves a
+//!   [`storage::Ledger`] and the blocking [`storage::Storage`] wraps it for the CLI.
+//! - The interfaces (the menu, subcommands and TUI in the `expenso` binary,
+//!   the `server`, the bots and the FFI, wasm and Python bindings) only turn
+//!   input into domain calls and render what comes back.
+
+pub mod alerts;
+pub mod bills;
+pub mod cards;
+#[cfg(feature = "charts")]
+pub mod chart;
+pub mod check;
+pub mod classify;
+pub mod cli;
+pub mod color;
+pub mod config;
+pub mod currency;
+pub mod daemon;
+pub mod dates;
+pub mod diff;
+#[cfg(feature = "email")]
+pub mod email;
+pub mod error;
+pub mod events;
+pub mod expense_report;
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod history;
+pub mod import;
+mod index;
+pub mod inflation;
+pub mod investments;
+pub mod loans;
+pub mod model;
+pub mod money;
+#[cfg(feature = "ocr")]
+pub mod ocr;
+pub mod output;
+pub mod pager;
+pub mod periods;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod projects;
+pub mod purge;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod query;
+pub mod quickadd;
+pub mod reconcile;
+#[cfg(feature = "remote")]
+pub mod remote;
+pub mod report;
+pub mod rules;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod shared;
+#[cfg(feature = "sheets")]
+pub mod sheets;
+pub mod storage;
+pub mod symbol;
+pub mod table;
+#[cfg(feature = "telegram")]
+pub mod telegram;
+pub mod tracker;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod whatif;
+
+pub use error::{ExpensoError, Result};
+pub use events::Event;
+pub use model::{
+    Fingerprint, NewTransaction, Split, Transaction, TransactionBuilder, TransactionType,
+};
+pub use shared::SharedTracker;
+pub use symbol::Symbol;
+pub use tracker::{FinanceTracker, ImportReport, MergeReport, SortKey, SortOrder};
@@ -0,0 +1,162 @@
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExpensoError, Result};
+use crate::{dates, money};
+
+/// A loan repaid in equal monthly installments, the first one a month after
+/// it starts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loan {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "principal")]
+    pub principal: f64,
+    /// Yearly interest rate in percent.
+    #[serde(rename = "rate")]
+    pub rate: f64,
+    #[serde(rename = "months")]
+    pub months: u32,
+    /// `YYYY-MM-DD`.
+    #[serde(rename = "start")]
+    pub start: String,
+}
+
+/// One payment of a [`Loan`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Installment {
+    pub number: u32,
+    pub date: String,
+    pub payment: f64,
+    pub interest: f64,
+    pub principal: f64,
+    /// What is still owed after this payment.
+    pub balance: f64,
+}
+
+impl Loan {
+    pub fn new(name: &str, principal: f64, rate: f64, months: u32, start: &str) -> Result<Loan> {
+        if name.trim().is_empty() {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "a loan needs a name",
+            )));
+        }
+        if !(principal.is_finite() && principal > 0.0) {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "principal must be a positive number",
+            )));
+        }
+        if !(rate.is_finite() && rate >= 0.0) {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "rate must be zero or more",
+            )));
+        }
+        if months == 0 {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "a loan needs at least one month",
+            )));
+        }
+        if !dates::is_iso_date(start) {
+            return Err(ExpensoError::InvalidInput(format!(
+                "invalid start date '{}'",
+                start
+            )));
+        }
+        Ok(Loan {
+            name: name.trim().to_string(),
+            principal,
+            rate,
+            months,
+            start: start.to_string(),
+        })
+    }
+
+    /// The monthly payment, before the last one absorbs rounding.
+    pub fn payment(&self) -> f64 {
+        let monthly = self.rate / 100.0 / 12.0;
+        let months = f64::from(self.months);
+        if monthly == 0.0 {
+            return money::round(self.principal / months);
+        }
+        money::round(self.principal * monthly / (1.0 - (1.0 + monthly).powf(-months)))
+    }
+
+    pub fn schedule(&self) -> Vec<Installment> {
+        let monthly = self.rate / 100.0 / 12.0;
+        let payment = self.payment();
+        let start = NaiveDate::parse_from_str(&self.start, dates::ISO_FORMAT).ok();
+        let mut balance = self.principal;
+        (1..=self.months)
+            .map(|number| {
+                let interest = money::round(balance * monthly);
+                let principal = if number == self.months {
+                    balance
+                } else {
+                    money::round(payment - interest).min(balance)
+                };
+                balance = money::round(balance - principal);
+                Installment {
+                    number,
+                    date: start
+                        .and_then(|start| start.checked_add_months(Months::new(number)))
+                        .map_or_else(String::new, |date| {
+                            date.format(dates::ISO_FORMAT).to_string()
+                        }),
+                    payment: money::sum([principal, interest]),
+                    interest,
+                    principal,
+                    balance,
+                }
+            })
+            .collect()
+    }
+
+    /// What is still owed once the payments due on or before `date` are made.
+    pub fn balance_on(&self, date: &str) -> f64 {
+        self.schedule()
+            .iter()
+            .take_while(|installment| installment.date.as_str() <= date)
+            .last()
+            .map_or(self.principal, |installment| installment.balance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_amortization_schedule() {
+        let loan = Loan::new("Car", 10000.0, 6.0, 12, "2024-01-15").unwrap();
+        assert_eq!(loan.payment(), 860.66);
+
+        let schedule = loan.schedule();
+        assert_eq!(schedule.len(), 12);
+        assert_eq!(
+            schedule[0],
+            Installment {
+                number: 1,
+                date: String::from("2024-02-15"),
+                payment: 860.66,
+                interest: 50.0,
+                principal: 810.66,
+                balance: 9189.34,
+            }
+        );
+        let last = &schedule[11];
+        assert_eq!(last.date, "2025-01-15");
+        assert_eq!(last.balance, 0.0);
+        assert!((last.payment - 860.66).abs() < 0.05);
+        let paid = money::sum(schedule.iter().map(|i| i.principal));
+        assert_eq!(paid, 10000.0);
+
+        assert_eq!(loan.balance_on("2024-02-14"), 10000.0);
+        assert_eq!(loan.balance_on("2024-02-15"), 9189.34);
+        assert_eq!(loan.balance_on("2030-01-01"), 0.0);
+
+        let free = Loan::new("Family", 1200.0, 0.0, 12, "2024-01-01").unwrap();
+        assert_eq!(free.payment(), 100.0);
+        assert!(Loan::new("Bad", 1000.0, 5.0, 0, "2024-01-01").is_err());
+    }
+}
@@ -1,247 +1,2553 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt;
+use std::io;
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, PartialEq)]
-enum TransactionType {
-    Income,
-    Expense,
+use clap::Parser;
+use regex::{Regex, RegexBuilder};
+
+mod alias;
+mod complete;
+mod input;
+#[cfg(feature = "tui")]
+mod tui;
+
+use chrono::Local;
+use complete::Completion;
+use expense_tracker::alerts;
+use expense_tracker::cards::CardStatus;
+#[cfg(feature = "charts")]
+use expense_tracker::chart;
+use expense_tracker::classify::Classifier;
+use expense_tracker::cli::{
+    self, Cli, Command, ExpenseReportArgs, ExportArgs, ImportArgs, InvestCommand, ListArgs,
+    LoanCommand, ProjectCommand, ReportArgs, ReportCommand, RulesCommand, SubscriptionReportArgs,
+    WhatIfArgs, build_new_transaction,
+};
+use expense_tracker::color::{self, type_color};
+use expense_tracker::config::Config;
+use expense_tracker::currency::Rates;
+use expense_tracker::daemon;
+use expense_tracker::diff::Diff;
+#[cfg(feature = "email")]
+use expense_tracker::email;
+use expense_tracker::expense_report::ExpenseReport;
+use expense_tracker::history::Entry;
+use expense_tracker::inflation::PriceIndex;
+use expense_tracker::investments;
+use expense_tracker::loans::Loan;
+#[cfg(feature = "ocr")]
+use expense_tracker::ocr;
+use expense_tracker::output::{self, OutputFormat};
+use expense_tracker::pager::{self, PageCommand};
+use expense_tracker::periods::ClosedPeriod;
+#[cfg(feature = "plugins")]
+use expense_tracker::plugin::{self, PluginHost};
+use expense_tracker::projects::{self, Project};
+use expense_tracker::purge;
+use expense_tracker::reconcile::Reconciliation;
+use expense_tracker::report::{
+    BalanceReport, CategoryReport, MonthlyReport, NetWorthReport, PayeeReport, ProjectReport,
+    Report, SubscriptionReport, Summary, YearlyReport,
+};
+#[cfg(feature = "server")]
+use expense_tracker::server;
+#[cfg(feature = "sheets")]
+use expense_tracker::sheets;
+use expense_tracker::storage::{self, Storage};
+use expense_tracker::table::{Align, Cell, Table};
+#[cfg(feature = "telegram")]
+use expense_tracker::telegram;
+use expense_tracker::whatif::{self, Projection};
+use expense_tracker::{
+    Event, ExpensoError, FinanceTracker, ImportReport, MergeReport, NewTransaction, Result,
+    SortKey, SortOrder, Split, Symbol, Transaction, TransactionType, check, dates, export, import,
+    money, rules,
+};
+use money::format_money;
+
+fn get_user_input(promt: &str) -> Option<String> {
+    input::read_line(promt).unwrap_or_else(|err| {
+        eprintln!("Could not read input: {}", err);
+        None
+    })
+}
+
+fn parse_amount(input: &str) -> Result<f64, std::num::ParseFloatError> {
+    input.parse::<f64>()
+}
+
+fn parse_bool(input: &str) -> bool {
+    matches!(input.to_lowercase().as_str(), "yes" | "y")
+}
+
+fn parse_sort(input: &str) -> Option<(SortKey, SortOrder)> {
+    let mut parts = input.split_whitespace();
+
+    let key = match parts.next().map(|part| part.to_lowercase()).as_deref() {
+        None | Some("id") => SortKey::Id,
+        Some("date") => SortKey::Date,
+        Some("amount") => SortKey::Amount,
+        Some("category") => SortKey::Category,
+        Some(_) => return None,
+    };
+    let order = match parts.next().map(|part| part.to_lowercase()).as_deref() {
+        None | Some("asc") => SortOrder::Ascending,
+        Some("desc") => SortOrder::Descending,
+        Some(_) => return None,
+    };
+
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((key, order))
+}
+
+fn display_menu() {
+    println!("\n=== Finance Tracker Menu ===");
+    println!("1) Add Transaction");
+    println!("2) View Summary");
+    println!("3) View Category Report");
+    println!("4) View All Transactions");
+    println!("5) Search Transactions");
+    println!("6) Switch Profile");
+    println!("7) Quit");
+    println!("===========================")
+}
+
+fn add_transaction_interactive(tracker: &mut FinanceTracker, config: &Config) {
+    match prompt_new_transaction(tracker, config).map(|new| tracker.add(new)) {
+        Some(Ok(_)) => println!("Transaction added successfully!"),
+        Some(Err(err)) => println!("Could not add transaction: {}", err),
+        None => println!("\nCancelled."),
+    }
+}
+
+fn prompt_new_transaction(tracker: &FinanceTracker, config: &Config) -> Option<NewTransaction> {
+    let description = get_user_input("Enter description: ")?;
+
+    let amount = loop {
+        let input = get_user_input("Enter amount: ")?;
+        match parse_amount(&input) {
+            Ok(amt) => break amt,
+            Err(_) => println!("Invalid amount. Please enter a number."),
+        }
+    };
+
+    let is_recurring_input = get_user_input("Is this recurring? (yes/no): ")?;
+    let is_recurring = parse_bool(&is_recurring_input);
+
+    let today = Local::now().date_naive();
+    let hint = dates::format_hint(&config.date_format);
+    let date = loop {
+        let input = get_user_input(&format!("Enter date ({}, today, 3 days ago, ...): ", hint))?;
+        if input.is_empty() {
+            break today.format(dates::ISO_FORMAT).to_string();
+        }
+        match dates::normalize_in(&input, &config.date_format, today) {
+            Some(date) => break date,
+            None => println!("Invalid date. Try {}, today, 3 days ago, ...", hint),
+        }
+    };
+
+    let previous = tracker.last_transaction();
+
+    let default_type = previous.map_or(&config.default_type, |t| &t.transaction_type);
+    let transaction_type = loop {
+        let input = prompt_with_default(
+            "Enter type (income/expense)",
+            &default_type.to_string().to_lowercase(),
+        )?;
+        match input.parse::<TransactionType>() {
+            Ok(transaction_type) => break transaction_type,
+            Err(_) => println!("Invalid type. Please enter income or expense."),
+        }
+    };
+
+    let payee = optional(get_user_input("Enter payee (optional): ")?);
+    let matched = NewTransaction {
+        description: description.clone(),
+        payee: payee.clone(),
+        ..Default::default()
+    };
+    let matched = rules::find(&config.rules, &matched);
+    let default_category = matched
+        .and_then(|rule| rule.category.as_deref())
+        .or_else(|| {
+            tracker
+                .past_category(&description, payee.as_deref())
+                .or_else(|| Classifier::train(tracker.get_transactions()).suggest(&description))
+                .map(|category| category.as_str())
+        })
+        .or(previous.map(|t| t.category.as_str()));
+    let category = prompt_category(&tracker.categories(), default_category)?;
+    let notes = optional(get_user_input("Enter notes (optional): ")?);
+    let tags = parse_tags(&get_user_input("Enter tags (comma separated, optional): ")?);
+    let default_account = previous.map_or(&config.default_account, |t| &t.account);
+    let account = prompt_with_default("Enter account", default_account)?;
+    let splits = loop {
+        let input = get_user_input("Split with (names, or name=amount owed, optional): ")?;
+        match cli::parse_splits(&parse_tags(&input), amount) {
+            Ok(splits) => break splits,
+            Err(err) => println!("{}", err),
+        }
+    };
+
+    let mut new = NewTransaction {
+        description,
+        amount,
+        is_recurring,
+        date,
+        transaction_type,
+        category,
+        payee,
+        notes,
+        tags,
+        account,
+        splits,
+        ..Default::default()
+    };
+    if let Some(rule) = rules::find(&config.rules, &new) {
+        rule.add_tags(&mut new);
+    }
+    Some(new)
+}
+
+fn prompt_with_default(prompt: &str, default: &str) -> Option<String> {
+    let input = get_user_input(&format!("{} [{}]: ", prompt, default))?;
+    if input.is_empty() {
+        Some(default.to_string())
+    } else {
+        Some(input)
+    }
+}
+
+fn prompt_category(categories: &[String], default: Option<&str>) -> Option<String> {
+    if !categories.is_empty() {
+        println!("Existing categories: {}", categories.join(", "));
+    }
+
+    loop {
+        let input = match default {
+            Some(default) => prompt_with_default("Enter category", default)?,
+            None => get_user_input("Enter category: ")?,
+        };
+        match complete::complete(&input, categories) {
+            Completion::Exact(category) => return Some(category),
+            Completion::Prefix(category) => {
+                println!("Using category '{}'", category);
+                return Some(category);
+            }
+            Completion::Ambiguous(matches) => {
+                println!("Matches: {}", matches.join(", "));
+            }
+            Completion::Suggestion(category) => {
+                let answer = get_user_input(&format!("Did you mean '{}'? (yes/no): ", category))?;
+                return Some(if parse_bool(&answer) { category } else { input });
+            }
+            Completion::New => return Some(input),
+        }
+    }
+}
+
+fn optional(input: String) -> Option<String> {
+    if input.is_empty() { None } else { Some(input) }
+}
+
+fn parse_tags(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+fn parse_search(input: &str) -> Result<SearchQuery, regex::Error> {
+    match input
+        .strip_prefix('/')
+        .and_then(|rest| rest.strip_suffix('/'))
+    {
+        Some(pattern) if !pattern.is_empty() => RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .map(SearchQuery::Regex),
+        _ => Ok(SearchQuery::Text(input.to_string())),
+    }
+}
+
+enum SearchQuery {
+    Text(String),
+    Regex(Regex),
+}
+
+impl SearchQuery {
+    fn run<'a>(&self, tracker: &'a FinanceTracker) -> Vec<&'a Transaction> {
+        match self {
+            SearchQuery::Text(query) => tracker.search(query),
+            SearchQuery::Regex(pattern) => tracker.search_regex(pattern),
+        }
+    }
+
+    /// What to highlight in the results: the regex, or the text anywhere.
+    fn pattern(&self) -> Regex {
+        match self {
+            SearchQuery::Text(query) => RegexBuilder::new(&regex::escape(query))
+                .case_insensitive(true)
+                .build()
+                .expect("an escaped pattern is valid"),
+            SearchQuery::Regex(pattern) => pattern.clone(),
+        }
+    }
+}
+
+fn search_interactive(tracker: &FinanceTracker, config: &Config) {
+    let Some(input) = get_user_input("Search (wrap in /.../ for a regex): ") else {
+        return;
+    };
+
+    let query = match parse_search(&input) {
+        Ok(query) => query,
+        Err(err) => {
+            println!("Invalid regex: {}", err);
+            return;
+        }
+    };
+    let results = query.run(tracker);
+
+    println!("\n=== Search Results ===");
+    if results.is_empty() {
+        println!("No transactions match \"{}\".", input);
+    } else {
+        print_search_results(
+            &results,
+            &query.pattern(),
+            archived_before(config).as_deref(),
+        );
+    }
+    println!("======================\n");
+}
+
+fn run_search(tracker: &FinanceTracker, config: &Config, input: &str) -> Result<()> {
+    let query = parse_search(input)
+        .map_err(|err| ExpensoError::InvalidInput(format!("invalid regex: {}", err)))?;
+    let results = query.run(tracker);
+    if results.is_empty() {
+        println!("No transactions match \"{}\".", input);
+    } else {
+        print_search_results(
+            &results,
+            &query.pattern(),
+            archived_before(config).as_deref(),
+        );
+    }
+    Ok(())
+}
+
+/// Like [`print_transaction_table`], with the payee and notes shown too
+/// and the matches highlighted.
+fn print_search_results(
+    transactions: &[&Transaction],
+    pattern: &Regex,
+    archived_before: Option<&str>,
+) {
+    let matched = |text: &str| {
+        let ranges = pattern.find_iter(text).map(|m| m.range()).collect();
+        Cell::highlighted(text.to_string(), ranges)
+    };
+    let mut table = Table::new()
+        .column("ID", Align::Right)
+        .column("Date", Align::Left)
+        .truncated_column("Description", Align::Left, 30)
+        .truncated_column("Category", Align::Left, 20)
+        .truncated_column("Payee", Align::Left, 20)
+        .truncated_column("Notes", Align::Left, 30)
+        .column("Amount", Align::Right);
+
+    for transaction in transactions {
+        let row = vec![
+            transaction.id.to_string().into(),
+            transaction.date.as_str().into(),
+            matched(&transaction.description),
+            matched(&transaction.category),
+            matched(transaction.payee.as_deref().unwrap_or_default()),
+            matched(transaction.notes.as_deref().unwrap_or_default()),
+            Cell::colored(
+                amount_text(transaction),
+                type_color(&transaction.transaction_type),
+            ),
+        ];
+        add_transaction_row(&mut table, transaction, row, archived_before);
+    }
+
+    println!("{}", table.render());
+}
+
+fn display_summary(tracker: &FinanceTracker) {
+    print_report::<Summary>(tracker);
+}
+
+fn display_category_report(tracker: &FinanceTracker, config: &Config) {
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let over = alerts::over_budget(&all, config, Local::now().date_naive());
+    let report = CategoryReport::from_tracker(tracker)
+        .ordered(config.category_order)
+        .mark_over_budget(&over);
+    println!("\n{}\n", report.text());
+}
+
+fn print_report<R: Report>(tracker: &FinanceTracker) {
+    println!("\n{}\n", R::from_tracker(tracker).text());
+}
+
+fn display_all_transactions(tracker: &mut FinanceTracker, config: &Config) {
+    let Some(input) = get_user_input("Sort by (id/date/amount/category, add 'desc' to reverse): ")
+    else {
+        return;
+    };
+    let (key, order) = parse_sort(&input).unwrap_or_else(|| {
+        println!("Unknown sort option, showing transactions by id.");
+        (SortKey::Id, SortOrder::Ascending)
+    });
+
+    let mut current = 0;
+
+    loop {
+        let transactions = tracker.sorted_transactions(key, order);
+        let pages = pager::page_count(transactions.len(), pager::DEFAULT_PAGE_SIZE);
+        current = current.min(pages - 1);
+
+        println!("\n=== All Transaction ===");
+        print_transaction_table(
+            pager::page(&transactions, current, pager::DEFAULT_PAGE_SIZE),
+            archived_before(config).as_deref(),
+        );
+        let prompt = if pages == 1 {
+            println!("=======================\n");
+            "[e]dit <id>, [q]uit: "
+        } else {
+            println!("=== Page {} of {} ===", current + 1, pages);
+            "[n]ext, [p]rev, [e]dit <id>, [q]uit: "
+        };
+
+        let Some(input) = get_user_input(prompt) else {
+            return;
+        };
+        match pager::parse_page_command(&input) {
+            Some(PageCommand::Next) if current + 1 < pages => current += 1,
+            Some(PageCommand::Previous) if current > 0 => current -= 1,
+            Some(PageCommand::Edit(id)) => edit_transaction_interactive(tracker, config, id),
+            Some(PageCommand::Quit) => return,
+            Some(PageCommand::Next) => return,
+            Some(PageCommand::Previous) => println!("Already on the first page."),
+            None => println!("Invalid option. Please try again."),
+        }
+    }
+}
+
+fn edit_transaction_interactive(tracker: &mut FinanceTracker, config: &Config, id: u32) {
+    let Some(existing) = tracker.get_transaction(id).cloned() else {
+        println!("No transaction with id {}.", id);
+        return;
+    };
+
+    println!(
+        "Editing transaction {} (Enter keeps a value, '-' clears an optional one)",
+        id
+    );
+    match prompt_edited_transaction(&existing, &tracker.categories(), config) {
+        Some(updated) if updated == existing => println!("No changes."),
+        Some(updated) => match tracker.edit(id, updated.into()) {
+            Ok(_) => println!("Transaction {} updated.", id),
+            Err(err) => println!("Could not update transaction {}: {}", id, err),
+        },
+        None => println!("\nCancelled."),
+    }
+}
+
+fn prompt_edited_transaction(
+    existing: &Transaction,
+    categories: &[String],
+    config: &Config,
+) -> Option<Transaction> {
+    let description = prompt_with_default("Description", &existing.description)?;
+
+    let amount = loop {
+        let input = prompt_with_default("Amount", &existing.amount.to_string())?;
+        match parse_amount(&input) {
+            Ok(amt) => break amt,
+            Err(_) => println!("Invalid amount. Please enter a number."),
+        }
+    };
+
+    let recurring_default = if existing.is_recurring { "yes" } else { "no" };
+    let is_recurring = parse_bool(&prompt_with_default(
+        "Recurring (yes/no)",
+        recurring_default,
+    )?);
+
+    let today = Local::now().date_naive();
+    let date = loop {
+        let input = prompt_with_default("Date", &existing.date)?;
+        match dates::normalize_in(&input, &config.date_format, today) {
+            Some(date) => break date,
+            None => println!(
+                "Invalid date. Try {}, today, 3 days ago, ...",
+                dates::format_hint(&config.date_format)
+            ),
+        }
+    };
+
+    let transaction_type = loop {
+        let input = prompt_with_default(
+            "Type (income/expense)",
+            &existing.transaction_type.to_string().to_lowercase(),
+        )?;
+        match input.parse::<TransactionType>() {
+            Ok(transaction_type) => break transaction_type,
+            Err(_) => println!("Invalid type. Please enter income or expense."),
+        }
+    };
+
+    let category = prompt_category(categories, Some(&existing.category))?;
+    let payee = edit_optional("Payee", existing.payee.as_deref())?;
+    let notes = edit_optional("Notes", existing.notes.as_deref())?;
+    let tags = match edit_optional("Tags (comma separated)", Some(&existing.tags.join(", ")))? {
+        Some(tags) => parse_tags(&tags),
+        None => Vec::new(),
+    };
+    let account = prompt_with_default("Account", &existing.account)?;
+
+    Some(Transaction {
+        description,
+        amount,
+        is_recurring,
+        date,
+        transaction_type,
+        category: category.into(),
+        payee: payee.map(Symbol::from),
+        notes,
+        tags,
+        account,
+        ..existing.clone()
+    })
+}
+
+fn edit_optional(prompt: &str, current: Option<&str>) -> Option<Option<String>> {
+    let input = match current.filter(|value| !value.is_empty()) {
+        Some(current) => get_user_input(&format!("{} [{}]: ", prompt, current))?,
+        None => get_user_input(&format!("{} (optional): ", prompt))?,
+    };
+    Some(replace_optional(&input, current))
+}
+
+fn replace_optional(input: &str, current: Option<&str>) -> Option<String> {
+    match input {
+        "" => current
+            .filter(|value| !value.is_empty())
+            .map(str::to_string),
+        "-" => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// The amount with its currency code when it's in another currency.
+fn amount_text(transaction: &Transaction) -> String {
+    match &transaction.currency {
+        Some(currency) => money::format_in(transaction.amount, currency),
+        None => format_money(transaction.amount),
+    }
+}
+
+/// Adds `row` for `transaction`, dimmed when it is dated before
+/// `archived_before`.
+fn add_transaction_row(
+    table: &mut Table,
+    transaction: &Transaction,
+    row: Vec<Cell>,
+    archived_before: Option<&str>,
+) {
+    if archived_before.is_some_and(|start| transaction.date.as_str() < start) {
+        table.add_dimmed_row(row);
+    } else {
+        table.add_row(row);
+    }
+}
+
+/// The first date of the recent years, before which transactions are
+/// archived and listed dimmed.
+fn archived_before(config: &Config) -> Option<String> {
+    config.recent_start(Local::now().date_naive())
+}
+
+fn print_transaction_table(transactions: &[&Transaction], archived_before: Option<&str>) {
+    let mut table = Table::new()
+        .column("ID", Align::Right)
+        .column("Date", Align::Left)
+        .truncated_column("Description", Align::Left, 30)
+        .column("Type", Align::Left)
+        .truncated_column("Category", Align::Left, 20)
+        .truncated_column("Account", Align::Left, 16)
+        .column("Amount", Align::Right)
+        .column("Recurring", Align::Left);
+
+    for transaction in transactions {
+        let recurring = if transaction.is_recurring {
+            "yes"
+        } else {
+            "no"
+        };
+        let row = vec![
+            transaction.id.to_string().into(),
+            transaction.date.as_str().into(),
+            transaction.description.as_str().into(),
+            transaction.transaction_type.to_string().into(),
+            transaction.category.as_str().into(),
+            transaction.account.as_str().into(),
+            Cell::colored(
+                amount_text(transaction),
+                type_color(&transaction.transaction_type),
+            ),
+            recurring.into(),
+        ];
+        add_transaction_row(&mut table, transaction, row, archived_before);
+    }
+
+    println!("{}", table.render());
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let profile = alias::profile_from_args(&args);
+    let config_path = match (alias::config_path_from_args(&args), &profile) {
+        (Some(path), _) => Some(PathBuf::from(path)),
+        (None, Some(name)) => Some(Config::profile_path(name).unwrap_or_else(|err| {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        })),
+        (None, None) => Config::default_path(),
+    };
+    let load = match profile {
+        Some(_) => Config::load_profile,
+        None => Config::load,
+    };
+    let mut config = match config_path.as_deref().map(load) {
+        Some(Ok(config)) => config,
+        Some(Err(err)) => {
+            let path = config_path.unwrap_or_default();
+            eprintln!("Could not load config {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+        None => Config::default(),
+    };
+
+    let args = alias::expand(args, &config.aliases).unwrap_or_else(|message| {
+        eprintln!("Error: {}", message);
+        std::process::exit(1);
+    });
+    let cli = Cli::parse_from(args);
+    color::init(cli.no_color);
+    cli.apply_overrides(&mut config);
+    money::init(&config);
+    dates::set_fiscal_year_start(config.fiscal_year_start);
+
+    let mut storage = Storage::from_config(&config);
+    let loaded = match (&cli.command, config.recent_start(Local::now().date_naive())) {
+        (None, Some(start)) => storage.load_since(&start),
+        _ => storage.load(),
+    };
+    let mut tracker = match loaded {
+        Ok(tracker) => tracker,
+        Err(err) => {
+            eprintln!("Could not load {}: {}", storage.location(), err);
+            std::process::exit(1);
+        }
+    };
+    for id in tracker.history_mismatches() {
+        eprintln!(
+            "Warning: transaction {} was changed outside expenso; `expenso check --repair` records the change in the history",
+            id
+        );
+    }
+    for t in tracker
+        .get_transactions()
+        .iter()
+        .filter(|t| !dates::is_iso_date(&t.date))
+    {
+        eprintln!(
+            "Warning: transaction {} has an invalid date \"{}\"; edit it to fix reports",
+            t.id, t.date
+        );
+    }
+    watch_budgets(&mut tracker, &config);
+    tracker.set_opening_balances(config.opening_balance, &config.opening_balances);
+    tracker.set_user(history_user(&config));
+
+    match &cli.command {
+        Some(Command::Add(args)) => {
+            let today = Local::now().date_naive();
+            match build_new_transaction(args, &config, today) {
+                Ok(mut new) => {
+                    if let Some(name) = &new.project {
+                        match projects::find(tracker.projects(), name) {
+                            Some(project) => new.project = Some(project.name.clone()),
+                            None => exit_on_error(Err(unknown_project(name))),
+                        }
+                    }
+                    #[cfg(feature = "plugins")]
+                    exit_on_error(load_plugins(&config).categorize(&mut new).map(|_| ()));
+                    let added = tracker
+                        .add(new)
+                        .and_then(|added| storage.save(&mut tracker).map(|()| added.id));
+                    match added {
+                        Ok(id) => println!("Added transaction {}", id),
+                        Err(err) => exit_on_error(Err(err)),
+                    }
+                }
+                Err(message) => {
+                    eprintln!("Error: {}", message);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(Command::List(args)) => exit_on_error(run_list(&tracker, &config, args)),
+        Some(Command::Search { query }) => exit_on_error(run_search(&tracker, &config, query)),
+        Some(Command::Delete { ids, dry_run }) => {
+            let change = |tracker: &mut FinanceTracker| {
+                Ok(ids
+                    .iter()
+                    .filter_map(|id| match tracker.remove(*id) {
+                        Ok(removed) => Some(removed.id),
+                        Err(ExpensoError::NotFound(_)) => None,
+                        Err(err) => {
+                            eprintln!("Skipped: {}", err);
+                            None
+                        }
+                    })
+                    .collect())
+            };
+            exit_on_error(apply_change(
+                &mut tracker,
+                &storage,
+                *dry_run,
+                ("delete", "Deleted"),
+                change,
+            ));
+        }
+        Some(Command::Recategorize { from, to, dry_run }) => {
+            let change = |tracker: &mut FinanceTracker| tracker.recategorize(from, to);
+            exit_on_error(apply_change(
+                &mut tracker,
+                &storage,
+                *dry_run,
+                ("recategorize", "Recategorized"),
+                change,
+            ));
+        }
+        Some(Command::Undo { dry_run }) => {
+            exit_on_error(run_undo(&mut tracker, &storage, *dry_run))
+        }
+        Some(Command::Settle {
+            person,
+            amount,
+            dry_run,
+        }) => exit_on_error(run_settle(
+            &mut tracker,
+            &storage,
+            &config,
+            person,
+            *amount,
+            *dry_run,
+        )),
+        Some(Command::Check { repair }) => {
+            exit_on_error(run_check(&mut tracker, &storage, &config, *repair))
+        }
+        Some(Command::Reconcile {
+            account,
+            balance,
+            date,
+            unlock,
+        }) => {
+            let account = account.as_deref().unwrap_or(&config.default_account);
+            exit_on_error(if unlock.is_empty() {
+                run_reconcile(&mut tracker, &storage, account, *balance, date.as_deref())
+            } else {
+                unlock_reconciled(&mut tracker, &storage, unlock)
+            })
+        }
+        Some(Command::History { limit, id }) => print_history(&tracker, *limit, *id),
+        Some(Command::Export(args)) => exit_on_error(run_export(&tracker, &config, args)),
+        Some(Command::ExpenseReport(args)) => {
+            exit_on_error(run_expense_report(&tracker, &config, args))
+        }
+        Some(Command::Import(args)) => {
+            let mut imported = match read_import(args, &config) {
+                Ok(imported) => imported,
+                Err(err) => {
+                    eprintln!("Could not import {}: {}", args.file.display(), err);
+                    std::process::exit(1);
+                }
+            };
+            rules::apply_all(&config.rules, &mut imported);
+            #[cfg(feature = "plugins")]
+            exit_on_error(
+                load_plugins(&config)
+                    .categorize_all(&mut imported)
+                    .map(|_| ()),
+            );
+            let mut report = ImportReport::default();
+            let change = |tracker: &mut FinanceTracker| {
+                report = tracker.add_transactions(imported);
+                Ok(report.added.clone())
+            };
+            exit_on_error(apply_change(
+                &mut tracker,
+                &storage,
+                args.dry_run,
+                ("import", "Imported"),
+                change,
+            ));
+            if !report.duplicates.is_empty() {
+                println!(
+                    "Skipped {} already imported transaction(s)",
+                    report.duplicates.len()
+                );
+            }
+            for (position, reason) in &report.invalid {
+                eprintln!("Skipped entry {}: {}", position + 1, reason);
+            }
+        }
+        Some(Command::Close { period }) => exit_on_error(match period {
+            Some(period) => close_period(&mut tracker, &storage, period),
+            None => {
+                print_closed_periods(&tracker);
+                Ok(())
+            }
+        }),
+        Some(Command::Reopen { period }) => {
+            exit_on_error(reopen_period(&mut tracker, &storage, period))
+        }
+        Some(Command::Diff { old, new }) => exit_on_error(run_diff(old, new)),
+        Some(Command::Merge { file, dry_run }) => {
+            exit_on_error(run_merge(&mut tracker, &storage, file, *dry_run))
+        }
+        #[cfg(feature = "ocr")]
+        Some(Command::Receipt { image }) => {
+            exit_on_error(run_receipt(&mut tracker, &storage, &config, image))
+        }
+        #[cfg(feature = "server")]
+        Some(Command::Serve { host, port }) => {
+            // The API saves to the local data file, which would be
+            // overwritten with the remote transactions and no history.
+            #[cfg(feature = "remote")]
+            if matches!(storage, Storage::Remote(_)) {
+                exit_on_error(Err(ExpensoError::InvalidInput(String::from(
+                    "serve needs a local data file, run it where the data is kept rather than with a remote server",
+                ))));
+            }
+            let address = std::net::SocketAddr::new(*host, *port);
+            if let Err(err) = server::run(tracker, config, address) {
+                eprintln!("Server error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "sheets")]
+        Some(Command::Sheets {
+            spreadsheet,
+            filter,
+        }) => {
+            let Some(spreadsheet) = spreadsheet.as_ref().or(config.google_sheet_id.as_ref()) else {
+                eprintln!("Error: pass --spreadsheet or set google_sheet_id in the config file");
+                std::process::exit(1);
+            };
+            let transactions =
+                tracker.filtered(&filter.to_filter(), SortKey::Date, SortOrder::Ascending);
+            match sheets::push(&transactions, &config, spreadsheet) {
+                Ok(tabs) => println!(
+                    "Pushed {} transaction(s) to {} tab(s)",
+                    transactions.len(),
+                    tabs
+                ),
+                Err(err) => {
+                    eprintln!("Could not update the spreadsheet: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        #[cfg(feature = "telegram")]
+        Some(Command::Telegram) => exit_on_error(telegram::run(&mut tracker, &storage, &config)),
+        #[cfg(feature = "plugins")]
+        Some(Command::Plugins) => list_plugins(&load_plugins(&config)),
+        Some(Command::Rules { rules }) => {
+            let Some(path) = &config_path else {
+                eprintln!("Error: could not determine the config file location, pass --config");
+                std::process::exit(1);
+            };
+            exit_on_error(run_rules(path, config.rules, rules));
+        }
+        Some(Command::Invest { invest }) => {
+            exit_on_error(run_invest(&mut tracker, &storage, &config, invest))
+        }
+        Some(Command::Project { project }) => {
+            exit_on_error(run_project(&mut tracker, &storage, project))
+        }
+        Some(Command::Card { account }) => {
+            exit_on_error(run_card(&tracker, &config, account.as_deref()))
+        }
+        #[cfg(feature = "notifications")]
+        Some(Command::Alerts { notify }) => exit_on_error(run_alerts(&tracker, &config, *notify)),
+        #[cfg(not(feature = "notifications"))]
+        Some(Command::Alerts {}) => exit_on_error(run_alerts(&tracker, &config, false)),
+        #[cfg(feature = "notifications")]
+        Some(Command::Daemon {
+            interval,
+            once,
+            notify,
+        }) => exit_on_error(run_daemon(
+            &storage,
+            &config,
+            *interval,
+            *once,
+            daemon::Options { notify: *notify },
+        )),
+        #[cfg(not(feature = "notifications"))]
+        Some(Command::Daemon { interval, once }) => exit_on_error(run_daemon(
+            &storage,
+            &config,
+            *interval,
+            *once,
+            daemon::Options::default(),
+        )),
+        Some(Command::Purge { dry_run }) => exit_on_error(run_purge(
+            &tracker,
+            &config,
+            config_path.as_deref(),
+            *dry_run,
+        )),
+        Some(Command::WhatIf(args)) => exit_on_error(run_what_if(&tracker, args)),
+        Some(Command::Loan { loan }) => exit_on_error(run_loan(&mut tracker, &storage, loan)),
+        Some(Command::Report {
+            report: ReportCommand::Subscriptions(args),
+        }) if args.mark_recurring => {
+            exit_on_error(mark_subscriptions(&mut tracker, &storage, args))
+        }
+        Some(Command::Report { report }) => exit_on_error(run_report(&tracker, &config, report)),
+        Some(Command::Completions { shell }) => cli::print_completions(*shell),
+        None => {
+            remind_card_payments(&tracker, &config);
+            run_interactive(&cli, &mut tracker, &mut config, &mut storage);
+            save_on_exit(&mut tracker, &storage, &config);
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn run_interactive(
+    cli: &Cli,
+    tracker: &mut FinanceTracker,
+    config: &mut Config,
+    storage: &mut Storage,
+) {
+    if cli.tui {
+        if let Err(err) = tui::run(tracker, config) {
+            eprintln!("TUI error: {}", err);
+        }
+    } else {
+        run_menu(tracker, config, storage);
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_interactive(
+    _cli: &Cli,
+    tracker: &mut FinanceTracker,
+    config: &mut Config,
+    storage: &mut Storage,
+) {
+    run_menu(tracker, config, storage);
+}
+
+/// Views over every transaction call this first, so the older ones a
+/// windowed start left out are read when they are first needed.
+fn load_archived(tracker: &mut FinanceTracker) {
+    if let Some(since) = tracker.loaded_since() {
+        println!("Loading transactions before {}...", since);
+        if let Err(err) = tracker.load_since(None) {
+            eprintln!("Could not load older transactions: {}", err);
+        }
+    }
+}
+
+fn save_on_exit(tracker: &mut FinanceTracker, storage: &Storage, config: &Config) {
+    if !tracker.is_dirty() {
+        return;
+    }
+
+    if !config.autosave {
+        let prompt = format!("Save changes to {}? (yes/no): ", storage.location());
+        if let Some(answer) = get_user_input(&prompt)
+            && !parse_bool(&answer)
+        {
+            println!("Changes discarded.");
+            return;
+        }
+    }
+
+    match storage.save(tracker) {
+        Ok(()) => println!("Saved to {}", storage.location()),
+        Err(err) => eprintln!("Could not save {}: {}", storage.location(), err),
+    }
+}
+
+fn run_rules(
+    path: &std::path::Path,
+    mut rules: Vec<rules::Rule>,
+    command: &RulesCommand,
+) -> Result<()> {
+    match command {
+        RulesCommand::List => {
+            if rules.is_empty() {
+                println!("No rules defined.");
+            }
+            for (index, rule) in rules.iter().enumerate() {
+                println!("{:>3}. {}", index + 1, rule);
+            }
+            return Ok(());
+        }
+        RulesCommand::Add {
+            contains,
+            field,
+            category,
+            tags,
+        } => {
+            if category.is_none() && tags.is_empty() {
+                return Err(ExpensoError::InvalidInput(String::from(
+                    "a rule needs --category or --tag",
+                )));
+            }
+            let rule = rules::Rule {
+                field: *field,
+                contains: contains.clone(),
+                category: category.clone(),
+                tags: tags.clone(),
+            };
+            println!("Added rule {}. {}", rules.len() + 1, rule);
+            rules.push(rule);
+        }
+        RulesCommand::Remove { number } => {
+            if *number == 0 || *number > rules.len() {
+                return Err(ExpensoError::InvalidInput(format!(
+                    "no rule number {}",
+                    number
+                )));
+            }
+            let rule = rules.remove(number - 1);
+            println!("Removed rule {}", rule);
+        }
+    }
+    Config::save_rules(path, &rules)
+}
+
+/// Who changes are recorded as made by: the configured user, else the
+/// login name.
+fn history_user(config: &Config) -> Option<String> {
+    config.user.clone().or_else(|| {
+        ["USER", "USERNAME"]
+            .into_iter()
+            .find_map(|name| std::env::var(name).ok())
+            .filter(|user| !user.is_empty())
+    })
+}
+
+fn watch_budgets(tracker: &mut FinanceTracker, config: &Config) {
+    tracker.set_budget_period(config.budget_period, config.calendar());
+    tracker.set_budgets(&config.budgets);
+    tracker.subscribe(|event| {
+        if let Event::BudgetExceeded {
+            category,
+            period,
+            limit,
+            spent,
+        } = event
+        {
+            eprintln!(
+                "Warning: {} spending for {} is {}, over the {} budget",
+                category,
+                period,
+                format_money(*spent),
+                format_money(*limit)
+            );
+        }
+    });
+}
+
+fn unknown_project(name: &str) -> ExpensoError {
+    ExpensoError::InvalidInput(format!(
+        "no project named {}, define it with `project add`",
+        name
+    ))
+}
+
+fn run_project(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    command: &ProjectCommand,
+) -> Result<()> {
+    if !matches!(
+        command,
+        ProjectCommand::List | ProjectCommand::Unassign { .. }
+    ) {
+        storage.require_local("projects")?;
+    }
+    match command {
+        ProjectCommand::List => {
+            if tracker.projects().is_empty() {
+                println!("No projects yet.");
+                return Ok(());
+            }
+            let mut table = Table::new()
+                .column("Name", Align::Left)
+                .column("Start", Align::Left)
+                .column("End", Align::Left)
+                .column("Budget", Align::Right);
+            for project in tracker.projects() {
+                table.add_row(vec![
+                    project.name.as_str().into(),
+                    project.start.as_deref().unwrap_or("").into(),
+                    project.end.as_deref().unwrap_or("").into(),
+                    project.budget.map_or_else(String::new, format_money).into(),
+                ]);
+            }
+            println!("{}", table.render());
+            return Ok(());
+        }
+        ProjectCommand::Add {
+            name,
+            budget,
+            start,
+            end,
+        } => {
+            if projects::find(tracker.projects(), name).is_some() {
+                return Err(ExpensoError::InvalidInput(format!(
+                    "there is already a project named {}",
+                    name
+                )));
+            }
+            let project = Project::new(name, *budget, start.as_deref(), end.as_deref())?;
+            println!("Added project {}", project.name);
+            tracker.projects_mut().push(project);
+        }
+        ProjectCommand::Assign { name, ids } => {
+            let project = projects::find(tracker.projects(), name)
+                .cloned()
+                .ok_or_else(|| unknown_project(name))?;
+            let assigned = set_project(tracker, ids, Some(&project.name))?;
+            for transaction in &assigned {
+                if !project.covers(&transaction.date) {
+                    eprintln!(
+                        "Note: transaction {} is dated {}, outside the project's dates",
+                        transaction.id, transaction.date
+                    );
+                }
+            }
+            println!(
+                "Assigned {} transaction(s) to {}",
+                assigned.len(),
+                project.name
+            );
+        }
+        ProjectCommand::Unassign { ids } => {
+            let unassigned = set_project(tracker, ids, None)?;
+            println!("Unassigned {} transaction(s)", unassigned.len());
+        }
+        ProjectCommand::Remove { name } => {
+            let index = tracker
+                .projects()
+                .iter()
+                .position(|p| p.name.eq_ignore_ascii_case(name))
+                .ok_or_else(|| unknown_project(name))?;
+            let project = tracker.projects_mut().remove(index);
+            let ids: Vec<u32> = tracker
+                .get_transactions()
+                .iter()
+                .filter(|t| {
+                    t.project
+                        .as_deref()
+                        .is_some_and(|p| p.eq_ignore_ascii_case(&project.name))
+                })
+                .map(|t| t.id)
+                .collect();
+            set_project(tracker, &ids, None)?;
+            println!(
+                "Removed project {}, unassigning {} transaction(s)",
+                project.name,
+                ids.len()
+            );
+        }
+    }
+    storage.save(tracker)
+}
+
+/// Puts the transactions `ids` in `project`, or takes them out of theirs,
+/// returning the ones changed.
+fn set_project(
+    tracker: &mut FinanceTracker,
+    ids: &[u32],
+    project: Option<&str>,
+) -> Result<Vec<Transaction>> {
+    let mut changed = Vec::new();
+    for id in ids {
+        let transaction = tracker
+            .get_transaction(*id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(*id))?;
+        if transaction.project.as_deref() == project {
+            continue;
+        }
+        let updated = Transaction {
+            project: project.map(str::to_string),
+            ..transaction
+        };
+        changed.push(tracker.update(updated)?);
+    }
+    Ok(changed)
+}
+
+fn card_statuses(tracker: &FinanceTracker, config: &Config) -> Vec<CardStatus> {
+    let today = Local::now().date_naive();
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    config
+        .cards
+        .iter()
+        .map(|(account, cycle)| CardStatus::compute(&all, account, cycle, today))
+        .collect()
+}
+
+fn remind_card_payments(tracker: &FinanceTracker, config: &Config) {
+    let today = Local::now().date_naive();
+    for status in card_statuses(tracker, config) {
+        if let Some(reminder) = status.reminder(today) {
+            eprintln!("Reminder: {}", reminder);
+        }
+    }
+}
+
+fn close_period(tracker: &mut FinanceTracker, storage: &Storage, period: &str) -> Result<()> {
+    storage.require_local("closed periods")?;
+    let closed = tracker.close_period(period)?.clone();
+    storage.save(tracker)?;
+    println!(
+        "Closed {}; its transactions can't be changed until `expenso reopen {}`.",
+        closed.period, closed.period
+    );
+    if !closed.balances.is_empty() {
+        println!("Balances at {}:", closed.last_day());
+        for (account, balance) in &closed.balances {
+            println!("  {}: {}", account, format_money(*balance));
+        }
+    }
+    Ok(())
 }
 
-impl fmt::Display for TransactionType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            TransactionType::Income => write!(f, "Income"),
-            TransactionType::Expense => write!(f, "Expense"),
+fn reopen_period(tracker: &mut FinanceTracker, storage: &Storage, period: &str) -> Result<()> {
+    storage.require_local("closed periods")?;
+    let reopened = tracker.reopen_period(period)?;
+    storage.save(tracker)?;
+    println!("Reopened {}.", reopened.period);
+    Ok(())
+}
+
+fn print_closed_periods(tracker: &FinanceTracker) {
+    let mut closed: Vec<&ClosedPeriod> = tracker.closed_periods().iter().collect();
+    if closed.is_empty() {
+        println!("No periods are closed.");
+        return;
+    }
+    closed.sort_by(|a, b| a.period.cmp(&b.period));
+    let mut table = Table::new()
+        .column("Period", Align::Left)
+        .column("Closed at", Align::Left)
+        .column("Balance", Align::Right);
+    for period in closed {
+        table.add_row(vec![
+            period.period.as_str().into(),
+            period.closed_at.as_str().into(),
+            format_money(money::sum(period.balances.values().copied())).into(),
+        ]);
+    }
+    println!("{}", table.render());
+}
+
+/// Reads another data file, which unlike the tracker's own must exist.
+fn load_snapshot(path: &std::path::Path) -> Result<FinanceTracker> {
+    if !path.is_file() {
+        return Err(ExpensoError::InvalidInput(format!(
+            "{} does not exist",
+            path.display()
+        )));
+    }
+    storage::load(path)
+}
+
+fn run_diff(old: &std::path::Path, new: &std::path::Path) -> Result<()> {
+    let (before, after) = (load_snapshot(old)?, load_snapshot(new)?);
+    let diff = Diff::compute(&before, &after);
+    if diff.is_empty() {
+        println!("No transactions changed.");
+        return Ok(());
+    }
+
+    for (heading, transactions) in [("Added", &diff.added), ("Removed", &diff.removed)] {
+        if !transactions.is_empty() {
+            println!("{} ({}):", heading, transactions.len());
+            print_transaction_table(transactions, None);
+            println!();
+        }
+    }
+    if !diff.modified.is_empty() {
+        println!("Modified ({}):", diff.modified.len());
+        let mut table = Table::new()
+            .column("ID", Align::Right)
+            .column("Field", Align::Left)
+            .truncated_column("Before", Align::Left, 30)
+            .truncated_column("After", Align::Left, 30);
+        for modified in &diff.modified {
+            let (before, after) = (
+                serde_json::to_value(modified.before)?,
+                serde_json::to_value(modified.after)?,
+            );
+            for (n, field) in modified.fields.iter().enumerate() {
+                let show = |value: Option<&serde_json::Value>| match value {
+                    None | Some(serde_json::Value::Null) => String::new(),
+                    Some(serde_json::Value::Number(amount)) if field == "amount" => {
+                        format_money(amount.as_f64().unwrap_or_default())
+                    }
+                    Some(serde_json::Value::String(text)) => text.clone(),
+                    Some(value) => value.to_string(),
+                };
+                let id = if n == 0 {
+                    modified.after.id.to_string()
+                } else {
+                    String::new()
+                };
+                table.add_row(vec![
+                    id.into(),
+                    field.as_str().into(),
+                    show(before.get(field)).into(),
+                    show(after.get(field)).into(),
+                ]);
+            }
         }
+        println!("{}\n", table.render());
     }
+
+    let mut totals = Table::new()
+        .column("", Align::Left)
+        .column("Before", Align::Right)
+        .column("After", Align::Right)
+        .column("Change", Align::Right);
+    let count = |tracker: &FinanceTracker| tracker.get_transactions().len();
+    totals.add_row(vec![
+        "Transactions".into(),
+        count(&before).to_string().into(),
+        count(&after).to_string().into(),
+        format!("{:+}", count(&after) as i64 - count(&before) as i64).into(),
+    ]);
+    let rows = [
+        ("Income", before.total_income(), after.total_income()),
+        ("Expense", before.total_expense(), after.total_expense()),
+        ("Net balance", before.net_balance(), after.net_balance()),
+    ]
+    .into_iter()
+    .chain(
+        diff.categories
+            .iter()
+            .map(|(name, old, new)| (name.as_str(), *old, *new)),
+    );
+    for (label, old, new) in rows {
+        totals.add_row(vec![
+            label.into(),
+            format_money(old).into(),
+            format_money(new).into(),
+            signed_money(money::round(new - old)).into(),
+        ]);
+    }
+    println!("{}", totals.render());
+    Ok(())
 }
 
-impl From<&str> for TransactionType {
-    fn from(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "income" => TransactionType::Income,
-            "expense" => TransactionType::Expense,
-            _ => TransactionType::Expense,
+fn run_merge(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    file: &std::path::Path,
+    dry_run: bool,
+) -> Result<()> {
+    let other = load_snapshot(file)?;
+    let mut report = MergeReport::default();
+    let change = |tracker: &mut FinanceTracker| {
+        report = tracker.merge(other.get_transactions().clone())?;
+        Ok(report.added.clone())
+    };
+    apply_change(tracker, storage, dry_run, ("merge", "Merged"), change)?;
+
+    if report.duplicates > 0 {
+        println!(
+            "Skipped {} transaction(s) already in this file",
+            report.duplicates
+        );
+    }
+    for (old, new) in &report.renumbered {
+        println!("Transaction {} from {} is now {}", old, file.display(), new);
+    }
+    for id in &report.deleted {
+        println!(
+            "Transaction {} was deleted here but is still in {}; it was left out",
+            id,
+            file.display()
+        );
+    }
+    if !report.conflicts.is_empty() {
+        println!(
+            "\n{} transaction(s) were edited differently in each file; both versions were kept:",
+            report.conflicts.len()
+        );
+        let mut table = Table::new()
+            .column("Version", Align::Left)
+            .column("ID", Align::Right)
+            .truncated_column("Description", Align::Left, 30)
+            .column("Date", Align::Left)
+            .column("Amount", Align::Right);
+        for (ours, theirs) in &report.conflicts {
+            // A dry run keeps nothing, so their version is read from the other file.
+            let their_version = if dry_run {
+                other.get_transaction(*ours)
+            } else {
+                tracker.get_transaction(*theirs)
+            };
+            let versions = [
+                ("ours", *ours, tracker.get_transaction(*ours)),
+                ("theirs", *theirs, their_version),
+            ];
+            for (version, id, transaction) in versions {
+                let Some(transaction) = transaction else {
+                    continue;
+                };
+                table.add_row(vec![
+                    version.into(),
+                    id.to_string().into(),
+                    transaction.description.as_str().into(),
+                    transaction.date.as_str().into(),
+                    format_money(transaction.amount).into(),
+                ]);
+            }
         }
+        println!("{}", table.render());
+        println!("Delete the version you don't want with `expenso delete ID`.");
     }
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct Transaction {
-    id: u32,
-    description: String,
-    amount: f64,
-    is_recurring: bool,
-    date: String,
-    transaction_type: TransactionType,
-    category: String,
+fn run_purge(
+    tracker: &FinanceTracker,
+    config: &Config,
+    config_path: Option<&std::path::Path>,
+    dry_run: bool,
+) -> Result<()> {
+    #[cfg(feature = "remote")]
+    if config.remote.is_some() {
+        return Err(ExpensoError::InvalidInput(String::from(
+            "purge only deletes local files, the data on the remote server stays",
+        )));
+    }
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let targets = purge::targets(config, config_path, &all);
+    if targets.is_empty() {
+        println!("Nothing to purge.");
+        return Ok(());
+    }
+    println!("These files will be overwritten and deleted:");
+    for path in &targets {
+        println!("  {}", path.display());
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let typed = get_user_input("This can't be undone. Type purge to continue: ");
+    if typed.as_deref().map(str::trim) != Some("purge") {
+        println!("Cancelled, nothing was deleted.");
+        return Ok(());
+    }
+    let sure = get_user_input("Are you sure you want to delete everything? (yes/no): ");
+    if !sure.is_some_and(|answer| parse_bool(&answer)) {
+        println!("Cancelled, nothing was deleted.");
+        return Ok(());
+    }
+    for path in &targets {
+        purge::shred(path)?;
+        println!("Deleted {}", path.display());
+    }
+    Ok(())
 }
 
-#[derive(Debug)]
-struct FinanceTracker {
-    transactions: Vec<Transaction>,
-    category_totals: HashMap<String, f64>,
-    unique_categories: HashSet<String>,
-    next_id: u32,
+fn run_daemon(
+    storage: &Storage,
+    config: &Config,
+    interval: u64,
+    once: bool,
+    options: daemon::Options,
+) -> Result<()> {
+    if once {
+        return daemon::run_once(storage, config, options, &mut daemon::State::default());
+    }
+    daemon::run(
+        storage,
+        config,
+        std::time::Duration::from_secs(interval * 60),
+        options,
+    )
 }
 
-impl FinanceTracker {
-    pub fn new() -> Self {
-        FinanceTracker {
-            transactions: Vec::new(),
-            category_totals: HashMap::new(),
-            unique_categories: HashSet::new(),
-            next_id: 1,
+#[cfg_attr(not(feature = "notifications"), allow(unused_variables))]
+fn run_alerts(tracker: &FinanceTracker, config: &Config, notify: bool) -> Result<()> {
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let alerts = alerts::pending(&all, config, Local::now().date_naive());
+    if alerts.is_empty() {
+        println!("Nothing needs attention.");
+    }
+    for alert in &alerts {
+        println!(
+            "{}: {}",
+            color::paint(&alert.title, color::Color::Yellow),
+            alert.body
+        );
+        #[cfg(feature = "notifications")]
+        if notify {
+            alerts::notify(alert)?;
         }
     }
+    Ok(())
+}
 
-    pub fn add_transaction(
-        &mut self,
-        description: String,
-        amount: f64,
-        is_recurring: bool,
-        date: String,
-        transaction_type: TransactionType,
-        category: String,
-    ) {
-        let transaction = Transaction {
-            id: self.next_id,
-            description,
-            amount,
-            is_recurring,
-            date,
-            transaction_type,
-            category: category.clone(),
-        };
+fn run_what_if(tracker: &FinanceTracker, args: &WhatIfArgs) -> Result<()> {
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let projection = Projection::compute(
+        &all,
+        &args.changes(),
+        args.months,
+        Local::now().date_naive(),
+    )?
+    .with_opening_balance(tracker.opening_balance());
+    let as_is = whatif::monthly(&projection.baseline);
+    let what_if = whatif::monthly(&projection.scenario);
+    println!("Balance today: {}", format_money(projection.balance));
+    println!("Each month as is: {}", signed_money(as_is));
+    println!(
+        "Each month with the changes: {} ({})",
+        signed_money(what_if),
+        signed_money(money::round(what_if - as_is))
+    );
+    println!();
+
+    let mut table = Table::new()
+        .column("Month", Align::Left)
+        .column("As is", Align::Right)
+        .column("What if", Align::Right)
+        .column("Difference", Align::Right);
+    for month in &projection.months {
+        table.add_row(vec![
+            month.month.as_str().into(),
+            format_money(month.baseline).into(),
+            format_money(month.scenario).into(),
+            signed_money(money::round(month.scenario - month.baseline)).into(),
+        ]);
+    }
+    println!("{}", table.render());
+    Ok(())
+}
+
+fn signed_money(amount: f64) -> String {
+    if amount > 0.0 {
+        format!("+{}", format_money(amount))
+    } else {
+        format_money(amount)
+    }
+}
 
-        self.transactions.push(transaction);
+fn run_card(tracker: &FinanceTracker, config: &Config, account: Option<&str>) -> Result<()> {
+    if config.cards.is_empty() {
+        return Err(ExpensoError::InvalidInput(String::from(
+            "add closing_day and due_day for a card under [cards.<account>] in the config file",
+        )));
+    }
+    let statuses: Vec<CardStatus> = card_statuses(tracker, config)
+        .into_iter()
+        .filter(|s| account.is_none_or(|account| s.account.eq_ignore_ascii_case(account)))
+        .collect();
+    if statuses.is_empty() {
+        return Err(ExpensoError::InvalidInput(format!(
+            "no statement cycle for {} in the config file",
+            account.unwrap_or_default()
+        )));
+    }
+    for status in statuses {
+        println!(
+            "{}: cycle {} to {}",
+            status.account, status.cycle_start, status.cycle_end
+        );
+        if !status.charges.is_empty() {
+            let refs: Vec<&Transaction> = status.charges.iter().collect();
+            print_transaction_table(&refs, archived_before(config).as_deref());
+        }
+        println!("Charged this cycle: {}", format_money(status.cycle_total));
+        println!(
+            "Statement closed {}: {}, paid {}, {} left to pay by {}",
+            status.last_closing,
+            format_money(status.statement_balance),
+            format_money(status.paid),
+            format_money(status.remaining_due),
+            status.due_date
+        );
+        if let Some(reminder) = status.reminder(Local::now().date_naive()) {
+            println!("{}", color::paint(&reminder, color::Color::Yellow));
+        }
+        println!();
+    }
+    Ok(())
+}
 
-        self.category_totals
-            .entry(category.clone())
-            .and_modify(|total| *total += amount)
-            .or_insert(amount);
+fn apply_change(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    dry_run: bool,
+    (action, done): (&str, &str),
+    change: impl FnOnce(&mut FinanceTracker) -> Result<Vec<u32>>,
+) -> Result<()> {
+    let mut updated = tracker.clone();
+    let affected = change(&mut updated)?;
 
-        self.unique_categories.insert(category);
+    let verb = if dry_run {
+        format!("Would {}", action)
+    } else {
+        done.to_string()
+    };
+    print_change(&verb, &affected, tracker, &updated);
 
-        self.next_id += 1;
+    if dry_run {
+        println!("Dry run: no changes were written.");
+    } else if !affected.is_empty() {
+        *tracker = updated;
+        storage.save(tracker)?;
     }
+    Ok(())
+}
 
-    pub fn total_income(&self) -> f64 {
-        self.transactions
-            .iter()
-            .filter(|t| t.transaction_type == TransactionType::Income)
-            .map(|t| t.amount)
-            .sum()
+fn run_undo(tracker: &mut FinanceTracker, storage: &Storage, dry_run: bool) -> Result<()> {
+    let mut updated = tracker.clone();
+    let Some(change) = updated.undo()? else {
+        println!("Nothing to undo.");
+        return Ok(());
+    };
+
+    let verb = if dry_run { "Would undo" } else { "Undid" };
+    println!("{}: {}", verb, change);
+    if dry_run {
+        println!("Dry run: no changes were written.");
+    } else {
+        *tracker = updated;
+        storage.save(tracker)?;
     }
+    Ok(())
+}
 
-    pub fn total_expense(&self) -> f64 {
-        self.transactions
-            .iter()
-            .filter(|t| t.transaction_type == TransactionType::Expense)
-            .map(|t| t.amount)
-            .sum()
+fn run_settle(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    config: &Config,
+    person: &str,
+    amount: Option<f64>,
+    dry_run: bool,
+) -> Result<()> {
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let balance = BalanceReport::compute(&all).balance(person);
+    if balance == 0.0 && amount.is_none() {
+        println!("You and {} are settled up.", person);
+        return Ok(());
     }
+    let amount = amount.unwrap_or(balance.abs());
+    // They pay you back when they owe you, otherwise you pay them.
+    let share = if balance > 0.0 { -amount } else { amount };
+    let new = NewTransaction {
+        description: format!("Settle up with {}", person),
+        amount,
+        transaction_type: TransactionType::Settlement,
+        category: String::from("Settlement"),
+        account: config.default_account.clone(),
+        splits: vec![Split {
+            person: person.to_string(),
+            share,
+        }],
+        ..Default::default()
+    };
+    apply_change(
+        tracker,
+        storage,
+        dry_run,
+        ("settle", "Settled"),
+        |tracker| Ok(vec![tracker.add(new)?.id]),
+    )
+}
+
+/// Walks through matching `account` against a statement: shows what is
+/// left to tick off and the difference until the user finishes or quits.
+fn run_reconcile(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    account: &str,
+    balance: Option<f64>,
+    date: Option<&str>,
+) -> Result<()> {
+    let date = date.map_or_else(
+        || Local::now().format(dates::ISO_FORMAT).to_string(),
+        str::to_string,
+    );
+    let balance = match balance {
+        Some(balance) => balance,
+        None => loop {
+            let Some(input) = get_user_input("Statement ending balance: ") else {
+                println!("\nCancelled.");
+                return Ok(());
+            };
+            match parse_amount(&input) {
+                Ok(balance) if balance.is_finite() => break balance,
+                _ => println!("Invalid balance. Please enter a number."),
+            }
+        },
+    };
 
-    pub fn net_balance(&self) -> f64 {
-        self.total_income() - self.total_expense()
+    let mut reconciliation = Reconciliation::start(tracker, account, &date, balance);
+    if reconciliation.candidates().is_empty() {
+        println!(
+            "No unreconciled transactions in {} up to {}.",
+            account, date
+        );
+        return Ok(());
     }
 
-    pub fn average_transaction(&self) -> f64 {
-        if self.transactions.is_empty() {
-            return 0.0;
+    loop {
+        let mut table = Table::new()
+            .column("", Align::Left)
+            .column("ID", Align::Right)
+            .column("Date", Align::Left)
+            .truncated_column("Description", Align::Left, 30)
+            .column("Amount", Align::Right);
+        for transaction in reconciliation.candidates() {
+            let mark = if reconciliation.is_ticked(transaction.id) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            table.add_row(vec![
+                mark.into(),
+                transaction.id.to_string().into(),
+                transaction.date.as_str().into(),
+                transaction.description.as_str().into(),
+                format_money(transaction.signed_amount()).into(),
+            ]);
+        }
+        println!("{}", table.render());
+        println!(
+            "Statement {}: {}  Cleared: {}  Difference: {}",
+            date,
+            format_money(balance),
+            format_money(reconciliation.cleared_balance()),
+            format_money(reconciliation.difference())
+        );
+
+        let Some(input) = get_user_input("Ids to tick, a = all, f = finish, q = quit: ") else {
+            println!("\nCancelled.");
+            return Ok(());
+        };
+        match input.as_str() {
+            "q" => {
+                println!("Nothing was reconciled.");
+                return Ok(());
+            }
+            "a" => reconciliation.tick_all(),
+            "f" => {
+                let difference = reconciliation.difference();
+                if difference == 0.0 {
+                    break;
+                }
+                let prompt = format!(
+                    "{} is still unaccounted for. Finish anyway? (yes/no): ",
+                    format_money(difference)
+                );
+                if get_user_input(&prompt).is_some_and(|answer| parse_bool(&answer)) {
+                    break;
+                }
+            }
+            ids => {
+                for word in ids.split([' ', ',']).filter(|word| !word.is_empty()) {
+                    if !word.parse().is_ok_and(|id| reconciliation.toggle(id)) {
+                        println!("{} is not a transaction to reconcile.", word);
+                    }
+                }
+            }
         }
+    }
+
+    let reconciled = reconciliation.finish(tracker)?;
+    storage.save(tracker)?;
+    println!(
+        "Reconciled {} transaction(s) in {}.",
+        reconciled.len(),
+        account
+    );
+    Ok(())
+}
 
-        let sum: f64 = self.transactions.iter().map(|t| t.amount).sum();
+/// Pre-fills a transaction from what OCR read off a receipt and lets the
+/// user correct it before it's added, with the image kept as its receipt.
+#[cfg(feature = "ocr")]
+fn run_receipt(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    config: &Config,
+    image: &std::path::Path,
+) -> Result<()> {
+    let scan = ocr::scan(image, config)?;
+    let missing = || String::from("?");
+    println!(
+        "Read from the receipt: {}, {}, {}",
+        scan.merchant.clone().unwrap_or_else(missing),
+        scan.date.clone().unwrap_or_else(missing),
+        scan.total.map_or_else(missing, format_money)
+    );
+
+    let mut new = NewTransaction {
+        description: scan.merchant.clone().unwrap_or_default(),
+        amount: scan.total.unwrap_or_default(),
+        date: scan
+            .date
+            .unwrap_or_else(|| Local::now().format(dates::ISO_FORMAT).to_string()),
+        transaction_type: TransactionType::Expense,
+        payee: scan.merchant,
+        account: config.default_account.clone(),
+        receipt: Some(image.display().to_string()),
+        ..Default::default()
+    };
+    match rules::find(&config.rules, &new) {
+        Some(rule) => rule.apply(&mut new),
+        None => {
+            if let Some(category) = tracker
+                .past_category(&new.description, new.payee.as_deref())
+                .or_else(|| Classifier::train(tracker.get_transactions()).suggest(&new.description))
+            {
+                new.category = category.to_string();
+            }
+        }
+    }
 
-        let count = self.transactions.len() as f64;
+    println!("Check each field (Enter keeps a value, '-' clears an optional one)");
+    let draft = new.into_transaction(tracker.next_id());
+    let Some(confirmed) = prompt_edited_transaction(&draft, &tracker.categories(), config) else {
+        println!("\nCancelled.");
+        return Ok(());
+    };
+    let added = tracker.add(confirmed.into())?;
+    storage.save(tracker)?;
+    println!("Added transaction {}", added.id);
+    Ok(())
+}
 
-        sum / count
+fn unlock_reconciled(tracker: &mut FinanceTracker, storage: &Storage, ids: &[u32]) -> Result<()> {
+    let mut unlocked = Vec::new();
+    for id in ids {
+        if tracker.get_transaction(*id).is_some_and(|t| t.reconciled) {
+            tracker.set_reconciled(*id, false)?;
+            unlocked.push(id.to_string());
+        } else {
+            println!("Transaction {} is not reconciled.", id);
+        }
+    }
+    if !unlocked.is_empty() {
+        storage.save(tracker)?;
+        println!("Unlocked transaction(s): {}", unlocked.join(", "));
     }
+    Ok(())
+}
 
-    pub fn category_breakdown(&self) -> &HashMap<String, f64> {
-        &self.category_totals
+fn run_check(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    config: &Config,
+    repair: bool,
+) -> Result<()> {
+    let problems = check::check(tracker);
+    if problems.is_empty() {
+        println!(
+            "No problems found in {} transactions.",
+            tracker.get_transactions().len()
+        );
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("- {}", problem);
+    }
+    if !repair {
+        println!("Run with --repair to fix what can be fixed.");
+        std::process::exit(1);
     }
 
-    pub fn get_transactions(&self) -> &Vec<Transaction> {
-        &self.transactions
+    for fix in check::repair(tracker, &config.date_format) {
+        println!("Repaired: {}", fix);
+    }
+    storage.save(tracker)?;
+    let remaining = check::check(tracker);
+    if !remaining.is_empty() {
+        println!("{} problem(s) need fixing by hand.", remaining.len());
+        std::process::exit(1);
     }
+    Ok(())
 }
 
-fn get_user_input(promt: &str) -> String {
-    use std::io::{self, Write};
+fn print_history(tracker: &FinanceTracker, limit: Option<usize>, id: Option<u32>) {
+    let history: Vec<&Entry> = match id {
+        Some(id) => tracker.history_of(id).collect(),
+        None => tracker.history().iter().collect(),
+    };
+    if history.is_empty() {
+        match id {
+            Some(id) => println!("No changes recorded for transaction {}.", id),
+            None => println!("No changes recorded yet."),
+        }
+        return;
+    }
 
-    print!("{}", promt);
-    io::stdout().flush().unwrap();
+    let mut table = Table::new()
+        .column("When", Align::Left)
+        .column("By", Align::Left)
+        .truncated_column("Change", Align::Left, 60);
+    for entry in history.into_iter().rev().take(limit.unwrap_or(usize::MAX)) {
+        let lines = match id {
+            Some(_) => entry.describe(),
+            None => vec![entry.to_string()],
+        };
+        for (index, line) in lines.into_iter().enumerate() {
+            let (at, by) = if index == 0 {
+                (entry.local_time(), entry.by.as_deref().unwrap_or("-"))
+            } else {
+                (String::new(), "")
+            };
+            table.add_row(vec![at.into(), by.into(), line.into()]);
+        }
+    }
+    println!("{}", table.render());
+}
 
-    let mut input = String::new();
-    io::stdin().read_line(&mut input).unwrap();
+fn print_change(verb: &str, affected: &[u32], before: &FinanceTracker, after: &FinanceTracker) {
+    let ids: Vec<String> = affected.iter().map(|id| id.to_string()).collect();
+    println!(
+        "{} {} transaction(s): {}",
+        verb,
+        affected.len(),
+        if ids.is_empty() {
+            String::from("none")
+        } else {
+            ids.join(", ")
+        }
+    );
 
-    input.trim().to_string()
+    let totals = [
+        ("Total Income", before.total_income(), after.total_income()),
+        (
+            "Total Expense",
+            before.total_expense(),
+            after.total_expense(),
+        ),
+        ("Net Balance", before.net_balance(), after.net_balance()),
+    ];
+    for (label, old, new) in totals {
+        println!("{}: {} -> {}", label, format_money(old), format_money(new));
+    }
 }
 
-fn parse_amount(input: &str) -> Result<f64, std::num::ParseFloatError> {
-    input.parse::<f64>()
+fn read_import(args: &ImportArgs, config: &Config) -> Result<Vec<NewTransaction>> {
+    #[cfg(feature = "plugins")]
+    if let Some(name) = &args.plugin {
+        return load_plugins(config).import(name, &std::fs::read(&args.file)?);
+    }
+    import::read(&args.file, args.format, &args.options(config))
 }
 
-fn parse_bool(input: &str) -> bool {
-    match input.to_lowercase().as_str() {
-        "yes" | "y" => true,
-        _ => false,
+#[cfg(feature = "plugins")]
+fn load_plugins(config: &Config) -> PluginHost {
+    let dir = plugin::dir(config);
+    match PluginHost::load(&dir) {
+        Ok(host) => host,
+        Err(err) => {
+            eprintln!("Could not load plugins from {}: {}", dir.display(), err);
+            std::process::exit(1);
+        }
     }
 }
 
-fn display_menu() {
-    println!("\n=== Finance Tracker Menu ===");
-    println!("1) Add Transaction");
-    println!("2) View Summary");
-    println!("3) View Category Report");
-    println!("4) View All Transactions");
-    println!("5) Quit");
-    println!("===========================")
+#[cfg(feature = "plugins")]
+fn list_plugins(host: &PluginHost) {
+    if host.plugins().is_empty() {
+        println!("No plugins installed.");
+        return;
+    }
+    for plugin in host.plugins() {
+        let capabilities: Vec<String> = plugin
+            .capabilities
+            .iter()
+            .map(|capability| format!("{:?}", capability).to_lowercase())
+            .collect();
+        println!("{}: {}", plugin.name, capabilities.join(", "));
+    }
 }
 
-fn add_transaction_interactive(tracker: &mut FinanceTracker) {
-    let description = get_user_input("Enter description: ");
+fn exit_on_error(result: Result<()>) {
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    }
+}
 
-    let amount = loop {
-        let input = get_user_input("Enter amount: ");
-        match parse_amount(&input) {
-            Ok(amt) => break amt,
-            Err(_) => println!("Invalid amount. Please enter a number."),
-        }
+fn run_list(tracker: &FinanceTracker, config: &Config, args: &ListArgs) -> Result<()> {
+    let transactions = tracker.filtered(&args.filter.to_filter(), args.sort, args.order());
+    let shown = match args.page {
+        Some(number) => pager::page(&transactions, number.saturating_sub(1), args.per_page),
+        None => &transactions,
     };
 
-    let is_recurring_input = get_user_input("Is this recurring? (yes/no): ");
-    let is_recurring = parse_bool(&is_recurring_input);
+    match args.format {
+        OutputFormat::Plain => {
+            print_transaction_table(shown, archived_before(config).as_deref());
+            if let Some(number) = args.page {
+                let pages = pager::page_count(transactions.len(), args.per_page);
+                println!("Page {} of {}", number, pages);
+            }
+        }
+        OutputFormat::Json => println!("{}", output::transactions_json(shown)?),
+        OutputFormat::Csv => print!("{}", output::transactions_csv(shown)?),
+    }
+    Ok(())
+}
 
-    let date = get_user_input("Enter date (YYYY-MM-DD): ");
+fn run_export(tracker: &FinanceTracker, config: &Config, args: &ExportArgs) -> Result<()> {
+    let transactions = tracker.filtered(
+        &args.filter.to_filter(),
+        SortKey::Date,
+        SortOrder::Ascending,
+    );
+    if args.format.is_binary() && args.output.is_none() {
+        return Err(ExpensoError::InvalidInput(String::from(
+            "This format is binary, pass --output <file>",
+        )));
+    }
+    let contents = if args.anonymize {
+        let anonymized = export::anonymize(&transactions);
+        let refs: Vec<&Transaction> = anonymized.iter().collect();
+        export::export(&refs, args.format, config)?
+    } else {
+        export::export(&transactions, args.format, config)?
+    };
 
-    let type_input = get_user_input("Enter type (income/expense): ");
-    let transaction_type = TransactionType::from(type_input.as_str());
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, contents)?;
+            println!(
+                "Exported {} transaction(s) to {}",
+                transactions.len(),
+                path.display()
+            );
+        }
+        None => io::Write::write_all(&mut io::stdout(), &contents)?,
+    }
+    Ok(())
+}
 
-    let category = get_user_input("Enter category: ");
+fn run_expense_report(
+    tracker: &FinanceTracker,
+    config: &Config,
+    args: &ExpenseReportArgs,
+) -> Result<()> {
+    if args.format.is_binary() && args.output.is_none() {
+        return Err(ExpensoError::InvalidInput(String::from(
+            "This format is binary, pass --output <file>",
+        )));
+    }
+    let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let report = ExpenseReport::collect(&all, &args.tag, args.from.as_deref(), args.to.as_deref());
+    let contents = report.render(args.format, history_user(config).as_deref())?;
 
-    tracker.add_transaction(
-        description,
-        amount,
-        is_recurring,
-        date,
-        transaction_type,
-        category,
-    );
+    match &args.output {
+        Some(path) => {
+            std::fs::write(path, contents)?;
+            println!(
+                "Wrote {} expense(s) totalling {} to {}",
+                report.items.len(),
+                format_money(report.total),
+                path.display()
+            );
+            if report.missing_receipts() > 0 {
+                eprintln!(
+                    "Note: {} expense(s) have no receipt attached",
+                    report.missing_receipts()
+                );
+            }
+        }
+        None => io::Write::write_all(&mut io::stdout(), &contents)?,
+    }
+    Ok(())
+}
 
-    println!("Transaction added successfully!")
+#[cfg_attr(
+    not(any(feature = "email", feature = "plugins")),
+    allow(unused_variables)
+)]
+fn run_invest(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    config: &Config,
+    command: &InvestCommand,
+) -> Result<()> {
+    if !matches!(command, InvestCommand::List) {
+        storage.require_local("holdings")?;
+    }
+    let today = Local::now().format(dates::ISO_FORMAT).to_string();
+    match command {
+        InvestCommand::List => {
+            let portfolio = tracker.portfolio();
+            if portfolio.is_empty() {
+                println!("No holdings yet.");
+                return Ok(());
+            }
+            let mut table = Table::new()
+                .column("Symbol", Align::Left)
+                .column("Quantity", Align::Right)
+                .column("Cost", Align::Right)
+                .column("Price", Align::Right)
+                .column("Priced on", Align::Left)
+                .column("Value", Align::Right)
+                .column("Gain", Align::Right);
+            for holding in portfolio.holdings() {
+                table.add_row(vec![
+                    holding.symbol.as_str().into(),
+                    holding.quantity.to_string().into(),
+                    format_money(holding.cost_basis).into(),
+                    holding.price.map_or_else(String::new, format_money).into(),
+                    holding.priced_on.as_deref().unwrap_or("").into(),
+                    format_money(holding.market_value()).into(),
+                    Cell::colored(
+                        format_money(holding.gain()),
+                        color::balance_color(holding.gain()),
+                    ),
+                ]);
+            }
+            println!("{}", table.render());
+            println!(
+                "Total value {} on a cost of {}",
+                format_money(portfolio.market_value()),
+                format_money(portfolio.cost_basis())
+            );
+            return Ok(());
+        }
+        InvestCommand::Buy {
+            symbol,
+            quantity,
+            price,
+        } => {
+            let holding = tracker
+                .portfolio_mut()
+                .buy(symbol, *quantity, *price, &today)?;
+            println!(
+                "Holding {} {} at an average cost of {}",
+                holding.quantity,
+                holding.symbol,
+                format_money(holding.average_cost())
+            );
+        }
+        InvestCommand::Sell {
+            symbol,
+            quantity,
+            price,
+        } => {
+            let gain = tracker
+                .portfolio_mut()
+                .sell(symbol, *quantity, *price, &today)?;
+            println!(
+                "Sold {} {}, realized gain {}",
+                quantity,
+                symbol,
+                format_money(gain)
+            );
+        }
+        InvestCommand::Price {
+            symbol: Some(symbol),
+            price: Some(price),
+            ..
+        } => tracker.portfolio_mut().set_price(symbol, *price, &today)?,
+        InvestCommand::Price { .. } => {
+            let Some(command) = &config.price_command else {
+                return Err(ExpensoError::InvalidInput(String::from(
+                    "set price_command in the config file to fetch prices",
+                )));
+            };
+            let symbols: Vec<String> = tracker
+                .portfolio()
+                .holdings()
+                .iter()
+                .map(|h| h.symbol.clone())
+                .collect();
+            for symbol in symbols {
+                match investments::fetch_price(command, &symbol) {
+                    Ok(price) => {
+                        tracker.portfolio_mut().set_price(&symbol, price, &today)?;
+                        println!("{}: {}", symbol, format_money(price));
+                    }
+                    Err(err) => eprintln!("{}: {}", symbol, err),
+                }
+            }
+        }
+    }
+    storage.save(tracker)
 }
 
-fn display_summary(tracker: &FinanceTracker) {
-    println!("\n=== Financial Summary ===");
-    println!("Total Income: ${:.2}", tracker.total_income());
-    println!("Total Expense: ${:.2}", tracker.total_expense());
-    println!("Net Balance: ${:.2}", tracker.net_balance());
-    println!("Average Transaction ${:.2}", tracker.average_transaction());
-    println!("======================\n")
+fn run_loan(tracker: &mut FinanceTracker, storage: &Storage, command: &LoanCommand) -> Result<()> {
+    if matches!(
+        command,
+        LoanCommand::Add { .. } | LoanCommand::Remove { .. }
+    ) {
+        storage.require_local("loans")?;
+    }
+    let today = Local::now().format(dates::ISO_FORMAT).to_string();
+    let find = |tracker: &FinanceTracker, name: &str| {
+        tracker
+            .loans()
+            .iter()
+            .position(|loan| loan.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| ExpensoError::InvalidInput(format!("no loan named {}", name)))
+    };
+    match command {
+        LoanCommand::List => {
+            if tracker.loans().is_empty() {
+                println!("No loans yet.");
+                return Ok(());
+            }
+            let mut table = Table::new()
+                .column("Name", Align::Left)
+                .column("Principal", Align::Right)
+                .column("Rate", Align::Right)
+                .column("Payment", Align::Right)
+                .column("Paid off", Align::Left)
+                .column("Owed", Align::Right);
+            for loan in tracker.loans() {
+                let paid_off = loan.schedule().last().map(|i| i.date.clone());
+                table.add_row(vec![
+                    loan.name.as_str().into(),
+                    format_money(loan.principal).into(),
+                    format!("{}%", loan.rate).into(),
+                    format_money(loan.payment()).into(),
+                    paid_off.unwrap_or_default().into(),
+                    format_money(loan.balance_on(&today)).into(),
+                ]);
+            }
+            println!("{}", table.render());
+            return Ok(());
+        }
+        LoanCommand::Add {
+            name,
+            principal,
+            rate,
+            months,
+            start,
+            no_payment,
+        } => {
+            if find(tracker, name).is_ok() {
+                return Err(ExpensoError::InvalidInput(format!(
+                    "there is already a loan named {}",
+                    name
+                )));
+            }
+            let loan = Loan::new(
+                name,
+                *principal,
+                *rate,
+                *months,
+                start.as_deref().unwrap_or(&today),
+            )?;
+            if !no_payment {
+                tracker.add(loan_payment(&loan))?;
+            }
+            println!(
+                "Added {}: {} a month for {} months",
+                loan.name,
+                format_money(loan.payment()),
+                loan.months
+            );
+            tracker.loans_mut().push(loan);
+        }
+        LoanCommand::Schedule { name } => {
+            let loan = &tracker.loans()[find(tracker, name)?];
+            let mut table = Table::new()
+                .column("#", Align::Right)
+                .column("Date", Align::Left)
+                .column("Payment", Align::Right)
+                .column("Interest", Align::Right)
+                .column("Principal", Align::Right)
+                .column("Balance", Align::Right);
+            for installment in loan.schedule() {
+                table.add_row(vec![
+                    installment.number.to_string().into(),
+                    installment.date.as_str().into(),
+                    format_money(installment.payment).into(),
+                    format_money(installment.interest).into(),
+                    format_money(installment.principal).into(),
+                    format_money(installment.balance).into(),
+                ]);
+            }
+            println!("{}", table.render());
+            let interest = money::sum(loan.schedule().iter().map(|i| i.interest));
+            println!("Total interest {}", format_money(interest));
+            return Ok(());
+        }
+        LoanCommand::Remove { name } => {
+            let index = find(tracker, name)?;
+            let loan = tracker.loans_mut().remove(index);
+            println!("Removed {}", loan.name);
+        }
+    }
+    storage.save(tracker)
 }
 
-fn display_category_report(tracker: &FinanceTracker) {
-    println!("\n=== Category Breakdown ===");
-    let breakdown = tracker.category_breakdown();
-    for (categoty, total) in breakdown.iter() {
-        println!("{} ${:.2}", categoty, total)
+/// The loan's first monthly payment, marked recurring so the later ones
+/// are filled in as they come due.
+fn loan_payment(loan: &Loan) -> NewTransaction {
+    NewTransaction {
+        description: format!("{} payment", loan.name),
+        amount: loan.payment(),
+        is_recurring: true,
+        date: loan.start.clone(),
+        transaction_type: TransactionType::Expense,
+        category: String::from("Loans"),
+        tags: vec![String::from("loan")],
+        ..Default::default()
     }
-    println!("=========================\n")
 }
 
-fn display_all_transactions(tracker: &FinanceTracker) {
-    println!("\n=== All Transaction ===");
-    let transactions = tracker.get_transactions();
-    for transaction in transactions.iter() {
+fn run_report(tracker: &FinanceTracker, config: &Config, report: &ReportCommand) -> Result<()> {
+    match report {
+        ReportCommand::Summary(args) => {
+            let mut summary = with_report_transactions(tracker, config, args, Summary::compute)?;
+            if args.real.is_none() && args.filter.to_filter().starts_at_beginning() {
+                summary = summary.with_opening_balance(tracker.opening_balance());
+            }
+            let transactions =
+                tracker.filtered(&args.filter.to_filter(), SortKey::Id, SortOrder::Ascending);
+            summary = summary
+                .with_currencies(&transactions, &Rates::new(&config.currency, &config.rates)?)?;
+            print!("{}", summary.render(args.format)?);
+        }
+        ReportCommand::Categories(args) => {
+            let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+            let over = alerts::over_budget(&all, config, Local::now().date_naive());
+            let report = with_report_transactions(tracker, config, &args.report, |transactions| {
+                CategoryReport::compute(transactions)
+                    .ordered(args.order.unwrap_or(config.category_order))
+                    .mark_over_budget(&over)
+            })?;
+            print!("{}", report.render(args.report.format)?);
+        }
+        ReportCommand::Monthly(args) => run_report_of::<MonthlyReport>(tracker, config, args)?,
+        ReportCommand::Yearly(args) => run_report_of::<YearlyReport>(tracker, config, args)?,
+        ReportCommand::Payees(args) => run_report_of::<PayeeReport>(tracker, config, args)?,
+        ReportCommand::Balances(args) => {
+            nominal_only(args, "balances")?;
+            run_report_of::<BalanceReport>(tracker, config, args)?
+        }
+        ReportCommand::Projects(args) => {
+            let report = with_report_transactions(tracker, config, args, |transactions| {
+                ProjectReport::compute(transactions).with_projects(tracker.projects())
+            })?;
+            print!("{}", report.render(args.format)?);
+        }
+        ReportCommand::Subscriptions(args) => {
+            run_report_of::<SubscriptionReport>(tracker, config, &args.report)?
+        }
+        ReportCommand::NetWorth(args) => {
+            nominal_only(args, "net worth")?;
+            let transactions =
+                tracker.filtered(&args.filter.to_filter(), SortKey::Id, SortOrder::Ascending);
+            let today = Local::now().format(dates::ISO_FORMAT).to_string();
+            let filter = args.filter.to_filter();
+            let report = if filter.starts_at_beginning() {
+                NetWorthReport::of_balances(tracker.account_balances(filter.to.as_deref()))
+            } else {
+                NetWorthReport::compute(&transactions)
+            };
+            let (unassigned, accounts) = tracker.opening_balances();
+            let report = report
+                .with_opening_balances(unassigned, accounts)
+                .with_portfolio(tracker.portfolio())
+                .with_loans(tracker.loans(), &today);
+            print!("{}", report.render(args.format)?);
+        }
+        #[cfg(feature = "plugins")]
+        ReportCommand::Plugin { name, filter } => {
+            let transactions =
+                tracker.filtered(&filter.to_filter(), SortKey::Date, SortOrder::Ascending);
+            print!("{}", load_plugins(config).report(name, &transactions)?);
+        }
+        #[cfg(feature = "charts")]
+        ReportCommand::Chart(args) => {
+            let mut transactions = tracker.filtered(
+                &args.filter.to_filter(),
+                SortKey::Date,
+                SortOrder::Ascending,
+            );
+            let rates = Rates::new(&config.currency, &config.rates)?;
+            let converted;
+            if rates.any_foreign(&transactions) {
+                converted = rates.convert(&transactions)?;
+                transactions = converted.iter().collect();
+            }
+            chart::render(
+                &transactions,
+                args.kind,
+                &args.out,
+                (args.width, args.height),
+            )?;
+            println!("Wrote {}", args.out.display());
+        }
+        #[cfg(feature = "email")]
+        ReportCommand::Email(args) => {
+            let email = email::compose(tracker, config, args.period, Local::now().date_naive());
+            if args.dry_run {
+                println!("Subject: {}\n\n{}", email.subject, email.body);
+            } else {
+                let recipients = if args.to.is_empty() {
+                    &config.email_to
+                } else {
+                    &args.to
+                };
+                email::send(email, config, recipients)?;
+                println!("Sent to {}", recipients.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Marks every charge of the detected subscriptions as recurring, so the
+/// next ones show up as upcoming bills.
+fn mark_subscriptions(
+    tracker: &mut FinanceTracker,
+    storage: &Storage,
+    args: &SubscriptionReportArgs,
+) -> Result<()> {
+    let transactions = tracker.filtered(
+        &args.report.filter.to_filter(),
+        SortKey::Id,
+        SortOrder::Ascending,
+    );
+    let report = SubscriptionReport::compute(&transactions);
+    let mut marked = 0;
+    for subscription in report.subscriptions.iter().filter(|s| !s.recurring) {
+        for id in &subscription.ids {
+            let Some(transaction) = tracker.get_transaction(*id).cloned() else {
+                continue;
+            };
+            if !transaction.is_recurring {
+                tracker.update(Transaction {
+                    is_recurring: true,
+                    ..transaction
+                })?;
+            }
+        }
         println!(
-            "ID: {} | {} | ${:.2} | {} | {} | {} | Recurring: {}",
-            transaction.id,
-            transaction.description,
-            transaction.amount,
-            transaction.transaction_type,
-            transaction.category,
-            transaction.date,
-            transaction.is_recurring
+            "{} marked recurring ({} {})",
+            subscription.payee,
+            format_money(subscription.amount),
+            subscription.cadence
         );
+        marked += 1;
     }
-    println!("=======================\n");
+    if marked == 0 {
+        println!("No new subscriptions to mark.");
+        return Ok(());
+    }
+    storage.save(tracker)
 }
 
-fn main() {
-    let mut tracker = FinanceTracker::new();
+fn run_report_of<R: Report>(
+    tracker: &FinanceTracker,
+    config: &Config,
+    args: &ReportArgs,
+) -> Result<()> {
+    let report = with_report_transactions(tracker, config, args, R::compute)?;
+    print!("{}", report.render(args.format)?);
+    Ok(())
+}
+
+/// Computes a report over the transactions `args` select, converted into
+/// the configured currency when some are in another, and restated in one
+/// year's money with `--real`.
+fn with_report_transactions<T>(
+    tracker: &FinanceTracker,
+    config: &Config,
+    args: &ReportArgs,
+    compute: impl FnOnce(&[&Transaction]) -> T,
+) -> Result<T> {
+    let mut transactions =
+        tracker.filtered(&args.filter.to_filter(), SortKey::Id, SortOrder::Ascending);
+    let rates = Rates::new(&config.currency, &config.rates)?;
+    let converted;
+    if rates.any_foreign(&transactions) {
+        converted = rates.convert(&transactions)?;
+        transactions = converted.iter().collect();
+    }
+    let Some(year) = args.real else {
+        return Ok(compute(&transactions));
+    };
+    let index = PriceIndex::new(&config.cpi, year)?;
+    eprintln!("Amounts in {} money", index.year());
+    let adjusted = index.adjust(&transactions);
+    Ok(compute(&adjusted.iter().collect::<Vec<_>>()))
+}
+
+/// Balances are owed as they stand, so restating them makes no sense.
+fn nominal_only(args: &ReportArgs, report: &str) -> Result<()> {
+    if args.real.is_some() {
+        return Err(ExpensoError::InvalidInput(format!(
+            "--real doesn't apply to the {} report",
+            report
+        )));
+    }
+    Ok(())
+}
 
+fn run_menu(tracker: &mut FinanceTracker, config: &mut Config, storage: &mut Storage) {
     loop {
         display_menu();
-        let choice = get_user_input("Enter choice: ");
+        let Some(choice) = get_user_input("Enter choice: ") else {
+            println!("Goodbye!");
+            break;
+        };
 
+        if matches!(choice.as_str(), "2" | "3" | "4" | "5") {
+            load_archived(tracker);
+        }
         match choice.as_str() {
-            "1" => add_transaction_interactive(&mut tracker),
-            "2" => display_summary(&tracker),
-            "3" => display_category_report(&tracker),
-            "4" => display_all_transactions(&tracker),
-            "5" => {
+            "1" => add_transaction_interactive(tracker, config),
+            "2" => display_summary(tracker),
+            "3" => display_category_report(tracker, config),
+            "4" => display_all_transactions(tracker, config),
+            "5" => search_interactive(tracker, config),
+            "6" => switch_profile(tracker, config, storage),
+            "7" => {
                 println!("Goodbye!");
                 break;
             }
@@ -250,6 +2556,48 @@ fn main() {
     }
 }
 
+/// Loads another profile in place of the current one, offering to save the
+/// current one's changes first. Nothing changes if the profile can't be read.
+fn switch_profile(tracker: &mut FinanceTracker, config: &mut Config, storage: &mut Storage) {
+    let names = Config::profile_names();
+    if !names.is_empty() {
+        println!("Profiles: {}", names.join(", "));
+    }
+    let Some(name) = get_user_input("Switch to profile (a new name creates it): ") else {
+        println!("\nCancelled.");
+        return;
+    };
+
+    let loaded = Config::profile_path(&name)
+        .and_then(|path| Config::load_profile(&path))
+        .and_then(|next_config| {
+            let next_storage = Storage::from_config(&next_config);
+            let next_tracker = match next_config.recent_start(Local::now().date_naive()) {
+                Some(start) => next_storage.load_since(&start),
+                None => next_storage.load(),
+            }?;
+            Ok((next_config, next_storage, next_tracker))
+        });
+    let (next_config, next_storage, next_tracker) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            println!("Could not switch to profile {}: {}", name, err);
+            return;
+        }
+    };
+
+    save_on_exit(tracker, storage, config);
+    *config = next_config;
+    *storage = next_storage;
+    *tracker = next_tracker;
+    money::init(config);
+    dates::set_fiscal_year_start(config.fiscal_year_start);
+    watch_budgets(tracker, config);
+    tracker.set_opening_balances(config.opening_balance, &config.opening_balances);
+    tracker.set_user(history_user(config));
+    println!("Switched to profile {} ({})", name, storage.location());
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -298,43 +2646,137 @@ mod tests {
     }
 
     #[test]
-    fn test_total_income() {
-        let tracker = create_test_tracker();
-        assert_eq!(tracker.total_income(), 6500.0);
+    fn test_parse_sort() {
+        assert_eq!(parse_sort(""), Some((SortKey::Id, SortOrder::Ascending)));
+        assert_eq!(
+            parse_sort("Amount desc"),
+            Some((SortKey::Amount, SortOrder::Descending))
+        );
+        assert_eq!(parse_sort("payee"), None);
+        assert_eq!(parse_sort("date sideways"), None);
     }
-    
+
     #[test]
-    fn test_total_expense() {
+    fn test_search_regex() {
         let tracker = create_test_tracker();
-        assert_eq!(tracker.total_expense(), 2500.0)
+        let Ok(SearchQuery::Regex(pattern)) = parse_search("/^(rent|salary)$/") else {
+            panic!("expected a regex query");
+        };
+
+        let ids: Vec<u32> = tracker
+            .search_regex(&pattern)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert!(parse_search("/[/").is_err());
+        assert!(matches!(parse_search("rent"), Ok(SearchQuery::Text(_))));
     }
 
     #[test]
-    fn test_net_balance() {
-        let tracker = create_test_tracker();
-        assert_eq!(tracker.net_balance(), 4000.0);
+    fn test_replace_optional() {
+        assert_eq!(
+            replace_optional("", Some("Cafe")),
+            Some(String::from("Cafe"))
+        );
+        assert_eq!(replace_optional("", None), None);
+        assert_eq!(replace_optional("-", Some("Cafe")), None);
+        assert_eq!(
+            replace_optional("Bakery", Some("Cafe")),
+            Some(String::from("Bakery"))
+        );
     }
 
     #[test]
-    fn test_average_transaction() {
-        let tracker = create_test_tracker();
-        assert_eq!(tracker.average_transaction(), 2250.0);
+    fn test_parse_tags() {
+        assert_eq!(parse_tags(" trip, work ,,"), vec!["trip", "work"]);
+        assert!(parse_tags("").is_empty());
     }
 
     #[test]
-    fn test_average_empty() {
-        let tracker = FinanceTracker::new();
-        assert_eq!(tracker.average_transaction(), 0.0)
+    fn test_apply_change_dry_run_leaves_tracker_untouched() {
+        let mut tracker = create_test_tracker();
+        let path = std::env::temp_dir().join("expenso-dry-run-never-written.json");
+        let storage = Storage::File(expense_tracker::storage::FileBackend::new(&path));
+
+        apply_change(&mut tracker, &storage, true, ("delete", "Deleted"), |t| {
+            Ok(t.delete_transaction(1).map(|t| t.id).into_iter().collect())
+        })
+        .unwrap();
+
+        assert_eq!(tracker.get_transactions().len(), 4);
+        assert!(!path.exists());
     }
 
     #[test]
-    fn test_category_totals() {
-        let tracker = create_test_tracker();
-        let breakdown = tracker.category_breakdown();
+    fn test_recategorize_and_merge_refuse_closed_periods() {
+        let mut tracker = create_test_tracker();
+        tracker.close_period("2024-01").unwrap();
+        let dir = std::env::temp_dir().join(format!("expenso-closed-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        let storage = Storage::File(expense_tracker::storage::FileBackend::new(&path));
+
+        let recategorized = apply_change(
+            &mut tracker,
+            &storage,
+            false,
+            ("recategorize", "Recategorized"),
+            |t| t.recategorize("Food", "Dining"),
+        );
+        assert!(matches!(recategorized, Err(ExpensoError::Closed(_))));
+
+        let other_path = dir.join("other.json");
+        let mut other = FinanceTracker::new();
+        other.insert(NewTransaction {
+            description: String::from("Coffee"),
+            amount: 4.0,
+            date: String::from("2024-01-11"),
+            ..Default::default()
+        });
+        storage::save(&other, &other_path).unwrap();
+        let merged = run_merge(&mut tracker, &storage, &other_path, false);
+        assert!(matches!(merged, Err(ExpensoError::Closed(_))));
+
+        let written = path.exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(!written);
+        assert_eq!(tracker.get_transactions().len(), 4);
+        assert_eq!(tracker.get_transaction(4).unwrap().category, "Food");
+    }
+
+    #[test]
+    fn test_loan_adds_a_recurring_payment() {
+        let mut tracker = create_test_tracker();
+        tracker.close_period("2024-01").unwrap();
+        let dir = std::env::temp_dir().join(format!("expenso-loan-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("data.json");
+        let storage = Storage::File(expense_tracker::storage::FileBackend::new(&path));
+        let add = |name: &str, start: &str| LoanCommand::Add {
+            name: name.to_string(),
+            principal: 12000.0,
+            rate: 6.0,
+            months: 24,
+            start: Some(start.to_string()),
+            no_payment: false,
+        };
 
-        assert_eq!(breakdown.get("Work"), Some(&6500.0));
-        assert_eq!(breakdown.get("Housing"), Some(&2000.0));
-        assert_eq!(breakdown.get("Food"), Some(&500.0));
+        let closed = run_loan(&mut tracker, &storage, &add("Van", "2024-01-15"));
+        assert!(matches!(closed, Err(ExpensoError::Closed(_))));
+        assert!(tracker.loans().is_empty());
+        assert!(!path.exists());
+
+        run_loan(&mut tracker, &storage, &add("Car", "2024-02-15")).unwrap();
+        let saved = storage::load(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(saved.loans().len(), 1);
+        let payment = saved.get_transaction(5).unwrap();
+        assert_eq!(payment.description, "Car payment");
+        assert_eq!(payment.amount, saved.loans()[0].payment());
+        assert_eq!(payment.date, "2024-02-15");
+        assert_eq!(payment.category, "Loans");
+        assert!(payment.is_recurring);
     }
 
     #[test]
@@ -349,12 +2791,4 @@ mod tests {
         let result = parse_amount("abc");
         assert!(result.is_err());
     }
-
-    #[test]
-    fn test_transaction_type_from_str() {
-        assert_eq!(TransactionType::from("income"), TransactionType::Income);
-        assert_eq!(TransactionType::from("INCOME"), TransactionType::Income);
-        assert_eq!(TransactionType::from("expense"), TransactionType::Expense);
-        assert_eq!(TransactionType::from("EXPENSE"), TransactionType::Expense);
-    }
 }
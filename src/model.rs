@@ -0,0 +1,602 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Local, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::DEFAULT_ACCOUNT;
+use crate::dates;
+use crate::error::ExpensoError;
+use crate::index;
+use crate::symbol::Symbol;
+
+/// Serialized as `"Income"`, `"Expense"` or `"Settlement"`; lowercase is
+/// accepted on input.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum TransactionType {
+    #[serde(rename = "Income", alias = "income")]
+    Income,
+    #[default]
+    #[serde(rename = "Expense", alias = "expense")]
+    Expense,
+    /// Money paid back to or by someone shared expenses are split with. Not
+    /// counted as income or spending; its `splits` say who and which way.
+    #[serde(rename = "Settlement", alias = "settlement")]
+    Settlement,
+}
+
+impl fmt::Display for TransactionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransactionType::Income => write!(f, "Income"),
+            TransactionType::Expense => write!(f, "Expense"),
+            TransactionType::Settlement => write!(f, "Settlement"),
+        }
+    }
+}
+
+impl FromStr for TransactionType {
+    type Err = ExpensoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "income" => Ok(TransactionType::Income),
+            "expense" => Ok(TransactionType::Expense),
+            "settlement" => Ok(TransactionType::Settlement),
+            _ => Err(ExpensoError::InvalidTransactionType(s.to_string())),
+        }
+    }
+}
+
+/// A stored transaction, as written to the data file and returned by the API.
+///
+/// The JSON field names below are part of the data file format and must not
+/// change. Fields added after the first release are optional and default when
+/// missing, so older files keep loading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    #[serde(rename = "id")]
+    pub id: u32,
+    #[serde(rename = "description")]
+    pub description: String,
+    /// Always positive; the direction comes from `transaction_type`.
+    #[serde(rename = "amount")]
+    pub amount: f64,
+    /// Code of the currency the amount is in, e.g. `EUR`, when it isn't the
+    /// configured `currency`.
+    #[serde(rename = "currency", default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(rename = "is_recurring")]
+    pub is_recurring: bool,
+    /// `YYYY-MM-DD`.
+    #[serde(rename = "date")]
+    pub date: String,
+    #[serde(rename = "transaction_type")]
+    pub transaction_type: TransactionType,
+    #[serde(rename = "category")]
+    pub category: Symbol,
+    #[serde(rename = "payee", default)]
+    pub payee: Option<Symbol>,
+    #[serde(rename = "notes", default)]
+    pub notes: Option<String>,
+    #[serde(rename = "tags", default)]
+    pub tags: Vec<String>,
+    #[serde(rename = "account", default = "default_account")]
+    pub account: String,
+    /// Id from the bank or app a transaction was imported from, used to skip duplicates.
+    #[serde(
+        rename = "external_id",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub external_id: Option<String>,
+    /// The statement it was imported from, used to skip it when an
+    /// overlapping statement is imported again.
+    #[serde(rename = "import", default, skip_serializing_if = "Option::is_none")]
+    pub import: Option<Fingerprint>,
+    /// Link or path to a receipt image.
+    #[serde(rename = "receipt", default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<String>,
+    /// The people this transaction is shared with.
+    #[serde(rename = "splits", default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<Split>,
+    /// The trip, renovation or other project it belongs to.
+    #[serde(rename = "project", default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// When the transaction was added, in UTC (`2024-03-01T18:30:00Z`), as
+    /// opposed to the date the user gave it. Missing on older transactions.
+    #[serde(
+        rename = "created_at",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub created_at: Option<String>,
+    /// When it was last edited, in UTC; the same as `created_at` until then.
+    #[serde(
+        rename = "updated_at",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub updated_at: Option<String>,
+    /// Matched against a bank statement, which locks the amount, date, type
+    /// and account.
+    #[serde(rename = "reconciled", default, skip_serializing_if = "is_false")]
+    pub reconciled: bool,
+}
+
+/// Where an imported transaction came from. The bank's own id, when the
+/// file had one, is the transaction's `external_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// Name of the imported file.
+    #[serde(rename = "source")]
+    pub source: String,
+    /// Hash of the date, amount, type, description and account, and of how
+    /// many rows in the file had them all before this one.
+    #[serde(rename = "hash")]
+    pub hash: String,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Someone's part in a shared transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Split {
+    #[serde(rename = "person")]
+    pub person: String,
+    /// What this transaction changes the person's debt to you by: positive
+    /// for their share of something you paid, negative for your share of
+    /// something they paid or for money they paid you back.
+    #[serde(rename = "share")]
+    pub share: f64,
+}
+
+fn default_account() -> String {
+    String::from(DEFAULT_ACCOUNT)
+}
+
+impl Transaction {
+    pub fn builder() -> TransactionBuilder {
+        TransactionBuilder::default()
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.searchable_fields()
+            .any(|field| field.to_lowercase().contains(&query))
+    }
+
+    pub fn matches_regex(&self, pattern: &Regex) -> bool {
+        self.searchable_fields()
+            .any(|field| pattern.is_match(field))
+    }
+
+    /// Whether money came in: income, or a settlement someone paid you.
+    pub fn is_inflow(&self) -> bool {
+        match self.transaction_type {
+            TransactionType::Income => true,
+            TransactionType::Expense => false,
+            TransactionType::Settlement => self.splits.iter().map(|s| s.share).sum::<f64>() < 0.0,
+        }
+    }
+
+    /// The amount as it moves the account's balance, negative when money went out.
+    pub fn signed_amount(&self) -> f64 {
+        if self.is_inflow() {
+            self.amount
+        } else {
+            -self.amount
+        }
+    }
+
+    /// The words [`FinanceTracker::search`](crate::FinanceTracker::search)
+    /// indexes this transaction under.
+    pub(crate) fn search_words(&self) -> BTreeSet<String> {
+        self.searchable_fields().flat_map(index::words).collect()
+    }
+
+    fn searchable_fields(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.description.as_str())
+            .chain(self.payee.as_deref())
+            .chain(self.notes.as_deref())
+            .chain(std::iter::once(self.category.as_str()))
+    }
+}
+
+/// The fields of a transaction before it has an id, as accepted by the API.
+/// Uses the same JSON names as [`Transaction`]; every field is optional.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewTransaction {
+    pub description: String,
+    pub amount: f64,
+    pub currency: Option<String>,
+    pub is_recurring: bool,
+    pub date: String,
+    pub transaction_type: TransactionType,
+    pub category: String,
+    pub payee: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+    pub account: String,
+    pub external_id: Option<String>,
+    pub import: Option<Fingerprint>,
+    pub receipt: Option<String>,
+    pub splits: Vec<Split>,
+    pub project: Option<String>,
+}
+
+impl NewTransaction {
+    /// Checks the fields and fills in defaults: today for an empty date and
+    /// `Uncategorized` for an empty category. Relative dates such as
+    /// `yesterday` are resolved to YYYY-MM-DD.
+    pub fn validate(mut self) -> Result<NewTransaction, ExpensoError> {
+        if !self.amount.is_finite() || self.amount <= 0.0 {
+            return Err(ExpensoError::InvalidInput(format!(
+                "amount must be a positive number, got {}",
+                self.amount
+            )));
+        }
+        self.currency = self
+            .currency
+            .map(|code| code.trim().to_uppercase())
+            .filter(|code| !code.is_empty());
+        if let Some(code) = &self.currency
+            && !(code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()))
+        {
+            return Err(ExpensoError::InvalidInput(format!(
+                "invalid currency '{}', expected a code like EUR",
+                code
+            )));
+        }
+        let today = Local::now().date_naive();
+        self.date = if self.date.is_empty() {
+            today.format(dates::ISO_FORMAT).to_string()
+        } else {
+            dates::normalize(&self.date, today).ok_or_else(|| {
+                ExpensoError::InvalidInput(format!(
+                    "invalid date '{}', expected YYYY-MM-DD",
+                    self.date
+                ))
+            })?
+        };
+        if self.category.trim().is_empty() {
+            self.category = String::from("Uncategorized");
+        }
+        if self.account.trim().is_empty() {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "account must not be empty",
+            )));
+        }
+        for split in &mut self.splits {
+            split.person = split.person.trim().to_string();
+            if split.person.is_empty() || !split.share.is_finite() {
+                return Err(ExpensoError::InvalidInput(String::from(
+                    "a split needs a person and a share",
+                )));
+            }
+        }
+        if self.transaction_type == TransactionType::Settlement && self.splits.is_empty() {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "a settlement needs the person it settles with",
+            )));
+        }
+        Ok(self)
+    }
+
+    pub fn into_transaction(self, id: u32) -> Transaction {
+        Transaction {
+            id,
+            description: self.description,
+            amount: self.amount,
+            currency: self.currency,
+            is_recurring: self.is_recurring,
+            date: self.date,
+            transaction_type: self.transaction_type,
+            category: self.category.into(),
+            payee: self.payee.map(Symbol::from),
+            notes: self.notes,
+            tags: self.tags,
+            account: self.account,
+            external_id: self.external_id,
+            import: self.import,
+            receipt: self.receipt,
+            splits: self.splits,
+            project: self.project,
+            created_at: None,
+            updated_at: None,
+            reconciled: false,
+        }
+    }
+}
+
+impl From<Transaction> for NewTransaction {
+    fn from(transaction: Transaction) -> Self {
+        NewTransaction {
+            description: transaction.description,
+            amount: transaction.amount,
+            currency: transaction.currency,
+            is_recurring: transaction.is_recurring,
+            date: transaction.date,
+            transaction_type: transaction.transaction_type,
+            category: transaction.category.into(),
+            payee: transaction.payee.map(String::from),
+            notes: transaction.notes,
+            tags: transaction.tags,
+            account: transaction.account,
+            external_id: transaction.external_id,
+            import: transaction.import,
+            receipt: transaction.receipt,
+            splits: transaction.splits,
+            project: transaction.project,
+        }
+    }
+}
+
+impl Default for NewTransaction {
+    fn default() -> Self {
+        NewTransaction {
+            description: String::new(),
+            amount: 0.0,
+            currency: None,
+            is_recurring: false,
+            date: String::new(),
+            transaction_type: TransactionType::default(),
+            category: String::new(),
+            payee: None,
+            notes: None,
+            tags: Vec::new(),
+            account: default_account(),
+            external_id: None,
+            import: None,
+            receipt: None,
+            splits: Vec::new(),
+            project: None,
+        }
+    }
+}
+
+/// Builds a validated [`NewTransaction`] for
+/// [`FinanceTracker::insert`](crate::FinanceTracker::insert).
+///
+/// An amount is required. The date defaults to today and the category to
+/// `Uncategorized`.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    new: NewTransaction,
+    amount: Option<f64>,
+    date: Option<String>,
+}
+
+impl TransactionBuilder {
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.new.description = description.into();
+        self
+    }
+
+    pub fn amount(mut self, amount: f64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn income(mut self) -> Self {
+        self.new.transaction_type = TransactionType::Income;
+        self
+    }
+
+    pub fn expense(mut self) -> Self {
+        self.new.transaction_type = TransactionType::Expense;
+        self
+    }
+
+    pub fn transaction_type(mut self, transaction_type: TransactionType) -> Self {
+        self.new.transaction_type = transaction_type;
+        self
+    }
+
+    /// `YYYY-MM-DD`.
+    pub fn date(mut self, date: impl Into<String>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn on(self, date: NaiveDate) -> Self {
+        self.date(date.format(dates::ISO_FORMAT).to_string())
+    }
+
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.new.category = category.into();
+        self
+    }
+
+    pub fn account(mut self, account: impl Into<String>) -> Self {
+        self.new.account = account.into();
+        self
+    }
+
+    pub fn payee(mut self, payee: impl Into<String>) -> Self {
+        self.new.payee = Some(payee.into());
+        self
+    }
+
+    pub fn notes(mut self, notes: impl Into<String>) -> Self {
+        self.new.notes = Some(notes.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.new.tags.contains(&tag) {
+            self.new.tags.push(tag);
+        }
+        self
+    }
+
+    pub fn recurring(mut self, is_recurring: bool) -> Self {
+        self.new.is_recurring = is_recurring;
+        self
+    }
+
+    pub fn external_id(mut self, external_id: impl Into<String>) -> Self {
+        self.new.external_id = Some(external_id.into());
+        self
+    }
+
+    pub fn receipt(mut self, receipt: impl Into<String>) -> Self {
+        self.new.receipt = Some(receipt.into());
+        self
+    }
+
+    pub fn project(mut self, project: impl Into<String>) -> Self {
+        self.new.project = Some(project.into());
+        self
+    }
+
+    /// Shares the transaction with `person`, see [`Split::share`].
+    pub fn split(mut self, person: impl Into<String>, share: f64) -> Self {
+        self.new.splits.push(Split {
+            person: person.into(),
+            share,
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<NewTransaction, ExpensoError> {
+        let mut new = self.new;
+        new.amount = self
+            .amount
+            .ok_or_else(|| ExpensoError::InvalidInput(String::from("an amount is required")))?;
+        new.date = self.date.unwrap_or_default();
+        new.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_transaction_type_from_str() {
+        assert_eq!(
+            "income".parse::<TransactionType>().unwrap(),
+            TransactionType::Income
+        );
+        assert_eq!(
+            "INCOME".parse::<TransactionType>().unwrap(),
+            TransactionType::Income
+        );
+        assert_eq!(
+            "expense".parse::<TransactionType>().unwrap(),
+            TransactionType::Expense
+        );
+        assert_eq!(
+            " EXPENSE ".parse::<TransactionType>().unwrap(),
+            TransactionType::Expense
+        );
+        assert_eq!(
+            "refund".parse::<TransactionType>().unwrap_err().to_string(),
+            "invalid transaction type 'refund', expected income, expense or settlement"
+        );
+    }
+
+    #[test]
+    fn test_transaction_json_schema() {
+        let transaction = Transaction {
+            id: 7,
+            description: String::from("Lunch"),
+            amount: 12.5,
+            currency: None,
+            is_recurring: false,
+            date: String::from("2024-03-01"),
+            transaction_type: TransactionType::Expense,
+            category: Symbol::new("Food"),
+            payee: Some(Symbol::new("Cafe")),
+            notes: None,
+            tags: vec![String::from("work")],
+            account: String::from("Card"),
+            external_id: None,
+            import: None,
+            receipt: None,
+            splits: Vec::new(),
+            project: None,
+            created_at: None,
+            updated_at: None,
+            reconciled: false,
+        };
+        let json = r#"{"id":7,"description":"Lunch","amount":12.5,"is_recurring":false,"date":"2024-03-01","transaction_type":"Expense","category":"Food","payee":"Cafe","notes":null,"tags":["work"],"account":"Card"}"#;
+
+        assert_eq!(serde_json::to_string(&transaction).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<Transaction>(json).unwrap(),
+            transaction
+        );
+    }
+
+    #[test]
+    fn test_older_and_lowercase_json_is_accepted() {
+        let json = r#"{"id":1,"description":"Pay","amount":100.0,"is_recurring":true,"date":"2024-01-31","transaction_type":"income","category":"Salary"}"#;
+        let transaction: Transaction = serde_json::from_str(json).unwrap();
+
+        assert_eq!(transaction.transaction_type, TransactionType::Income);
+        assert_eq!(transaction.account, DEFAULT_ACCOUNT);
+        assert!(transaction.tags.is_empty());
+        assert_eq!(transaction.payee, None);
+    }
+
+    #[test]
+    fn test_builder_builds_new_transaction() {
+        let new = Transaction::builder()
+            .description("Lunch")
+            .amount(12.5)
+            .category("Food")
+            .date("2024-03-01")
+            .tag("work")
+            .tag("work")
+            .build()
+            .unwrap();
+
+        assert_eq!(new.description, "Lunch");
+        assert_eq!(new.amount, 12.5);
+        assert_eq!(new.category, "Food");
+        assert_eq!(new.date, "2024-03-01");
+        assert_eq!(new.tags, vec![String::from("work")]);
+        assert_eq!(new.transaction_type, TransactionType::Expense);
+        assert_eq!(new.account, DEFAULT_ACCOUNT);
+
+        let income = Transaction::builder()
+            .amount(100.0)
+            .income()
+            .build()
+            .unwrap();
+        assert_eq!(income.category, "Uncategorized");
+        assert_eq!(income.transaction_type, TransactionType::Income);
+    }
+
+    #[test]
+    fn test_builder_validates() {
+        let error = |builder: TransactionBuilder| builder.build().unwrap_err().to_string();
+
+        assert_eq!(error(Transaction::builder()), "an amount is required");
+        assert_eq!(
+            error(Transaction::builder().amount(-5.0)),
+            "amount must be a positive number, got -5"
+        );
+        assert!(Transaction::builder().amount(f64::NAN).build().is_err());
+        assert_eq!(
+            error(Transaction::builder().amount(5.0).date("03/01/2024")),
+            "invalid date '03/01/2024', expected YYYY-MM-DD"
+        );
+        assert!(
+            Transaction::builder()
+                .amount(5.0)
+                .account(" ")
+                .build()
+                .is_err()
+        );
+    }
+}
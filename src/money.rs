@@ -0,0 +1,252 @@
+use std::sync::{LazyLock, PoisonError, RwLock, RwLockReadGuard};
+
+use crate::config::Config;
+
+static FORMAT: LazyLock<RwLock<MoneyFormat>> =
+    LazyLock::new(|| RwLock::new(MoneyFormat::default()));
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoneyFormat {
+    symbol: String,
+    symbol_after: bool,
+    thousands_separator: &'static str,
+    decimal_separator: &'static str,
+    decimal_places: usize,
+}
+
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        MoneyFormat::new("$", "USD", "en-US")
+    }
+}
+
+impl MoneyFormat {
+    pub fn new(symbol: &str, currency: &str, locale: &str) -> Self {
+        let language = locale.split(['-', '_']).next().unwrap_or("en");
+        let (thousands_separator, decimal_separator, symbol_after) =
+            match language.to_lowercase().as_str() {
+                "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" => (".", ",", true),
+                "fr" | "pl" | "cs" | "sv" | "nb" | "fi" | "ru" | "uk" => ("\u{202f}", ",", true),
+                _ => (",", ".", false),
+            };
+
+        MoneyFormat {
+            symbol: symbol.to_string(),
+            symbol_after,
+            thousands_separator,
+            decimal_separator,
+            decimal_places: decimal_places(currency),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        MoneyFormat::new(&config.currency_symbol, &config.currency, &config.locale)
+    }
+
+    pub fn format(&self, amount: f64) -> String {
+        let rounded = round_to(amount.abs(), self.decimal_places);
+        let fixed = format!("{:.*}", self.decimal_places, rounded);
+        let (whole, fraction) = match fixed.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (fixed.as_str(), None),
+        };
+
+        let mut number = group_thousands(whole, self.thousands_separator);
+        if let Some(fraction) = fraction {
+            number.push_str(self.decimal_separator);
+            number.push_str(fraction);
+        }
+
+        let is_zero = fixed.chars().all(|c| c == '0' || c == '.');
+        let sign = if amount < 0.0 && !is_zero { "-" } else { "" };
+
+        if self.symbol_after {
+            format!("{}{} {}", sign, number, self.symbol)
+        } else {
+            format!("{}{}{}", sign, self.symbol, number)
+        }
+    }
+}
+
+pub fn decimal_places(currency: &str) -> usize {
+    match currency.to_uppercase().as_str() {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "UGX" | "PYG" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" | "LYD" | "IQD" => 3,
+        _ => 2,
+    }
+}
+
+fn group_thousands(digits: &str, separator: &str) -> String {
+    let mut grouped = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Rounds to `places` decimals, half away from zero: 2.675 becomes 2.68, as
+/// on a receipt, not 2.67 as banker's rounding or the float itself would
+/// give. Every amount that is rounded goes through here.
+pub fn round_to(amount: f64, places: usize) -> f64 {
+    from_minor(to_minor(amount, places), places)
+}
+
+/// Rounds to the configured currency's minor unit.
+pub fn round(amount: f64) -> f64 {
+    round_to(amount, places())
+}
+
+/// `amount` in minor units, such as cents. It is snapped to a millionth of a
+/// unit first, so 1.005, stored as 1.00499999..., still rounds up.
+pub fn to_minor(amount: f64, places: usize) -> i64 {
+    let scaled = amount * 10f64.powi(places as i32);
+    ((scaled * 1e6).round() / 1e6).round() as i64
+}
+
+pub fn from_minor(minor: i64, places: usize) -> f64 {
+    minor as f64 / 10f64.powi(places as i32)
+}
+
+/// Adds amounts in minor units, so the total is exactly the sum of the
+/// rounded amounts however many there are.
+pub fn sum(amounts: impl IntoIterator<Item = f64>) -> f64 {
+    let places = places();
+    from_minor(
+        amounts.into_iter().map(|a| to_minor(a, places)).sum(),
+        places,
+    )
+}
+
+/// Splits `amount` into `parts` shares that add back up to exactly `amount`.
+/// Shares differ by at most one minor unit, the larger ones first.
+pub fn split(amount: f64, parts: usize) -> Vec<f64> {
+    if parts == 0 {
+        return Vec::new();
+    }
+    let places = places();
+    let total = to_minor(amount, places);
+    let (share, remainder) = (total / parts as i64, total % parts as i64);
+    (0..parts as i64)
+        .map(|part| {
+            let extra = if part < remainder.abs() {
+                remainder.signum()
+            } else {
+                0
+            };
+            from_minor(share + extra, places)
+        })
+        .collect()
+}
+
+/// Converts `amount` at an exchange `rate`, rounding once at the end.
+pub fn convert(amount: f64, rate: f64) -> f64 {
+    round(amount * rate)
+}
+
+fn places() -> usize {
+    current().decimal_places
+}
+
+/// Sets the format used from here on; called again when switching profiles.
+pub fn init(config: &Config) {
+    *FORMAT.write().unwrap_or_else(PoisonError::into_inner) = MoneyFormat::from_config(config);
+}
+
+fn current() -> RwLockReadGuard<'static, MoneyFormat> {
+    FORMAT.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub fn format_money(amount: f64) -> String {
+    current().format(amount)
+}
+
+/// `amount` in `currency`, a code such as `EUR`, written after it: `12.50 EUR`.
+pub fn format_in(amount: f64, currency: &str) -> String {
+    MoneyFormat {
+        symbol: currency.to_string(),
+        symbol_after: true,
+        decimal_places: decimal_places(currency),
+        ..current().clone()
+    }
+    .format(amount)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_us_dollars() {
+        let format = MoneyFormat::default();
+        assert_eq!(format.format(1234567.891), "$1,234,567.89");
+        assert_eq!(format.format(4.5), "$4.50");
+        assert_eq!(format.format(-2000.0), "-$2,000.00");
+        assert_eq!(format.format(-0.001), "$0.00");
+    }
+
+    #[test]
+    fn test_euro_locales() {
+        let german = MoneyFormat::new("€", "EUR", "de-DE");
+        assert_eq!(german.format(1234.5), "1.234,50 €");
+
+        let french = MoneyFormat::new("€", "EUR", "fr_FR");
+        assert_eq!(french.format(-1234.5), "-1\u{202f}234,50 €");
+    }
+
+    #[test]
+    fn test_currency_decimal_places() {
+        let yen = MoneyFormat::new("¥", "JPY", "ja-JP");
+        assert_eq!(yen.format(1500.4), "¥1,500");
+
+        let dinar = MoneyFormat::new("KD", "kwd", "en-KW");
+        assert_eq!(dinar.format(12.3456), "KD12.346");
+    }
+
+    #[test]
+    fn test_rounds_half_away_from_zero() {
+        assert_eq!(round_to(2.675, 2), 2.68);
+        assert_eq!(round_to(1.005, 2), 1.01);
+        assert_eq!(round_to(0.125, 2), 0.13);
+        assert_eq!(round_to(-0.125, 2), -0.13);
+        assert_eq!(round_to(1500.5, 0), 1501.0);
+        assert_eq!(MoneyFormat::default().format(2.675), "$2.68");
+        assert_eq!(sum([0.1, 0.2]), 0.3);
+        assert_eq!(split(10.0, 3), vec![3.34, 3.33, 3.33]);
+    }
+
+    proptest! {
+        #[test]
+        fn test_splits_add_up(cents in -10_000_000i64..10_000_000, parts in 1usize..50) {
+            let amount = from_minor(cents, 2);
+            let shares = split(amount, parts);
+            prop_assert_eq!(shares.len(), parts);
+            prop_assert_eq!(sum(shares.iter().copied()), amount);
+            let smallest = shares.iter().copied().fold(f64::INFINITY, f64::min);
+            let largest = shares.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            prop_assert!(to_minor(largest - smallest, 2) <= 1);
+        }
+
+        #[test]
+        fn test_sums_do_not_drift(cents in prop::collection::vec(0i64..1_000_000, 0..200)) {
+            let amounts: Vec<f64> = cents.iter().map(|&c| from_minor(c, 2)).collect();
+            let expected: i64 = cents.iter().sum();
+            prop_assert_eq!(to_minor(sum(amounts.iter().copied()), 2), expected);
+            // What a budget compares against its limit.
+            let running = amounts.iter().fold(0.0, |spent, &amount| sum([spent, amount]));
+            prop_assert_eq!(to_minor(running, 2), expected);
+        }
+
+        #[test]
+        fn test_conversions_round_once(cents in -10_000_000i64..10_000_000, rate in 0.0001f64..1000.0) {
+            let amount = from_minor(cents, 2);
+            let converted = convert(amount, rate);
+            prop_assert_eq!(round(converted), converted);
+            prop_assert!((converted - amount * rate).abs() <= 0.005 + 1e-6);
+        }
+    }
+}
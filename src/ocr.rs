@@ -0,0 +1,169 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::LazyLock;
+
+use chrono::{Datelike, NaiveDate};
+use regex::Regex;
+
+use crate::config::Config;
+use crate::dates;
+use crate::error::{ExpensoError, Result};
+
+/// An amount with two decimals, optionally grouped: `12.50`, `1,234.50`, `1.234,50`.
+static AMOUNT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d{1,3}(?:[.,]\d{3})+[.,]\d{2}\b|\d+[.,]\d{2}\b").unwrap());
+
+/// What could be read off a receipt. Any of it may be missing or wrong, so
+/// it is only ever used to pre-fill a transaction the user confirms.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReceiptScan {
+    pub merchant: Option<String>,
+    /// `YYYY-MM-DD`.
+    pub date: Option<String>,
+    pub total: Option<f64>,
+}
+
+/// Runs OCR over the image at `path` and picks out the merchant, date and
+/// total.
+pub fn scan(path: &Path, config: &Config) -> Result<ReceiptScan> {
+    let text = read_text(path, config.ocr_command.as_deref())?;
+    Ok(parse(&text, &config.date_format))
+}
+
+/// The text of a receipt image. `command` is run with the image path
+/// appended and prints the text, e.g. a script calling an OCR API; without
+/// one `tesseract` is used.
+pub fn read_text(path: &Path, command: Option<&str>) -> Result<String> {
+    let mut words = command.unwrap_or("tesseract").split_whitespace();
+    let program = words
+        .next()
+        .ok_or_else(|| ExpensoError::Ocr(String::from("ocr_command is empty")))?;
+    let mut process = Command::new(program);
+    process.args(words).arg(path);
+    if command.is_none() {
+        process.arg("stdout");
+    }
+
+    let output = process
+        .output()
+        .map_err(|err| ExpensoError::Ocr(format!("could not run {}: {}", program, err)))?;
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr);
+        return Err(ExpensoError::Ocr(format!(
+            "{} failed: {}",
+            program,
+            message.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Picks the merchant, date and total out of a receipt's text: the first
+/// line that reads like a name, the first date and the largest amount on a
+/// line mentioning the total, or on any line when none does.
+pub fn parse(text: &str, date_format: &str) -> ReceiptScan {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let merchant = lines
+        .iter()
+        .find(|line| {
+            let letters = line.chars().filter(|c| c.is_alphabetic()).count();
+            let digits = line.chars().filter(|c| c.is_ascii_digit()).count();
+            letters >= 3 && letters > digits
+        })
+        .map(|line| line.to_string());
+
+    let date = lines
+        .iter()
+        .flat_map(|line| line.split_whitespace())
+        .find_map(|word| parse_date(word, date_format));
+
+    let is_total = |line: &&&str| {
+        let line = line.to_lowercase();
+        (line.contains("total") && !line.contains("subtotal") && !line.contains("sub total"))
+            || line.contains("amount due")
+            || line.contains("balance due")
+    };
+    let largest = |lines: &mut dyn Iterator<Item = &&str>| {
+        lines
+            .flat_map(|line| line.split_whitespace())
+            // 14.03.2024 would otherwise read as 14.03.
+            .filter(|word| parse_date(word, date_format).is_none())
+            .flat_map(|word| AMOUNT.find_iter(word))
+            .filter_map(|amount| parse_amount(amount.as_str()))
+            .max_by(f64::total_cmp)
+    };
+    let total = largest(&mut lines.iter().filter(is_total)).or_else(|| largest(&mut lines.iter()));
+
+    ReceiptScan {
+        merchant,
+        date,
+        total,
+    }
+}
+
+fn parse_date(word: &str, date_format: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| !c.is_ascii_digit());
+    [
+        date_format,
+        dates::ISO_FORMAT,
+        "%d/%m/%Y",
+        "%m/%d/%Y",
+        "%d.%m.%Y",
+        "%d-%m-%Y",
+    ]
+    .into_iter()
+    .find_map(|format| NaiveDate::parse_from_str(word, format).ok())
+    .filter(|date| (2000..2100).contains(&date.year()))
+    .map(|date| date.format(dates::ISO_FORMAT).to_string())
+}
+
+/// The last `.` or `,` is the decimal point, any others group thousands.
+fn parse_amount(amount: &str) -> Option<f64> {
+    let (whole, cents) = amount.split_at(amount.len() - 3);
+    let whole: String = whole.chars().filter(char::is_ascii_digit).collect();
+    format!("{}.{}", whole, &cents[1..]).parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_parse_receipt() {
+        let text = "
+            BLUE BOTTLE COFFEE
+            123 Main St. 94103
+            03/14/2024 08:15
+            Latte          4.50
+            Croissant      3,75
+            Subtotal       8.25
+            Tax            0.74
+            TOTAL          8.99
+            Cash          20.00
+            Change        11.01
+        ";
+        assert_eq!(
+            parse(text, "%m/%d/%Y"),
+            ReceiptScan {
+                merchant: Some(String::from("BLUE BOTTLE COFFEE")),
+                date: Some(String::from("2024-03-14")),
+                total: Some(8.99),
+            }
+        );
+
+        let scan = parse(
+            "Bäckerei Müller\n14.03.2024\nSumme EUR 1.234,50\n",
+            "%Y-%m-%d",
+        );
+        assert_eq!(scan.merchant.as_deref(), Some("Bäckerei Müller"));
+        assert_eq!(scan.date.as_deref(), Some("2024-03-14"));
+        assert_eq!(scan.total, Some(1234.5));
+        assert_eq!(parse("", "%Y-%m-%d"), ReceiptScan::default());
+    }
+}
@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+use std::io;
+
+use crate::Transaction;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Plain,
+    Json,
+    Csv,
+}
+
+pub fn transactions_json(transactions: &[&Transaction]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(transactions)
+}
+
+pub fn transactions_csv(transactions: &[&Transaction]) -> io::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        "id",
+        "date",
+        "description",
+        "type",
+        "category",
+        "account",
+        "amount",
+        "recurring",
+        "payee",
+        "notes",
+        "tags",
+    ])?;
+
+    for t in transactions {
+        writer.write_record([
+            t.id.to_string(),
+            t.date.clone(),
+            csv_cell(&t.description).into_owned(),
+            t.transaction_type.to_string(),
+            csv_cell(&t.category).into_owned(),
+            csv_cell(&t.account).into_owned(),
+            t.amount.to_string(),
+            t.is_recurring.to_string(),
+            csv_cell(t.payee.as_deref().unwrap_or_default()).into_owned(),
+            csv_cell(t.notes.as_deref().unwrap_or_default()).into_owned(),
+            csv_cell(&t.tags.join(";")).into_owned(),
+        ])?;
+    }
+
+    into_string(writer)
+}
+
+/// Spreadsheets run a cell starting with `=`, `+`, `-` or `@` as a formula,
+/// so such text gets a leading `'`. Numbers like `-12.5` are left as they are.
+pub fn csv_cell(value: &str) -> Cow<'_, str> {
+    let formula = value.starts_with(['=', '+', '-', '@', '\t', '\r']);
+    if formula && value.parse::<f64>().is_err() {
+        Cow::Owned(format!("'{}", value))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn into_string(writer: csv::Writer<Vec<u8>>) -> io::Result<String> {
+    let bytes = writer.into_inner().map_err(|err| err.into_error())?;
+    String::from_utf8(bytes).map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Lunch, with \"team\""),
+            amount: 12.5,
+            date: String::from("2024-03-01"),
+            category: String::from("Food"),
+            tags: vec![String::from("work"), String::from("team")],
+            ..Default::default()
+        });
+        tracker
+    }
+
+    #[test]
+    fn test_transactions_csv_quotes_fields() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let csv = transactions_csv(&all).unwrap();
+        let mut lines = csv.lines();
+
+        assert!(lines.next().unwrap().starts_with("id,date,description"));
+        assert_eq!(
+            lines.next(),
+            Some(
+                "1,2024-03-01,\"Lunch, with \"\"team\"\"\",Expense,Food,Cash,12.5,false,,,work;team"
+            )
+        );
+    }
+
+    #[test]
+    fn test_csv_cells_never_start_a_formula() {
+        assert_eq!(csv_cell("=HYPERLINK(\"x\")"), "'=HYPERLINK(\"x\")");
+        assert_eq!(csv_cell("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_cell("-cmd"), "'-cmd");
+        assert_eq!(csv_cell("-12.5"), "-12.5");
+        assert_eq!(csv_cell("Coffee"), "Coffee");
+    }
+}
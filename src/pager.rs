@@ -0,0 +1,75 @@
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+pub fn page_count(total: usize, per_page: usize) -> usize {
+    if total == 0 {
+        1
+    } else {
+        total.div_ceil(per_page.max(1))
+    }
+}
+
+pub fn page<T>(items: &[T], page: usize, per_page: usize) -> &[T] {
+    let per_page = per_page.max(1);
+    let start = (page * per_page).min(items.len());
+    let end = (start + per_page).min(items.len());
+    &items[start..end]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageCommand {
+    Next,
+    Previous,
+    Edit(u32),
+    Quit,
+}
+
+pub fn parse_page_command(input: &str) -> Option<PageCommand> {
+    let input = input.trim().to_lowercase();
+    let mut parts = input.split_whitespace();
+    let command = parts.next().unwrap_or("");
+    let argument = parts.next();
+    if parts.next().is_some() {
+        return None;
+    }
+
+    match (command, argument) {
+        ("" | "n" | "next", None) => Some(PageCommand::Next),
+        ("p" | "prev" | "previous", None) => Some(PageCommand::Previous),
+        ("q" | "quit", None) => Some(PageCommand::Quit),
+        ("e" | "edit", Some(id)) => id.parse().ok().map(PageCommand::Edit),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(0, 20), 1);
+        assert_eq!(page_count(20, 20), 1);
+        assert_eq!(page_count(21, 20), 2);
+    }
+
+    #[test]
+    fn test_page_slices() {
+        let items: Vec<u32> = (1..=45).collect();
+        assert_eq!(page(&items, 0, 20).len(), 20);
+        assert_eq!(page(&items, 2, 20), &[41, 42, 43, 44, 45]);
+        assert!(page(&items, 3, 20).is_empty());
+    }
+
+    #[test]
+    fn test_parse_page_command() {
+        assert_eq!(parse_page_command(""), Some(PageCommand::Next));
+        assert_eq!(parse_page_command("P"), Some(PageCommand::Previous));
+        assert_eq!(parse_page_command("q"), Some(PageCommand::Quit));
+        assert_eq!(parse_page_command("x"), None);
+        assert_eq!(parse_page_command("e 12"), Some(PageCommand::Edit(12)));
+        assert_eq!(parse_page_command(" Edit 3 "), Some(PageCommand::Edit(3)));
+        assert_eq!(parse_page_command("e"), None);
+        assert_eq!(parse_page_command("e x"), None);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ExpensoError, Result};
+use crate::{Transaction, dates, money};
+
+/// A month (`YYYY-MM`) or year (`YYYY`) closed against changes, with the
+/// balances it closed on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosedPeriod {
+    #[serde(rename = "period")]
+    pub period: String,
+    /// When it was closed, in UTC.
+    #[serde(rename = "closed_at")]
+    pub closed_at: String,
+    /// Each account's balance at the end of the period, from every
+    /// transaction up to then. Opening balances are not included.
+    #[serde(rename = "balances", default)]
+    pub balances: BTreeMap<String, f64>,
+}
+
+impl ClosedPeriod {
+    /// Closes `period` on the balances of `transactions`.
+    pub fn close(period: &str, transactions: &[Transaction]) -> Result<ClosedPeriod> {
+        let period = period.trim();
+        let last = last_day(period)?;
+        Ok(ClosedPeriod {
+            period: period.to_string(),
+            closed_at: dates::utc_now(),
+            balances: balances(
+                transactions.iter().filter(|t| t.date <= last),
+                BTreeMap::new(),
+            ),
+        })
+    }
+
+    /// Whether `date` (`YYYY-MM-DD`) falls within the period.
+    pub fn covers(&self, date: &str) -> bool {
+        date.starts_with(&self.period) && date.as_bytes().get(self.period.len()) == Some(&b'-')
+    }
+
+    /// Whether the whole of `period`, another month or year, falls within this one.
+    pub fn contains(&self, period: &str) -> bool {
+        period == self.period || self.covers(&format!("{}-", period))
+    }
+
+    /// The last day of the period, `YYYY-MM-DD`.
+    pub fn last_day(&self) -> String {
+        last_day(&self.period).unwrap_or_default()
+    }
+}
+
+/// The last day of a `YYYY-MM` or `YYYY` period, or an error when it is
+/// neither.
+fn last_day(period: &str) -> Result<String> {
+    let first = match period.len() {
+        4 => NaiveDate::parse_from_str(&format!("{}-01-01", period), dates::ISO_FORMAT)
+            .ok()
+            .map(|first| (first, Months::new(12))),
+        7 => NaiveDate::parse_from_str(&format!("{}-01", period), dates::ISO_FORMAT)
+            .ok()
+            .map(|first| (first, Months::new(1))),
+        _ => None,
+    };
+    let last = first
+        .and_then(|(first, length)| first.checked_add_months(length))
+        .and_then(|next| next.pred_opt())
+        .ok_or_else(|| {
+            ExpensoError::InvalidInput(format!(
+                "invalid period '{}', expected YYYY-MM or YYYY",
+                period
+            ))
+        })?;
+    Ok(last.format(dates::ISO_FORMAT).to_string())
+}
+
+/// Adds `transactions` to `start`, each account's balance, matching account
+/// names ignoring case and keeping the first spelling.
+pub fn balances<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+    start: BTreeMap<String, f64>,
+) -> BTreeMap<String, f64> {
+    let mut by_key: BTreeMap<String, (String, f64)> = start
+        .into_iter()
+        .map(|(account, balance)| (account.to_lowercase(), (account, balance)))
+        .collect();
+    for t in transactions {
+        let entry = by_key
+            .entry(t.account.to_lowercase())
+            .or_insert_with(|| (t.account.clone(), 0.0));
+        entry.1 = money::sum([entry.1, t.signed_amount()]);
+    }
+    by_key.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_close_period() {
+        let mut tracker = FinanceTracker::new();
+        for (amount, date, account) in [
+            (10.0, "2024-01-31", "Cash"),
+            (20.0, "2024-02-29", "card"),
+            (5.0, "2024-02-01", "Card"),
+            (40.0, "2024-03-01", "Cash"),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from("Shop"),
+                amount,
+                date: String::from(date),
+                account: String::from(account),
+                ..Default::default()
+            });
+        }
+
+        let february = ClosedPeriod::close("2024-02", tracker.get_transactions()).unwrap();
+        assert_eq!(february.last_day(), "2024-02-29");
+        assert_eq!(
+            february.balances,
+            BTreeMap::from([(String::from("Cash"), -10.0), (String::from("card"), -25.0)])
+        );
+        assert!(february.covers("2024-02-10"));
+        assert!(!february.covers("2024-03-01"));
+
+        let year = ClosedPeriod::close("2024", tracker.get_transactions()).unwrap();
+        assert_eq!(year.last_day(), "2024-12-31");
+        assert!(year.covers("2024-03-01"));
+        assert!(year.contains("2024-07"));
+        assert!(!february.contains("2024"));
+
+        for invalid in ["2024-13", "24", "2024-02-01", "march"] {
+            assert!(ClosedPeriod::close(invalid, &[]).is_err());
+        }
+    }
+}
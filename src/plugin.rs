@@ -0,0 +1,406 @@
+//! WASM plugins that add importers, reports and categorization.
+//!
+//! A plugin is a core WASM module (`.wasm`, or `.wat` text) in the plugin
+//! directory. It exports `memory`, an `alloc(len) -> ptr` function and any of:
+//!
+//! - `categorize(ptr, len) -> i64`: given a [`NewTransaction`] as JSON, returns
+//!   `{"category": ..., "tags": [...]}` (both optional), or nothing to pass.
+//! - `import(ptr, len) -> i64`: given the raw bytes of a file, returns a JSON
+//!   array of [`NewTransaction`]s.
+//! - `report(ptr, len) -> i64`: given a JSON array of [`Transaction`]s, returns
+//!   the report as text.
+//!
+//! Results are packed as `ptr << 32 | len`; a length of 0 means no result.
+//!
+//! Plugins only see the data they are handed. The one host function they may
+//! import is `expenso.log(ptr, len)`; there is no file, network or clock
+//! access, and every call runs in a fresh instance with limited fuel and memory.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use wasmtime::{Caller, Engine, Instance, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::config::Config;
+use crate::error::{ExpensoError, Result};
+use crate::{NewTransaction, Transaction};
+
+const FUEL: u64 = 1_000_000_000;
+const MAX_MEMORY: usize = 64 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capability {
+    Categorize,
+    Import,
+    Report,
+}
+
+impl Capability {
+    fn export(self) -> &'static str {
+        match self {
+            Capability::Categorize => "categorize",
+            Capability::Import => "import",
+            Capability::Report => "report",
+        }
+    }
+}
+
+pub struct Plugin {
+    pub name: String,
+    pub capabilities: Vec<Capability>,
+    module: Module,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Categorization {
+    category: Option<String>,
+    tags: Vec<String>,
+}
+
+struct State {
+    plugin: String,
+    limits: StoreLimits,
+}
+
+pub struct PluginHost {
+    engine: Engine,
+    linker: Linker<State>,
+    plugins: Vec<Plugin>,
+}
+
+pub fn dir(config: &Config) -> PathBuf {
+    config
+        .plugin_dir
+        .clone()
+        .or_else(|| dirs::config_dir().map(|dir| dir.join("expenso").join("plugins")))
+        .unwrap_or_else(|| PathBuf::from("plugins"))
+}
+
+impl PluginHost {
+    pub fn new() -> Result<Self> {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|err| plugin_error("host", err))?;
+
+        let mut linker = Linker::new(&engine);
+        linker
+            .func_wrap(
+                "expenso",
+                "log",
+                |mut caller: Caller<'_, State>, ptr: u32, len: u32| {
+                    let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory())
+                    else {
+                        return;
+                    };
+                    let data = memory.data(&caller);
+                    let message = data
+                        .get(ptr as usize..ptr as usize + len as usize)
+                        .map(String::from_utf8_lossy)
+                        .unwrap_or_default();
+                    eprintln!("[{}] {}", caller.data().plugin, message);
+                },
+            )
+            .map_err(|err| plugin_error("host", err))?;
+
+        Ok(PluginHost {
+            engine,
+            linker,
+            plugins: Vec::new(),
+        })
+    }
+
+    /// Loads every `.wasm` and `.wat` file in `dir`, in name order. A missing
+    /// directory means no plugins.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut host = PluginHost::new()?;
+        if !dir.is_dir() {
+            return Ok(host);
+        }
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|ext| ext == "wasm" || ext == "wat")
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            host.add(&name, &fs::read(&path)?)?;
+        }
+        Ok(host)
+    }
+
+    pub fn add(&mut self, name: &str, bytes: &[u8]) -> Result<()> {
+        let module = Module::new(&self.engine, bytes).map_err(|err| plugin_error(name, err))?;
+        let capabilities = [
+            Capability::Categorize,
+            Capability::Import,
+            Capability::Report,
+        ]
+        .into_iter()
+        .filter(|capability| module.get_export(capability.export()).is_some())
+        .collect();
+
+        self.plugins.push(Plugin {
+            name: name.to_string(),
+            capabilities,
+            module,
+        });
+        Ok(())
+    }
+
+    pub fn plugins(&self) -> &[Plugin] {
+        &self.plugins
+    }
+
+    /// Offers an uncategorized transaction to each categorizing plugin in turn
+    /// and applies the first answer. Returns whether one matched.
+    pub fn categorize(&self, new: &mut NewTransaction) -> Result<bool> {
+        if new.category != "Uncategorized" {
+            return Ok(false);
+        }
+        let input = serde_json::to_vec(new)?;
+
+        for plugin in self.with(Capability::Categorize) {
+            let Some(output) = self.call(plugin, Capability::Categorize, &input)? else {
+                continue;
+            };
+            let answer: Categorization =
+                serde_json::from_slice(&output).map_err(|err| plugin_error(&plugin.name, err))?;
+            if let Some(category) = answer.category {
+                new.category = category;
+            }
+            for tag in answer.tags {
+                if !new.tags.contains(&tag) {
+                    new.tags.push(tag);
+                }
+            }
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    pub fn categorize_all(&self, transactions: &mut [NewTransaction]) -> Result<usize> {
+        let mut matched = 0;
+        for new in transactions {
+            if self.categorize(new)? {
+                matched += 1;
+            }
+        }
+        Ok(matched)
+    }
+
+    pub fn import(&self, name: &str, contents: &[u8]) -> Result<Vec<NewTransaction>> {
+        let plugin = self.find(name, Capability::Import)?;
+        let output = self
+            .call(plugin, Capability::Import, contents)?
+            .unwrap_or_else(|| b"[]".to_vec());
+        serde_json::from_slice(&output).map_err(|err| plugin_error(&plugin.name, err))
+    }
+
+    pub fn report(&self, name: &str, transactions: &[&Transaction]) -> Result<String> {
+        let plugin = self.find(name, Capability::Report)?;
+        let input = serde_json::to_vec(transactions)?;
+        let output = self
+            .call(plugin, Capability::Report, &input)?
+            .unwrap_or_default();
+        String::from_utf8(output).map_err(|err| plugin_error(&plugin.name, err))
+    }
+
+    fn with(&self, capability: Capability) -> impl Iterator<Item = &Plugin> {
+        self.plugins
+            .iter()
+            .filter(move |plugin| plugin.capabilities.contains(&capability))
+    }
+
+    fn find(&self, name: &str, capability: Capability) -> Result<&Plugin> {
+        self.with(capability)
+            .find(|plugin| plugin.name == name)
+            .ok_or_else(|| {
+                ExpensoError::Plugin(format!(
+                    "no plugin named '{}' provides {}",
+                    name,
+                    capability.export()
+                ))
+            })
+    }
+
+    fn call(
+        &self,
+        plugin: &Plugin,
+        capability: Capability,
+        input: &[u8],
+    ) -> Result<Option<Vec<u8>>> {
+        let name = plugin.name.as_str();
+        let state = State {
+            plugin: plugin.name.clone(),
+            limits: StoreLimitsBuilder::new().memory_size(MAX_MEMORY).build(),
+        };
+        let mut store = Store::new(&self.engine, state);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(FUEL)
+            .map_err(|err| plugin_error(name, err))?;
+
+        let instance: Instance = self
+            .linker
+            .instantiate(&mut store, &plugin.module)
+            .map_err(|err| plugin_error(name, err))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| plugin_error(name, "no memory export"))?;
+        let alloc = instance
+            .get_typed_func::<u32, u32>(&mut store, "alloc")
+            .map_err(|err| plugin_error(name, err))?;
+        let run = instance
+            .get_typed_func::<(u32, u32), u64>(&mut store, capability.export())
+            .map_err(|err| plugin_error(name, err))?;
+
+        let len = u32::try_from(input.len()).map_err(|_| plugin_error(name, "input too large"))?;
+        let ptr = alloc
+            .call(&mut store, len)
+            .map_err(|err| plugin_error(name, err))?;
+        memory
+            .write(&mut store, ptr as usize, input)
+            .map_err(|err| plugin_error(name, err))?;
+
+        let packed = run
+            .call(&mut store, (ptr, len))
+            .map_err(|err| plugin_error(name, err))?;
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        if len == 0 {
+            return Ok(None);
+        }
+        let mut output = vec![0; len];
+        memory
+            .read(&store, ptr, &mut output)
+            .map_err(|err| plugin_error(name, err))?;
+        Ok(Some(output))
+    }
+}
+
+fn plugin_error(name: &str, err: impl fmt::Display) -> ExpensoError {
+    ExpensoError::Plugin(format!("{}: {}", name, err))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    /// Answers every categorize call with a fixed category and echoes its
+    /// input back for imports and reports.
+    const ECHO: &str = r#"
+        (module
+          (import "expenso" "log" (func $log (param i32 i32)))
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "{\"category\":\"Food:Coffee\",\"tags\":[\"coffee\"]}")
+          (data (i32.const 100) "categorized")
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "categorize") (param i32 i32) (result i64)
+            (call $log (i32.const 100) (i32.const 11))
+            (i64.const 44))
+          (func $echo (param $ptr i32) (param $len i32) (result i64)
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len))))
+          (export "import" (func $echo))
+          (export "report" (func $echo)))
+    "#;
+
+    fn echo_host() -> PluginHost {
+        let mut host = PluginHost::new().unwrap();
+        host.add("echo", ECHO.as_bytes()).unwrap();
+        host
+    }
+
+    #[test]
+    fn test_capabilities_come_from_exports() {
+        let host = echo_host();
+        assert_eq!(
+            host.plugins()[0].capabilities,
+            vec![
+                Capability::Categorize,
+                Capability::Import,
+                Capability::Report
+            ]
+        );
+    }
+
+    #[test]
+    fn test_categorize_only_uncategorized() {
+        let host = echo_host();
+        let mut new = NewTransaction {
+            description: String::from("Latte"),
+            category: String::from("Uncategorized"),
+            ..Default::default()
+        };
+        assert!(host.categorize(&mut new).unwrap());
+        assert_eq!(new.category, "Food:Coffee");
+        assert_eq!(new.tags, vec![String::from("coffee")]);
+
+        let mut rent = NewTransaction {
+            category: String::from("Housing"),
+            ..Default::default()
+        };
+        assert!(!host.categorize(&mut rent).unwrap());
+        assert_eq!(rent.category, "Housing");
+    }
+
+    #[test]
+    fn test_import_and_report_round_trip() {
+        let host = echo_host();
+        let imported = host
+            .import("echo", br#"[{"description":"Lunch","amount":12.5}]"#)
+            .unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].amount, 12.5);
+
+        let report = host.report("echo", &[]).unwrap();
+        assert_eq!(report, "[]");
+        assert!(host.report("missing", &[]).is_err());
+    }
+
+    #[test]
+    fn test_plugins_are_sandboxed() {
+        let mut host = PluginHost::new().unwrap();
+        let wasi = r#"
+            (module
+              (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32)))
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "categorize") (param i32 i32) (result i64) (i64.const 0)))
+        "#;
+        host.add("wasi", wasi.as_bytes()).unwrap();
+        let spin = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 0))
+              (func (export "report") (param i32 i32) (result i64)
+                (loop $forever (br $forever))
+                (i64.const 0)))
+        "#;
+        host.add("spin", spin.as_bytes()).unwrap();
+
+        let mut new = NewTransaction {
+            category: String::from("Uncategorized"),
+            ..Default::default()
+        };
+        assert!(host.categorize(&mut new).is_err());
+        assert!(host.report("spin", &[]).is_err());
+    }
+}
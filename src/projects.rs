@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::dates;
+use crate::error::{ExpensoError, Result};
+
+/// A trip, renovation, client job or anything else transactions are grouped
+/// under, see [`Transaction::project`](crate::Transaction::project).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    #[serde(rename = "name")]
+    pub name: String,
+    #[serde(rename = "budget", default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<f64>,
+    /// First and last day, `YYYY-MM-DD`.
+    #[serde(rename = "start", default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(rename = "end", default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+}
+
+impl Project {
+    pub fn new(
+        name: &str,
+        budget: Option<f64>,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Project> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "a project needs a name",
+            )));
+        }
+        if budget.is_some_and(|budget| !(budget.is_finite() && budget > 0.0)) {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "budget must be a positive number",
+            )));
+        }
+        for date in start.iter().chain(end.iter()) {
+            if !dates::is_iso_date(date) {
+                return Err(ExpensoError::InvalidInput(format!(
+                    "invalid date '{}', expected YYYY-MM-DD",
+                    date
+                )));
+            }
+        }
+        if let (Some(start), Some(end)) = (start, end)
+            && start > end
+        {
+            return Err(ExpensoError::InvalidInput(String::from(
+                "a project can't end before it starts",
+            )));
+        }
+        Ok(Project {
+            name: name.to_string(),
+            budget,
+            start: start.map(str::to_string),
+            end: end.map(str::to_string),
+        })
+    }
+
+    /// Whether `date` falls within the project's dates, open-ended on
+    /// either side when unset.
+    pub fn covers(&self, date: &str) -> bool {
+        self.start.as_deref().is_none_or(|start| date >= start)
+            && self.end.as_deref().is_none_or(|end| date <= end)
+    }
+}
+
+pub fn find<'a>(projects: &'a [Project], name: &str) -> Option<&'a Project> {
+    projects.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_project_dates() {
+        let trip = Project::new(
+            " Berlin ",
+            Some(1500.0),
+            Some("2024-04-01"),
+            Some("2024-04-07"),
+        )
+        .unwrap();
+        assert_eq!(trip.name, "Berlin");
+        assert!(trip.covers("2024-04-01"));
+        assert!(trip.covers("2024-04-07"));
+        assert!(!trip.covers("2024-04-08"));
+
+        let client = Project::new("Acme", None, Some("2024-01-01"), None).unwrap();
+        assert!(client.covers("2030-01-01"));
+        assert!(!client.covers("2023-12-31"));
+        assert_eq!(find(&[trip, client], "acme").unwrap().name, "Acme");
+
+        assert!(Project::new("Late", None, Some("2024-02-01"), Some("2024-01-01")).is_err());
+        assert!(Project::new("Free", Some(0.0), None, None).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::Transaction;
+use crate::config::Config;
+use crate::daemon;
+
+/// Every file `expenso purge` deletes that exists: the data file, the
+/// daemon's backups, receipts kept as local files and the config file.
+pub fn targets(
+    config: &Config,
+    config_path: Option<&Path>,
+    transactions: &[&Transaction],
+) -> Vec<PathBuf> {
+    let mut targets = vec![config.data_file.clone()];
+    if let Some(dir) = &config.backup_dir {
+        targets.extend(daemon::backups(dir).unwrap_or_default());
+    }
+    targets.extend(
+        transactions
+            .iter()
+            .filter_map(|t| t.receipt.as_deref())
+            .filter(|receipt| !receipt.contains("://"))
+            .map(PathBuf::from),
+    );
+    targets.extend(config_path.map(Path::to_path_buf));
+
+    let mut unique = Vec::new();
+    for path in targets {
+        if path.is_file() && !unique.contains(&path) {
+            unique.push(path);
+        }
+    }
+    unique
+}
+
+/// Overwrites `path` with zeros before deleting it, so its contents can't
+/// be read back from the disk by undeleting it. Drives and filesystems that
+/// remap writes, like SSDs or copy-on-write filesystems, may still keep the
+/// old blocks.
+pub fn shred(path: &Path) -> io::Result<()> {
+    let length = fs::metadata(path)?.len();
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    let zeros = [0u8; 8192];
+    let mut left = length;
+    while left > 0 {
+        let chunk = left.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..chunk])?;
+        left -= chunk as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_purge_targets_and_shred() {
+        let dir = std::env::temp_dir().join(format!("expenso-purge-{}", std::process::id()));
+        let backups = dir.join("backups");
+        fs::create_dir_all(&backups).unwrap();
+        let data_file = dir.join("expenso.json");
+        let config_path = dir.join("config.toml");
+        let receipt = dir.join("receipt.jpg");
+        for path in [
+            &data_file,
+            &config_path,
+            &receipt,
+            &backups.join("expenso-2024-04-01.json"),
+            &backups.join("notes.txt"),
+        ] {
+            fs::write(path, "secret").unwrap();
+        }
+
+        let mut tracker = FinanceTracker::new();
+        for receipt in [
+            Some(receipt.display().to_string()),
+            Some(String::from("https://example.com/receipt.jpg")),
+            Some(dir.join("missing.jpg").display().to_string()),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from("Lunch"),
+                amount: 12.0,
+                receipt,
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let config = Config {
+            data_file: data_file.clone(),
+            backup_dir: Some(backups.clone()),
+            ..Config::default()
+        };
+
+        let targets = targets(&config, Some(&config_path), &all);
+        assert_eq!(
+            targets,
+            vec![
+                data_file.clone(),
+                backups.join("expenso-2024-04-01.json"),
+                receipt.clone(),
+                config_path.clone(),
+            ]
+        );
+        for path in &targets {
+            shred(path).unwrap();
+        }
+        let left = backups.join("notes.txt").exists();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(targets.iter().all(|path| !path.exists()));
+        assert!(left);
+    }
+}
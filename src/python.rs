@@ -0,0 +1,254 @@
+//! Python bindings, built into an `expenso` module with `maturin develop`.
+//!
+//! ```python
+//! import expenso, pandas
+//!
+//! tracker = expenso.FinanceTracker.load()
+//! df = pandas.DataFrame(tracker.transactions())
+//! ```
+//!
+//! Transactions and reports come back as plain dicts and lists with the same
+//! keys as the JSON API, so they drop straight into `pandas.DataFrame`.
+
+use std::path::PathBuf;
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::error::ExpensoError;
+use crate::report::{CategoryReport, MonthlyReport, Report, Summary};
+use crate::{FinanceTracker, Transaction, storage};
+
+impl From<ExpensoError> for PyErr {
+    fn from(err: ExpensoError) -> PyErr {
+        match err {
+            ExpensoError::Io(err) => PyOSError::new_err(err.to_string()),
+            err => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+fn to_python<'py>(py: Python<'py>, value: &serde_json::Value) -> PyResult<Bound<'py, PyAny>> {
+    use serde_json::Value;
+
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(flag) => flag.into_pyobject(py)?.to_owned().into_any(),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => integer.into_pyobject(py)?.into_any(),
+            None => number.as_f64().into_pyobject(py)?.into_any(),
+        },
+        Value::String(text) => text.into_pyobject(py)?.into_any(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(to_python(py, item)?)?;
+            }
+            list.into_any()
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, field) in fields {
+                dict.set_item(key, to_python(py, field)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+fn serialize<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_value(value).map_err(ExpensoError::from)?;
+    to_python(py, &value)
+}
+
+#[pyclass(name = "FinanceTracker", module = "expenso")]
+#[derive(Debug, Default)]
+pub struct PyFinanceTracker {
+    inner: FinanceTracker,
+}
+
+impl PyFinanceTracker {
+    fn report<'py, R: Report + Serialize>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        serialize(py, &R::from_tracker(&self.inner))
+    }
+}
+
+#[pymethods]
+impl PyFinanceTracker {
+    #[new]
+    fn new() -> Self {
+        PyFinanceTracker::default()
+    }
+
+    /// Loads a data file, by default the one in the expenso config.
+    #[staticmethod]
+    #[pyo3(signature = (path = None))]
+    fn load(path: Option<PathBuf>) -> PyResult<Self> {
+        let path = match path {
+            Some(path) => path,
+            None => match Config::default_path() {
+                Some(config) => Config::load(&config)?.data_file,
+                None => Config::default().data_file,
+            },
+        };
+        Ok(PyFinanceTracker {
+            inner: storage::load(&path)?,
+        })
+    }
+
+    fn save(&self, path: PathBuf) -> PyResult<()> {
+        Ok(storage::save(&self.inner, &path)?)
+    }
+
+    /// Adds an expense, or an income with `income=True`, and returns its id.
+    #[pyo3(signature = (description, amount, category = None, date = None, account = None, income = false))]
+    fn add(
+        &mut self,
+        description: String,
+        amount: f64,
+        category: Option<String>,
+        date: Option<String>,
+        account: Option<String>,
+        income: bool,
+    ) -> PyResult<u32> {
+        let mut builder = Transaction::builder()
+            .description(description)
+            .amount(amount);
+        if income {
+            builder = builder.income();
+        }
+        if let Some(category) = category {
+            builder = builder.category(category);
+        }
+        if let Some(date) = date {
+            builder = builder.date(date);
+        }
+        if let Some(account) = account {
+            builder = builder.account(account);
+        }
+        Ok(self.inner.add(builder.build()?)?.id)
+    }
+
+    /// Returns False when there is no transaction with `id`, and raises for
+    /// one that is reconciled or in a closed period.
+    fn delete(&mut self, id: u32) -> PyResult<bool> {
+        match self.inner.remove(id) {
+            Ok(_) => Ok(true),
+            Err(ExpensoError::NotFound(_)) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Every transaction as a list of dicts.
+    fn transactions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        serialize(py, self.inner.get_transactions())
+    }
+
+    fn summary<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.report::<Summary>(py)
+    }
+
+    fn categories<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.report::<CategoryReport>(py)
+    }
+
+    fn monthly<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.report::<MonthlyReport>(py)
+    }
+
+    /// The transactions as a `pandas.DataFrame` with a parsed `date` column.
+    fn to_pandas<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let pandas = py.import("pandas")?;
+        let frame = pandas
+            .getattr("DataFrame")?
+            .call1((self.transactions(py)?,))?;
+        if !self.inner.get_transactions().is_empty() {
+            let dates = pandas
+                .getattr("to_datetime")?
+                .call1((frame.get_item("date")?,))?;
+            frame.set_item("date", dates)?;
+        }
+        Ok(frame)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.get_transactions().len()
+    }
+}
+
+#[pymodule]
+fn expenso(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyFinanceTracker>()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_transactions_become_dicts() {
+        Python::initialize();
+        Python::attach(|py| {
+            let mut tracker = PyFinanceTracker::new();
+            let id = tracker
+                .add(
+                    String::from("Lunch"),
+                    12.5,
+                    Some(String::from("Food")),
+                    Some(String::from("2024-03-01")),
+                    None,
+                    false,
+                )
+                .unwrap();
+            assert_eq!(id, 1);
+
+            let rows = tracker.transactions(py).unwrap();
+            let row = rows.get_item(0).unwrap();
+            assert_eq!(
+                row.get_item("category")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "Food"
+            );
+            assert_eq!(
+                row.get_item("amount").unwrap().extract::<f64>().unwrap(),
+                12.5
+            );
+
+            let summary = tracker.summary(py).unwrap();
+            assert_eq!(
+                summary
+                    .get_item("total_expense")
+                    .unwrap()
+                    .extract::<f64>()
+                    .unwrap(),
+                12.5
+            );
+
+            let err = tracker
+                .add(String::from("Bad"), -1.0, None, None, None, false)
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+
+            tracker.inner.close_period("2024-03").unwrap();
+            let err = tracker
+                .add(
+                    String::from("Dinner"),
+                    30.0,
+                    None,
+                    Some(String::from("2024-03-02")),
+                    None,
+                    false,
+                )
+                .unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(tracker.delete(id).is_err());
+            assert!(!tracker.delete(7).unwrap());
+        });
+    }
+}
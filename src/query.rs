@@ -0,0 +1,202 @@
+use chrono::NaiveDate;
+
+use crate::dates;
+use crate::filter::TransactionFilter;
+use crate::{FinanceTracker, Transaction, TransactionType};
+
+type Predicate<'a> = Box<dyn Fn(&Transaction) -> bool + 'a>;
+
+/// A lazy iterator over a tracker's transactions, returned by
+/// [`FinanceTracker::query`](crate::FinanceTracker::query).
+///
+/// Conditions are combined with AND and checked as the iterator is consumed,
+/// so nothing is copied until the caller asks for it. A category or date
+/// bounds narrow the transactions looked at through the tracker's indexes.
+pub struct Query<'a> {
+    tracker: &'a FinanceTracker,
+    category: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    positions: Option<Box<dyn Iterator<Item = usize> + 'a>>,
+    predicates: Vec<Predicate<'a>>,
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(tracker: &'a FinanceTracker) -> Self {
+        Query {
+            tracker,
+            category: None,
+            from: None,
+            to: None,
+            positions: None,
+            predicates: Vec::new(),
+        }
+    }
+
+    pub fn filter(mut self, predicate: impl Fn(&Transaction) -> bool + 'a) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    pub fn transaction_type(self, transaction_type: TransactionType) -> Self {
+        self.filter(move |t| t.transaction_type == transaction_type)
+    }
+
+    pub fn income(self) -> Self {
+        self.transaction_type(TransactionType::Income)
+    }
+
+    pub fn expenses(self) -> Self {
+        self.transaction_type(TransactionType::Expense)
+    }
+
+    /// Case-insensitive; `Food` also matches subcategories such as `Food:Coffee`.
+    pub fn category(mut self, category: &str) -> Self {
+        let category = category.to_lowercase();
+        if self.category.is_none() {
+            self.category = Some(category);
+            return self;
+        }
+        self.filter(move |t| {
+            let own = t.category.to_lowercase();
+            own == category
+                || own
+                    .strip_prefix(&category)
+                    .is_some_and(|rest| rest.starts_with(':'))
+        })
+    }
+
+    pub fn tag(self, tag: &str) -> Self {
+        let tag = tag.to_string();
+        self.filter(move |t| t.tags.iter().any(|own| own.eq_ignore_ascii_case(&tag)))
+    }
+
+    pub fn account(self, account: &str) -> Self {
+        let account = account.to_string();
+        self.filter(move |t| t.account.eq_ignore_ascii_case(&account))
+    }
+
+    /// Inclusive on both ends.
+    pub fn between(self, from: NaiveDate, to: NaiveDate) -> Self {
+        self.since(from).until(to)
+    }
+
+    pub fn since(mut self, from: NaiveDate) -> Self {
+        let from = from.format(dates::ISO_FORMAT).to_string();
+        self.from = self.from.max(Some(from));
+        self
+    }
+
+    pub fn until(mut self, to: NaiveDate) -> Self {
+        let to = to.format(dates::ISO_FORMAT).to_string();
+        self.to = Some(self.to.map_or(to.clone(), |current| current.min(to)));
+        self
+    }
+
+    pub fn matching(self, filter: &'a TransactionFilter) -> Self {
+        self.filter(move |t| filter.matches(t))
+    }
+
+    pub fn total(self) -> f64 {
+        self.map(|t| t.amount).sum()
+    }
+}
+
+impl<'a> Iterator for Query<'a> {
+    type Item = &'a Transaction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Query {
+            tracker,
+            category,
+            from,
+            to,
+            positions,
+            predicates,
+        } = self;
+        let all = tracker.get_transactions();
+        let in_bounds = |t: &Transaction| {
+            from.as_ref().is_none_or(|from| t.date >= *from)
+                && to.as_ref().is_none_or(|to| t.date <= *to)
+        };
+        positions
+            .get_or_insert_with(
+                || match (category.as_deref(), from.as_deref(), to.as_deref()) {
+                    (Some(category), _, _) => {
+                        Box::new(tracker.positions_in_category(category).into_iter())
+                    }
+                    (None, None, None) => Box::new(0..all.len()),
+                    (None, from, to) => Box::new(tracker.positions_between(from, to).into_iter()),
+                },
+            )
+            .map(|position| &all[position])
+            .find(|t| in_bounds(t) && predicates.iter().all(|predicate| predicate(t)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.positions {
+            Some(positions) => (0, positions.size_hint().1),
+            None => (0, Some(self.tracker.get_transactions().len())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::FinanceTracker;
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, category, income) in [
+            ("Salary", 3000.0, "2024-01-31", "Salary", true),
+            ("Coffee", 4.5, "2024-02-03", "Food:Coffee", false),
+            ("Groceries", 80.0, "2024-02-10", "Food", false),
+            ("Foodbank gift", 20.0, "2024-02-12", "Foodbank", false),
+            ("Rent", 1200.0, "2024-03-01", "Housing", false),
+        ] {
+            let builder = Transaction::builder()
+                .description(description)
+                .amount(amount)
+                .date(date)
+                .category(category);
+            let builder = if income { builder.income() } else { builder };
+            tracker.insert(builder.build().unwrap());
+        }
+        tracker
+    }
+
+    fn date(input: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(input, dates::ISO_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn test_query_combines_conditions() {
+        let tracker = create_test_tracker();
+
+        assert_eq!(tracker.query().count(), 5);
+        assert_eq!(tracker.query().income().total(), 3000.0);
+        assert_eq!(tracker.query().expenses().category("food").total(), 84.5);
+
+        let february: Vec<&str> = tracker
+            .query()
+            .between(date("2024-02-01"), date("2024-02-29"))
+            .filter(|t| t.amount > 10.0)
+            .map(|t| t.description.as_str())
+            .collect();
+        assert_eq!(february, vec!["Groceries", "Foodbank gift"]);
+    }
+
+    #[test]
+    fn test_query_matching_filter() {
+        let tracker = create_test_tracker();
+        let filter = TransactionFilter {
+            from: Some(String::from("2024-02-11")),
+            ..Default::default()
+        };
+
+        let ids: Vec<u32> = tracker.query().matching(&filter).map(|t| t.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::NaiveDate;
+
+use crate::{TransactionType, dates};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuickAdd {
+    pub amount: Option<f64>,
+    pub transaction_type: Option<TransactionType>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub account: Option<String>,
+    pub date: Option<String>,
+}
+
+pub fn parse(input: &str, today: NaiveDate) -> Result<QuickAdd, String> {
+    let mut entry = QuickAdd::default();
+    let mut words = Vec::new();
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let token = tokens[index];
+        if let Some((date, used)) = date_at(&tokens[index..], today) {
+            entry.date = Some(date);
+            index += used;
+            continue;
+        }
+
+        if let Some(category) = token.strip_prefix('#').filter(|c| !c.is_empty()) {
+            entry.category = Some(category.to_string());
+        } else if let Some(account) = token.strip_prefix('@').filter(|a| !a.is_empty()) {
+            entry.account = Some(account.to_string());
+        } else if entry.amount.is_none()
+            && let Some((amount, transaction_type)) = parse_amount_token(token)
+        {
+            entry.amount = Some(amount);
+            entry.transaction_type = transaction_type;
+        } else {
+            words.push(token);
+        }
+        index += 1;
+    }
+
+    if entry.amount.is_none() {
+        return Err(format!("No amount found in \"{}\"", input.trim()));
+    }
+    if !words.is_empty() {
+        entry.description = Some(words.join(" "));
+    }
+    Ok(entry)
+}
+
+fn parse_amount_token(token: &str) -> Option<(f64, Option<TransactionType>)> {
+    let (token, transaction_type) = match token.strip_prefix('+') {
+        Some(rest) => (rest, Some(TransactionType::Income)),
+        None => (token, None),
+    };
+    let amount = token.trim_start_matches('$').parse::<f64>().ok()?;
+    Some((amount, transaction_type))
+}
+
+fn date_at(tokens: &[&str], today: NaiveDate) -> Option<(String, usize)> {
+    (1..=tokens.len().min(3)).rev().find_map(|length| {
+        let phrase = tokens[..length].join(" ");
+        if length == 1 && phrase.parse::<f64>().is_ok() {
+            return None;
+        }
+        dates::normalize(&phrase, today).map(|date| (date, length))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 1).unwrap()
+    }
+
+    #[test]
+    fn test_full_shorthand() {
+        let entry = parse("12.50 lunch #Food @cash yesterday", today()).unwrap();
+
+        assert_eq!(entry.amount, Some(12.5));
+        assert_eq!(entry.transaction_type, None);
+        assert_eq!(entry.description.as_deref(), Some("lunch"));
+        assert_eq!(entry.category.as_deref(), Some("Food"));
+        assert_eq!(entry.account.as_deref(), Some("cash"));
+        assert_eq!(entry.date.as_deref(), Some("2024-02-29"));
+    }
+
+    #[test]
+    fn test_income_and_multi_word_description() {
+        let entry = parse("+5000 March salary 2024-03-28", today()).unwrap();
+
+        assert_eq!(entry.amount, Some(5000.0));
+        assert_eq!(entry.transaction_type, Some(TransactionType::Income));
+        assert_eq!(entry.description.as_deref(), Some("March salary"));
+        assert_eq!(entry.date.as_deref(), Some("2024-03-28"));
+        assert_eq!(entry.category, None);
+    }
+
+    #[test]
+    fn test_multi_word_dates() {
+        let entry = parse("30 gas last friday #Car", today()).unwrap();
+        assert_eq!(entry.date.as_deref(), Some("2024-02-23"));
+        assert_eq!(entry.description.as_deref(), Some("gas"));
+
+        let entry = parse("15 book 3 days ago", today()).unwrap();
+        assert_eq!(entry.date.as_deref(), Some("2024-02-27"));
+        assert_eq!(entry.description.as_deref(), Some("book"));
+    }
+
+    #[test]
+    fn test_amount_is_required() {
+        assert!(parse("lunch #Food", today()).is_err());
+    }
+
+    #[test]
+    fn test_only_first_number_is_the_amount() {
+        let entry = parse("$20 2 coffees", today()).unwrap();
+        assert_eq!(entry.amount, Some(20.0));
+        assert_eq!(entry.description.as_deref(), Some("2 coffees"));
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use crate::error::Result;
+use crate::{FinanceTracker, Transaction, money};
+
+/// Matching an account against a bank statement. The account's opening
+/// balance and the transactions reconciled before give the balance to start
+/// from; ticking off the rest should bring it to the statement's ending
+/// balance.
+#[derive(Debug, Clone)]
+pub struct Reconciliation {
+    pub account: String,
+    pub statement_date: String,
+    pub statement_balance: f64,
+    /// The opening balance plus the transactions already reconciled.
+    reconciled_balance: f64,
+    /// Unreconciled transactions in the account up to the statement date,
+    /// oldest first.
+    candidates: Vec<Transaction>,
+    ticked: BTreeSet<u32>,
+}
+
+impl Reconciliation {
+    pub fn start(
+        tracker: &FinanceTracker,
+        account: &str,
+        statement_date: &str,
+        statement_balance: f64,
+    ) -> Self {
+        let in_account: Vec<&Transaction> = tracker
+            .get_transactions()
+            .iter()
+            .filter(|t| t.account.eq_ignore_ascii_case(account))
+            .collect();
+        let reconciled_balance = money::sum(
+            in_account
+                .iter()
+                .filter(|t| t.reconciled)
+                .map(|t| t.signed_amount())
+                .chain([tracker.account_opening_balance(account)]),
+        );
+        let mut candidates: Vec<Transaction> = in_account
+            .into_iter()
+            .filter(|t| !t.reconciled && t.date.as_str() <= statement_date)
+            .cloned()
+            .collect();
+        candidates.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+
+        Reconciliation {
+            account: account.to_string(),
+            statement_date: statement_date.to_string(),
+            statement_balance,
+            reconciled_balance,
+            candidates,
+            ticked: BTreeSet::new(),
+        }
+    }
+
+    pub fn candidates(&self) -> &[Transaction] {
+        &self.candidates
+    }
+
+    pub fn is_ticked(&self, id: u32) -> bool {
+        self.ticked.contains(&id)
+    }
+
+    /// Ticks transaction `id` off, or unticks it. Returns false when it is
+    /// not one of the candidates.
+    pub fn toggle(&mut self, id: u32) -> bool {
+        if !self.candidates.iter().any(|t| t.id == id) {
+            return false;
+        }
+        if !self.ticked.remove(&id) {
+            self.ticked.insert(id);
+        }
+        true
+    }
+
+    pub fn tick_all(&mut self) {
+        self.ticked = self.candidates.iter().map(|t| t.id).collect();
+    }
+
+    /// The balance the statement should show if the ticked transactions are
+    /// all it contains.
+    pub fn cleared_balance(&self) -> f64 {
+        money::sum(
+            std::iter::once(self.reconciled_balance).chain(
+                self.candidates
+                    .iter()
+                    .filter(|t| self.is_ticked(t.id))
+                    .map(|t| t.signed_amount()),
+            ),
+        )
+    }
+
+    /// What is still unaccounted for; zero once the account matches.
+    pub fn difference(&self) -> f64 {
+        money::round(self.statement_balance - self.cleared_balance())
+    }
+
+    /// Marks the ticked transactions as reconciled, returning their ids.
+    pub fn finish(&self, tracker: &mut FinanceTracker) -> Result<Vec<u32>> {
+        for id in &self.ticked {
+            tracker.set_reconciled(*id, true)?;
+        }
+        Ok(self.ticked.iter().copied().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{ExpensoError, NewTransaction, TransactionType};
+
+    #[test]
+    fn test_reconcile_locks_ticked_transactions() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, transaction_type, account) in [
+            (
+                "Salary",
+                2000.0,
+                "2024-03-01",
+                TransactionType::Income,
+                "Bank",
+            ),
+            (
+                "Rent",
+                1200.0,
+                "2024-03-02",
+                TransactionType::Expense,
+                "Bank",
+            ),
+            (
+                "Coffee",
+                4.5,
+                "2024-03-03",
+                TransactionType::Expense,
+                "Cash",
+            ),
+            (
+                "Groceries",
+                80.25,
+                "2024-03-20",
+                TransactionType::Expense,
+                "Bank",
+            ),
+            (
+                "Dinner",
+                40.0,
+                "2024-04-02",
+                TransactionType::Expense,
+                "Bank",
+            ),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from(date),
+                transaction_type,
+                account: String::from(account),
+                ..Default::default()
+            });
+        }
+
+        let mut reconciliation = Reconciliation::start(&tracker, "bank", "2024-03-31", 800.0);
+        let ids: Vec<u32> = reconciliation.candidates().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+        assert_eq!(reconciliation.difference(), 800.0);
+        assert!(reconciliation.toggle(1));
+        assert!(reconciliation.toggle(2));
+        assert!(!reconciliation.toggle(3));
+        assert_eq!(reconciliation.difference(), 0.0);
+
+        assert_eq!(reconciliation.finish(&mut tracker).unwrap(), vec![1, 2]);
+        let changes = NewTransaction {
+            amount: 1250.0,
+            ..tracker.get_transaction(2).unwrap().clone().into()
+        };
+        assert!(matches!(
+            tracker.edit(2, changes),
+            Err(ExpensoError::Reconciled(2))
+        ));
+        assert!(matches!(
+            tracker.remove(1),
+            Err(ExpensoError::Reconciled(1))
+        ));
+        let renamed = NewTransaction {
+            description: String::from("March rent"),
+            ..tracker.get_transaction(2).unwrap().clone().into()
+        };
+        assert!(tracker.edit(2, renamed).unwrap().reconciled);
+
+        let next = Reconciliation::start(&tracker, "Bank", "2024-04-30", 679.75);
+        assert_eq!(next.candidates().len(), 2);
+        assert_eq!(next.cleared_balance(), 800.0);
+    }
+}
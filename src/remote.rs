@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::Transaction;
+use crate::config::Config;
+use crate::error::{ExpensoError, Result};
+use crate::investments::Portfolio;
+use crate::storage::{Backend, Ledger, unblock};
+
+/// Keeps transactions on another machine running `expenso serve`.
+#[derive(Debug)]
+pub struct Client {
+    http: Http,
+    snapshot: Mutex<Vec<Transaction>>,
+}
+
+#[derive(Debug, Clone)]
+struct Http {
+    base: String,
+    auth: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Changes<'a> {
+    created: Vec<&'a Transaction>,
+    updated: Vec<&'a Transaction>,
+    deleted: Vec<u32>,
+}
+
+impl Client {
+    pub fn new(url: &str, config: &Config) -> Self {
+        Client {
+            http: Http {
+                base: format!("{}/api/transactions", url.trim_end_matches('/')),
+                auth: config
+                    .remote_token
+                    .as_ref()
+                    .map(|token| format!("Bearer {}", token)),
+            },
+            snapshot: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn snapshot(&self) -> MutexGuard<'_, Vec<Transaction>> {
+        self.snapshot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Backend for Client {
+    fn location(&self) -> String {
+        self.http.base.clone()
+    }
+
+    /// The server only shares transactions, so the history, holdings, loans,
+    /// projects and closed periods start empty.
+    async fn load(&self) -> Result<Ledger> {
+        let http = self.http.clone();
+        let transactions = unblock(move || http.list()).await?;
+        *self.snapshot() = transactions.clone();
+        Ok(Ledger {
+            transactions,
+            history: Vec::new(),
+            next_id: 0,
+            holdings: Portfolio::default(),
+            loans: Vec::new(),
+            projects: Vec::new(),
+            closed: Vec::new(),
+        })
+    }
+
+    async fn save(&self, ledger: Ledger) -> Result<Ledger> {
+        let snapshot = self.snapshot().clone();
+        let http = self.http.clone();
+        let transactions = ledger.transactions;
+        unblock(move || http.push(&snapshot, &transactions)).await?;
+
+        // The server assigns ids to new transactions, so pick them up along with
+        // anything other machines changed in the meantime.
+        Ok(Ledger {
+            history: ledger.history,
+            holdings: ledger.holdings,
+            loans: ledger.loans,
+            projects: ledger.projects,
+            closed: ledger.closed,
+            ..self.load().await?
+        })
+    }
+}
+
+impl Http {
+    fn list(&self) -> Result<Vec<Transaction>> {
+        let mut request = ureq::get(&self.base);
+        if let Some(auth) = &self.auth {
+            request = request.header("Authorization", auth);
+        }
+        request
+            .call()
+            .map_err(remote_error)?
+            .body_mut()
+            .read_json()
+            .map_err(|err| ExpensoError::Remote(err.to_string()))
+    }
+
+    fn push(&self, snapshot: &[Transaction], current: &[Transaction]) -> Result<()> {
+        let changes = changes(snapshot, current);
+
+        for id in changes.deleted {
+            let mut request = ureq::delete(format!("{}/{}", self.base, id));
+            if let Some(auth) = &self.auth {
+                request = request.header("Authorization", auth);
+            }
+            request.call().map_err(remote_error)?;
+        }
+        for transaction in changes.updated {
+            let mut request = ureq::put(format!("{}/{}", self.base, transaction.id));
+            if let Some(auth) = &self.auth {
+                request = request.header("Authorization", auth);
+            }
+            request.send_json(transaction).map_err(remote_error)?;
+        }
+        for transaction in changes.created {
+            let mut request = ureq::post(&self.base);
+            if let Some(auth) = &self.auth {
+                request = request.header("Authorization", auth);
+            }
+            request.send_json(transaction).map_err(remote_error)?;
+        }
+        Ok(())
+    }
+}
+
+fn changes<'a>(snapshot: &[Transaction], current: &'a [Transaction]) -> Changes<'a> {
+    let before: HashMap<u32, &Transaction> = snapshot.iter().map(|t| (t.id, t)).collect();
+    let mut changes = Changes::default();
+
+    for transaction in current {
+        match before.get(&transaction.id) {
+            None => changes.created.push(transaction),
+            Some(previous) if *previous != transaction => changes.updated.push(transaction),
+            Some(_) => {}
+        }
+    }
+    changes.deleted = snapshot
+        .iter()
+        .map(|t| t.id)
+        .filter(|id| !current.iter().any(|t| t.id == *id))
+        .collect();
+    changes
+}
+
+fn remote_error(err: ureq::Error) -> ExpensoError {
+    ExpensoError::Remote(match err {
+        ureq::Error::StatusCode(401) => {
+            String::from("the server rejected the token, pass --token or set remote_token")
+        }
+        err => err.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    #[test]
+    fn test_changes_against_snapshot() {
+        let mut tracker = FinanceTracker::new();
+        for description in ["Rent", "Coffee", "Gym"] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount: 10.0,
+                ..Default::default()
+            });
+        }
+        let snapshot = tracker.get_transactions().clone();
+
+        let mut coffee = tracker.get_transaction(2).unwrap().clone();
+        coffee.amount = 4.5;
+        tracker.update_transaction(coffee);
+        tracker.delete_transaction(3);
+        tracker.insert(NewTransaction {
+            description: String::from("Books"),
+            ..Default::default()
+        });
+
+        let changes = changes(&snapshot, tracker.get_transactions());
+        assert_eq!(changes.updated.len(), 1);
+        assert_eq!(changes.updated[0].amount, 4.5);
+        assert_eq!(changes.deleted, vec![3]);
+        assert_eq!(changes.created.len(), 1);
+        assert_eq!(changes.created[0].description, "Books");
+    }
+
+    #[test]
+    fn test_client_url_and_token() {
+        let config = Config {
+            remote_token: Some(String::from("secret")),
+            ..Config::default()
+        };
+        let client = Client::new("https://home.example:8080/", &config);
+        assert_eq!(
+            client.location(),
+            "https://home.example:8080/api/transactions"
+        );
+        assert_eq!(client.http.auth.as_deref(), Some("Bearer secret"));
+    }
+}
@@ -0,0 +1,1760 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::OverBudget;
+use crate::color::{Color, balance_color, paint};
+use crate::currency::Rates;
+use crate::dates;
+use crate::error::Result;
+use crate::investments::Portfolio;
+use crate::loans::Loan;
+use crate::money::{self, format_money};
+use crate::output::csv_cell;
+use crate::periods;
+use crate::projects::Project;
+use crate::table::{Align, Table};
+use crate::{FinanceTracker, Symbol, Transaction, TransactionType};
+
+/// How the category report is ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryOrder {
+    /// Alphabetically by category
+    #[default]
+    Name,
+    /// Largest total first
+    Total,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ReportFormat {
+    Plain,
+    Json,
+    Csv,
+    Html,
+}
+
+/// A report computed over a filtered set of transactions.
+///
+/// Implementations provide the plain text and JSON renderings and describe
+/// themselves as a table; CSV and HTML are built from that table.
+pub trait Report {
+    fn compute(transactions: &[&Transaction]) -> Self
+    where
+        Self: Sized;
+
+    /// The report over every transaction in `tracker`. Reports the tracker
+    /// keeps running totals for build from those instead.
+    fn from_tracker(tracker: &FinanceTracker) -> Self
+    where
+        Self: Sized,
+    {
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        Self::compute(&all)
+    }
+
+    fn title(&self) -> &'static str;
+
+    fn headers(&self) -> Vec<&'static str>;
+
+    fn rows(&self) -> Vec<Vec<String>>;
+
+    fn text(&self) -> String;
+
+    fn json(&self) -> serde_json::Result<String>;
+
+    fn csv(&self) -> io::Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(self.headers())?;
+        for row in self.rows() {
+            writer.write_record(row.iter().map(|cell| csv_cell(cell).into_owned()))?;
+        }
+        let bytes = writer.into_inner().map_err(|err| err.into_error())?;
+        String::from_utf8(bytes).map_err(io::Error::other)
+    }
+
+    fn html(&self) -> String {
+        let cells = |tag: &str, row: &[String]| -> String {
+            row.iter()
+                .map(|cell| format!("<{tag}>{}</{tag}>", escape_html(cell)))
+                .collect()
+        };
+        let headers: Vec<String> = self.headers().iter().map(|h| h.to_string()).collect();
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{title}</title></head>\n<body>\n<h1>{title}</h1>\n<table>\n<thead><tr>{}</tr></thead>\n<tbody>\n",
+            cells("th", &headers),
+            title = escape_html(self.title())
+        );
+        for row in self.rows() {
+            html.push_str(&format!("<tr>{}</tr>\n", cells("td", &row)));
+        }
+        html.push_str("</tbody>\n</table>\n</body>\n</html>\n");
+        html
+    }
+
+    /// JSON and HTML end with a newline so every format can be printed as is.
+    fn render(&self, format: ReportFormat) -> crate::Result<String> {
+        Ok(match format {
+            ReportFormat::Plain => format!("\n{}\n\n", self.text()),
+            ReportFormat::Json => self.json()? + "\n",
+            ReportFormat::Csv => self.csv()?,
+            ReportFormat::Html => self.html(),
+        })
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Numbers in CSV and HTML keep their full precision, as `serde` writes them.
+fn number(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+fn banner(title: &str, lines: Vec<String>, rule_width: usize) -> String {
+    let mut text = vec![format!("=== {} ===", title)];
+    text.extend(lines);
+    text.push("=".repeat(rule_width));
+    text.join("\n")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "graphql", derive(async_graphql::SimpleObject))]
+pub struct Summary {
+    pub total_income: f64,
+    pub total_expense: f64,
+    pub net_balance: f64,
+    pub average_transaction: f64,
+    /// The currency the totals were converted into, when the transactions
+    /// are in more than one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub converted_to: Option<String>,
+    /// The totals in each of those currencies, unconverted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[cfg_attr(feature = "graphql", graphql(skip))]
+    pub currencies: Vec<CurrencyTotal>,
+}
+
+/// What the transactions in one currency add up to, in that currency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CurrencyTotal {
+    pub currency: String,
+    pub total_income: f64,
+    pub total_expense: f64,
+    pub net_balance: f64,
+    /// What one unit is worth in the currency the summary was converted into.
+    pub rate: f64,
+}
+
+impl Summary {
+    /// Adds the totals in each currency of `transactions`, the ones the
+    /// summary was computed from before they were converted by `rates`,
+    /// when they are in more than one.
+    pub fn with_currencies(mut self, transactions: &[&Transaction], rates: &Rates) -> Result<Self> {
+        if !rates.any_foreign(transactions) {
+            return Ok(self);
+        }
+        let mut by_currency: BTreeMap<String, Vec<&Transaction>> = BTreeMap::new();
+        for t in transactions {
+            by_currency
+                .entry(rates.currency_of(t).to_uppercase())
+                .or_default()
+                .push(t);
+        }
+        self.currencies = by_currency
+            .into_iter()
+            .map(|(currency, transactions)| {
+                let summary = Summary::of(&transactions);
+                Ok(CurrencyTotal {
+                    rate: rates.rate(&currency)?,
+                    currency,
+                    total_income: summary.total_income,
+                    total_expense: summary.total_expense,
+                    net_balance: summary.net_balance,
+                })
+            })
+            .collect::<Result<_>>()?;
+        self.converted_to = Some(rates.base().to_uppercase());
+        Ok(self)
+    }
+
+    /// Adds `opening`, what there was before the first transaction, to the
+    /// net balance.
+    pub fn with_opening_balance(mut self, opening: f64) -> Self {
+        self.net_balance = money::sum([self.net_balance, opening]);
+        self
+    }
+
+    pub fn of(transactions: &[&Transaction]) -> Self {
+        let total_of = |transaction_type: TransactionType| -> f64 {
+            transactions
+                .iter()
+                .filter(|t| t.transaction_type == transaction_type)
+                .map(|t| t.amount)
+                .fold(0.0, |total, amount| money::sum([total, amount]))
+        };
+        let total_income = total_of(TransactionType::Income);
+        let total_expense = total_of(TransactionType::Expense);
+
+        let counted = transactions
+            .iter()
+            .filter(|t| t.transaction_type != TransactionType::Settlement)
+            .count();
+        let average_transaction = if counted == 0 {
+            0.0
+        } else {
+            money::round((total_income + total_expense) / counted as f64)
+        };
+
+        Summary {
+            total_income,
+            total_expense,
+            net_balance: money::round(total_income - total_expense),
+            average_transaction,
+            converted_to: None,
+            currencies: Vec::new(),
+        }
+    }
+}
+
+impl Report for Summary {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        Summary::of(transactions)
+    }
+
+    fn from_tracker(tracker: &FinanceTracker) -> Self {
+        Summary {
+            total_income: tracker.total_income(),
+            total_expense: tracker.total_expense(),
+            net_balance: tracker.net_balance(),
+            average_transaction: tracker.average_transaction(),
+            converted_to: None,
+            currencies: Vec::new(),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        "Financial Summary"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec![
+            "total_income",
+            "total_expense",
+            "net_balance",
+            "average_transaction",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        vec![vec![
+            number(self.total_income),
+            number(self.total_expense),
+            number(self.net_balance),
+            number(self.average_transaction),
+        ]]
+    }
+
+    fn text(&self) -> String {
+        // Converted totals are only approximately what was spent.
+        let mark = if self.converted_to.is_some() {
+            "≈"
+        } else {
+            ""
+        };
+        let income = format!("{}{}", mark, format_money(self.total_income));
+        let expense = format!("{}{}", mark, format_money(self.total_expense));
+        let net = format!("{}{}", mark, format_money(self.net_balance));
+        let mut lines = vec![
+            format!("Total Income: {}", paint(&income, Color::Green)),
+            format!("Total Expense: {}", paint(&expense, Color::Red)),
+            format!(
+                "Net Balance: {}",
+                paint(&net, balance_color(self.net_balance))
+            ),
+            format!(
+                "Average Transaction {}{}",
+                mark,
+                format_money(self.average_transaction)
+            ),
+        ];
+        if let Some(base) = &self.converted_to {
+            let mut table = Table::new()
+                .column("Currency", Align::Left)
+                .column("Income", Align::Right)
+                .column("Expense", Align::Right)
+                .column("Net", Align::Right)
+                .column("Rate", Align::Right);
+            for c in &self.currencies {
+                table.add_row(vec![
+                    c.currency.as_str().into(),
+                    money::format_in(c.total_income, &c.currency).into(),
+                    money::format_in(c.total_expense, &c.currency).into(),
+                    money::format_in(c.net_balance, &c.currency).into(),
+                    c.rate.to_string().into(),
+                ]);
+            }
+            lines.push(String::new());
+            lines.extend(table.render().lines().map(String::from));
+            lines.push(format!("≈ converted into {} at these rates", base));
+        }
+        banner(self.title(), lines, 22)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub total: f64,
+    /// Set by [`CategoryReport::mark_over_budget`], for the text to stand out.
+    #[serde(skip)]
+    pub over_budget: bool,
+}
+
+/// Totals per category, sorted by name unless [`ordered`](CategoryReport::ordered) otherwise.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct CategoryReport {
+    pub categories: Vec<CategoryTotal>,
+}
+
+impl CategoryReport {
+    /// Builds the report from totals already kept per category, such as
+    /// [`FinanceTracker::category_breakdown`](crate::FinanceTracker::category_breakdown).
+    pub fn from_totals(totals: HashMap<Symbol, f64>) -> Self {
+        let categories: Vec<CategoryTotal> = totals
+            .into_iter()
+            .map(|(category, total)| CategoryTotal {
+                category: category.to_string(),
+                total: money::round(total),
+                over_budget: false,
+            })
+            .collect();
+        CategoryReport { categories }.ordered(CategoryOrder::Name)
+    }
+
+    /// Ties on the total are broken by name, so the order never depends on
+    /// how the totals were collected.
+    pub fn ordered(mut self, order: CategoryOrder) -> Self {
+        match order {
+            CategoryOrder::Name => self.categories.sort_by(|a, b| a.category.cmp(&b.category)),
+            CategoryOrder::Total => self.categories.sort_by(|a, b| {
+                b.total
+                    .total_cmp(&a.total)
+                    .then_with(|| a.category.cmp(&b.category))
+            }),
+        }
+        self
+    }
+
+    /// Marks the categories named in `over`, case-insensitively, as over
+    /// their budget.
+    pub fn mark_over_budget(mut self, over: &[OverBudget]) -> Self {
+        for total in &mut self.categories {
+            total.over_budget = over
+                .iter()
+                .any(|o| o.category.eq_ignore_ascii_case(&total.category));
+        }
+        self
+    }
+}
+
+impl Report for CategoryReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        CategoryReport::from_totals(category_totals(transactions))
+    }
+
+    fn from_tracker(tracker: &FinanceTracker) -> Self {
+        CategoryReport::from_totals(tracker.category_breakdown().clone())
+    }
+
+    fn title(&self) -> &'static str {
+        "Category Breakdown"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["category", "total"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.categories
+            .iter()
+            .map(|c| vec![c.category.clone(), number(c.total)])
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let lines = self
+            .categories
+            .iter()
+            .map(|c| {
+                let category = if c.over_budget {
+                    paint(&c.category, Color::Alert)
+                } else {
+                    c.category.clone()
+                };
+                format!("{} {}", category, format_money(c.total))
+            })
+            .collect();
+        banner(self.title(), lines, 25)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthTotal {
+    /// `YYYY-MM`.
+    pub month: String,
+    pub income: f64,
+    pub expense: f64,
+    pub net: f64,
+}
+
+/// Income, expense and net per calendar month, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct MonthlyReport {
+    pub months: Vec<MonthTotal>,
+}
+
+impl MonthlyReport {
+    fn from_totals<'a>(totals: impl IntoIterator<Item = (&'a str, (f64, f64))>) -> Self {
+        let months = totals
+            .into_iter()
+            .map(|(month, (income, expense))| MonthTotal {
+                month: month.to_string(),
+                income: money::round(income),
+                expense: money::round(expense),
+                net: money::round(income - expense),
+            })
+            .collect();
+        MonthlyReport { months }
+    }
+}
+
+impl Report for MonthlyReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        let totals: BTreeMap<&str, (f64, f64)> = totals_by(
+            transactions,
+            |t| t.date.get(..7).unwrap_or(&t.date),
+            |t| match t.transaction_type {
+                TransactionType::Income => (t.amount, 0.0),
+                TransactionType::Expense => (0.0, t.amount),
+                TransactionType::Settlement => (0.0, 0.0),
+            },
+            |total, (income, expense)| {
+                total.0 += income;
+                total.1 += expense;
+            },
+        )
+        .into_iter()
+        .collect();
+        MonthlyReport::from_totals(totals)
+    }
+
+    fn from_tracker(tracker: &FinanceTracker) -> Self {
+        MonthlyReport::from_totals(
+            tracker
+                .month_totals()
+                .iter()
+                .map(|(month, totals)| (month.as_str(), *totals)),
+        )
+    }
+
+    fn title(&self) -> &'static str {
+        "Monthly Report"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["month", "income", "expense", "net"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.months
+            .iter()
+            .map(|m| {
+                vec![
+                    m.month.clone(),
+                    number(m.income),
+                    number(m.expense),
+                    number(m.net),
+                ]
+            })
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let mut table = Table::new()
+            .column("Month", Align::Left)
+            .column("Income", Align::Right)
+            .column("Expense", Align::Right)
+            .column("Net", Align::Right);
+        for m in &self.months {
+            table.add_row(vec![
+                m.month.as_str().into(),
+                format_money(m.income).into(),
+                format_money(m.expense).into(),
+                format_money(m.net).into(),
+            ]);
+        }
+        let lines = table.render().lines().map(String::from).collect();
+        banner(self.title(), lines, 22)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct YearTotal {
+    /// `2024`, or `2024/25` for a fiscal year starting later than January.
+    pub year: String,
+    pub income: f64,
+    pub expense: f64,
+    pub net: f64,
+}
+
+/// Income, expense and net per fiscal year, oldest first. Transactions
+/// with an invalid date are left out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct YearlyReport {
+    pub years: Vec<YearTotal>,
+}
+
+impl YearlyReport {
+    /// The report over fiscal years starting in month `start`.
+    pub fn fiscal(transactions: &[&Transaction], start: u32) -> Self {
+        let totals: BTreeMap<i32, (f64, f64)> = totals_by(
+            transactions,
+            |t| {
+                NaiveDate::parse_from_str(&t.date, dates::ISO_FORMAT)
+                    .ok()
+                    .map(|date| dates::fiscal_year(date, start))
+            },
+            |t| match t.transaction_type {
+                TransactionType::Income => (t.amount, 0.0),
+                TransactionType::Expense => (0.0, t.amount),
+                TransactionType::Settlement => (0.0, 0.0),
+            },
+            |total, (income, expense)| {
+                total.0 += income;
+                total.1 += expense;
+            },
+        )
+        .into_iter()
+        .filter_map(|(year, totals)| Some((year?, totals)))
+        .collect();
+        let years = totals
+            .into_iter()
+            .map(|(year, (income, expense))| YearTotal {
+                year: dates::fiscal_year_label(year, start),
+                income: money::round(income),
+                expense: money::round(expense),
+                net: money::round(income - expense),
+            })
+            .collect();
+        YearlyReport { years }
+    }
+}
+
+impl Report for YearlyReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        YearlyReport::fiscal(transactions, dates::fiscal_year_start())
+    }
+
+    fn title(&self) -> &'static str {
+        "Yearly Report"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["year", "income", "expense", "net"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.years
+            .iter()
+            .map(|y| {
+                vec![
+                    y.year.clone(),
+                    number(y.income),
+                    number(y.expense),
+                    number(y.net),
+                ]
+            })
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let mut table = Table::new()
+            .column("Year", Align::Left)
+            .column("Income", Align::Right)
+            .column("Expense", Align::Right)
+            .column("Net", Align::Right);
+        for y in &self.years {
+            table.add_row(vec![
+                y.year.as_str().into(),
+                format_money(y.income).into(),
+                format_money(y.expense).into(),
+                format_money(y.net).into(),
+            ]);
+        }
+        let lines = table.render().lines().map(String::from).collect();
+        banner(self.title(), lines, 22)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PayeeTotal {
+    pub payee: String,
+    pub total: f64,
+    pub count: usize,
+}
+
+/// Totals per payee, largest first. Transactions without a payee are left out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct PayeeReport {
+    pub payees: Vec<PayeeTotal>,
+}
+
+impl Report for PayeeReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        let mut payees: Vec<PayeeTotal> = payee_totals(transactions)
+            .into_iter()
+            .map(|(payee, (total, count))| PayeeTotal {
+                payee: payee.to_string(),
+                total: money::round(total),
+                count,
+            })
+            .collect();
+        payees.sort_by(|a, b| b.total.total_cmp(&a.total).then(a.payee.cmp(&b.payee)));
+        PayeeReport { payees }
+    }
+
+    fn title(&self) -> &'static str {
+        "Payees"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["payee", "total", "count"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.payees
+            .iter()
+            .map(|p| vec![p.payee.clone(), number(p.total), p.count.to_string()])
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let lines = self
+            .payees
+            .iter()
+            .map(|p| format!("{} {} ({})", p.payee, format_money(p.total), p.count))
+            .collect();
+        banner(self.title(), lines, 25)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PersonBalance {
+    pub person: String,
+    /// Positive when they owe you.
+    pub balance: f64,
+}
+
+/// Who owes whom across shared transactions. People who are settled up are
+/// left out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct BalanceReport {
+    pub balances: Vec<PersonBalance>,
+}
+
+impl BalanceReport {
+    pub fn balance(&self, person: &str) -> f64 {
+        self.balances
+            .iter()
+            .find(|b| b.person.eq_ignore_ascii_case(person))
+            .map_or(0.0, |b| b.balance)
+    }
+}
+
+impl Report for BalanceReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        // Keyed by the lowercased name, keeping the first spelling seen.
+        let mut balances: BTreeMap<String, (String, f64)> = BTreeMap::new();
+        for split in transactions.iter().flat_map(|t| &t.splits) {
+            let entry = balances
+                .entry(split.person.to_lowercase())
+                .or_insert_with(|| (split.person.clone(), 0.0));
+            entry.1 = money::sum([entry.1, split.share]);
+        }
+        let balances = balances
+            .into_values()
+            .filter(|(_, balance)| *balance != 0.0)
+            .map(|(person, balance)| PersonBalance { person, balance })
+            .collect();
+        BalanceReport { balances }
+    }
+
+    fn title(&self) -> &'static str {
+        "Balances"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["person", "balance"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.balances
+            .iter()
+            .map(|b| vec![b.person.clone(), number(b.balance)])
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let lines = self
+            .balances
+            .iter()
+            .map(|b| {
+                let amount = format_money(b.balance.abs());
+                if b.balance > 0.0 {
+                    format!("{} owes you {}", b.person, paint(&amount, Color::Green))
+                } else {
+                    format!("You owe {} {}", b.person, paint(&amount, Color::Red))
+                }
+            })
+            .collect();
+        banner(self.title(), lines, 25)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountBalance {
+    pub account: String,
+    pub balance: f64,
+}
+
+/// What you're worth: every account's balance plus the market value of the
+/// holdings, less what is still owed on loans.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NetWorthReport {
+    pub accounts: Vec<AccountBalance>,
+    /// The opening balance not tied to an account.
+    pub opening_balance: f64,
+    pub investments: f64,
+    /// Principal still owed, as a positive amount.
+    pub loans: f64,
+    pub net_worth: f64,
+}
+
+impl NetWorthReport {
+    /// The report for account balances already added up, such as by
+    /// [`FinanceTracker::account_balances`].
+    pub fn of_balances(balances: BTreeMap<String, f64>) -> Self {
+        let mut accounts: Vec<AccountBalance> = balances
+            .into_iter()
+            .map(|(account, balance)| AccountBalance { account, balance })
+            .collect();
+        accounts.sort_by_key(|a| a.account.to_lowercase());
+        NetWorthReport {
+            net_worth: money::sum(accounts.iter().map(|a| a.balance)),
+            accounts,
+            opening_balance: 0.0,
+            investments: 0.0,
+            loans: 0.0,
+        }
+    }
+
+    /// Adds what there was before the first transaction: `unassigned`, and
+    /// each of `accounts`' opening balance to its balance.
+    pub fn with_opening_balances(
+        mut self,
+        unassigned: f64,
+        accounts: &BTreeMap<String, f64>,
+    ) -> Self {
+        for (account, opening) in accounts {
+            match self
+                .accounts
+                .iter_mut()
+                .find(|a| a.account.eq_ignore_ascii_case(account))
+            {
+                Some(a) => a.balance = money::sum([a.balance, *opening]),
+                None => self.accounts.push(AccountBalance {
+                    account: account.clone(),
+                    balance: *opening,
+                }),
+            }
+        }
+        self.accounts.sort_by_key(|a| a.account.to_lowercase());
+        self.opening_balance = unassigned;
+        self.net_worth = self.total();
+        self
+    }
+
+    /// Adds the market value of `portfolio`, which no transaction records.
+    pub fn with_portfolio(mut self, portfolio: &Portfolio) -> Self {
+        self.investments = portfolio.market_value();
+        self.net_worth = self.total();
+        self
+    }
+
+    /// Subtracts the principal still owed on `loans` once the payments due
+    /// by `date` are made.
+    pub fn with_loans(mut self, loans: &[Loan], date: &str) -> Self {
+        self.loans = money::sum(loans.iter().map(|loan| loan.balance_on(date)));
+        self.net_worth = self.total();
+        self
+    }
+
+    fn total(&self) -> f64 {
+        money::sum(self.accounts.iter().map(|a| a.balance).chain([
+            self.opening_balance,
+            self.investments,
+            -self.loans,
+        ]))
+    }
+}
+
+impl Report for NetWorthReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        NetWorthReport::of_balances(periods::balances(
+            transactions.iter().copied(),
+            BTreeMap::new(),
+        ))
+    }
+
+    fn from_tracker(tracker: &FinanceTracker) -> Self {
+        let today = Local::now().format(dates::ISO_FORMAT).to_string();
+        let (unassigned, accounts) = tracker.opening_balances();
+        NetWorthReport::of_balances(tracker.account_balances(None))
+            .with_opening_balances(unassigned, accounts)
+            .with_portfolio(tracker.portfolio())
+            .with_loans(tracker.loans(), &today)
+    }
+
+    fn title(&self) -> &'static str {
+        "Net Worth"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec!["item", "value"]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        let mut rows: Vec<Vec<String>> = self
+            .accounts
+            .iter()
+            .map(|a| vec![a.account.clone(), number(a.balance)])
+            .collect();
+        if self.opening_balance != 0.0 {
+            rows.push(vec![
+                String::from("Opening balance"),
+                number(self.opening_balance),
+            ]);
+        }
+        rows.push(vec![String::from("Investments"), number(self.investments)]);
+        rows.push(vec![String::from("Loans"), number(-self.loans)]);
+        rows.push(vec![String::from("Net worth"), number(self.net_worth)]);
+        rows
+    }
+
+    fn text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .accounts
+            .iter()
+            .map(|a| format!("{}: {}", a.account, format_money(a.balance)))
+            .collect();
+        if self.opening_balance != 0.0 {
+            lines.push(format!(
+                "Opening balance: {}",
+                format_money(self.opening_balance)
+            ));
+        }
+        lines.push(format!("Investments: {}", format_money(self.investments)));
+        lines.push(format!("Loans: {}", format_money(-self.loans)));
+        let net_worth = format_money(self.net_worth);
+        lines.push(format!(
+            "Net Worth: {}",
+            paint(&net_worth, balance_color(self.net_worth))
+        ));
+        banner(self.title(), lines, 22)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// How often a subscription charges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cadence {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl Cadence {
+    /// The cadence a gap of `days` between two charges fits, with some
+    /// slack for billing dates that drift.
+    fn of(days: i64) -> Option<Cadence> {
+        match days {
+            6..=8 => Some(Cadence::Weekly),
+            26..=35 => Some(Cadence::Monthly),
+            358..=372 => Some(Cadence::Yearly),
+            _ => None,
+        }
+    }
+
+    pub fn monthly_cost(self, amount: f64) -> f64 {
+        money::round(match self {
+            Cadence::Weekly => amount * 52.0 / 12.0,
+            Cadence::Monthly => amount,
+            Cadence::Yearly => amount / 12.0,
+        })
+    }
+}
+
+impl fmt::Display for Cadence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cadence::Weekly => "weekly",
+            Cadence::Monthly => "monthly",
+            Cadence::Yearly => "yearly",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Subscription {
+    pub payee: String,
+    pub amount: f64,
+    pub cadence: Cadence,
+    pub count: usize,
+    pub last_charged: String,
+    pub monthly_cost: f64,
+    /// The charges it was detected from, oldest first.
+    pub ids: Vec<u32>,
+    /// Whether every charge is already marked recurring.
+    pub recurring: bool,
+}
+
+/// Expenses charged again and again by the same payee for the same amount at
+/// a steady weekly, monthly or yearly pace. The payee falls back to the
+/// description for transactions without one.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubscriptionReport {
+    pub subscriptions: Vec<Subscription>,
+    pub monthly_total: f64,
+}
+
+impl Report for SubscriptionReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        let mut charges: BTreeMap<(String, i64), Vec<&Transaction>> = BTreeMap::new();
+        for transaction in transactions {
+            if transaction.transaction_type != TransactionType::Expense {
+                continue;
+            }
+            let payee = match transaction.payee {
+                Some(payee) => payee.to_string(),
+                None => transaction.description.clone(),
+            };
+            charges
+                .entry((payee.to_lowercase(), money::to_minor(transaction.amount, 2)))
+                .or_default()
+                .push(transaction);
+        }
+
+        let mut subscriptions: Vec<Subscription> = charges
+            .into_values()
+            .filter_map(|mut charges| {
+                charges.sort_by(|a, b| a.date.cmp(&b.date).then(a.id.cmp(&b.id)));
+                let days: Vec<NaiveDate> = charges
+                    .iter()
+                    .map(|t| NaiveDate::parse_from_str(&t.date, dates::ISO_FORMAT).ok())
+                    .collect::<Option<_>>()?;
+                let mut gaps = days
+                    .windows(2)
+                    .map(|pair| Cadence::of((pair[1] - pair[0]).num_days()));
+                let cadence = gaps.next()??;
+                if !gaps.all(|gap| gap == Some(cadence)) {
+                    return None;
+                }
+                // One repeat is enough for a yearly charge, not a monthly one.
+                if charges.len() < 3 && cadence != Cadence::Yearly {
+                    return None;
+                }
+                let last = charges.last()?;
+                Some(Subscription {
+                    payee: match last.payee {
+                        Some(payee) => payee.to_string(),
+                        None => last.description.clone(),
+                    },
+                    amount: last.amount,
+                    cadence,
+                    count: charges.len(),
+                    last_charged: last.date.clone(),
+                    monthly_cost: cadence.monthly_cost(last.amount),
+                    ids: charges.iter().map(|t| t.id).collect(),
+                    recurring: charges.iter().all(|t| t.is_recurring),
+                })
+            })
+            .collect();
+        subscriptions.sort_by(|a, b| {
+            b.monthly_cost
+                .total_cmp(&a.monthly_cost)
+                .then(a.payee.cmp(&b.payee))
+        });
+        SubscriptionReport {
+            monthly_total: money::sum(subscriptions.iter().map(|s| s.monthly_cost)),
+            subscriptions,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        "Detected Subscriptions"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec![
+            "payee",
+            "amount",
+            "cadence",
+            "count",
+            "last_charged",
+            "monthly_cost",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.subscriptions
+            .iter()
+            .map(|s| {
+                vec![
+                    s.payee.clone(),
+                    number(s.amount),
+                    s.cadence.to_string(),
+                    s.count.to_string(),
+                    s.last_charged.clone(),
+                    number(s.monthly_cost),
+                ]
+            })
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let mut lines: Vec<String> = self
+            .subscriptions
+            .iter()
+            .map(|s| {
+                format!(
+                    "{} {} {} (last {}, {} a month)",
+                    s.payee,
+                    format_money(s.amount),
+                    s.cadence,
+                    s.last_charged,
+                    format_money(s.monthly_cost)
+                )
+            })
+            .collect();
+        lines.push(format!(
+            "Total: {} a month",
+            format_money(self.monthly_total)
+        ));
+        banner(self.title(), lines, 30)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectTotal {
+    pub project: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub count: usize,
+    pub spent: f64,
+    /// Refunds and reimbursements, which count against what was spent.
+    pub income: f64,
+    pub budget: Option<f64>,
+    /// What is left of the budget after spending net of income.
+    pub remaining: Option<f64>,
+}
+
+/// Spending per project. Transactions outside a project are left out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ProjectReport {
+    pub projects: Vec<ProjectTotal>,
+}
+
+impl ProjectReport {
+    /// Adds the budgets and dates of `projects`, including the ones nothing
+    /// is assigned to yet, in the order they were defined.
+    pub fn with_projects(mut self, projects: &[Project]) -> Self {
+        let mut ordered = Vec::new();
+        for project in projects {
+            let position = self
+                .projects
+                .iter()
+                .position(|p| p.project.eq_ignore_ascii_case(&project.name));
+            let mut total = match position {
+                Some(position) => self.projects.remove(position),
+                None => ProjectTotal {
+                    project: project.name.clone(),
+                    start: None,
+                    end: None,
+                    count: 0,
+                    spent: 0.0,
+                    income: 0.0,
+                    budget: None,
+                    remaining: None,
+                },
+            };
+            total.project = project.name.clone();
+            total.start = project.start.clone();
+            total.end = project.end.clone();
+            total.budget = project.budget;
+            total.remaining = project
+                .budget
+                .map(|budget| money::sum([budget, -total.spent, total.income]));
+            ordered.push(total);
+        }
+        ordered.append(&mut self.projects);
+        self.projects = ordered;
+        self
+    }
+}
+
+impl Report for ProjectReport {
+    fn compute(transactions: &[&Transaction]) -> Self {
+        // Keyed by the lowercased name, keeping the first spelling seen.
+        let mut totals: BTreeMap<String, ProjectTotal> = BTreeMap::new();
+        for transaction in transactions {
+            let Some(project) = &transaction.project else {
+                continue;
+            };
+            let total = totals
+                .entry(project.to_lowercase())
+                .or_insert_with(|| ProjectTotal {
+                    project: project.clone(),
+                    start: None,
+                    end: None,
+                    count: 0,
+                    spent: 0.0,
+                    income: 0.0,
+                    budget: None,
+                    remaining: None,
+                });
+            total.count += 1;
+            match transaction.transaction_type {
+                TransactionType::Expense => {
+                    total.spent = money::sum([total.spent, transaction.amount])
+                }
+                TransactionType::Income => {
+                    total.income = money::sum([total.income, transaction.amount])
+                }
+                TransactionType::Settlement => {}
+            }
+        }
+        ProjectReport {
+            projects: totals.into_values().collect(),
+        }
+    }
+
+    fn from_tracker(tracker: &FinanceTracker) -> Self {
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        ProjectReport::compute(&all).with_projects(tracker.projects())
+    }
+
+    fn title(&self) -> &'static str {
+        "Projects"
+    }
+
+    fn headers(&self) -> Vec<&'static str> {
+        vec![
+            "project",
+            "start",
+            "end",
+            "count",
+            "spent",
+            "income",
+            "budget",
+            "remaining",
+        ]
+    }
+
+    fn rows(&self) -> Vec<Vec<String>> {
+        self.projects
+            .iter()
+            .map(|p| {
+                vec![
+                    p.project.clone(),
+                    p.start.clone().unwrap_or_default(),
+                    p.end.clone().unwrap_or_default(),
+                    p.count.to_string(),
+                    number(p.spent),
+                    number(p.income),
+                    p.budget.map(number).unwrap_or_default(),
+                    p.remaining.map(number).unwrap_or_default(),
+                ]
+            })
+            .collect()
+    }
+
+    fn text(&self) -> String {
+        let lines = self
+            .projects
+            .iter()
+            .map(|p| {
+                let spent = format_money(money::round(p.spent - p.income));
+                match (p.budget, p.remaining) {
+                    (Some(budget), Some(remaining)) => format!(
+                        "{}: {} of {} ({} left)",
+                        p.project,
+                        spent,
+                        format_money(budget),
+                        paint(&format_money(remaining), balance_color(remaining))
+                    ),
+                    _ => format!("{}: {}", p.project, spent),
+                }
+            })
+            .collect();
+        banner(self.title(), lines, 25)
+    }
+
+    fn json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+pub fn category_totals(transactions: &[&Transaction]) -> HashMap<Symbol, f64> {
+    totals_by(
+        transactions,
+        |t| t.category,
+        |t| t.amount,
+        |total, amount| *total += amount,
+    )
+}
+
+/// Total and number of transactions per payee.
+pub fn payee_totals(transactions: &[&Transaction]) -> HashMap<Symbol, (f64, usize)> {
+    let totals = totals_by(
+        transactions,
+        |t| t.payee,
+        |t| (t.amount, 1),
+        |total: &mut (f64, usize), (amount, count)| {
+            total.0 += amount;
+            total.1 += count;
+        },
+    );
+    totals
+        .into_iter()
+        .filter_map(|(payee, total)| Some((payee?, total)))
+        .collect()
+}
+
+/// Groups `transactions` by `key`, combining each group's `value`s with
+/// `add`. With the `parallel` feature the groups are built on several
+/// threads and merged, so `add` must not depend on order.
+fn totals_by<'a, K, V>(
+    transactions: &[&'a Transaction],
+    key: impl Fn(&'a Transaction) -> K + Sync,
+    value: impl Fn(&'a Transaction) -> V + Sync,
+    add: impl Fn(&mut V, V) + Sync,
+) -> HashMap<K, V>
+where
+    K: Eq + Hash + Send,
+    V: Default + Send,
+{
+    let fold = |mut totals: HashMap<K, V>, transaction: &&'a Transaction| {
+        add(
+            totals.entry(key(transaction)).or_default(),
+            value(transaction),
+        );
+        totals
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        transactions.par_iter().fold(HashMap::new, fold).reduce(
+            HashMap::new,
+            |mut totals, other| {
+                for (key, value) in other {
+                    add(totals.entry(key).or_default(), value);
+                }
+                totals
+            },
+        )
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        transactions.iter().fold(HashMap::new(), fold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction};
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+        tracker.add_transaction(
+            String::from("Salary"),
+            5000.0,
+            true,
+            String::from("2024-01-04"),
+            TransactionType::Income,
+            String::from("Work"),
+        );
+        tracker.add_transaction(
+            String::from("Rent"),
+            2000.0,
+            true,
+            String::from("2024-01-01"),
+            TransactionType::Expense,
+            String::from("Housing"),
+        );
+        tracker
+    }
+
+    #[test]
+    fn test_summary_matches_tracker() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let summary = Summary::of(&all);
+
+        assert_eq!(summary.total_income, tracker.total_income());
+        assert_eq!(summary.total_expense, tracker.total_expense());
+        assert_eq!(summary.net_balance, tracker.net_balance());
+        assert_eq!(summary.average_transaction, tracker.average_transaction());
+    }
+
+    #[test]
+    fn test_summary_per_currency() {
+        let mut tracker = create_test_tracker();
+        tracker.insert(NewTransaction {
+            description: String::from("Hotel"),
+            amount: 100.0,
+            currency: Some(String::from("EUR")),
+            date: String::from("2024-01-05"),
+            ..Default::default()
+        });
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let table = BTreeMap::from([(String::from("EUR"), 1.1)]);
+        let rates = Rates::new("USD", &table).unwrap();
+
+        let converted = rates.convert(&all).unwrap();
+        let summary = Summary::of(&converted.iter().collect::<Vec<_>>())
+            .with_currencies(&all, &rates)
+            .unwrap();
+        assert_eq!(summary.total_expense, 2110.0);
+        assert_eq!(summary.converted_to.as_deref(), Some("USD"));
+        assert_eq!(
+            summary
+                .currencies
+                .iter()
+                .map(|c| (c.currency.as_str(), c.total_expense, c.rate))
+                .collect::<Vec<_>>(),
+            vec![("EUR", 100.0, 1.1), ("USD", 2000.0, 1.0)]
+        );
+        assert!(summary.text().contains("converted into USD"));
+
+        let usd = Summary::of(&all[..2])
+            .with_currencies(&all[..2], &rates)
+            .unwrap();
+        assert!(usd.currencies.is_empty() && usd.converted_to.is_none());
+    }
+
+    #[test]
+    fn test_summary_of_nothing() {
+        let summary = Summary::of(&[]);
+        assert!(summary.total_income.is_sign_positive());
+        assert_eq!(summary.net_balance, 0.0);
+        assert_eq!(summary.average_transaction, 0.0);
+    }
+
+    #[test]
+    fn test_category_totals_matches_breakdown() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        assert_eq!(&category_totals(&all), tracker.category_breakdown());
+    }
+
+    #[test]
+    fn test_summary_renderings() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let summary = Summary::compute(&all);
+
+        let json: serde_json::Value = serde_json::from_str(&summary.json().unwrap()).unwrap();
+        assert_eq!(json["net_balance"], 3000.0);
+        assert_eq!(
+            summary.csv().unwrap(),
+            "total_income,total_expense,net_balance,average_transaction\n5000.0,2000.0,3000.0,3500.0\n"
+        );
+        assert!(summary.text().starts_with("=== Financial Summary ===\n"));
+    }
+
+    #[test]
+    fn test_category_report_is_sorted() {
+        let tracker = create_test_tracker();
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let report = CategoryReport::compute(&all);
+
+        assert_eq!(
+            report.csv().unwrap(),
+            "category,total\nHousing,2000.0\nWork,5000.0\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&report.json().unwrap()).unwrap();
+        assert_eq!(json[0]["category"], "Housing");
+
+        let by_total = report.ordered(CategoryOrder::Total);
+        assert_eq!(by_total.categories[0].category, "Work");
+    }
+
+    #[test]
+    fn test_monthly_report() {
+        let mut tracker = create_test_tracker();
+        tracker.add_transaction(
+            String::from("Groceries"),
+            150.0,
+            false,
+            String::from("2024-02-03"),
+            TransactionType::Expense,
+            String::from("Food"),
+        );
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let report = MonthlyReport::compute(&all);
+
+        assert_eq!(report.months.len(), 2);
+        assert_eq!(report.months[0].month, "2024-01");
+        assert_eq!(report.months[0].net, 3000.0);
+        assert_eq!(report.months[1].expense, 150.0);
+        assert!(
+            report
+                .html()
+                .contains("<tr><td>2024-02</td><td>0.0</td><td>150.0</td><td>-150.0</td></tr>")
+        );
+    }
+
+    #[test]
+    fn test_yearly_report_follows_fiscal_year() {
+        let mut tracker = create_test_tracker();
+        for (description, amount, date, transaction_type) in [
+            ("Bonus", 1000.0, "2024-04-01", TransactionType::Income),
+            ("Trip", 300.0, "2025-03-31", TransactionType::Expense),
+        ] {
+            tracker.add_transaction(
+                String::from(description),
+                amount,
+                false,
+                String::from(date),
+                transaction_type,
+                String::from("Other"),
+            );
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let calendar = YearlyReport::fiscal(&all, 1);
+        assert_eq!(
+            calendar
+                .years
+                .iter()
+                .map(|y| y.year.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2024", "2025"]
+        );
+        assert_eq!(calendar.years[0].net, 4000.0);
+
+        let fiscal = YearlyReport::fiscal(&all, 4);
+        assert_eq!(fiscal.years.len(), 2);
+        assert_eq!(fiscal.years[0].year, "2023/24");
+        assert_eq!(fiscal.years[0].net, 3000.0);
+        assert_eq!(fiscal.years[1].year, "2024/25");
+        assert_eq!(fiscal.years[1].income, 1000.0);
+        assert_eq!(fiscal.years[1].expense, 300.0);
+    }
+
+    #[test]
+    fn test_mark_over_budget() {
+        let mut totals = HashMap::new();
+        totals.insert(Symbol::new("Food"), 240.0);
+        totals.insert(Symbol::new("Rent"), 1200.0);
+        let over = [OverBudget {
+            category: String::from("food"),
+            spent: 240.0,
+            limit: 200.0,
+        }];
+        let report = CategoryReport::from_totals(totals).mark_over_budget(&over);
+        let marked: Vec<(&str, bool)> = report
+            .categories
+            .iter()
+            .map(|c| (c.category.as_str(), c.over_budget))
+            .collect();
+        assert_eq!(marked, vec![("Food", true), ("Rent", false)]);
+        assert!(!report.json().unwrap().contains("over_budget"));
+    }
+
+    #[test]
+    fn test_html_escapes_cells() {
+        let report = CategoryReport {
+            categories: vec![CategoryTotal {
+                category: String::from("Food & <Drink>"),
+                total: 1.0,
+                over_budget: false,
+            }],
+        };
+        assert!(report.html().contains("<td>Food &amp; &lt;Drink&gt;</td>"));
+    }
+
+    #[test]
+    fn test_balances_and_settlements() {
+        let mut tracker = FinanceTracker::new();
+        let shared = [
+            (
+                TransactionType::Expense,
+                90.0,
+                vec![("Alice", 30.0), ("Bob", 30.0)],
+            ),
+            (TransactionType::Expense, 20.0, vec![("Bob", -20.0)]),
+            (TransactionType::Settlement, 25.0, vec![("alice", -25.0)]),
+        ];
+        for (transaction_type, amount, splits) in shared {
+            let mut builder = Transaction::builder()
+                .amount(amount)
+                .transaction_type(transaction_type);
+            for (person, share) in splits {
+                builder = builder.split(person, share);
+            }
+            tracker.insert(builder.build().unwrap());
+        }
+
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let report = BalanceReport::compute(&all);
+        assert_eq!(report.balance("Alice"), 5.0);
+        assert_eq!(report.balance("bob"), 10.0);
+        assert_eq!(
+            report.csv().unwrap(),
+            "person,balance\nAlice,5.0\nBob,10.0\n"
+        );
+        assert_eq!(tracker.total_expense(), 110.0);
+        assert_eq!(tracker.total_income(), 0.0);
+        assert!(all[2].is_inflow());
+        assert_eq!(tracker.average_transaction(), 55.0);
+        assert_eq!(Summary::compute(&all).average_transaction, 55.0);
+    }
+
+    #[test]
+    fn test_net_worth_includes_holdings() {
+        let mut tracker = create_test_tracker();
+        tracker.add_transaction(
+            String::from("Coffee"),
+            4.5,
+            false,
+            String::from("2024-01-05"),
+            TransactionType::Expense,
+            String::from("Food"),
+        );
+        let card = tracker.get_transaction(3).unwrap().clone();
+        tracker.update_transaction(Transaction {
+            account: String::from("Card"),
+            ..card
+        });
+        tracker
+            .portfolio_mut()
+            .buy("VTI", 2.0, 100.0, "2024-01-05")
+            .unwrap();
+        tracker
+            .portfolio_mut()
+            .set_price("VTI", 150.0, "2024-02-01")
+            .unwrap();
+
+        let report = NetWorthReport::from_tracker(&tracker);
+        assert_eq!(
+            report.accounts,
+            vec![
+                AccountBalance {
+                    account: String::from("Card"),
+                    balance: -4.5
+                },
+                AccountBalance {
+                    account: String::from("Cash"),
+                    balance: 3000.0
+                },
+            ]
+        );
+        assert_eq!(report.investments, 300.0);
+        assert_eq!(report.net_worth, 3295.5);
+
+        let loan = Loan::new("Car", 1200.0, 0.0, 12, "2024-01-01").unwrap();
+        let report = report.with_loans(&[loan], "2024-03-15");
+        assert_eq!(report.loans, 1000.0);
+        assert_eq!(report.net_worth, 2295.5);
+
+        tracker.set_opening_balances(
+            100.0,
+            &BTreeMap::from([
+                (String::from("cash"), 50.0),
+                (String::from("Savings"), 2000.0),
+            ]),
+        );
+        let report = NetWorthReport::from_tracker(&tracker);
+        let balances: Vec<f64> = report.accounts.iter().map(|a| a.balance).collect();
+        assert_eq!(balances, vec![-4.5, 3050.0, 2000.0]);
+        assert_eq!(report.net_worth, 5445.5);
+    }
+
+    #[test]
+    fn test_payee_report_and_cached_reports() {
+        let mut tracker = create_test_tracker();
+        for (payee, amount) in [("Cafe", 4.5), ("Grocer", 60.0), ("Cafe", 3.0)] {
+            let new = Transaction::builder()
+                .description("Shop")
+                .amount(amount)
+                .date("2024-02-03")
+                .payee(payee)
+                .build()
+                .unwrap();
+            tracker.insert(new);
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        assert_eq!(
+            PayeeReport::compute(&all).csv().unwrap(),
+            "payee,total,count\nGrocer,60.0,1\nCafe,7.5,2\n"
+        );
+        assert_eq!(
+            MonthlyReport::from_tracker(&tracker),
+            MonthlyReport::compute(&all)
+        );
+        assert_eq!(
+            CategoryReport::from_tracker(&tracker),
+            CategoryReport::compute(&all)
+        );
+    }
+
+    #[test]
+    fn test_subscription_report_detects_repeated_charges() {
+        let mut tracker = FinanceTracker::new();
+        for (payee, amount, date) in [
+            ("Netflix", 15.49, "2024-01-05"),
+            ("Netflix", 15.49, "2024-02-05"),
+            ("Netflix", 15.49, "2024-03-04"),
+            ("Grocer", 60.0, "2024-01-10"),
+            ("Grocer", 60.0, "2024-01-11"),
+            ("Grocer", 60.0, "2024-03-30"),
+            ("Domains", 120.0, "2023-03-01"),
+            ("Domains", 120.0, "2024-03-01"),
+            ("Gym", 30.0, "2024-01-15"),
+            ("Gym", 30.0, "2024-02-15"),
+        ] {
+            let new = Transaction::builder()
+                .description("Charge")
+                .amount(amount)
+                .date(date)
+                .payee(payee)
+                .build()
+                .unwrap();
+            tracker.insert(new);
+        }
+
+        let report = SubscriptionReport::from_tracker(&tracker);
+        let found: Vec<(&str, Cadence, f64)> = report
+            .subscriptions
+            .iter()
+            .map(|s| (s.payee.as_str(), s.cadence, s.monthly_cost))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Netflix", Cadence::Monthly, 15.49),
+                ("Domains", Cadence::Yearly, 10.0),
+            ]
+        );
+        assert_eq!(report.subscriptions[0].ids, vec![1, 2, 3]);
+        assert_eq!(report.monthly_total, 25.49);
+    }
+
+    #[test]
+    fn test_project_report_tracks_budgets() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, transaction_type, project) in [
+            ("Hotel", 600.0, TransactionType::Expense, Some("Berlin")),
+            ("Train", 120.0, TransactionType::Expense, Some("berlin")),
+            ("Refund", 20.0, TransactionType::Income, Some("Berlin")),
+            ("Paint", 80.0, TransactionType::Expense, Some("Kitchen")),
+            ("Coffee", 4.5, TransactionType::Expense, None),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from("2024-04-02"),
+                transaction_type,
+                project: project.map(String::from),
+                ..Default::default()
+            });
+        }
+        tracker.projects_mut().extend([
+            Project::new("Office", None, None, None).unwrap(),
+            Project::new("Berlin", Some(1000.0), Some("2024-04-01"), None).unwrap(),
+        ]);
+
+        let report = ProjectReport::from_tracker(&tracker);
+        let totals: Vec<(&str, usize, f64, Option<f64>)> = report
+            .projects
+            .iter()
+            .map(|p| (p.project.as_str(), p.count, p.spent, p.remaining))
+            .collect();
+        assert_eq!(
+            totals,
+            vec![
+                ("Office", 0, 0.0, None),
+                ("Berlin", 3, 720.0, Some(300.0)),
+                ("Kitchen", 1, 80.0, None),
+            ]
+        );
+        assert_eq!(report.projects[1].income, 20.0);
+        assert_eq!(report.projects[1].start.as_deref(), Some("2024-04-01"));
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::NewTransaction;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleField {
+    #[default]
+    Any,
+    Description,
+    Payee,
+    Notes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(default)]
+    pub field: RuleField,
+    pub contains: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Rule {
+    pub fn matches(&self, new: &NewTransaction) -> bool {
+        let needle = self.contains.to_lowercase();
+        let fields: Vec<&str> = match self.field {
+            RuleField::Any => std::iter::once(new.description.as_str())
+                .chain(new.payee.as_deref())
+                .chain(new.notes.as_deref())
+                .collect(),
+            RuleField::Description => vec![new.description.as_str()],
+            RuleField::Payee => new.payee.as_deref().into_iter().collect(),
+            RuleField::Notes => new.notes.as_deref().into_iter().collect(),
+        };
+        !needle.is_empty()
+            && fields
+                .iter()
+                .any(|field| field.to_lowercase().contains(&needle))
+    }
+
+    pub fn add_tags(&self, new: &mut NewTransaction) {
+        for tag in &self.tags {
+            if !new.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                new.tags.push(tag.clone());
+            }
+        }
+    }
+
+    pub fn apply(&self, new: &mut NewTransaction) {
+        if let Some(category) = &self.category {
+            new.category = category.clone();
+        }
+        self.add_tags(new);
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let field = match self.field {
+            RuleField::Any => "any field",
+            RuleField::Description => "description",
+            RuleField::Payee => "payee",
+            RuleField::Notes => "notes",
+        };
+        write!(f, "{} contains \"{}\" →", field, self.contains)?;
+        if let Some(category) = &self.category {
+            write!(f, " category {}", category)?;
+        }
+        if !self.tags.is_empty() {
+            write!(f, " tags {}", self.tags.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn find<'a>(rules: &'a [Rule], new: &NewTransaction) -> Option<&'a Rule> {
+    rules.iter().find(|rule| rule.matches(new))
+}
+
+pub fn apply_all(rules: &[Rule], transactions: &mut [NewTransaction]) -> usize {
+    transactions
+        .iter_mut()
+        .filter_map(|new| find(rules, new).map(|rule| rule.apply(new)))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn coffee_rule() -> Rule {
+        Rule {
+            field: RuleField::Payee,
+            contains: String::from("starbucks"),
+            category: Some(String::from("Food:Coffee")),
+            tags: vec![String::from("coffee")],
+        }
+    }
+
+    #[test]
+    fn test_rule_matches_selected_field() {
+        let mut new = NewTransaction {
+            description: String::from("Card purchase"),
+            payee: Some(String::from("STARBUCKS #123")),
+            ..Default::default()
+        };
+        assert!(coffee_rule().matches(&new));
+
+        new.payee = None;
+        new.notes = Some(String::from("starbucks"));
+        assert!(!coffee_rule().matches(&new));
+        assert!(
+            Rule {
+                field: RuleField::Any,
+                ..coffee_rule()
+            }
+            .matches(&new)
+        );
+    }
+
+    #[test]
+    fn test_first_matching_rule_applies() {
+        let rules = vec![
+            coffee_rule(),
+            Rule {
+                field: RuleField::Any,
+                contains: String::from("star"),
+                category: Some(String::from("Other")),
+                tags: Vec::new(),
+            },
+        ];
+        let mut imported = vec![
+            NewTransaction {
+                payee: Some(String::from("Starbucks")),
+                category: String::from("Uncategorized"),
+                tags: vec![String::from("Coffee")],
+                ..Default::default()
+            },
+            NewTransaction {
+                description: String::from("Groceries"),
+                category: String::from("Food"),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(apply_all(&rules, &mut imported), 1);
+        assert_eq!(imported[0].category, "Food:Coffee");
+        assert_eq!(imported[0].tags, vec!["Coffee"]);
+        assert_eq!(imported[1].category, "Food");
+    }
+
+    #[test]
+    fn test_rules_in_config_toml() {
+        #[derive(Deserialize)]
+        struct Rules {
+            rules: Vec<Rule>,
+        }
+        let parsed: Rules = toml::from_str(
+            r#"
+            [[rules]]
+            field = "payee"
+            contains = "starbucks"
+            category = "Food:Coffee"
+            tags = ["coffee"]
+
+            [[rules]]
+            contains = "uber"
+            category = "Transport"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.rules[0], coffee_rule());
+        assert_eq!(parsed.rules[1].field, RuleField::Any);
+        assert_eq!(
+            parsed.rules[0].to_string(),
+            "payee contains \"starbucks\" → category Food:Coffee tags coffee"
+        );
+    }
+}
@@ -0,0 +1,545 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::Local;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::config::Config;
+use crate::dates;
+use crate::export::{self, ExportFormat};
+use crate::filter::TransactionFilter;
+use crate::report::{CategoryReport, Report, Summary};
+use crate::storage::{Backend, FileBackend, Ledger};
+use crate::{
+    ExpensoError, FinanceTracker, NewTransaction, SharedTracker, SortKey, SortOrder, Transaction,
+    TransactionType,
+};
+
+const INDEX_HTML: &str = include_str!("../web/index.html");
+const APP_JS: &str = include_str!("../web/app.js");
+const STYLE_CSS: &str = include_str!("../web/style.css");
+
+#[derive(Clone)]
+pub struct AppState {
+    tracker: SharedTracker,
+    config: Arc<Config>,
+    storage: Arc<FileBackend>,
+    /// Held from a change until it is written, so saves land in order.
+    writing: Arc<tokio::sync::Mutex<()>>,
+    #[cfg(feature = "graphql")]
+    schema: crate::graphql::ExpensoSchema,
+}
+
+impl AppState {
+    pub fn new(tracker: FinanceTracker, config: Config) -> Self {
+        let tracker = SharedTracker::new(tracker);
+        AppState {
+            #[cfg(feature = "graphql")]
+            schema: crate::graphql::schema(tracker.clone()),
+            tracker,
+            storage: Arc::new(FileBackend::new(&config.data_file)),
+            writing: Arc::new(tokio::sync::Mutex::new(())),
+            config: Arc::new(config),
+        }
+    }
+
+    #[cfg(feature = "graphql")]
+    pub fn schema(&self) -> &crate::graphql::ExpensoSchema {
+        &self.schema
+    }
+
+    async fn save(&self, ledger: Ledger) -> Result<(), ApiError> {
+        self.storage
+            .save(ledger)
+            .await
+            .map_err(|err| ApiError::Internal(format!("Could not save data file: {}", err)))?;
+        self.tracker.write().mark_saved();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(u32),
+    BadRequest(String),
+    Conflict(String),
+    Unauthorized,
+    Internal(String),
+}
+
+impl From<ExpensoError> for ApiError {
+    fn from(err: ExpensoError) -> Self {
+        match err {
+            ExpensoError::NotFound(id) => ApiError::NotFound(id),
+            ExpensoError::InvalidInput(message) => ApiError::BadRequest(message),
+            err @ (ExpensoError::Reconciled(_) | ExpensoError::Closed(_)) => {
+                ApiError::Conflict(err.to_string())
+            }
+            err => ApiError::Internal(err.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            ApiError::NotFound(id) => (
+                StatusCode::NOT_FOUND,
+                format!("No transaction with id {}", id),
+            ),
+            ApiError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            ApiError::Conflict(message) => (StatusCode::CONFLICT, message),
+            ApiError::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                String::from("Missing or invalid access token"),
+            ),
+            ApiError::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message),
+        };
+        (status, Json(json!({ "error": message }))).into_response()
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FilterQuery {
+    category: Option<String>,
+    #[serde(rename = "type")]
+    transaction_type: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+    min: Option<f64>,
+    max: Option<f64>,
+    tag: Option<String>,
+    recurring: Option<bool>,
+    project: Option<String>,
+}
+
+impl FilterQuery {
+    fn to_filter(&self) -> Result<TransactionFilter, ApiError> {
+        let today = Local::now().date_naive();
+        let date = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|value| {
+                    dates::normalize(value, today)
+                        .ok_or_else(|| ApiError::BadRequest(format!("Invalid date '{}'", value)))
+                })
+                .transpose()
+        };
+
+        Ok(TransactionFilter {
+            category: self.category.clone(),
+            transaction_type: self
+                .transaction_type
+                .as_deref()
+                .map(|value| {
+                    value
+                        .parse::<TransactionType>()
+                        .map_err(|err| ApiError::BadRequest(err.to_string()))
+                })
+                .transpose()?,
+            from: date(&self.from)?,
+            to: date(&self.to)?,
+            min_amount: self.min,
+            max_amount: self.max,
+            tag: self.tag.clone(),
+            recurring: self.recurring,
+            project: self.project.clone(),
+        })
+    }
+}
+
+pub fn router(state: AppState) -> Router {
+    let router = Router::new()
+        .route("/api/settings", get(settings))
+        .route("/bills.ics", get(bills_calendar))
+        .route(
+            "/api/transactions",
+            get(list_transactions).post(create_transaction),
+        )
+        .route(
+            "/api/transactions/{id}",
+            get(get_transaction)
+                .put(update_transaction)
+                .delete(delete_transaction),
+        )
+        .route("/api/summary", get(summary))
+        .route("/api/categories", get(categories));
+
+    #[cfg(feature = "graphql")]
+    let router = router.route("/graphql", axum::routing::post(crate::graphql::handler));
+
+    router
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .route("/", get(|| asset("text/html; charset=utf-8", INDEX_HTML)))
+        .route(
+            "/app.js",
+            get(|| asset("text/javascript; charset=utf-8", APP_JS)),
+        )
+        .route(
+            "/style.css",
+            get(|| asset("text/css; charset=utf-8", STYLE_CSS)),
+        )
+        .with_state(state)
+}
+
+pub fn run(
+    tracker: FinanceTracker,
+    mut config: Config,
+    address: SocketAddr,
+) -> std::io::Result<()> {
+    if let Ok(token) = std::env::var("EXPENSO_SERVER_TOKEN") {
+        config.server_token = Some(token);
+    }
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        println!(
+            "Serving {} on http://{}",
+            config.data_file.display(),
+            address
+        );
+        axum::serve(listener, router(AppState::new(tracker, config)))
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await
+    })
+}
+
+async fn require_token(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(expected) = state.config.server_token.as_deref() else {
+        return Ok(next.run(request).await);
+    };
+
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    // Calendar apps cannot send headers, so also accept ?token= on the URL.
+    let query = request.uri().query().and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+    });
+
+    match bearer.or(query) {
+        Some(token) if tokens_match(token, expected) => Ok(next.run(request).await),
+        _ => Err(ApiError::Unauthorized),
+    }
+}
+
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn asset(content_type: &'static str, body: &'static str) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, content_type)], body)
+}
+
+async fn settings(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(json!({
+        "currency": state.config.currency,
+        "locale": state.config.locale,
+        "default_account": state.config.default_account,
+    }))
+}
+
+async fn bills_calendar(State(state): State<AppState>) -> Result<impl IntoResponse, ApiError> {
+    let tracker = state.tracker.read();
+    let transactions: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+    let calendar = export::export(&transactions, ExportFormat::Ical, &state.config)
+        .map_err(|err| ApiError::Internal(err.to_string()))?;
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        calendar,
+    ))
+}
+
+async fn list_transactions(
+    State(state): State<AppState>,
+    Query(query): Query<FilterQuery>,
+) -> Result<Json<Vec<Transaction>>, ApiError> {
+    let filter = query.to_filter()?;
+    let tracker = state.tracker.read();
+    let transactions = tracker
+        .filtered(&filter, SortKey::Id, SortOrder::Ascending)
+        .into_iter()
+        .cloned()
+        .collect();
+    Ok(Json(transactions))
+}
+
+async fn get_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<Json<Transaction>, ApiError> {
+    state
+        .tracker
+        .get_transaction(id)
+        .map(Json)
+        .ok_or(ApiError::NotFound(id))
+}
+
+async fn create_transaction(
+    State(state): State<AppState>,
+    Json(new): Json<NewTransaction>,
+) -> Result<(StatusCode, Json<Transaction>), ApiError> {
+    let _writing = state.writing.lock().await;
+    let (created, ledger) = {
+        let mut tracker = state.tracker.write();
+        let created = tracker.add(new)?;
+        (created, Ledger::from(&*tracker))
+    };
+    state.save(ledger).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn update_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+    Json(changes): Json<NewTransaction>,
+) -> Result<Json<Transaction>, ApiError> {
+    let _writing = state.writing.lock().await;
+    let (updated, ledger) = {
+        let mut tracker = state.tracker.write();
+        let updated = tracker.edit(id, changes)?;
+        (updated, Ledger::from(&*tracker))
+    };
+    state.save(ledger).await?;
+    Ok(Json(updated))
+}
+
+async fn delete_transaction(
+    State(state): State<AppState>,
+    Path(id): Path<u32>,
+) -> Result<StatusCode, ApiError> {
+    let _writing = state.writing.lock().await;
+    let ledger = {
+        let mut tracker = state.tracker.write();
+        tracker.remove(id)?;
+        Ledger::from(&*tracker)
+    };
+    state.save(ledger).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn summary(
+    State(state): State<AppState>,
+    Query(query): Query<FilterQuery>,
+) -> Result<Json<Summary>, ApiError> {
+    let filter = query.to_filter()?;
+    let tracker = state.tracker.read();
+    let transactions = tracker.filtered(&filter, SortKey::Id, SortOrder::Ascending);
+    Ok(Json(Summary::of(&transactions)))
+}
+
+async fn categories(
+    State(state): State<AppState>,
+    Query(query): Query<FilterQuery>,
+) -> Result<Json<CategoryReport>, ApiError> {
+    let filter = query.to_filter()?;
+    let tracker = state.tracker.read();
+    let transactions = tracker.filtered(&filter, SortKey::Id, SortOrder::Ascending);
+    Ok(Json(CategoryReport::compute(&transactions)))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn test_state(name: &str) -> AppState {
+        test_state_with_config(name, Config::default())
+    }
+
+    fn test_state_with_config(name: &str, config: Config) -> AppState {
+        let path = std::env::temp_dir().join(format!(
+            "expenso-server-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        let mut tracker = FinanceTracker::new();
+        tracker.insert(NewTransaction {
+            description: String::from("Rent"),
+            amount: 1200.0,
+            date: String::from("2024-03-01"),
+            category: String::from("Housing"),
+            ..Default::default()
+        });
+        tracker.insert(NewTransaction {
+            description: String::from("Salary"),
+            amount: 3000.0,
+            date: String::from("2024-03-02"),
+            transaction_type: TransactionType::Income,
+            category: String::from("Work"),
+            ..Default::default()
+        });
+        let config = Config {
+            data_file: path,
+            ..config
+        };
+        AppState::new(tracker, config)
+    }
+
+    async fn send(
+        state: &AppState,
+        method: &str,
+        uri: &str,
+        body: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+            .unwrap();
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let value = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+        (status, value)
+    }
+
+    #[tokio::test]
+    async fn test_list_and_filter_transactions() {
+        let state = test_state("list");
+
+        let (status, all) = send(&state, "GET", "/api/transactions", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(all.as_array().unwrap().len(), 2);
+
+        let (_, income) = send(&state, "GET", "/api/transactions?type=income", None).await;
+        assert_eq!(income[0]["description"], "Salary");
+
+        let (status, _) = send(&state, "GET", "/api/transactions?from=someday", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_create_update_delete() {
+        let state = test_state("crud");
+
+        let (status, created) = send(
+            &state,
+            "POST",
+            "/api/transactions",
+            Some(r#"{"description":"Coffee","amount":4.5,"category":"Food","date":"2024-03-03"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["id"], 3);
+        assert!(state.config.data_file.exists());
+
+        let (status, updated) = send(
+            &state,
+            "PUT",
+            "/api/transactions/3",
+            Some(r#"{"description":"Latte","amount":5.0,"category":"Food","date":"2024-03-03"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["description"], "Latte");
+
+        let (status, _) = send(&state, "DELETE", "/api/transactions/3", None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, error) = send(&state, "GET", "/api/transactions/3", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["error"], "No transaction with id 3");
+
+        let _ = std::fs::remove_file(&state.config.data_file);
+    }
+
+    #[tokio::test]
+    async fn test_summary_and_categories() {
+        let state = test_state("reports");
+
+        let (_, summary) = send(&state, "GET", "/api/summary", None).await;
+        assert_eq!(summary["net_balance"], 1800.0);
+
+        let (_, categories) = send(&state, "GET", "/api/categories?type=expense", None).await;
+        assert_eq!(
+            categories,
+            json!([{ "category": "Housing", "total": 1200.0 }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_required_when_configured() {
+        let config = Config {
+            server_token: Some(String::from("s3cret")),
+            ..Config::default()
+        };
+        let state = test_state_with_config("token", config);
+
+        let (status, body) = send(&state, "GET", "/api/transactions", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "Missing or invalid access token");
+
+        let request = Request::builder()
+            .uri("/api/transactions")
+            .header(header::AUTHORIZATION, "Bearer s3cret")
+            .body(Body::empty())
+            .unwrap();
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let (status, _) = send(&state, "GET", "/bills.ics?token=s3cret", None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&state, "GET", "/bills.ics?token=wrong", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_serves_dashboard_assets() {
+        let state = test_state("assets");
+
+        for (uri, content_type) in [
+            ("/", "text/html; charset=utf-8"),
+            ("/app.js", "text/javascript; charset=utf-8"),
+            ("/style.css", "text/css; charset=utf-8"),
+        ] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let response = router(state.clone()).oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], content_type);
+        }
+
+        let request = Request::builder()
+            .uri("/bills.ics")
+            .body(Body::empty())
+            .unwrap();
+        let response = router(state.clone()).oneshot(request).await.unwrap();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/calendar; charset=utf-8"
+        );
+
+        let (_, settings) = send(&state, "GET", "/api/settings", None).await;
+        assert_eq!(settings["currency"], "USD");
+    }
+}
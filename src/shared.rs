@@ -0,0 +1,128 @@
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{Event, FinanceTracker, NewTransaction, Transaction};
+
+/// A [`FinanceTracker`] that can be cloned and used from several threads,
+/// e.g. by the server's request handlers and a background job.
+///
+/// Readers share the lock; each mutation takes it exclusively. A panic while
+/// holding the lock does not make the tracker unusable.
+#[derive(Debug, Clone, Default)]
+pub struct SharedTracker(Arc<RwLock<FinanceTracker>>);
+
+impl SharedTracker {
+    pub fn new(tracker: FinanceTracker) -> Self {
+        SharedTracker(Arc::new(RwLock::new(tracker)))
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, FinanceTracker> {
+        self.0
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn write(&self) -> RwLockWriteGuard<'_, FinanceTracker> {
+        self.0
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn with<T>(&self, f: impl FnOnce(&FinanceTracker) -> T) -> T {
+        f(&self.read())
+    }
+
+    pub fn update<T>(&self, f: impl FnOnce(&mut FinanceTracker) -> T) -> T {
+        f(&mut self.write())
+    }
+
+    pub fn insert(&self, new: NewTransaction) -> u32 {
+        self.write().insert(new)
+    }
+
+    pub fn update_transaction(&self, updated: Transaction) -> bool {
+        self.write().update_transaction(updated)
+    }
+
+    pub fn delete_transaction(&self, id: u32) -> Option<Transaction> {
+        self.write().delete_transaction(id)
+    }
+
+    pub fn get_transaction(&self, id: u32) -> Option<Transaction> {
+        self.read().get_transaction(id).cloned()
+    }
+
+    /// A copy of every transaction, taken under a single read lock.
+    pub fn snapshot(&self) -> Vec<Transaction> {
+        self.read().get_transactions().clone()
+    }
+
+    pub fn subscribe(&self, hook: impl Fn(&Event) + Send + Sync + 'static) {
+        self.write().subscribe(hook);
+    }
+}
+
+impl From<FinanceTracker> for SharedTracker {
+    fn from(tracker: FinanceTracker) -> Self {
+        SharedTracker::new(tracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::thread;
+
+    use super::*;
+    use crate::Symbol;
+
+    #[test]
+    fn test_inserts_from_many_threads() {
+        let shared = SharedTracker::default();
+
+        let handles: Vec<_> = (0..8)
+            .map(|worker| {
+                let shared = shared.clone();
+                thread::spawn(move || {
+                    for _ in 0..25 {
+                        shared.insert(NewTransaction {
+                            description: format!("Worker {}", worker),
+                            amount: 1.0,
+                            category: String::from("Food"),
+                            ..Default::default()
+                        });
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut ids: Vec<u32> = shared.snapshot().iter().map(|t| t.id).collect();
+        ids.sort();
+        assert_eq!(ids, (1..=200).collect::<Vec<u32>>());
+        assert_eq!(
+            shared.with(|tracker| tracker
+                .category_breakdown()
+                .get(&Symbol::new("Food"))
+                .copied()),
+            Some(200.0)
+        );
+    }
+
+    #[test]
+    fn test_survives_a_panicking_writer() {
+        let shared = SharedTracker::default();
+        let writer = shared.clone();
+        let _ = thread::spawn(move || {
+            writer.update(|tracker| {
+                tracker.insert(NewTransaction::default());
+                panic!("writer failed");
+            })
+        })
+        .join();
+
+        assert_eq!(shared.snapshot().len(), 1);
+        assert!(shared.get_transaction(1).is_some());
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::Transaction;
+use crate::config::Config;
+use crate::report::Summary;
+
+const API: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+const SUMMARY_TAB: &str = "Summary";
+
+const TRANSACTION_HEADER: [&str; 10] = [
+    "ID",
+    "Date",
+    "Description",
+    "Type",
+    "Category",
+    "Account",
+    "Amount",
+    "Payee",
+    "Notes",
+    "Tags",
+];
+
+#[derive(Debug, Deserialize)]
+struct Spreadsheet {
+    sheets: Vec<Sheet>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Sheet {
+    properties: SheetProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct SheetProperties {
+    title: String,
+}
+
+pub fn tabs(transactions: &[&Transaction]) -> BTreeMap<String, Vec<Vec<Value>>> {
+    let mut months: BTreeMap<String, Vec<&Transaction>> = BTreeMap::new();
+    for t in transactions {
+        let month = t.date.get(..7).unwrap_or(&t.date).to_string();
+        months.entry(month).or_default().push(t);
+    }
+
+    let mut summary = vec![vec![
+        json!("Month"),
+        json!("Income"),
+        json!("Expense"),
+        json!("Net"),
+    ]];
+    let mut tabs = BTreeMap::new();
+
+    for (month, transactions) in months {
+        let totals = Summary::of(&transactions);
+        summary.push(vec![
+            json!(month),
+            json!(totals.total_income),
+            json!(totals.total_expense),
+            json!(totals.net_balance),
+        ]);
+
+        let header = TRANSACTION_HEADER.iter().map(|name| json!(name)).collect();
+        let rows = std::iter::once(header)
+            .chain(transactions.iter().map(|t| {
+                vec![
+                    json!(t.id),
+                    json!(t.date),
+                    json!(t.description),
+                    json!(t.transaction_type.to_string()),
+                    json!(t.category),
+                    json!(t.account),
+                    json!(t.amount),
+                    json!(t.payee.unwrap_or_default()),
+                    json!(t.notes.clone().unwrap_or_default()),
+                    json!(t.tags.join(", ")),
+                ]
+            }))
+            .collect();
+        tabs.insert(month, rows);
+    }
+
+    tabs.insert(String::from(SUMMARY_TAB), summary);
+    tabs
+}
+
+pub fn push(
+    transactions: &[&Transaction],
+    config: &Config,
+    spreadsheet: &str,
+) -> io::Result<usize> {
+    let token = std::env::var("EXPENSO_GOOGLE_TOKEN")
+        .ok()
+        .or_else(|| config.google_access_token.clone())
+        .ok_or_else(|| {
+            io::Error::other(
+                "Set EXPENSO_GOOGLE_TOKEN (e.g. from `gcloud auth print-access-token`) \
+                 or google_access_token in the config file",
+            )
+        })?;
+    let auth = format!("Bearer {}", token);
+    let base = format!("{}/{}", API, spreadsheet);
+    let tabs = tabs(transactions);
+
+    let existing: Spreadsheet = ureq::get(&base)
+        .query("fields", "sheets.properties.title")
+        .header("Authorization", &auth)
+        .call()
+        .map_err(io::Error::other)?
+        .body_mut()
+        .read_json()
+        .map_err(io::Error::other)?;
+    let existing: BTreeSet<String> = existing
+        .sheets
+        .into_iter()
+        .map(|sheet| sheet.properties.title)
+        .collect();
+
+    let new_tabs: Vec<Value> = tabs
+        .keys()
+        .filter(|title| !existing.contains(*title))
+        .map(|title| json!({ "addSheet": { "properties": { "title": title } } }))
+        .collect();
+    if !new_tabs.is_empty() {
+        post(
+            &format!("{}:batchUpdate", base),
+            &auth,
+            json!({ "requests": new_tabs }),
+        )?;
+    }
+
+    let ranges: Vec<String> = tabs.keys().map(|title| range(title)).collect();
+    post(
+        &format!("{}/values:batchClear", base),
+        &auth,
+        json!({ "ranges": ranges }),
+    )?;
+
+    let data: Vec<Value> = tabs
+        .iter()
+        .map(|(title, rows)| json!({ "range": range(title), "values": rows }))
+        .collect();
+    post(
+        &format!("{}/values:batchUpdate", base),
+        &auth,
+        json!({ "valueInputOption": "RAW", "data": data }),
+    )?;
+
+    Ok(tabs.len())
+}
+
+fn range(title: &str) -> String {
+    format!("'{}'!A1:Z", title.replace('\'', "''"))
+}
+
+fn post(url: &str, auth: &str, body: Value) -> io::Result<()> {
+    ureq::post(url)
+        .header("Authorization", auth)
+        .send_json(body)
+        .map(|_| ())
+        .map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction, TransactionType};
+
+    #[test]
+    fn test_tab_per_month_with_summary() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, transaction_type) in [
+            ("Rent", 1200.0, "2024-01-01", TransactionType::Expense),
+            ("Salary", 3000.0, "2024-01-25", TransactionType::Income),
+            ("Rent", 1200.0, "2024-02-01", TransactionType::Expense),
+        ] {
+            tracker.insert(NewTransaction {
+                description: description.to_string(),
+                amount,
+                date: date.to_string(),
+                transaction_type,
+                category: String::from("Bills"),
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+
+        let tabs = tabs(&all);
+        assert_eq!(
+            tabs.keys().collect::<Vec<_>>(),
+            vec!["2024-01", "2024-02", "Summary"]
+        );
+        assert_eq!(tabs["2024-01"].len(), 3);
+        assert_eq!(tabs["2024-01"][0][0], "ID");
+        assert_eq!(tabs["2024-02"][1][2], "Rent");
+        assert_eq!(
+            tabs["Summary"][1],
+            vec![
+                json!("2024-01"),
+                json!(3000.0),
+                json!(1200.0),
+                json!(1800.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_range_quotes_titles() {
+        assert_eq!(range("2024-01"), "'2024-01'!A1:Z");
+        assert_eq!(range("Bob's"), "'Bob''s'!A1:Z");
+    }
+}
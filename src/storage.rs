@@ -0,0 +1,753 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::config::Config;
+use crate::error::{ExpensoError, Result};
+use crate::history::Entry;
+use crate::investments::Portfolio;
+use crate::loans::Loan;
+use crate::periods::ClosedPeriod;
+use crate::projects::Project;
+use crate::{FinanceTracker, Transaction};
+
+/// Version of the data file layout written by this build.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DataFile {
+    #[serde(rename = "version", default = "first_version")]
+    version: u32,
+    #[serde(flatten)]
+    ledger: Ledger,
+}
+
+/// What a [`Backend`] keeps: the current transactions and the history of
+/// changes that produced them.
+///
+/// The transactions are stored in full next to the history so the file stays
+/// readable by older builds and other tools. They stay what is loaded, and
+/// [`FinanceTracker::history_mismatches`] tells when they were changed
+/// without the history. Files written before the history existed simply
+/// start with an empty one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ledger {
+    #[serde(rename = "transactions")]
+    pub transactions: Vec<Transaction>,
+    #[serde(rename = "history", default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<Entry>,
+    /// The id the next transaction gets, kept so ids of deleted transactions
+    /// are not reused. Zero in files written before it was stored.
+    #[serde(rename = "next_id", default, skip_serializing_if = "is_zero")]
+    pub next_id: u32,
+    #[serde(
+        rename = "holdings",
+        default,
+        skip_serializing_if = "Portfolio::is_empty"
+    )]
+    pub holdings: Portfolio,
+    #[serde(rename = "loans", default, skip_serializing_if = "Vec::is_empty")]
+    pub loans: Vec<Loan>,
+    #[serde(rename = "projects", default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<Project>,
+    #[serde(rename = "closed", default, skip_serializing_if = "Vec::is_empty")]
+    pub closed: Vec<ClosedPeriod>,
+}
+
+impl From<&FinanceTracker> for Ledger {
+    fn from(tracker: &FinanceTracker) -> Self {
+        Ledger {
+            transactions: tracker.get_transactions().clone(),
+            history: tracker.history().to_vec(),
+            next_id: tracker.next_id(),
+            holdings: tracker.portfolio().clone(),
+            loans: tracker.loans().to_vec(),
+            projects: tracker.projects().to_vec(),
+            closed: tracker.closed_periods().to_vec(),
+        }
+    }
+}
+
+impl From<Ledger> for FinanceTracker {
+    fn from(ledger: Ledger) -> Self {
+        FinanceTracker::from_transactions(ledger.transactions)
+            .with_history(ledger.history)
+            .with_next_id(ledger.next_id)
+            .with_portfolio(ledger.holdings)
+            .with_loans(ledger.loans)
+            .with_projects(ledger.projects)
+            .with_closed_periods(ledger.closed)
+    }
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// The transactions and history a partial load left out, kept as they
+/// appear in the file until [`FinanceTracker::load_since`] asks for them.
+#[derive(Debug, Clone, Default)]
+pub struct Archive {
+    since: Option<String>,
+    max_id: u32,
+    transactions: Vec<Archived>,
+    /// The oldest history entries, made before `since`, in order.
+    history: Vec<Archived>,
+}
+
+/// `date` is when a history entry was made.
+#[derive(Debug, Clone)]
+struct Archived {
+    date: String,
+    raw: Box<RawValue>,
+}
+
+impl Archive {
+    /// The first date loaded, when older transactions are still in the archive.
+    pub fn since(&self) -> Option<&str> {
+        self.since.as_deref()
+    }
+
+    pub(crate) fn max_id(&self) -> u32 {
+        self.max_id
+    }
+
+    pub(crate) fn has_history(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    /// Parses and removes the archived transactions dated `from` or later
+    /// and the history entries made since, or all of them for `None`.
+    /// Nothing is removed if one fails to parse.
+    pub(crate) fn take_since(
+        &mut self,
+        from: Option<&str>,
+    ) -> Result<(Vec<Transaction>, Vec<Entry>)> {
+        let wanted = |archived: &Archived| from.is_none_or(|from| archived.date.as_str() >= from);
+        let taken = self
+            .transactions
+            .iter()
+            .filter(|archived| wanted(archived))
+            .map(|archived| Ok(serde_json::from_str(archived.raw.get())?))
+            .collect::<Result<Vec<Transaction>>>()?;
+        let history = self.take_history(from)?;
+        self.transactions.retain(|archived| !wanted(archived));
+        if self.transactions.is_empty() && self.history.is_empty() {
+            self.since = None;
+        } else if let Some(from) = from
+            && self.since.as_deref().is_none_or(|since| from < since)
+        {
+            self.since = Some(from.to_string());
+        }
+        Ok((taken, history))
+    }
+
+    /// Parses and removes the archived history entries made on `from` or
+    /// later, or all of them for `None`. Being the newest archived, they
+    /// are the last ones.
+    pub(crate) fn take_history(&mut self, from: Option<&str>) -> Result<Vec<Entry>> {
+        let start = from.map_or(0, |from| {
+            self.history
+                .iter()
+                .rposition(|archived| archived.date.as_str() < from)
+                .map_or(0, |position| position + 1)
+        });
+        let taken = self.history[start..]
+            .iter()
+            .map(|archived| Ok(serde_json::from_str(archived.raw.get())?))
+            .collect::<Result<Vec<Entry>>>()?;
+        self.history.truncate(start);
+        if self.transactions.is_empty() && self.history.is_empty() {
+            self.since = None;
+        }
+        Ok(taken)
+    }
+}
+
+/// Loads only the transactions dated `from` or later; the rest stay in the
+/// tracker's [`Archive`] until needed.
+pub fn load_since(path: &Path, from: &str) -> Result<FinanceTracker> {
+    let (ledger, archive) = read_since(path, from)?;
+    Ok(FinanceTracker::from(ledger).with_archive(archive))
+}
+
+pub fn load(path: &Path) -> Result<FinanceTracker> {
+    Ok(read(path)?.into())
+}
+
+pub fn save(tracker: &FinanceTracker, path: &Path) -> Result<()> {
+    write(path, &Ledger::from(tracker))
+}
+
+fn read(path: &Path) -> Result<Ledger> {
+    if !path.exists() {
+        return Ok(Ledger::default());
+    }
+    parse(&fs::read_to_string(path)?)
+}
+
+fn read_since(path: &Path, from: &str) -> Result<(Ledger, Archive)> {
+    if !path.exists() {
+        return Ok(Default::default());
+    }
+    parse_since(&fs::read_to_string(path)?, from)
+}
+
+fn write(path: &Path, ledger: &Ledger) -> Result<()> {
+    // A new profile's directory doesn't exist until its first save.
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, to_json(ledger)?)?;
+    Ok(())
+}
+
+/// Reads the contents of a data file without touching the filesystem.
+pub fn parse(contents: &str) -> Result<Ledger> {
+    let data: DataFile = serde_json::from_str(contents)?;
+    check_version(data.version)?;
+    Ok(data.ledger)
+}
+
+/// Like [`parse`], but only transactions dated `from` or later and the
+/// history from then on are parsed in full. Older ones are only scanned for
+/// their dates and transaction ids.
+pub fn parse_since(contents: &str, from: &str) -> Result<(Ledger, Archive)> {
+    #[derive(Deserialize)]
+    struct RawDataFile<'a> {
+        #[serde(rename = "version", default = "first_version")]
+        version: u32,
+        #[serde(rename = "transactions", borrow)]
+        transactions: Vec<&'a RawValue>,
+        #[serde(rename = "history", default, borrow)]
+        history: Vec<&'a RawValue>,
+        #[serde(rename = "next_id", default)]
+        next_id: u32,
+        #[serde(rename = "holdings", default)]
+        holdings: Portfolio,
+        #[serde(rename = "loans", default)]
+        loans: Vec<Loan>,
+        #[serde(rename = "projects", default)]
+        projects: Vec<Project>,
+        #[serde(rename = "closed", default)]
+        closed: Vec<ClosedPeriod>,
+    }
+
+    #[derive(Deserialize)]
+    struct Head {
+        #[serde(rename = "id")]
+        id: u32,
+        #[serde(rename = "date")]
+        date: String,
+    }
+
+    #[derive(Deserialize)]
+    struct EntryHead {
+        #[serde(rename = "at")]
+        at: String,
+        #[serde(rename = "transaction", default)]
+        transaction: Option<Id>,
+        #[serde(rename = "after", default)]
+        after: Option<Id>,
+    }
+
+    #[derive(Deserialize)]
+    struct Id {
+        #[serde(rename = "id")]
+        id: u32,
+    }
+
+    let data: RawDataFile = serde_json::from_str(contents)?;
+    check_version(data.version)?;
+    let mut transactions = Vec::new();
+    let mut archive = Archive::default();
+    for raw in data.transactions {
+        let head: Head = serde_json::from_str(raw.get())?;
+        if head.date.as_str() >= from {
+            transactions.push(serde_json::from_str(raw.get())?);
+        } else {
+            archive.max_id = archive.max_id.max(head.id);
+            archive.transactions.push(Archived {
+                date: head.date,
+                raw: raw.to_owned(),
+            });
+        }
+    }
+    let mut history = Vec::new();
+    for raw in data.history {
+        if !history.is_empty() {
+            history.push(serde_json::from_str(raw.get())?);
+            continue;
+        }
+        let head: EntryHead = serde_json::from_str(raw.get())?;
+        if head.at.as_str() >= from {
+            history.push(serde_json::from_str(raw.get())?);
+            continue;
+        }
+        if let Some(id) = head.transaction.or(head.after) {
+            archive.max_id = archive.max_id.max(id.id);
+        }
+        archive.history.push(Archived {
+            date: head.at,
+            raw: raw.to_owned(),
+        });
+    }
+    if !archive.transactions.is_empty() || !archive.history.is_empty() {
+        archive.since = Some(from.to_string());
+    }
+    let ledger = Ledger {
+        transactions,
+        history,
+        next_id: data.next_id,
+        holdings: data.holdings,
+        loans: data.loans,
+        projects: data.projects,
+        closed: data.closed,
+    };
+    Ok((ledger, archive))
+}
+
+fn check_version(version: u32) -> Result<()> {
+    if version > SCHEMA_VERSION {
+        return Err(ExpensoError::UnsupportedVersion {
+            found: version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// The data file contents for `ledger`, as written by [`save`].
+pub fn to_json(ledger: &Ledger) -> Result<String> {
+    let data = DataFile {
+        version: SCHEMA_VERSION,
+        ledger: ledger.clone(),
+    };
+    Ok(serde_json::to_string_pretty(&data)?)
+}
+
+/// Where transactions are kept.
+///
+/// The methods are async so the server and the remote client can wait on IO
+/// without holding a thread per request. The CLI goes through [`Storage`],
+/// which drives them to completion on the calling thread.
+pub trait Backend: Send + Sync {
+    fn location(&self) -> String;
+
+    fn load(&self) -> impl Future<Output = Result<Ledger>> + Send;
+
+    /// Writes `ledger` and returns it as stored, which differs from the input
+    /// when the backend assigns ids.
+    fn save(&self, ledger: Ledger) -> impl Future<Output = Result<Ledger>> + Send;
+}
+
+#[derive(Debug, Clone)]
+pub struct FileBackend {
+    path: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileBackend { path: path.into() }
+    }
+}
+
+impl Backend for FileBackend {
+    fn location(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    async fn load(&self) -> Result<Ledger> {
+        let path = self.path.clone();
+        unblock(move || read(&path)).await
+    }
+
+    async fn save(&self, ledger: Ledger) -> Result<Ledger> {
+        let path = self.path.clone();
+        unblock(move || write(&path, &ledger).map(|()| ledger)).await
+    }
+}
+
+/// Runs blocking IO on tokio's blocking pool when called from inside the
+/// server's runtime, and inline everywhere else.
+pub(crate) async fn unblock<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    #[cfg(feature = "server")]
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return tokio::task::spawn_blocking(work)
+            .await
+            .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()));
+    }
+    work()
+}
+
+/// Polls `future` to completion on the current thread.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+/// The blocking front end to a [`Backend`] used by the CLI, the TUI and the bots.
+#[derive(Debug)]
+pub enum Storage {
+    File(FileBackend),
+    #[cfg(feature = "remote")]
+    Remote(crate::remote::Client),
+}
+
+impl Storage {
+    pub fn from_config(config: &Config) -> Self {
+        #[cfg(feature = "remote")]
+        if let Some(url) = &config.remote {
+            return Storage::Remote(crate::remote::Client::new(url, config));
+        }
+        Storage::File(FileBackend::new(&config.data_file))
+    }
+
+    pub fn location(&self) -> String {
+        match self {
+            Storage::File(backend) => backend.location(),
+            #[cfg(feature = "remote")]
+            Storage::Remote(client) => client.location(),
+        }
+    }
+
+    pub fn load(&self) -> Result<FinanceTracker> {
+        let ledger = match self {
+            Storage::File(backend) => block_on(backend.load())?,
+            #[cfg(feature = "remote")]
+            Storage::Remote(client) => block_on(client.load())?,
+        };
+        Ok(ledger.into())
+    }
+
+    /// Like [`load`](Storage::load), but a data file only has the
+    /// transactions dated `from` or later parsed up front. A remote server
+    /// always sends everything.
+    pub fn load_since(&self, from: &str) -> Result<FinanceTracker> {
+        match self {
+            Storage::File(backend) => load_since(&backend.path, from),
+            #[cfg(feature = "remote")]
+            Storage::Remote(_) => self.load(),
+        }
+    }
+
+    /// Fails for a remote server, which only shares transactions, so a
+    /// change to `what` would be gone once the process exits.
+    #[cfg_attr(not(feature = "remote"), allow(unused_variables))]
+    pub fn require_local(&self, what: &str) -> Result<()> {
+        match self {
+            Storage::File(_) => Ok(()),
+            #[cfg(feature = "remote")]
+            Storage::Remote(_) => Err(ExpensoError::InvalidInput(format!(
+                "the remote server only keeps transactions, change {} in a local data file",
+                what
+            ))),
+        }
+    }
+
+    /// Archived transactions are read back in first, so nothing is dropped.
+    pub fn save(&self, tracker: &mut FinanceTracker) -> Result<()> {
+        tracker.load_since(None)?;
+        let current = Ledger::from(&*tracker);
+        let stored = match self {
+            Storage::File(backend) => block_on(backend.save(current))?,
+            #[cfg(feature = "remote")]
+            Storage::Remote(client) => block_on(client.save(current))?,
+        };
+        if stored.transactions != *tracker.get_transactions() {
+            tracker.replace_transactions(FinanceTracker::from_transactions(stored.transactions));
+        }
+        tracker.mark_saved();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{Symbol, TransactionType};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("expenso-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let path = temp_path("round-trip");
+        let mut tracker = FinanceTracker::new();
+        tracker.add_transaction(
+            String::from("Rent"),
+            2000.0,
+            true,
+            String::from("2024-01-01"),
+            TransactionType::Expense,
+            String::from("Housing"),
+        );
+
+        save(&tracker, &path).unwrap();
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.get_transactions().len(), 1);
+        assert_eq!(loaded.total_expense(), 2000.0);
+        assert_eq!(
+            loaded.category_breakdown().get(&Symbol::new("Housing")),
+            Some(&2000.0)
+        );
+        assert_eq!(loaded.next_id(), 2);
+    }
+
+    #[test]
+    fn test_rejects_newer_data_file_version() {
+        let path = temp_path("future");
+        fs::write(&path, r#"{"version": 99, "transactions": []}"#).unwrap();
+        let err = load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(
+            err,
+            ExpensoError::UnsupportedVersion {
+                found: 99,
+                supported: SCHEMA_VERSION
+            }
+        ));
+        assert!(err.to_string().starts_with("data file version 99"));
+    }
+
+    #[test]
+    fn test_load_missing_file_is_empty() {
+        let tracker = load(&temp_path("missing")).unwrap();
+        assert!(tracker.get_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_storage_drives_file_backend() {
+        let path = temp_path("backend");
+        let storage = Storage::File(FileBackend::new(&path));
+        let mut tracker = storage.load().unwrap();
+        tracker.add_transaction(
+            String::from("Coffee"),
+            4.5,
+            false,
+            String::from("2024-01-02"),
+            TransactionType::Expense,
+            String::from("Food"),
+        );
+
+        storage.save(&mut tracker).unwrap();
+        let stored = block_on(FileBackend::new(&path).load()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(!tracker.is_dirty());
+        assert_eq!(stored.transactions, *tracker.get_transactions());
+        assert_eq!(stored.history.len(), 1);
+        assert_eq!(
+            FinanceTracker::replay(stored.history).get_transactions(),
+            tracker.get_transactions()
+        );
+    }
+
+    #[test]
+    fn test_files_without_history_still_load() {
+        let ledger = parse(r#"{"version": 1, "transactions": []}"#).unwrap();
+        assert!(ledger.history.is_empty());
+        assert!(!to_json(&ledger).unwrap().contains("history"));
+    }
+
+    #[test]
+    fn test_deleted_ids_are_not_reused() {
+        let mut tracker = FinanceTracker::new();
+        for description in ["Coffee", "Lunch"] {
+            tracker.add_transaction(
+                String::from(description),
+                5.0,
+                false,
+                String::from("2024-01-01"),
+                TransactionType::Expense,
+                String::from("Food"),
+            );
+        }
+        tracker.delete_transaction(2);
+
+        let mut ledger = Ledger::from(&tracker);
+        ledger.history.clear();
+        let reloaded = FinanceTracker::from(parse(&to_json(&ledger).unwrap()).unwrap());
+        assert_eq!(reloaded.next_id(), 3);
+    }
+
+    #[test]
+    fn test_partial_load_keeps_older_transactions() {
+        let path = temp_path("partial");
+        let mut tracker = FinanceTracker::new();
+        for (description, date) in [
+            ("Old", "2019-05-01"),
+            ("Older", "2018-02-01"),
+            ("New", "2024-03-01"),
+        ] {
+            tracker.add_transaction(
+                String::from(description),
+                10.0,
+                false,
+                String::from(date),
+                TransactionType::Expense,
+                String::from("Food"),
+            );
+        }
+        save(&tracker, &path).unwrap();
+
+        let storage = Storage::File(FileBackend::new(&path));
+        let mut partial = storage.load_since("2024-01-01").unwrap();
+        assert_eq!(partial.loaded_since(), Some("2024-01-01"));
+        assert_eq!(partial.total_expense(), 10.0);
+        assert_eq!(partial.next_id(), 4);
+
+        assert_eq!(partial.load_since(Some("2019-01-01")).unwrap(), 1);
+        assert_eq!(partial.loaded_since(), Some("2019-01-01"));
+        partial.add_transaction(
+            String::from("Newer"),
+            5.0,
+            false,
+            String::from("2024-03-02"),
+            TransactionType::Expense,
+            String::from("Food"),
+        );
+        storage.save(&mut partial).unwrap();
+        let stored = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(partial.loaded_since(), None);
+        let ids: Vec<u32> = stored.get_transactions().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert_eq!(stored.total_expense(), 35.0);
+    }
+
+    #[test]
+    fn test_parse_since_leaves_older_history_unparsed() {
+        let mut tracker = FinanceTracker::new();
+        for (description, date) in [("Old", "2019-05-01"), ("New", "2024-03-01")] {
+            tracker.insert(crate::NewTransaction {
+                description: String::from(description),
+                amount: 10.0,
+                date: String::from(date),
+                ..Default::default()
+            });
+        }
+        let mut ledger = Ledger::from(&tracker);
+        ledger.history[0].at = String::from("2019-05-01T09:00:00");
+        ledger.history[1].at = String::from("2024-03-01T09:00:00");
+        let contents = to_json(&ledger).unwrap();
+
+        let (partial, archive) = parse_since(&contents, "2024-01-01").unwrap();
+        assert_eq!(partial.history, ledger.history[1..]);
+        let mut loaded = FinanceTracker::from(partial).with_archive(archive);
+        assert_eq!(loaded.loaded_since(), Some("2024-01-01"));
+        assert_eq!(loaded.next_id(), 3);
+        loaded.load_since(None).unwrap();
+        assert_eq!(loaded.history(), ledger.history);
+        assert_eq!(loaded.loaded_since(), None);
+
+        let (partial, archive) = parse_since(&contents, "2024-01-01").unwrap();
+        let mut undone = FinanceTracker::from(partial).with_archive(archive);
+        undone.undo().unwrap();
+        undone.undo().unwrap();
+        assert!(undone.get_transactions().is_empty());
+        assert_eq!(undone.history().len(), 4);
+
+        // Undoing an edit to a transaction still archived loads it first.
+        let mut edited = FinanceTracker::from(parse(&contents).unwrap());
+        let old = edited.get_transaction(1).unwrap().clone();
+        edited.update_transaction(crate::Transaction {
+            amount: 12.0,
+            ..old
+        });
+        let (partial, archive) =
+            parse_since(&to_json(&Ledger::from(&edited)).unwrap(), "2024-01-01").unwrap();
+        let mut undone = FinanceTracker::from(partial).with_archive(archive);
+        assert!(undone.get_transaction(1).is_none());
+        undone.undo().unwrap();
+        assert_eq!(undone.loaded_since(), None);
+        assert_eq!(undone.get_transaction(1).unwrap().amount, 10.0);
+        assert_eq!(undone.history().len(), 4);
+
+        // An older entry is only read once it is needed, so a broken one
+        // doesn't stop the partial load.
+        let broken = contents.replacen("\"TransactionAdded\"", "\"Unknown\"", 1);
+        assert!(parse(&broken).is_err());
+        let (partial, archive) = parse_since(&broken, "2024-01-01").unwrap();
+        assert_eq!(partial.history.len(), 1);
+        let mut loaded = FinanceTracker::from(partial).with_archive(archive);
+        assert!(loaded.load_history().is_err());
+        assert!(loaded.load_since(None).is_err());
+    }
+
+    #[test]
+    fn test_history_mismatches_are_found_and_rebaselined() {
+        let mut tracker = FinanceTracker::new();
+        for description in ["Coffee", "Lunch", "Books"] {
+            tracker.insert(crate::NewTransaction {
+                description: String::from(description),
+                amount: 10.0,
+                date: String::from("2024-03-01"),
+                ..Default::default()
+            });
+        }
+        tracker.delete_transaction(3);
+        let ledger = Ledger::from(&tracker);
+        let loaded =
+            |ledger: &Ledger| FinanceTracker::from(parse(&to_json(ledger).unwrap()).unwrap());
+        assert!(loaded(&ledger).history_mismatches().is_empty());
+
+        // Transactions from before the history started are left alone.
+        let mut older = ledger.clone();
+        older.transactions.push(Transaction {
+            id: 7,
+            ..ledger.transactions[0].clone()
+        });
+        assert!(loaded(&older).history_mismatches().is_empty());
+
+        let mut edited = ledger.clone();
+        edited.transactions[1].amount = 12.0;
+        let mut revived = ledger.clone();
+        revived.transactions.push(Transaction {
+            id: 3,
+            ..ledger.transactions[0].clone()
+        });
+        for (tampered, id) in [(edited, 2), (revived, 3)] {
+            let mut tracker = loaded(&tampered);
+            assert_eq!(tracker.history_mismatches(), vec![id]);
+            assert_eq!(tracker.rebaseline_history(), vec![id]);
+            assert!(tracker.history_mismatches().is_empty());
+            let replayed = FinanceTracker::replay(tracker.history().to_vec());
+            assert_eq!(replayed.get_transaction(id), tracker.get_transaction(id));
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{LazyLock, RwLock};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An interned string, used for the categories and payees many transactions
+/// share.
+///
+/// Equal strings get the same id, so every transaction in a category points
+/// at one copy of its name and grouping compares and hashes integers.
+/// Interned names live until the process exits; there are only ever a few
+/// hundred of them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+#[derive(Default)]
+struct SymbolTable {
+    names: Vec<&'static str>,
+    ids: HashMap<&'static str, u32>,
+}
+
+static TABLE: LazyLock<RwLock<SymbolTable>> = LazyLock::new(Default::default);
+
+impl Symbol {
+    pub fn new(name: &str) -> Self {
+        let read = TABLE
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(&id) = read.ids.get(name) {
+            return Symbol(id);
+        }
+        drop(read);
+
+        let mut table = TABLE
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Another thread may have added it between the two locks.
+        if let Some(&id) = table.ids.get(name) {
+            return Symbol(id);
+        }
+        let name: &'static str = Box::leak(name.into());
+        let id = table.names.len() as u32;
+        table.names.push(name);
+        table.ids.insert(name, id);
+        Symbol(id)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        let table = TABLE
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        table.names[self.0 as usize]
+    }
+}
+
+impl Default for Symbol {
+    fn default() -> Self {
+        Symbol::new("")
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(name: String) -> Self {
+        Symbol::new(&name)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(name: &String) -> Self {
+        Symbol::new(name)
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(symbol: Symbol) -> Self {
+        symbol.as_str().to_string()
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Symbol> for &str {
+    fn eq(&self, other: &Symbol) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Symbol> for String {
+    fn eq(&self, other: &Symbol) -> bool {
+        self == other.as_str()
+    }
+}
+
+/// Alphabetical, like the strings themselves.
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self == other {
+            return Ordering::Equal;
+        }
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = std::borrow::Cow::<'de, str>::deserialize(deserializer)?;
+        Ok(Symbol::new(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_equal_names_share_an_id() {
+        let food = Symbol::new("Food");
+        assert_eq!(food, Symbol::from(String::from("Food")));
+        assert_ne!(food, Symbol::new("food"));
+        assert_eq!(food, "Food");
+        assert!(Symbol::new("Coffee") < food);
+
+        let json = serde_json::to_string(&food).unwrap();
+        assert_eq!(json, "\"Food\"");
+        assert_eq!(serde_json::from_str::<Symbol>(&json).unwrap(), food);
+    }
+}
@@ -0,0 +1,260 @@
+use std::ops::Range;
+
+use crate::color::{Color, paint};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug)]
+struct Column {
+    header: String,
+    align: Align,
+    max_width: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cell {
+    text: String,
+    color: Option<Color>,
+    /// Byte ranges of `text` to make stand out, in order.
+    highlights: Vec<Range<usize>>,
+}
+
+impl Cell {
+    pub fn colored(text: String, color: Color) -> Self {
+        Cell {
+            text,
+            color: Some(color),
+            highlights: Vec::new(),
+        }
+    }
+
+    /// A cell with parts of its text, such as search matches, highlighted.
+    pub fn highlighted(text: String, highlights: Vec<Range<usize>>) -> Self {
+        Cell {
+            text,
+            color: None,
+            highlights,
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Self {
+        Cell {
+            text,
+            color: None,
+            highlights: Vec::new(),
+        }
+    }
+}
+
+impl From<&str> for Cell {
+    fn from(text: &str) -> Self {
+        Cell::from(text.to_string())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        Table::default()
+    }
+
+    pub fn column(mut self, header: &str, align: Align) -> Self {
+        self.columns.push(Column {
+            header: header.to_string(),
+            align,
+            max_width: None,
+        });
+        self
+    }
+
+    pub fn truncated_column(mut self, header: &str, align: Align, max_width: usize) -> Self {
+        self.columns.push(Column {
+            header: header.to_string(),
+            align,
+            max_width: Some(max_width),
+        });
+        self
+    }
+
+    pub fn add_row(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    /// Adds `row` faint, in place of its cells' own colors.
+    pub fn add_dimmed_row(&mut self, row: Vec<Cell>) {
+        let row = row
+            .into_iter()
+            .map(|cell| Cell {
+                color: Some(Color::Dim),
+                ..cell
+            })
+            .collect();
+        self.rows.push(row);
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let widest = self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.get(index))
+                    .map(|cell| cell.text.chars().count())
+                    .chain(std::iter::once(column.header.chars().count()))
+                    .max()
+                    .unwrap_or(0);
+                match column.max_width {
+                    Some(max) => widest.min(max.max(column.header.chars().count())),
+                    None => widest,
+                }
+            })
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let widths = self.widths();
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, &width)| pad(&column.header, width, column.align))
+            .collect();
+        lines.push(header.join("  ").trim_end().to_string());
+
+        let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        lines.push(rule.join("  "));
+
+        for row in &self.rows {
+            let cells: Vec<String> = self
+                .columns
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(index, (column, &width))| {
+                    let cell = row.get(index).cloned().unwrap_or_else(|| Cell::from(""));
+                    let truncated = truncate(&cell.text, width);
+                    let text = pad(&truncated, width, column.align);
+                    if !cell.highlights.is_empty() {
+                        return highlight(&cell, &truncated, &text);
+                    }
+                    match cell.color {
+                        Some(color) => paint(&text, color),
+                        None => text,
+                    }
+                })
+                .collect();
+            lines.push(cells.join("  ").trim_end().to_string());
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// `padded`, the cell's text after truncating and padding, with the cell's
+/// highlights that survived the truncation painted.
+fn highlight(cell: &Cell, truncated: &str, padded: &str) -> String {
+    let kept = if truncated == cell.text {
+        truncated.len()
+    } else {
+        truncated.len() - '…'.len_utf8()
+    };
+    // Right-aligned text is padded on the left.
+    let offset = padded.find(truncated).unwrap_or(0);
+    let mut out = String::with_capacity(padded.len());
+    let mut at = 0;
+    for range in &cell.highlights {
+        let (start, end) = (range.start.min(kept), range.end.min(kept));
+        if start >= end || start < at {
+            continue;
+        }
+        out.push_str(&padded[at..offset + start]);
+        out.push_str(&paint(
+            &padded[offset + start..offset + end],
+            Color::Highlight,
+        ));
+        at = offset + end;
+    }
+    out.push_str(&padded[at..]);
+    out
+}
+
+fn pad(text: &str, width: usize, align: Align) -> String {
+    match align {
+        Align::Left => format!("{:<width$}", text, width = width),
+        Align::Right => format!("{:>width$}", text, width = width),
+    }
+}
+
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let kept: String = text.chars().take(width - 1).collect();
+    format!("{}…", kept)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Groceries", 20), "Groceries");
+        assert_eq!(truncate("Groceries", 5), "Groc…");
+        assert_eq!(truncate("Groceries", 0), "");
+    }
+
+    #[test]
+    fn test_render_aligns_columns() {
+        let mut table = Table::new()
+            .column("ID", Align::Right)
+            .column("Description", Align::Left)
+            .column("Amount", Align::Right);
+        table.add_row(vec!["1".into(), "Rent".into(), "$2000.00".into()]);
+        table.add_row(vec!["12".into(), "Coffee".into(), "$4.50".into()]);
+
+        let expected = "\
+ID  Description    Amount
+--  -----------  --------
+ 1  Rent         $2000.00
+12  Coffee          $4.50";
+        assert_eq!(table.render(), expected);
+    }
+
+    #[test]
+    fn test_render_truncates_long_cells() {
+        let mut table = Table::new().truncated_column("Description", Align::Left, 12);
+        table.add_row(vec!["Quarterly insurance premium".into()]);
+
+        let rendered = table.render();
+        assert_eq!(rendered.lines().nth(2), Some("Quarterly i…"));
+    }
+
+    #[test]
+    fn test_highlight_survives_padding_and_truncation() {
+        let cell = Cell::highlighted(String::from("Quarterly insurance"), vec![0..3, 10..19]);
+        let truncated = truncate(&cell.text, 12);
+        let padded = pad(&truncated, 14, Align::Right);
+        // Without colors the text is unchanged, wherever the highlights are.
+        assert_eq!(highlight(&cell, &truncated, &padded), "  Quarterly i…");
+    }
+}
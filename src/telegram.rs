@@ -0,0 +1,249 @@
+use std::io;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::cli::{AddArgs, build_new_transaction};
+use crate::config::Config;
+use crate::error::Result;
+use crate::money::format_money;
+use crate::report::Summary;
+use crate::storage::Storage;
+use crate::{FinanceTracker, Transaction};
+
+const API: &str = "https://api.telegram.org";
+const POLL_SECONDS: u64 = 30;
+
+const HELP: &str = "Send an entry like \"12.50 lunch #food\" to record it.\n\
+/summary shows this month's totals.";
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Reply {
+    pub text: String,
+    pub changed: bool,
+}
+
+impl Reply {
+    fn text(text: impl Into<String>) -> Self {
+        Reply {
+            text: text.into(),
+            changed: false,
+        }
+    }
+}
+
+pub fn handle_message(
+    tracker: &mut FinanceTracker,
+    config: &Config,
+    text: &str,
+    today: NaiveDate,
+) -> Reply {
+    let text = text.trim();
+    let command = text
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .split('@')
+        .next()
+        .unwrap_or("");
+
+    match command {
+        "/start" | "/help" => Reply::text(HELP),
+        "/summary" => Reply::text(monthly_summary(tracker, today)),
+        _ if text.starts_with('/') => Reply::text(format!("Unknown command {}\n{}", command, HELP)),
+        _ => {
+            let args = AddArgs {
+                entry: Some(text.to_string()),
+                ..Default::default()
+            };
+            match build_new_transaction(&args, config, today) {
+                Ok(new) => match tracker.add(new) {
+                    Ok(added) => Reply {
+                        text: format!(
+                            "Added {} {} ({}) on {}",
+                            added.description,
+                            format_money(added.amount),
+                            added.category,
+                            added.date
+                        ),
+                        changed: true,
+                    },
+                    Err(err) => Reply::text(format!("{}\n{}", err, HELP)),
+                },
+                Err(message) => Reply::text(format!("{}\n{}", message, HELP)),
+            }
+        }
+    }
+}
+
+fn monthly_summary(tracker: &FinanceTracker, today: NaiveDate) -> String {
+    let month = today.format("%Y-%m").to_string();
+    let transactions: Vec<&Transaction> = tracker
+        .get_transactions()
+        .iter()
+        .filter(|t| t.date.starts_with(&month))
+        .collect();
+    let summary = Summary::of(&transactions);
+
+    format!(
+        "Summary for {}\nIncome: {}\nExpense: {}\nBalance: {}",
+        month,
+        format_money(summary.total_income),
+        format_money(summary.total_expense),
+        format_money(summary.net_balance)
+    )
+}
+
+pub fn run(tracker: &mut FinanceTracker, storage: &Storage, config: &Config) -> Result<()> {
+    let token = std::env::var("EXPENSO_TELEGRAM_TOKEN")
+        .ok()
+        .or_else(|| config.telegram_token.clone())
+        .ok_or_else(|| {
+            io::Error::other("Set telegram_token in the config file or EXPENSO_TELEGRAM_TOKEN")
+        })?;
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(POLL_SECONDS + 10)))
+        .build()
+        .into();
+    let base = format!("{}/bot{}", API, token);
+
+    println!("Telegram bot running, press Ctrl-C to stop");
+    let mut offset = 0;
+    loop {
+        let updates: Vec<Update> = call(
+            &agent,
+            &format!("{}/getUpdates", base),
+            json!({ "offset": offset, "timeout": POLL_SECONDS }),
+        )?;
+
+        for update in updates {
+            offset = offset.max(update.update_id + 1);
+            let Some(Message {
+                chat,
+                text: Some(text),
+            }) = update.message
+            else {
+                continue;
+            };
+
+            let reply = if config.telegram_chats.contains(&chat.id) {
+                handle_message(tracker, config, &text, Local::now().date_naive())
+            } else {
+                Reply::text(format!(
+                    "This chat ({}) is not allowed. Add it to telegram_chats in the expenso config.",
+                    chat.id
+                ))
+            };
+
+            if reply.changed {
+                storage.save(tracker)?;
+            }
+            let _: serde_json::Value = call(
+                &agent,
+                &format!("{}/sendMessage", base),
+                json!({ "chat_id": chat.id, "text": reply.text }),
+            )?;
+        }
+    }
+}
+
+fn call<T: serde::de::DeserializeOwned>(
+    agent: &ureq::Agent,
+    url: &str,
+    body: serde_json::Value,
+) -> io::Result<T> {
+    let response: ApiResponse<T> = agent
+        .post(url)
+        .send_json(body)
+        .map_err(io::Error::other)?
+        .body_mut()
+        .read_json()
+        .map_err(io::Error::other)?;
+
+    match response {
+        ApiResponse {
+            ok: true,
+            result: Some(result),
+            ..
+        } => Ok(result),
+        ApiResponse { description, .. } => Err(io::Error::other(
+            description.unwrap_or_else(|| String::from("Telegram API error")),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()
+    }
+
+    #[test]
+    fn test_entry_records_transaction() {
+        let mut tracker = FinanceTracker::new();
+        let reply = handle_message(
+            &mut tracker,
+            &Config::default(),
+            "12.50 lunch #food",
+            today(),
+        );
+
+        assert!(reply.changed);
+        assert_eq!(reply.text, "Added lunch $12.50 (food) on 2024-03-15");
+        assert_eq!(tracker.get_transactions()[0].category, "food");
+    }
+
+    #[test]
+    fn test_summary_covers_current_month() {
+        let mut tracker = FinanceTracker::new();
+        let config = Config::default();
+        handle_message(&mut tracker, &config, "20 groceries 2024-03-02", today());
+        handle_message(&mut tracker, &config, "+100 refund", today());
+        handle_message(&mut tracker, &config, "99 old 2024-02-28", today());
+
+        let reply = handle_message(&mut tracker, &config, "/summary@expenso_bot", today());
+        assert!(!reply.changed);
+        assert_eq!(
+            reply.text,
+            "Summary for 2024-03\nIncome: $100.00\nExpense: $20.00\nBalance: $80.00"
+        );
+    }
+
+    #[test]
+    fn test_bad_input_replies_with_help() {
+        let mut tracker = FinanceTracker::new();
+        let reply = handle_message(&mut tracker, &Config::default(), "lunch", today());
+        assert!(!reply.changed);
+        assert!(reply.text.ends_with(HELP));
+        assert!(tracker.get_transactions().is_empty());
+    }
+}
@@ -0,0 +1,2276 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::dates::{self, BudgetPeriod, Calendar};
+use crate::error::{ExpensoError, Result};
+use crate::events::{Event, Hooks};
+use crate::filter::TransactionFilter;
+use crate::history::{Change, Entry};
+use crate::index::{self, PositionIndex};
+use crate::investments::Portfolio;
+use crate::loans::Loan;
+use crate::model::{NewTransaction, Transaction, TransactionType};
+use crate::money;
+use crate::periods::{self, ClosedPeriod};
+use crate::projects::Project;
+use crate::query::Query;
+use crate::storage::Archive;
+use crate::symbol::Symbol;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SortKey {
+    Id,
+    Date,
+    Amount,
+    Category,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// What [`FinanceTracker::add_transactions`] did with a batch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// Ids given to the added transactions, in input order.
+    pub added: Vec<u32>,
+    /// Input positions skipped because their external id or import
+    /// fingerprint was already known.
+    pub duplicates: Vec<usize>,
+    /// Input positions that failed validation, and why.
+    pub invalid: Vec<(usize, String)>,
+}
+
+/// What [`FinanceTracker::merge`] did with another data file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// Ids given to the added transactions.
+    pub added: Vec<u32>,
+    /// How many were skipped for being the same as one already here, apart
+    /// from the id and timestamps.
+    pub duplicates: usize,
+    /// Ids that had to change, as `(old, new)` pairs, so references to them
+    /// can be updated.
+    pub renumbered: BTreeMap<u32, u32>,
+    /// `(ours, theirs)` ids of a transaction edited differently in each
+    /// file. Both versions are kept for the user to pick one.
+    pub conflicts: Vec<(u32, u32)>,
+    /// Ids of transactions deleted here but still in the other file, which
+    /// are left out.
+    pub deleted: Vec<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FinanceTracker {
+    transactions: Vec<Transaction>,
+    /// Positions in `transactions` by date and by lowercased category, so
+    /// date ranges and categories are found without scanning everything.
+    by_date: PositionIndex,
+    by_category: PositionIndex,
+    /// Positions by each word of their description, payee, notes and
+    /// category, for [`FinanceTracker::search`].
+    by_word: PositionIndex,
+    category_totals: HashMap<Symbol, f64>,
+    /// Transactions per category, so a category's total goes once its last
+    /// transaction does.
+    category_counts: HashMap<Symbol, usize>,
+    income_total: f64,
+    expense_total: f64,
+    /// Settlements, which count as neither income nor expense.
+    settlements: usize,
+    /// Income and expense per `YYYY-MM`.
+    month_totals: BTreeMap<String, (f64, f64)>,
+    unique_categories: HashSet<Symbol>,
+    next_id: u32,
+    dirty: bool,
+    budgets: HashMap<String, f64>,
+    /// See [`FinanceTracker::set_budget_period`].
+    budget_period: BudgetPeriod,
+    calendar: Calendar,
+    /// See [`FinanceTracker::set_opening_balances`].
+    opening_balance: f64,
+    opening_balances: BTreeMap<String, f64>,
+    hooks: Hooks,
+    history: Vec<Entry>,
+    portfolio: Portfolio,
+    loans: Vec<Loan>,
+    projects: Vec<Project>,
+    /// Months and years closed against changes, in the order they were closed.
+    closed: Vec<ClosedPeriod>,
+    /// Recorded with every change, see [`FinanceTracker::set_user`].
+    user: Option<String>,
+    /// Older transactions not loaded yet, see [`FinanceTracker::load_since`].
+    archive: Archive,
+}
+
+impl Default for FinanceTracker {
+    fn default() -> Self {
+        FinanceTracker::new()
+    }
+}
+
+impl FinanceTracker {
+    pub fn new() -> Self {
+        FinanceTracker {
+            transactions: Vec::new(),
+            by_date: PositionIndex::default(),
+            by_category: PositionIndex::default(),
+            by_word: PositionIndex::default(),
+            category_totals: HashMap::new(),
+            category_counts: HashMap::new(),
+            income_total: 0.0,
+            expense_total: 0.0,
+            settlements: 0,
+            month_totals: BTreeMap::new(),
+            unique_categories: HashSet::new(),
+            next_id: 1,
+            dirty: false,
+            budgets: HashMap::new(),
+            budget_period: BudgetPeriod::Month,
+            calendar: Calendar::default(),
+            opening_balance: 0.0,
+            opening_balances: BTreeMap::new(),
+            hooks: Hooks::default(),
+            history: Vec::new(),
+            portfolio: Portfolio::default(),
+            loans: Vec::new(),
+            projects: Vec::new(),
+            closed: Vec::new(),
+            user: None,
+            archive: Archive::default(),
+        }
+    }
+
+    pub fn from_transactions(transactions: Vec<Transaction>) -> Self {
+        let mut tracker = FinanceTracker::new();
+
+        for transaction in transactions {
+            tracker.add_to_totals(&transaction);
+            tracker.next_id = tracker.next_id.max(transaction.id + 1);
+            tracker.transactions.push(transaction);
+        }
+        tracker.reindex();
+
+        tracker
+    }
+
+    /// Rebuilds a tracker by applying `history` to an empty one.
+    pub fn replay(history: Vec<Entry>) -> Self {
+        let mut tracker = FinanceTracker::new();
+        for entry in &history {
+            tracker.apply(&entry.change);
+        }
+        tracker.history = history;
+        tracker.dirty = false;
+        tracker
+    }
+
+    /// Attaches the history that led to the current transactions, restoring
+    /// the budgets it set. Ids of transactions deleted since stay used.
+    pub fn with_history(mut self, history: Vec<Entry>) -> Self {
+        for entry in &history {
+            if let Some(id) = entry.change.transaction_id() {
+                self.next_id = self.next_id.max(id + 1);
+            }
+            if let Change::BudgetSet {
+                category, limit, ..
+            } = &entry.change
+            {
+                self.apply_budget(category, *limit);
+            }
+        }
+        self.history = history;
+        self
+    }
+
+    /// Continues numbering from a stored `next_id`. Ids only ever grow, so a
+    /// deleted transaction's id is never handed out again.
+    pub(crate) fn with_next_id(mut self, next_id: u32) -> Self {
+        self.next_id = self.next_id.max(next_id);
+        self
+    }
+
+    /// Holds back the older transactions of a partial load. Ids keep
+    /// counting past the archived ones.
+    pub fn with_archive(mut self, archive: Archive) -> Self {
+        self.next_id = self.next_id.max(archive.max_id() + 1);
+        self.archive = archive;
+        self
+    }
+
+    /// The first date loaded when older transactions are still archived, or
+    /// `None` when everything is loaded. Totals, reports and lookups only
+    /// cover what is loaded.
+    pub fn loaded_since(&self) -> Option<&str> {
+        self.archive.since()
+    }
+
+    /// Loads the archived transactions dated `from` or later and the history
+    /// from then on, or all of them for `None`, and returns how many
+    /// transactions were added. They are not changes, so nothing new is
+    /// recorded in the history.
+    pub fn load_since(&mut self, from: Option<&str>) -> Result<usize> {
+        let (older, history) = self.archive.take_since(from)?;
+        self.history.splice(0..0, history);
+        for transaction in &older {
+            self.add_to_totals(transaction);
+        }
+        let count = older.len();
+        if count > 0 {
+            self.transactions.extend(older);
+            self.transactions.sort_by_key(|t| t.id);
+            self.reindex();
+        }
+        Ok(count)
+    }
+
+    pub fn add_transaction(
+        &mut self,
+        description: String,
+        amount: f64,
+        is_recurring: bool,
+        date: String,
+        transaction_type: TransactionType,
+        category: String,
+    ) {
+        self.insert(NewTransaction {
+            description,
+            amount,
+            is_recurring,
+            date,
+            transaction_type,
+            category,
+            ..Default::default()
+        });
+    }
+
+    /// Adds `new` as is; callers are expected to have validated it. Interfaces
+    /// taking user input should use [`FinanceTracker::add`].
+    pub fn insert(&mut self, new: NewTransaction) -> u32 {
+        let id = self.next_id;
+        let mut transaction = new.into_transaction(id);
+        transaction.created_at = Some(dates::utc_now());
+        transaction.updated_at = transaction.created_at.clone();
+        self.record(Change::TransactionAdded {
+            transaction: Box::new(transaction),
+        });
+        id
+    }
+
+    /// Validates and adds a batch, such as a bank import. Transactions whose
+    /// external id or import fingerprint hash is already in the tracker, or
+    /// earlier in the batch, are skipped; the rest are appended in one go,
+    /// each kept in the history.
+    pub fn add_transactions(&mut self, batch: Vec<NewTransaction>) -> ImportReport {
+        let mut seen: HashSet<String> = self
+            .transactions
+            .iter()
+            .filter_map(|t| t.external_id.clone())
+            .collect();
+        let mut hashes: HashSet<String> = self
+            .transactions
+            .iter()
+            .filter_map(|t| t.import.as_ref().map(|import| import.hash.clone()))
+            .collect();
+        let mut report = ImportReport::default();
+        self.transactions.reserve(batch.len());
+
+        for (position, new) in batch.into_iter().enumerate() {
+            let known_id = new
+                .external_id
+                .as_ref()
+                .is_some_and(|external_id| !seen.insert(external_id.clone()));
+            let known_hash = new
+                .import
+                .as_ref()
+                .is_some_and(|import| !hashes.insert(import.hash.clone()));
+            if known_id || known_hash {
+                report.duplicates.push(position);
+                continue;
+            }
+            match new
+                .validate()
+                .and_then(|new| self.check_open(&new.date).map(|_| new))
+            {
+                Ok(new) => report.added.push(self.insert(new)),
+                Err(err) => report.invalid.push((position, err.to_string())),
+            }
+        }
+        report
+    }
+
+    /// Adds the transactions of another data file, such as a copy edited on
+    /// another machine.
+    ///
+    /// A transaction the same as one already here, apart from the id and
+    /// timestamps, is skipped. Any other id that was already handed out here,
+    /// including to a deleted transaction, collides and gets a fresh one.
+    /// When the colliding transaction here was created at the same moment
+    /// and one of them was edited since, it is the same one edited apart and
+    /// is reported as a conflict. One the history shows was deleted here is
+    /// reported and left out.
+    ///
+    /// Nothing is added when any of the new transactions is dated in a
+    /// closed period.
+    pub fn merge(&mut self, mut transactions: Vec<Transaction>) -> Result<MergeReport> {
+        self.load_since(None)?;
+        transactions.sort_by_key(|t| t.id);
+        let issued = self.next_id;
+        let mut known: HashSet<String> = self.transactions.iter().map(content_key).collect();
+        let mut deleted_here: HashMap<u32, &Transaction> = HashMap::new();
+        for entry in &self.history {
+            match &entry.change {
+                Change::TransactionDeleted { transaction } => {
+                    deleted_here.insert(transaction.id, transaction)
+                }
+                change => change
+                    .transaction_id()
+                    .and_then(|id| deleted_here.remove(&id)),
+            };
+        }
+        let deleted: HashSet<u32> = transactions
+            .iter()
+            .filter(|t| {
+                deleted_here
+                    .get(&t.id)
+                    .is_some_and(|gone| same_transaction(gone, t))
+            })
+            .map(|t| t.id)
+            .collect();
+        for transaction in &transactions {
+            if !known.contains(&content_key(transaction)) && !deleted.contains(&transaction.id) {
+                self.check_open(&transaction.date)?;
+            }
+        }
+        let mut report = MergeReport::default();
+
+        for mut transaction in transactions {
+            if !known.insert(content_key(&transaction)) {
+                report.duplicates += 1;
+                continue;
+            }
+            if deleted.contains(&transaction.id) {
+                report.deleted.push(transaction.id);
+                continue;
+            }
+            let ours = self.get_transaction(transaction.id);
+            if transaction.id < issued || ours.is_some() {
+                let id = self.next_id;
+                if ours.is_some_and(|ours| edited_apart(ours, &transaction)) {
+                    report.conflicts.push((transaction.id, id));
+                }
+                report.renumbered.insert(transaction.id, id);
+                transaction.id = id;
+            }
+            report.added.push(transaction.id);
+            self.record(Change::TransactionAdded {
+                transaction: Box::new(transaction),
+            });
+        }
+        Ok(report)
+    }
+
+    /// Validates `new` and adds it, unless it is dated in a closed period.
+    pub fn add(&mut self, new: NewTransaction) -> Result<Transaction> {
+        let new = new.validate()?;
+        self.check_open(&new.date)?;
+        let id = self.insert(new);
+        self.get_transaction(id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(id))
+    }
+
+    /// Validates `changes` and applies them to transaction `id`. The external
+    /// id, import fingerprint and receipt are kept unless `changes` sets new ones. A reconciled
+    /// transaction only takes changes that leave it matching the statement,
+    /// and one in a closed period none at all.
+    pub fn edit(&mut self, id: u32, changes: NewTransaction) -> Result<Transaction> {
+        let existing = self
+            .get_transaction(id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(id))?;
+        let mut updated = changes.validate()?.into_transaction(id);
+        updated.external_id = updated.external_id.or(existing.external_id.clone());
+        updated.import = updated.import.or(existing.import.clone());
+        updated.receipt = updated.receipt.or(existing.receipt.clone());
+        updated.created_at = existing.created_at.clone();
+        updated.updated_at = existing.updated_at.clone();
+        updated.reconciled = existing.reconciled;
+        self.check_editable(&existing, &updated)?;
+        if updated != existing {
+            self.update_transaction(updated);
+        }
+        self.get_transaction(id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(id))
+    }
+
+    /// Replaces the transaction with `updated.id`, with the checks
+    /// [`FinanceTracker::edit`] makes, for changes that `NewTransaction`
+    /// can't carry, such as the project.
+    pub fn update(&mut self, updated: Transaction) -> Result<Transaction> {
+        let id = updated.id;
+        let existing = self.get_transaction(id).ok_or(ExpensoError::NotFound(id))?;
+        self.check_editable(existing, &updated)?;
+        if *existing != updated {
+            self.update_transaction(updated);
+        }
+        self.get_transaction(id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(id))
+    }
+
+    pub fn remove(&mut self, id: u32) -> Result<Transaction> {
+        if let Some(existing) = self.get_transaction(id) {
+            self.check_removable(existing)?;
+        }
+        self.delete_transaction(id)
+            .ok_or(ExpensoError::NotFound(id))
+    }
+
+    /// Marks transaction `id` as matched against a statement, or unlocks it.
+    pub fn set_reconciled(&mut self, id: u32, reconciled: bool) -> Result<Transaction> {
+        let existing = self
+            .get_transaction(id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(id))?;
+        if existing.reconciled != reconciled {
+            self.update_transaction(Transaction {
+                reconciled,
+                ..existing
+            });
+        }
+        self.get_transaction(id)
+            .cloned()
+            .ok_or(ExpensoError::NotFound(id))
+    }
+
+    pub fn total_income(&self) -> f64 {
+        money::round(self.income_total)
+    }
+
+    pub fn total_expense(&self) -> f64 {
+        money::round(self.expense_total)
+    }
+
+    /// The opening balances plus income less expenses.
+    pub fn net_balance(&self) -> f64 {
+        money::sum([
+            self.opening_balance(),
+            self.income_total,
+            -self.expense_total,
+        ])
+    }
+
+    /// Sets the money there was before the first transaction: `unassigned`
+    /// not tied to an account, and what each of `accounts` held, so balances
+    /// match reality without a made-up income transaction.
+    pub fn set_opening_balances(&mut self, unassigned: f64, accounts: &BTreeMap<String, f64>) {
+        self.opening_balance = unassigned;
+        self.opening_balances = accounts.clone();
+    }
+
+    /// All there was before the first transaction.
+    pub fn opening_balance(&self) -> f64 {
+        money::sum(
+            self.opening_balances
+                .values()
+                .copied()
+                .chain([self.opening_balance]),
+        )
+    }
+
+    /// The opening balance not tied to an account and those of the accounts.
+    pub fn opening_balances(&self) -> (f64, &BTreeMap<String, f64>) {
+        (self.opening_balance, &self.opening_balances)
+    }
+
+    /// What `account` held before its first transaction, ignoring case.
+    pub fn account_opening_balance(&self, account: &str) -> f64 {
+        money::sum(
+            self.opening_balances
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(account))
+                .map(|(_, balance)| *balance),
+        )
+    }
+
+    pub fn average_transaction(&self) -> f64 {
+        let count = self.transactions.len() - self.settlements;
+        if count == 0 {
+            return 0.0;
+        }
+
+        let sum = self.income_total + self.expense_total;
+
+        let count = count as f64;
+
+        money::round(sum / count)
+    }
+
+    pub fn category_breakdown(&self) -> &HashMap<Symbol, f64> {
+        &self.category_totals
+    }
+
+    /// Income and expense totals per `YYYY-MM`, oldest first.
+    pub fn month_totals(&self) -> &BTreeMap<String, (f64, f64)> {
+        &self.month_totals
+    }
+
+    pub fn get_transactions(&self) -> &Vec<Transaction> {
+        &self.transactions
+    }
+
+    pub fn query(&self) -> Query<'_> {
+        Query::new(self)
+    }
+
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = self
+            .unique_categories
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        categories.sort_by_key(|c| c.to_lowercase());
+        categories
+    }
+
+    pub fn last_transaction(&self) -> Option<&Transaction> {
+        self.transactions.iter().max_by_key(|t| t.id)
+    }
+
+    /// The category last used with this payee or, failing that, this exact
+    /// description, ignoring case.
+    pub fn past_category(&self, description: &str, payee: Option<&str>) -> Option<Symbol> {
+        let latest = |matches: &dyn Fn(&Transaction) -> bool| {
+            self.transactions
+                .iter()
+                .rev()
+                .find(|t| matches(t))
+                .map(|t| t.category)
+        };
+        payee
+            .and_then(|payee| latest(&|t| t.payee.is_some_and(|p| p.eq_ignore_ascii_case(payee))))
+            .or_else(|| latest(&|t| t.description.eq_ignore_ascii_case(description)))
+    }
+
+    pub fn get_transaction(&self, id: u32) -> Option<&Transaction> {
+        self.transactions.iter().find(|t| t.id == id)
+    }
+
+    pub fn sorted_transactions(&self, key: SortKey, order: SortOrder) -> Vec<&Transaction> {
+        let mut sorted: Vec<&Transaction> = self.transactions.iter().collect();
+        sort(&mut sorted, key, order);
+        sorted
+    }
+
+    /// The transactions matching `filter`, sorted. A category, or else a
+    /// `from` or `to` date, is looked up in its index, so only those
+    /// transactions are checked.
+    pub fn filtered(
+        &self,
+        filter: &TransactionFilter,
+        key: SortKey,
+        order: SortOrder,
+    ) -> Vec<&Transaction> {
+        let positions = match (
+            &filter.category,
+            filter.from.as_deref(),
+            filter.to.as_deref(),
+        ) {
+            (Some(category), _, _) => self.by_category.get(&category.to_lowercase()).to_vec(),
+            (None, None, None) => (0..self.transactions.len()).collect(),
+            (None, from, to) => self.positions_between(from, to),
+        };
+        let mut matching: Vec<&Transaction> = positions
+            .into_iter()
+            .map(|position| &self.transactions[position])
+            .filter(|t| filter.matches(t))
+            .collect();
+        sort(&mut matching, key, order);
+        matching
+    }
+
+    /// Transactions in `category`, ignoring case, in the order they are stored.
+    pub fn in_category(&self, category: &str) -> impl Iterator<Item = &Transaction> {
+        self.by_category
+            .get(&category.to_lowercase())
+            .iter()
+            .map(|&position| &self.transactions[position])
+    }
+
+    /// Positions of the transactions in `category` or one of its
+    /// subcategories (`Food:Coffee` for `Food`), ignoring case, in the order
+    /// they are stored.
+    pub(crate) fn positions_in_category(&self, category: &str) -> Vec<usize> {
+        let category = category.to_lowercase();
+        let subcategories = format!("{}:", category);
+        let mut positions: Vec<usize> = self
+            .by_category
+            .get(&category)
+            .iter()
+            .copied()
+            .chain(self.by_category.with_prefix(&subcategories))
+            .collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    /// Transactions dated from `from` to `to` inclusive (YYYY-MM-DD, `None`
+    /// leaves that end open), in date order.
+    pub fn in_date_range(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+    ) -> impl Iterator<Item = &Transaction> {
+        self.by_date
+            .range(from, to)
+            .map(|position| &self.transactions[position])
+    }
+
+    /// Positions of the transactions in a date range, in the order they are stored.
+    pub(crate) fn positions_between(&self, from: Option<&str>, to: Option<&str>) -> Vec<usize> {
+        let mut positions: Vec<usize> = self.by_date.range(from, to).collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    /// Transactions with `query` in their description, payee, notes or
+    /// category, ignoring case. Only those with a word containing the
+    /// query's longest word are checked, found through the word index.
+    pub fn search(&self, query: &str) -> Vec<&Transaction> {
+        let words = index::words(query);
+        let Some(longest) = words.iter().max_by_key(|word| word.len()) else {
+            return self
+                .transactions
+                .iter()
+                .filter(|t| t.matches(query))
+                .collect();
+        };
+        let mut positions: Vec<usize> = self.by_word.containing(longest).collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+            .into_iter()
+            .map(|position| &self.transactions[position])
+            .filter(|t| t.matches(query))
+            .collect()
+    }
+
+    pub fn search_regex(&self, pattern: &Regex) -> Vec<&Transaction> {
+        self.transactions
+            .iter()
+            .filter(|t| t.matches_regex(pattern))
+            .collect()
+    }
+
+    /// Replaces the transaction with `updated.id`, keeping when it was
+    /// created and stamping when it was updated.
+    pub fn update_transaction(&mut self, mut updated: Transaction) -> bool {
+        let Some(before) = self.get_transaction(updated.id).cloned() else {
+            return false;
+        };
+        updated.created_at = before.created_at.clone();
+        updated.updated_at = Some(dates::utc_now());
+        self.record(Change::TransactionEdited {
+            before: Box::new(before),
+            after: Box::new(updated),
+        });
+        true
+    }
+
+    pub fn delete_transaction(&mut self, id: u32) -> Option<Transaction> {
+        let removed = self.get_transaction(id)?.clone();
+        self.record(Change::TransactionDeleted {
+            transaction: Box::new(removed.clone()),
+        });
+        Some(removed)
+    }
+
+    /// Moves every transaction in category `from` to `to`, or none of them
+    /// when any is in a closed period.
+    pub fn recategorize(&mut self, from: &str, to: &str) -> Result<Vec<u32>> {
+        let matching: Vec<Transaction> = self
+            .transactions
+            .iter()
+            .filter(|t| t.category.eq_ignore_ascii_case(from))
+            .map(|transaction| Transaction {
+                category: Symbol::new(to),
+                ..transaction.clone()
+            })
+            .collect();
+        for updated in &matching {
+            self.check_open(&updated.date)?;
+        }
+
+        Ok(matching
+            .into_iter()
+            .map(|updated| {
+                let id = updated.id;
+                self.update_transaction(updated);
+                id
+            })
+            .collect())
+    }
+
+    /// Calls `hook` after every later mutation of this tracker.
+    pub fn subscribe(&mut self, hook: impl Fn(&Event) + Send + Sync + 'static) {
+        self.hooks.add(hook);
+    }
+
+    /// Sets a spending limit per budget period for a category, checked as
+    /// expenses are added or edited. Setting the limit it already has
+    /// records nothing.
+    pub fn set_budget(&mut self, category: &str, limit: f64) {
+        let previous = self.budget(category);
+        if previous != Some(limit) {
+            self.record(Change::BudgetSet {
+                category: category.to_string(),
+                limit: Some(limit),
+                previous,
+            });
+        }
+    }
+
+    /// Makes budgets limit spending per `period`, with weeks and months
+    /// starting where `calendar` says; calendar months by default.
+    pub fn set_budget_period(&mut self, period: BudgetPeriod, calendar: Calendar) {
+        self.budget_period = period;
+        self.calendar = calendar;
+    }
+
+    /// Sets the limits configured for each category, replacing every
+    /// budget set before, including those restored from the history. They
+    /// are configuration, so nothing is recorded.
+    pub fn set_budgets(&mut self, budgets: &BTreeMap<String, f64>) {
+        self.budgets = budgets
+            .iter()
+            .map(|(category, limit)| (category.to_lowercase(), *limit))
+            .collect();
+    }
+
+    pub fn remove_budget(&mut self, category: &str) {
+        if let Some(previous) = self.budget(category) {
+            self.record(Change::BudgetSet {
+                category: category.to_string(),
+                limit: None,
+                previous: Some(previous),
+            });
+        }
+    }
+
+    pub fn budget(&self, category: &str) -> Option<f64> {
+        self.budgets.get(&category.to_lowercase()).copied()
+    }
+
+    /// Takes the transactions of `other` while keeping this tracker's budgets,
+    /// opening balances, closed periods, hooks and history, e.g. after
+    /// reloading from a server.
+    pub fn replace_transactions(&mut self, other: FinanceTracker) {
+        let budgets = std::mem::take(&mut self.budgets);
+        let (budget_period, calendar) = (self.budget_period, self.calendar);
+        let opening_balance = self.opening_balance;
+        let opening_balances = std::mem::take(&mut self.opening_balances);
+        let closed = std::mem::take(&mut self.closed);
+        let hooks = std::mem::take(&mut self.hooks);
+        let history = std::mem::take(&mut self.history);
+        let portfolio = std::mem::take(&mut self.portfolio);
+        let loans = std::mem::take(&mut self.loans);
+        let projects = std::mem::take(&mut self.projects);
+        let user = self.user.take();
+        *self = FinanceTracker {
+            budgets,
+            budget_period,
+            calendar,
+            opening_balance,
+            opening_balances,
+            hooks,
+            history,
+            portfolio,
+            loans,
+            projects,
+            closed,
+            user,
+            ..other
+        };
+    }
+
+    /// Loads the archived history, for views of every change, and returns
+    /// how many entries were added.
+    pub fn load_history(&mut self) -> Result<usize> {
+        let history = self.archive.take_history(None)?;
+        let count = history.len();
+        self.history.splice(0..0, history);
+        Ok(count)
+    }
+
+    /// Every change made to this tracker, oldest first. After a partial
+    /// load only those since the first date loaded.
+    pub fn history(&self) -> &[Entry] {
+        &self.history
+    }
+
+    pub fn portfolio(&self) -> &Portfolio {
+        &self.portfolio
+    }
+
+    /// For buying, selling and pricing holdings. They are saved with the
+    /// transactions but, unlike them, not kept in the history.
+    pub fn portfolio_mut(&mut self) -> &mut Portfolio {
+        self.dirty = true;
+        &mut self.portfolio
+    }
+
+    pub fn with_portfolio(mut self, portfolio: Portfolio) -> Self {
+        self.portfolio = portfolio;
+        self
+    }
+
+    pub fn loans(&self) -> &[Loan] {
+        &self.loans
+    }
+
+    /// Like [`FinanceTracker::portfolio_mut`], loans are saved but not kept
+    /// in the history.
+    pub fn loans_mut(&mut self) -> &mut Vec<Loan> {
+        self.dirty = true;
+        &mut self.loans
+    }
+
+    pub fn with_loans(mut self, loans: Vec<Loan>) -> Self {
+        self.loans = loans;
+        self
+    }
+
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    /// For defining projects. Assigning transactions to one is an edit of
+    /// the transaction and kept in the history as usual.
+    pub fn projects_mut(&mut self) -> &mut Vec<Project> {
+        self.dirty = true;
+        &mut self.projects
+    }
+
+    pub fn with_projects(mut self, projects: Vec<Project>) -> Self {
+        self.projects = projects;
+        self
+    }
+
+    pub fn closed_periods(&self) -> &[ClosedPeriod] {
+        &self.closed
+    }
+
+    pub fn with_closed_periods(mut self, closed: Vec<ClosedPeriod>) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    /// The closed period `date` falls in, if any.
+    pub fn closed_period(&self, date: &str) -> Option<&ClosedPeriod> {
+        self.closed.iter().find(|closed| closed.covers(date))
+    }
+
+    fn check_open(&self, date: &str) -> Result<()> {
+        match self.closed_period(date) {
+            Some(closed) => Err(ExpensoError::Closed(closed.period.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether `existing` may become `updated`: neither may be dated in a
+    /// closed period and a reconciled transaction has to keep matching the
+    /// statement.
+    fn check_editable(&self, existing: &Transaction, updated: &Transaction) -> Result<()> {
+        self.check_open(&existing.date)?;
+        self.check_open(&updated.date)?;
+        if existing.reconciled
+            && (updated.amount != existing.amount
+                || updated.date != existing.date
+                || updated.transaction_type != existing.transaction_type
+                || updated.account != existing.account)
+        {
+            return Err(ExpensoError::Reconciled(existing.id));
+        }
+        Ok(())
+    }
+
+    fn check_removable(&self, existing: &Transaction) -> Result<()> {
+        if existing.reconciled {
+            return Err(ExpensoError::Reconciled(existing.id));
+        }
+        self.check_open(&existing.date)
+    }
+
+    /// Whether `change` passes the checks [`FinanceTracker::add`],
+    /// [`FinanceTracker::edit`] and [`FinanceTracker::remove`] make.
+    fn check_change(&self, change: &Change) -> Result<()> {
+        match change {
+            Change::TransactionAdded { transaction } => self.check_open(&transaction.date),
+            Change::TransactionEdited { after, .. } => match self.get_transaction(after.id) {
+                Some(existing) => self.check_editable(existing, after),
+                None => Err(ExpensoError::NotFound(after.id)),
+            },
+            Change::TransactionDeleted { transaction } => {
+                match self.get_transaction(transaction.id) {
+                    Some(existing) => self.check_removable(existing),
+                    None => Err(ExpensoError::NotFound(transaction.id)),
+                }
+            }
+            Change::BudgetSet { .. } => Ok(()),
+        }
+    }
+
+    /// Closes a month (`YYYY-MM`) or year (`YYYY`), so its transactions
+    /// can't be added to, edited or deleted until it is reopened, and keeps
+    /// each account's balance at its end. Closing a year takes the place of
+    /// its closed months.
+    pub fn close_period(&mut self, period: &str) -> Result<&ClosedPeriod> {
+        self.load_since(None)?;
+        let closed = ClosedPeriod::close(period, &self.transactions)?;
+        if let Some(covering) = self.closed.iter().find(|c| c.contains(&closed.period)) {
+            return Err(ExpensoError::InvalidInput(format!(
+                "{} is already closed",
+                covering.period
+            )));
+        }
+        self.closed.retain(|c| !closed.contains(&c.period));
+        self.closed.push(closed);
+        self.dirty = true;
+        Ok(&self.closed[self.closed.len() - 1])
+    }
+
+    /// Reopens a closed month or year for changes.
+    pub fn reopen_period(&mut self, period: &str) -> Result<ClosedPeriod> {
+        let period = period.trim();
+        if let Some(position) = self.closed.iter().position(|c| c.period == period) {
+            self.dirty = true;
+            return Ok(self.closed.remove(position));
+        }
+        Err(ExpensoError::InvalidInput(
+            match self.closed.iter().find(|c| c.contains(period)) {
+                Some(covering) => format!(
+                    "{} is part of {}, reopen that instead",
+                    period, covering.period
+                ),
+                None => format!("{} is not closed", period),
+            },
+        ))
+    }
+
+    /// Each account's balance from the transactions dated up to `to`, or
+    /// all of them. Starts from the balances of the latest closed period
+    /// that ends by then, so only the transactions after it are added up.
+    /// Opening balances are not included.
+    pub fn account_balances(&self, to: Option<&str>) -> BTreeMap<String, f64> {
+        let latest = self
+            .closed
+            .iter()
+            .map(|closed| (closed.last_day(), closed))
+            .filter(|(last, _)| to.is_none_or(|to| last.as_str() <= to))
+            .max_by(|(a, _), (b, _)| a.cmp(b));
+        match latest {
+            Some((last, closed)) => periods::balances(
+                self.in_date_range(Some(&last), to)
+                    .filter(|t| t.date > last),
+                closed.balances.clone(),
+            ),
+            None => periods::balances(self.in_date_range(None, to), BTreeMap::new()),
+        }
+    }
+
+    /// Keeps the balances of the periods closed after `transaction`'s date
+    /// in line when it is added, with `sign` 1, or taken away, with -1.
+    fn adjust_closing_balances(&mut self, transaction: &Transaction, sign: f64) {
+        for closed in &mut self.closed {
+            if transaction.date > closed.last_day() {
+                continue;
+            }
+            let amount = sign * transaction.signed_amount();
+            match closed
+                .balances
+                .iter_mut()
+                .find(|(account, _)| account.eq_ignore_ascii_case(&transaction.account))
+            {
+                Some((_, balance)) => *balance = money::sum([*balance, amount]),
+                None => {
+                    closed
+                        .balances
+                        .insert(transaction.account.clone(), money::round(amount));
+                }
+            }
+        }
+    }
+
+    /// The changes made to one transaction, oldest first.
+    pub fn history_of(&self, id: u32) -> impl Iterator<Item = &Entry> {
+        self.history
+            .iter()
+            .filter(move |entry| entry.change.transaction_id() == Some(id))
+    }
+
+    /// The ids of the transactions not stored as replaying the history
+    /// leaves them, because they were changed outside expenso. Transactions
+    /// from before the history started and ids stored twice, which
+    /// [`check`](crate::check) repairs, are not compared, nor is anything
+    /// while older transactions are still archived.
+    pub fn history_mismatches(&self) -> Vec<u32> {
+        self.history_differences()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Records what was changed outside expenso, so the history leads to the
+    /// stored transactions again, and returns the ids of those changed.
+    pub fn rebaseline_history(&mut self) -> Vec<u32> {
+        let differences = self.history_differences();
+        let mut ids = Vec::with_capacity(differences.len());
+        for (id, change) in differences {
+            self.history.push(Entry {
+                by: self.user.clone(),
+                ..Entry::now(change)
+            });
+            self.dirty = true;
+            ids.push(id);
+        }
+        ids
+    }
+
+    /// Each mismatched id with the change from what the history last
+    /// recorded for it to what is stored.
+    fn history_differences(&self) -> Vec<(u32, Change)> {
+        if self.loaded_since().is_some() {
+            return Vec::new();
+        }
+        let mut recorded: BTreeMap<u32, Option<&Transaction>> = BTreeMap::new();
+        for entry in &self.history {
+            match &entry.change {
+                Change::TransactionAdded { transaction } => {
+                    recorded.insert(transaction.id, Some(transaction))
+                }
+                Change::TransactionEdited { after, .. } => recorded.insert(after.id, Some(after)),
+                Change::TransactionDeleted { transaction } => recorded.insert(transaction.id, None),
+                Change::BudgetSet { .. } => None,
+            };
+        }
+        let mut stored: HashMap<u32, Vec<&Transaction>> = HashMap::new();
+        for transaction in &self.transactions {
+            stored.entry(transaction.id).or_default().push(transaction);
+        }
+        recorded
+            .into_iter()
+            .filter_map(|(id, recorded)| {
+                let change = match (recorded, stored.get(&id).map(Vec::as_slice)) {
+                    (Some(transaction), None) => Change::TransactionDeleted {
+                        transaction: Box::new(transaction.clone()),
+                    },
+                    (None, Some([transaction])) => Change::TransactionAdded {
+                        transaction: Box::new(Transaction::clone(transaction)),
+                    },
+                    (Some(before), Some([after])) if before != *after => {
+                        Change::TransactionEdited {
+                            before: Box::new(before.clone()),
+                            after: Box::new(Transaction::clone(after)),
+                        }
+                    }
+                    _ => return None,
+                };
+                Some((id, change))
+            })
+            .collect()
+    }
+
+    /// Who the changes made from now on are recorded as made by.
+    pub fn set_user(&mut self, user: Option<String>) {
+        self.user = user;
+    }
+
+    /// Reverts the most recent change not undone yet. The revert is
+    /// recorded as a change of its own, so the history keeps both. Like any
+    /// other change, reverting can't touch a closed period or take a
+    /// reconciled transaction off its statement.
+    pub fn undo(&mut self) -> Result<Option<Change>> {
+        if self.undoable().is_none() && self.archive.has_history() {
+            self.load_since(None)?;
+        }
+        let Some(change) = self.undoable().map(|entry| entry.change.clone()) else {
+            return Ok(None);
+        };
+        let inverse = change.inverse();
+        if self.loaded_since().is_some() && !self.is_loaded(&inverse) {
+            self.load_since(None)?;
+        }
+        self.check_change(&inverse)?;
+        self.apply(&inverse);
+        self.history.push(Entry {
+            by: self.user.clone(),
+            undo: true,
+            ..Entry::now(inverse)
+        });
+        Ok(Some(change))
+    }
+
+    /// Whether the transaction `change` edits or deletes is loaded.
+    fn is_loaded(&self, change: &Change) -> bool {
+        match change {
+            Change::TransactionEdited {
+                after: transaction, ..
+            }
+            | Change::TransactionDeleted { transaction } => {
+                self.get_transaction(transaction.id).is_some()
+            }
+            Change::TransactionAdded { .. } | Change::BudgetSet { .. } => true,
+        }
+    }
+
+    /// The latest entry that is not an undo and wasn't undone.
+    fn undoable(&self) -> Option<&Entry> {
+        let mut undone = 0;
+        for entry in self.history.iter().rev() {
+            if entry.undo {
+                undone += 1;
+            } else if undone > 0 {
+                undone -= 1;
+            } else {
+                return Some(entry);
+            }
+        }
+        None
+    }
+
+    pub fn next_id(&self) -> u32 {
+        self.next_id
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn mark_saved(&mut self) {
+        self.dirty = false;
+    }
+
+    /// The first and last day of the budget period `date` falls in.
+    fn budget_period_of(&self, date: &str) -> Option<(NaiveDate, NaiveDate)> {
+        let date = NaiveDate::parse_from_str(date, dates::ISO_FORMAT).ok()?;
+        Some(self.calendar.period(self.budget_period, date))
+    }
+
+    /// What was already spent in the transaction's budget category and period.
+    fn budget_spending(&self, transaction: &Transaction) -> f64 {
+        if transaction.transaction_type != TransactionType::Expense
+            || self.budget(&transaction.category).is_none()
+        {
+            return 0.0;
+        }
+        let Some((first, last)) = self.budget_period_of(&transaction.date) else {
+            return 0.0;
+        };
+        let (first, last) = (
+            first.format(dates::ISO_FORMAT).to_string(),
+            last.format(dates::ISO_FORMAT).to_string(),
+        );
+        self.in_date_range(Some(&first), Some(&last))
+            .filter(|t| {
+                t.id != transaction.id
+                    && t.transaction_type == TransactionType::Expense
+                    && t.category.eq_ignore_ascii_case(&transaction.category)
+            })
+            .map(|t| t.amount)
+            .fold(0.0, |spent, amount| money::sum([spent, amount]))
+    }
+
+    fn check_budget(&self, transaction: &Transaction, spent_before: f64) {
+        let Some(limit) = self.budget(&transaction.category) else {
+            return;
+        };
+        if transaction.transaction_type != TransactionType::Expense {
+            return;
+        }
+        let Some((first, last)) = self.budget_period_of(&transaction.date) else {
+            return;
+        };
+        let spent = money::sum([spent_before, transaction.amount]);
+        if spent > limit && spent_before <= limit {
+            self.hooks.emit(Event::BudgetExceeded {
+                category: transaction.category.to_string(),
+                period: dates::period_label(first, last),
+                limit,
+                spent,
+            });
+        }
+    }
+
+    fn record(&mut self, change: Change) {
+        self.apply(&change);
+        self.history.push(Entry {
+            by: self.user.clone(),
+            ..Entry::now(change)
+        });
+    }
+
+    /// Brings the derived state in line with `change` and notifies the hooks.
+    /// This is the only place transactions and budgets are modified.
+    fn apply(&mut self, change: &Change) {
+        match change {
+            Change::TransactionAdded { transaction } => {
+                let transaction = Transaction::clone(transaction);
+                let spent_before = self.budget_spending(&transaction);
+                let index = self.transactions.partition_point(|t| t.id < transaction.id);
+                self.transactions.insert(index, transaction.clone());
+                if index + 1 == self.transactions.len() {
+                    self.by_date.push(transaction.date.clone(), index);
+                    self.by_category
+                        .push(transaction.category.to_lowercase(), index);
+                    for word in transaction.search_words() {
+                        self.by_word.push(word, index);
+                    }
+                } else {
+                    self.reindex();
+                }
+                self.add_to_totals(&transaction);
+                self.adjust_closing_balances(&transaction, 1.0);
+                self.next_id = self.next_id.max(transaction.id + 1);
+                self.hooks
+                    .emit(Event::TransactionAdded(transaction.clone()));
+                self.check_budget(&transaction, spent_before);
+            }
+            Change::TransactionEdited { after, .. } => {
+                let updated = Transaction::clone(after);
+                let Some(index) = self.transactions.iter().position(|t| t.id == updated.id) else {
+                    return;
+                };
+                let spent_before = self.budget_spending(&updated);
+                let old = std::mem::replace(&mut self.transactions[index], updated.clone());
+                if old.date != updated.date {
+                    self.by_date.remove(&old.date, index);
+                    self.by_date.insert(updated.date.clone(), index);
+                }
+                let (old_key, new_key) =
+                    (old.category.to_lowercase(), updated.category.to_lowercase());
+                if old_key != new_key {
+                    self.by_category.remove(&old_key, index);
+                    self.by_category.insert(new_key, index);
+                }
+                let (old_words, new_words) = (old.search_words(), updated.search_words());
+                for word in old_words.difference(&new_words) {
+                    self.by_word.remove(word, index);
+                }
+                for word in new_words.difference(&old_words) {
+                    self.by_word.insert(word.clone(), index);
+                }
+                self.remove_from_totals(&old);
+                self.add_to_totals(&updated);
+                self.adjust_closing_balances(&old, -1.0);
+                self.adjust_closing_balances(&updated, 1.0);
+                self.hooks.emit(Event::TransactionUpdated {
+                    before: Box::new(old),
+                    after: Box::new(updated.clone()),
+                });
+                self.check_budget(&updated, spent_before);
+            }
+            Change::TransactionDeleted { transaction } => {
+                let Some(index) = self
+                    .transactions
+                    .iter()
+                    .position(|t| t.id == transaction.id)
+                else {
+                    return;
+                };
+                let removed = self.transactions.remove(index);
+                self.reindex();
+                self.remove_from_totals(&removed);
+                self.adjust_closing_balances(&removed, -1.0);
+                self.hooks.emit(Event::TransactionDeleted(removed));
+            }
+            Change::BudgetSet {
+                category, limit, ..
+            } => self.apply_budget(category, *limit),
+        }
+        self.dirty = true;
+    }
+
+    fn apply_budget(&mut self, category: &str, limit: Option<f64>) {
+        match limit {
+            Some(limit) => self.budgets.insert(category.to_lowercase(), limit),
+            None => self.budgets.remove(&category.to_lowercase()),
+        };
+    }
+
+    /// Gives every transaction sharing an id with an earlier one a fresh id,
+    /// returning the `(old, new)` pairs. Each copy is recorded as added
+    /// under its new id; whether the one kept at the old id is what the
+    /// history says is left to [`FinanceTracker::rebaseline_history`].
+    pub(crate) fn renumber_duplicates(&mut self) -> Vec<(u32, u32)> {
+        let mut seen = HashSet::new();
+        let mut renumbered = Vec::new();
+        let mut added = Vec::new();
+        for transaction in &mut self.transactions {
+            if !seen.insert(transaction.id) {
+                renumbered.push((transaction.id, self.next_id));
+                transaction.id = self.next_id;
+                self.next_id += 1;
+                added.push(Change::TransactionAdded {
+                    transaction: Box::new(transaction.clone()),
+                });
+            }
+        }
+        for change in added {
+            self.history.push(Entry {
+                by: self.user.clone(),
+                ..Entry::now(change)
+            });
+        }
+        if !renumbered.is_empty() {
+            self.transactions.sort_by_key(|t| t.id);
+            self.reindex();
+            self.dirty = true;
+        }
+        renumbered
+    }
+
+    fn reindex(&mut self) {
+        self.by_date.clear();
+        self.by_category.clear();
+        self.by_word.clear();
+        for (position, transaction) in self.transactions.iter().enumerate() {
+            self.by_date.push(transaction.date.clone(), position);
+            self.by_category
+                .push(transaction.category.to_lowercase(), position);
+            for word in transaction.search_words() {
+                self.by_word.push(word, position);
+            }
+        }
+    }
+
+    fn add_to_totals(&mut self, transaction: &Transaction) {
+        self.adjust_totals(transaction, transaction.amount);
+        *self
+            .category_counts
+            .entry(transaction.category)
+            .or_default() += 1;
+        self.unique_categories.insert(transaction.category);
+        if transaction.transaction_type == TransactionType::Settlement {
+            self.settlements += 1;
+        }
+    }
+
+    fn remove_from_totals(&mut self, transaction: &Transaction) {
+        self.adjust_totals(transaction, -transaction.amount);
+        let count = self
+            .category_counts
+            .entry(transaction.category)
+            .or_default();
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            self.category_counts.remove(&transaction.category);
+            self.category_totals.remove(&transaction.category);
+            self.unique_categories.remove(&transaction.category);
+        }
+        let month = month_of(transaction);
+        if self
+            .by_date
+            .with_prefix(&format!("{}-", month))
+            .next()
+            .is_none()
+        {
+            self.month_totals.remove(&month);
+        }
+        if transaction.transaction_type == TransactionType::Settlement {
+            self.settlements -= 1;
+        }
+    }
+
+    /// Adds `amount` to the totals `transaction` counts towards, in minor
+    /// units so adding and taking away the same amounts leaves no drift.
+    fn adjust_totals(&mut self, transaction: &Transaction, amount: f64) {
+        let add = |total: &mut f64| *total = money::sum([*total, amount]);
+        add(self
+            .category_totals
+            .entry(transaction.category)
+            .or_default());
+        let month = self.month_totals.entry(month_of(transaction)).or_default();
+        match transaction.transaction_type {
+            TransactionType::Income => {
+                add(&mut self.income_total);
+                add(&mut month.0);
+            }
+            TransactionType::Expense => {
+                add(&mut self.expense_total);
+                add(&mut month.1);
+            }
+            TransactionType::Settlement => {}
+        }
+    }
+}
+
+/// What tells a transaction apart when merging: everything but the id and
+/// when it was added or edited.
+fn content_key(transaction: &Transaction) -> String {
+    let content = Transaction {
+        id: 0,
+        created_at: None,
+        updated_at: None,
+        ..transaction.clone()
+    };
+    serde_json::to_string(&content).unwrap_or_default()
+}
+
+/// Whether `b`, under the id `a` had, is `a` or `a` edited elsewhere.
+fn same_transaction(a: &Transaction, b: &Transaction) -> bool {
+    content_key(a) == content_key(b) || (a.created_at.is_some() && a.created_at == b.created_at)
+}
+
+/// Whether `a` and `b` are one transaction edited differently in two copies
+/// of the data, rather than two added separately under the same id.
+fn edited_apart(a: &Transaction, b: &Transaction) -> bool {
+    a.created_at.is_some()
+        && a.created_at == b.created_at
+        && (a.updated_at != a.created_at || b.updated_at != b.created_at)
+}
+
+fn month_of(transaction: &Transaction) -> String {
+    transaction
+        .date
+        .get(..7)
+        .unwrap_or(&transaction.date)
+        .to_string()
+}
+
+fn sort(transactions: &mut [&Transaction], key: SortKey, order: SortOrder) {
+    transactions.sort_by(|a, b| {
+        let ordering = match key {
+            SortKey::Id => a.id.cmp(&b.id),
+            SortKey::Date => a.date.cmp(&b.date),
+            SortKey::Amount => a.amount.total_cmp(&b.amount),
+            SortKey::Category => a.category.to_lowercase().cmp(&b.category.to_lowercase()),
+        };
+        match order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    fn create_test_tracker() -> FinanceTracker {
+        let mut tracker = FinanceTracker::new();
+
+        tracker.add_transaction(
+            String::from("Salary"),
+            5000.0,
+            true,
+            String::from("2025-01-04"),
+            TransactionType::Income,
+            String::from("Work"),
+        );
+
+        tracker.add_transaction(
+            String::from("Freelance"),
+            1500.0,
+            false,
+            String::from("2024-01-20"),
+            TransactionType::Income,
+            String::from("Work"),
+        );
+
+        tracker.add_transaction(
+            String::from("Rent"),
+            2000.0,
+            true,
+            String::from("2024-01-01"),
+            TransactionType::Expense,
+            String::from("Housing"),
+        );
+
+        tracker.add_transaction(
+            String::from("Groceries"),
+            500.0,
+            false,
+            String::from("2024-01-10"),
+            TransactionType::Expense,
+            String::from("Food"),
+        );
+
+        tracker
+    }
+
+    #[test]
+    fn test_total_income() {
+        let tracker = create_test_tracker();
+        assert_eq!(tracker.total_income(), 6500.0);
+    }
+
+    #[test]
+    fn test_total_expense() {
+        let tracker = create_test_tracker();
+        assert_eq!(tracker.total_expense(), 2500.0)
+    }
+
+    #[test]
+    fn test_net_balance() {
+        let tracker = create_test_tracker();
+        assert_eq!(tracker.net_balance(), 4000.0);
+    }
+
+    #[test]
+    fn test_average_transaction() {
+        let tracker = create_test_tracker();
+        assert_eq!(tracker.average_transaction(), 2250.0);
+    }
+
+    #[test]
+    fn test_average_empty() {
+        let tracker = FinanceTracker::new();
+        assert_eq!(tracker.average_transaction(), 0.0)
+    }
+
+    #[test]
+    fn test_category_totals() {
+        let tracker = create_test_tracker();
+        let breakdown = tracker.category_breakdown();
+
+        assert_eq!(breakdown.get(&Symbol::new("Work")), Some(&6500.0));
+        assert_eq!(breakdown.get(&Symbol::new("Housing")), Some(&2000.0));
+        assert_eq!(breakdown.get(&Symbol::new("Food")), Some(&500.0));
+    }
+
+    #[test]
+    fn test_update_transaction_moves_category_total() {
+        let mut tracker = create_test_tracker();
+        let mut groceries = tracker.get_transactions()[3].clone();
+        groceries.amount = 650.0;
+        groceries.category = Symbol::new("Household");
+
+        assert!(tracker.update_transaction(groceries));
+
+        let breakdown = tracker.category_breakdown();
+        assert_eq!(breakdown.get(&Symbol::new("Food")), None);
+        assert_eq!(breakdown.get(&Symbol::new("Household")), Some(&650.0));
+        assert_eq!(tracker.total_expense(), 2650.0);
+        assert!(!tracker.unique_categories.contains(&Symbol::new("Food")));
+    }
+
+    #[test]
+    fn test_running_totals_do_not_drift() {
+        let mut tracker = create_test_tracker();
+        let ids: Vec<u32> = (0..10)
+            .map(|_| {
+                tracker.insert(NewTransaction {
+                    description: String::from("Gum"),
+                    amount: 0.1,
+                    date: String::from("2024-02-03"),
+                    category: String::from("Food"),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        assert_eq!(tracker.category_breakdown()[&Symbol::new("Food")], 501.0);
+        assert_eq!(tracker.month_totals()["2024-02"], (0.0, 1.0));
+
+        for id in &ids[1..] {
+            assert!(tracker.delete_transaction(*id).is_some());
+        }
+        assert_eq!(tracker.category_breakdown()[&Symbol::new("Food")], 500.1);
+        assert_eq!(tracker.month_totals()["2024-02"], (0.0, 0.1));
+        assert_eq!(tracker.total_expense(), 2500.1);
+
+        assert!(tracker.delete_transaction(ids[0]).is_some());
+        assert!(!tracker.month_totals().contains_key("2024-02"));
+        assert_eq!(tracker.category_breakdown()[&Symbol::new("Food")], 500.0);
+    }
+
+    #[test]
+    fn test_update_unknown_transaction() {
+        let mut tracker = create_test_tracker();
+        let mut ghost = tracker.get_transactions()[0].clone();
+        ghost.id = 99;
+        assert!(!tracker.update_transaction(ghost));
+    }
+
+    #[test]
+    fn test_sorted_transactions() {
+        let tracker = create_test_tracker();
+
+        let by_date: Vec<u32> = tracker
+            .sorted_transactions(SortKey::Date, SortOrder::Ascending)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(by_date, vec![3, 4, 2, 1]);
+
+        let by_amount_desc: Vec<u32> = tracker
+            .sorted_transactions(SortKey::Amount, SortOrder::Descending)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(by_amount_desc, vec![1, 3, 2, 4]);
+    }
+
+    #[test]
+    fn test_from_transactions_rebuilds_totals() {
+        let original = create_test_tracker();
+        let tracker = FinanceTracker::from_transactions(original.get_transactions().clone());
+
+        assert_eq!(
+            tracker.category_breakdown().get(&Symbol::new("Work")),
+            Some(&6500.0)
+        );
+        assert_eq!(tracker.next_id, 5);
+        assert_eq!(tracker.unique_categories.len(), 3);
+        assert!(!tracker.is_dirty());
+    }
+
+    #[test]
+    fn test_changes_mark_tracker_dirty() {
+        let mut tracker = FinanceTracker::new();
+        assert!(!tracker.is_dirty());
+
+        tracker.insert(NewTransaction::default());
+        assert!(tracker.is_dirty());
+
+        tracker.mark_saved();
+        assert!(tracker.delete_transaction(99).is_none());
+        assert!(!tracker.is_dirty());
+        tracker.delete_transaction(1);
+        assert!(tracker.is_dirty());
+    }
+
+    #[test]
+    fn test_search_is_case_insensitive() {
+        let mut tracker = create_test_tracker();
+        tracker.insert(NewTransaction {
+            description: String::from("Coffee"),
+            amount: 4.5,
+            date: String::from("2024-01-12"),
+            category: String::from("Food"),
+            payee: Some(String::from("Starbucks")),
+            notes: Some(String::from("Meeting with the landlord")),
+            ..Default::default()
+        });
+
+        let ids: Vec<u32> = tracker.search("GROCER").iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![4]);
+
+        let ids: Vec<u32> = tracker.search("starbucks").iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![5]);
+
+        let ids: Vec<u32> = tracker.search("landlord").iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![5]);
+
+        let ids: Vec<u32> = tracker.search("food").iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+
+        assert!(tracker.search("netflix").is_empty());
+    }
+
+    #[test]
+    fn test_search_index_follows_changes() {
+        let mut tracker = create_test_tracker();
+        let ids = |tracker: &FinanceTracker, query: &str| -> Vec<u32> {
+            tracker.search(query).iter().map(|t| t.id).collect()
+        };
+        assert_eq!(ids(&tracker, "ocer"), vec![4]);
+        assert_eq!(ids(&tracker, "at work"), Vec::<u32>::new());
+
+        let mut groceries = tracker.get_transaction(4).unwrap().clone();
+        groceries.description = String::from("Farmers market");
+        groceries.notes = Some(String::from("Eggs, bread"));
+        assert!(tracker.update_transaction(groceries));
+        assert!(ids(&tracker, "groceries").is_empty());
+        assert_eq!(ids(&tracker, "eggs, bread"), vec![4]);
+
+        tracker.delete_transaction(1);
+        assert_eq!(ids(&tracker, "work"), vec![2]);
+        assert_eq!(ids(&tracker, "market"), vec![4]);
+    }
+
+    #[test]
+    fn test_opening_balances() {
+        let mut tracker = create_test_tracker();
+        tracker.set_opening_balances(
+            250.0,
+            &BTreeMap::from([
+                (String::from("Checking"), 1520.33),
+                (String::from("Cash"), 40.0),
+            ]),
+        );
+        assert_eq!(tracker.net_balance(), 5810.33);
+        assert_eq!(tracker.total_income(), 6500.0);
+        assert_eq!(tracker.account_opening_balance("checking"), 1520.33);
+        assert_eq!(tracker.account_opening_balance("Savings"), 0.0);
+    }
+
+    #[test]
+    fn test_replace_transactions_keeps_opening_balances() {
+        let mut tracker = create_test_tracker();
+        let accounts = BTreeMap::from([(String::from("Checking"), 1520.33)]);
+        tracker.set_opening_balances(250.0, &accounts);
+
+        let mut reloaded = tracker.get_transactions().clone();
+        reloaded.retain(|t| t.id != 4);
+        tracker.replace_transactions(FinanceTracker::from_transactions(reloaded));
+        assert_eq!(tracker.opening_balances(), (250.0, &accounts));
+        assert_eq!(tracker.net_balance(), 6270.33);
+    }
+
+    #[test]
+    fn test_closed_periods_lock_transactions_and_keep_balances() {
+        let mut tracker = create_test_tracker();
+        let closed = tracker.close_period("2024-01").unwrap();
+        assert_eq!(
+            closed.balances,
+            BTreeMap::from([(String::from("Cash"), -1000.0)])
+        );
+
+        let reloaded = FinanceTracker::from_transactions(tracker.get_transactions().clone());
+        tracker.replace_transactions(reloaded);
+        assert_eq!(tracker.closed_periods().len(), 1);
+
+        let rent = NewTransaction::from(tracker.get_transaction(3).unwrap().clone());
+        assert!(matches!(
+            tracker.edit(3, rent.clone()),
+            Err(ExpensoError::Closed(period)) if period == "2024-01"
+        ));
+        assert!(tracker.remove(3).is_err());
+        let moved = NewTransaction {
+            date: String::from("2024-01-15"),
+            ..NewTransaction::from(tracker.get_transaction(1).unwrap().clone())
+        };
+        assert!(tracker.edit(1, moved.clone()).is_err());
+        assert!(tracker.add(moved.clone()).is_err());
+        assert_eq!(tracker.add_transactions(vec![moved]).invalid.len(), 1);
+        assert!(tracker.recategorize("Food", "Dining").is_err());
+        let project = Transaction {
+            project: Some(String::from("Move")),
+            ..tracker.get_transaction(4).unwrap().clone()
+        };
+        assert!(tracker.update(project).is_err());
+        assert_eq!(tracker.get_transaction(4).unwrap().category, "Food");
+
+        tracker
+            .add(NewTransaction {
+                description: String::from("Bank fee"),
+                amount: 100.0,
+                date: String::from("2023-12-31"),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(tracker.closed_periods()[0].balances["Cash"], -1100.0);
+        assert_eq!(
+            tracker.account_balances(Some("2024-06-30"))["Cash"],
+            -1100.0
+        );
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        assert_eq!(
+            tracker.account_balances(None),
+            periods::balances(all, BTreeMap::new())
+        );
+
+        tracker.close_period("2024").unwrap();
+        assert_eq!(tracker.closed_periods().len(), 1);
+        assert!(tracker.close_period("2024-03").is_err());
+        assert!(tracker.reopen_period("2024-01").is_err());
+        tracker.reopen_period("2024").unwrap();
+        assert!(tracker.edit(3, rent).is_ok());
+    }
+
+    #[test]
+    fn test_last_transaction() {
+        let tracker = create_test_tracker();
+        assert_eq!(tracker.last_transaction().unwrap().description, "Groceries");
+        assert!(FinanceTracker::new().last_transaction().is_none());
+    }
+
+    #[test]
+    fn test_past_category_by_payee_then_description() {
+        let mut tracker = create_test_tracker();
+        tracker.insert(NewTransaction {
+            description: String::from("Monthly plan"),
+            amount: 15.0,
+            category: String::from("Entertainment"),
+            payee: Some(String::from("Netflix")),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            tracker.past_category("Something else", Some("netflix")),
+            Some(Symbol::new("Entertainment"))
+        );
+        assert_eq!(
+            tracker.past_category("groceries", Some("Unknown")),
+            Some(Symbol::new("Food"))
+        );
+        assert_eq!(tracker.past_category("Dinner", None), None);
+    }
+
+    #[test]
+    fn test_categories_are_sorted() {
+        let tracker = create_test_tracker();
+        assert_eq!(tracker.categories(), vec!["Food", "Housing", "Work"]);
+    }
+
+    #[test]
+    fn test_delete_transaction_updates_totals() {
+        let mut tracker = create_test_tracker();
+
+        let removed = tracker.delete_transaction(4).unwrap();
+        assert_eq!(removed.description, "Groceries");
+        assert_eq!(tracker.total_expense(), 2000.0);
+        assert_eq!(tracker.category_breakdown().get(&Symbol::new("Food")), None);
+        assert!(tracker.delete_transaction(4).is_none());
+    }
+
+    #[test]
+    fn test_recategorize() {
+        let mut tracker = create_test_tracker();
+
+        assert_eq!(tracker.recategorize("work", "Salary").unwrap(), vec![1, 2]);
+        assert_eq!(
+            tracker.category_breakdown().get(&Symbol::new("Salary")),
+            Some(&6500.0)
+        );
+        assert_eq!(tracker.category_breakdown().get(&Symbol::new("Work")), None);
+        assert!(tracker.recategorize("Travel", "Trips").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_hooks_see_mutations() {
+        let mut tracker = create_test_tracker();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&events);
+        tracker.subscribe(move |event| seen.lock().unwrap().push(event.clone()));
+
+        let id = tracker.insert(NewTransaction {
+            description: String::from("Coffee"),
+            amount: 4.5,
+            date: String::from("2025-01-10"),
+            category: String::from("Food"),
+            ..Default::default()
+        });
+        let mut coffee = tracker.get_transaction(id).unwrap().clone();
+        coffee.amount = 5.0;
+        tracker.update_transaction(coffee);
+        tracker.delete_transaction(id);
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(matches!(&events[0], Event::TransactionAdded(t) if t.id == id));
+        assert!(
+            matches!(&events[1], Event::TransactionUpdated { before, after } if before.amount == 4.5 && after.amount == 5.0)
+        );
+        assert!(matches!(&events[2], Event::TransactionDeleted(t) if t.id == id));
+    }
+
+    #[test]
+    fn test_configured_budgets_replace_earlier_ones() {
+        let mut tracker = create_test_tracker();
+        tracker.set_budget("Food", 10.0);
+        let mut reloaded = FinanceTracker::from_transactions(tracker.get_transactions().clone())
+            .with_history(tracker.history().to_vec());
+        assert_eq!(reloaded.budget("food"), Some(10.0));
+
+        let history = reloaded.history().len();
+        reloaded.set_budgets(&BTreeMap::from([(String::from("Rent"), 1500.0)]));
+        assert_eq!(reloaded.budget("Food"), None);
+        assert_eq!(reloaded.budget("rent"), Some(1500.0));
+        assert_eq!(reloaded.history().len(), history);
+    }
+
+    #[test]
+    fn test_budget_exceeded_fires_once_per_crossing() {
+        let mut tracker = FinanceTracker::new();
+        tracker.set_budget("food", 100.0);
+        let exceeded = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&exceeded);
+        tracker.subscribe(move |event| {
+            if let Event::BudgetExceeded { period, spent, .. } = event {
+                seen.lock().unwrap().push((period.clone(), *spent));
+            }
+        });
+
+        for (amount, date) in [
+            (60.0, "2025-02-01"),
+            (50.0, "2025-02-10"),
+            (20.0, "2025-02-11"),
+            (90.0, "2025-03-01"),
+            // Exactly at the limit, though the floats add up to 100.00000000000001.
+            (64.01, "2025-04-01"),
+            (0.04, "2025-04-02"),
+            (35.95, "2025-04-03"),
+        ] {
+            tracker.insert(NewTransaction {
+                amount,
+                date: String::from(date),
+                category: String::from("Food"),
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(
+            *exceeded.lock().unwrap(),
+            vec![(String::from("2025-02"), 110.0)]
+        );
+    }
+
+    #[test]
+    fn test_weekly_budget_follows_week_start() {
+        let mut tracker = FinanceTracker::new();
+        tracker.set_budget_period(
+            BudgetPeriod::Week,
+            Calendar {
+                week_start: chrono::Weekday::Sun,
+                month_start: 1,
+            },
+        );
+        tracker.set_budget("food", 100.0);
+        let exceeded = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&exceeded);
+        tracker.subscribe(move |event| {
+            if let Event::BudgetExceeded { period, spent, .. } = event {
+                seen.lock().unwrap().push((period.clone(), *spent));
+            }
+        });
+
+        // A Saturday, then the Sunday starting the next week.
+        for (amount, date) in [
+            (60.0, "2025-02-01"),
+            (50.0, "2025-02-02"),
+            (60.0, "2025-02-08"),
+        ] {
+            tracker.insert(NewTransaction {
+                amount,
+                date: String::from(date),
+                category: String::from("Food"),
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(
+            *exceeded.lock().unwrap(),
+            vec![(String::from("2025-02-02 to 2025-02-08"), 110.0)]
+        );
+    }
+
+    #[test]
+    fn test_undo_reverts_changes() {
+        let mut tracker = create_test_tracker();
+        let original = tracker.get_transactions().clone();
+
+        tracker.delete_transaction(2);
+        tracker.recategorize("Food", "Groceries").unwrap();
+        tracker.set_budget("Food", 300.0);
+        assert_eq!(tracker.history().len(), 7);
+
+        tracker.set_user(Some(String::from("sam")));
+        assert!(matches!(tracker.undo(), Ok(Some(Change::BudgetSet { .. }))));
+        assert_eq!(tracker.budget("Food"), None);
+        tracker.undo().unwrap();
+        tracker.undo().unwrap();
+        assert_eq!(*tracker.get_transactions(), original);
+        assert_eq!(
+            tracker.category_breakdown().get(&Symbol::new("Food")),
+            Some(&500.0)
+        );
+
+        let history = tracker.history();
+        assert_eq!(history.len(), 10);
+        assert!(history[..7].iter().all(|entry| !entry.undo));
+        assert!(
+            history[7..]
+                .iter()
+                .all(|entry| entry.undo && entry.by.as_deref() == Some("sam"))
+        );
+        assert_eq!(
+            history[9].to_string(),
+            "undo: added #2 Freelance ($1,500.00)"
+        );
+
+        assert!(matches!(
+            tracker.undo(),
+            Ok(Some(Change::TransactionAdded { transaction })) if transaction.id == 4
+        ));
+        let replayed = FinanceTracker::replay(tracker.history().to_vec());
+        assert_eq!(replayed.get_transactions(), tracker.get_transactions());
+    }
+
+    #[test]
+    fn test_undo_respects_closed_periods_and_reconciled() {
+        let mut tracker = create_test_tracker();
+        tracker.close_period("2024-01").unwrap();
+        let balances = tracker.closed_periods()[0].balances.clone();
+        assert!(matches!(tracker.undo(), Err(ExpensoError::Closed(period)) if period == "2024-01"));
+        assert_eq!(tracker.get_transactions().len(), 4);
+        assert_eq!(tracker.closed_periods()[0].balances, balances);
+        assert_eq!(tracker.history().len(), 4);
+
+        tracker.set_reconciled(1, true).unwrap();
+        let mut salary = NewTransaction::from(tracker.get_transaction(1).unwrap().clone());
+        salary.notes = Some(String::from("January"));
+        tracker.edit(1, salary).unwrap();
+        tracker.undo().unwrap();
+        assert_eq!(tracker.get_transaction(1).unwrap().notes, None);
+        tracker.undo().unwrap();
+        assert!(!tracker.get_transaction(1).unwrap().reconciled);
+
+        let before = tracker.get_transaction(1).unwrap().clone();
+        let after = Transaction {
+            amount: 5200.0,
+            reconciled: true,
+            ..before.clone()
+        };
+        let mut locked =
+            FinanceTracker::from_transactions(vec![after.clone()]).with_history(vec![Entry::now(
+                Change::TransactionEdited {
+                    before: Box::new(before),
+                    after: Box::new(after),
+                },
+            )]);
+        assert!(matches!(locked.undo(), Err(ExpensoError::Reconciled(1))));
+    }
+
+    #[test]
+    fn test_replay_rebuilds_state() {
+        let mut tracker = create_test_tracker();
+        tracker.delete_transaction(1);
+        tracker.set_budget("Food", 300.0);
+
+        let replayed = FinanceTracker::replay(tracker.history().to_vec());
+        assert_eq!(replayed.get_transactions(), tracker.get_transactions());
+        assert_eq!(replayed.category_breakdown(), tracker.category_breakdown());
+        assert_eq!(replayed.budget("food"), Some(300.0));
+        assert_eq!(replayed.next_id(), 5);
+        assert!(!replayed.is_dirty());
+    }
+
+    #[test]
+    fn test_history_of_records_who_changed_what() {
+        let mut tracker = create_test_tracker();
+        tracker.set_user(Some(String::from("sam")));
+        let mut rent = tracker.get_transaction(3).unwrap().clone();
+        rent.amount = 2100.0;
+        rent.notes = Some(String::from("new lease"));
+        tracker.update_transaction(rent);
+        tracker.delete_transaction(4);
+
+        let history: Vec<&Entry> = tracker.history_of(3).collect();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].by, None);
+        assert_eq!(history[1].by.as_deref(), Some("sam"));
+        assert_eq!(
+            history[1].describe(),
+            vec!["amount: $2,000.00 -> $2,100.00", "notes: none -> new lease"]
+        );
+    }
+
+    #[test]
+    fn test_add_edit_remove_validate() {
+        let mut tracker = create_test_tracker();
+        let added = tracker
+            .add(NewTransaction {
+                description: String::from("Coffee"),
+                amount: 4.5,
+                date: String::from("2024-01-12"),
+                external_id: Some(String::from("bank-1")),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(added.category, "Uncategorized");
+
+        let edited = tracker
+            .edit(
+                added.id,
+                NewTransaction {
+                    amount: 5.0,
+                    external_id: None,
+                    ..added.clone().into()
+                },
+            )
+            .unwrap();
+        assert_eq!(edited.amount, 5.0);
+        assert_eq!(edited.external_id.as_deref(), Some("bank-1"));
+
+        let invalid = NewTransaction {
+            amount: -1.0,
+            ..Default::default()
+        };
+        assert!(tracker.add(invalid.clone()).is_err());
+        assert!(matches!(
+            tracker.edit(99, invalid),
+            Err(ExpensoError::NotFound(99))
+        ));
+        assert!(tracker.remove(added.id).is_ok());
+        assert!(matches!(
+            tracker.remove(added.id),
+            Err(ExpensoError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_date_index_follows_changes() {
+        let mut tracker = create_test_tracker();
+        let dated = |tracker: &FinanceTracker, from, to| -> Vec<u32> {
+            tracker.in_date_range(from, to).map(|t| t.id).collect()
+        };
+        assert_eq!(
+            dated(&tracker, Some("2024-01-01"), Some("2024-01-31")),
+            vec![3, 4, 2]
+        );
+
+        let mut rent = tracker.get_transaction(3).unwrap().clone();
+        rent.date = String::from("2024-02-01");
+        tracker.update_transaction(rent);
+        tracker.delete_transaction(2);
+        assert_eq!(
+            dated(&tracker, Some("2024-01-01"), Some("2024-01-31")),
+            vec![4]
+        );
+        assert_eq!(dated(&tracker, Some("2024-02-01"), None), vec![3, 1]);
+
+        tracker.undo().unwrap();
+        assert_eq!(
+            dated(&tracker, Some("2024-01-01"), Some("2024-01-31")),
+            vec![4, 2]
+        );
+        assert!(dated(&tracker, Some("2024-02-01"), Some("2024-01-01")).is_empty());
+
+        let filter = TransactionFilter {
+            from: Some(String::from("2024-01-05")),
+            transaction_type: Some(TransactionType::Expense),
+            ..Default::default()
+        };
+        let ids: Vec<u32> = tracker
+            .filtered(&filter, SortKey::Amount, SortOrder::Descending)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_category_index_follows_changes() {
+        let mut tracker = create_test_tracker();
+        let in_category = |tracker: &FinanceTracker, category| -> Vec<u32> {
+            tracker.in_category(category).map(|t| t.id).collect()
+        };
+        assert_eq!(in_category(&tracker, "work"), vec![1, 2]);
+
+        let mut groceries = tracker.get_transaction(4).unwrap().clone();
+        groceries.category = Symbol::new("Food:Groceries");
+        tracker.update_transaction(groceries);
+        tracker.delete_transaction(1);
+        assert_eq!(in_category(&tracker, "WORK"), vec![2]);
+        assert!(in_category(&tracker, "Food").is_empty());
+        assert_eq!(tracker.query().category("food").total(), 500.0);
+
+        let filter = TransactionFilter {
+            category: Some(String::from("food:groceries")),
+            ..Default::default()
+        };
+        let ids: Vec<u32> = tracker
+            .filtered(&filter, SortKey::Id, SortOrder::Ascending)
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![4]);
+    }
+
+    #[test]
+    fn test_cached_totals_follow_changes() {
+        let mut tracker = create_test_tracker();
+        assert_eq!(
+            tracker.month_totals().get("2024-01"),
+            Some(&(1500.0, 2500.0))
+        );
+
+        let mut salary = tracker.get_transaction(1).unwrap().clone();
+        salary.amount = 4000.0;
+        salary.date = String::from("2024-02-01");
+        tracker.update_transaction(salary);
+        tracker.delete_transaction(4);
+        assert_eq!(tracker.total_income(), 5500.0);
+        assert_eq!(tracker.total_expense(), 2000.0);
+        assert_eq!(
+            tracker.month_totals().keys().collect::<Vec<_>>(),
+            vec!["2024-01", "2024-02"]
+        );
+        assert_eq!(tracker.month_totals()["2024-02"], (4000.0, 0.0));
+
+        let rebuilt = FinanceTracker::from_transactions(tracker.get_transactions().clone());
+        assert_eq!(rebuilt.month_totals(), tracker.month_totals());
+        assert_eq!(rebuilt.average_transaction(), tracker.average_transaction());
+    }
+
+    #[test]
+    fn test_edits_keep_created_at() {
+        let mut tracker = create_test_tracker();
+        let mut rent = tracker.get_transaction(3).unwrap().clone();
+        let created_at = rent.created_at.clone().unwrap();
+        assert!(created_at.ends_with('Z'));
+        assert_eq!(rent.updated_at.as_ref(), Some(&created_at));
+
+        rent.created_at = None;
+        rent.updated_at = None;
+        tracker.update_transaction(rent);
+        let rent = tracker.get_transaction(3).unwrap();
+        assert_eq!(rent.created_at.as_ref(), Some(&created_at));
+        assert!(rent.updated_at.as_ref() >= Some(&created_at));
+    }
+
+    #[test]
+    fn test_merge_renumbers_colliding_ids() {
+        let mut tracker = create_test_tracker();
+        let mut other = tracker.get_transactions().clone();
+        tracker.delete_transaction(4);
+        other[1].amount = 1600.0;
+        other[1].updated_at = Some(String::from("2099-01-01T00:00:00Z"));
+        other.push(Transaction {
+            id: 9,
+            ..other[0].clone()
+        });
+
+        let report = tracker.merge(other).unwrap();
+        assert_eq!(report.renumbered, BTreeMap::from([(2, 5)]));
+        assert_eq!(report.conflicts, vec![(2, 5)]);
+        assert_eq!(report.deleted, vec![4]);
+        assert_eq!(report.duplicates, 3);
+        let ids: Vec<u32> = tracker.get_transactions().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 5]);
+        assert_eq!(tracker.get_transaction(5).unwrap().amount, 1600.0);
+        assert_eq!(tracker.next_id(), 6);
+    }
+
+    #[test]
+    fn test_merge_skips_copies_added_under_other_ids() {
+        let mut tracker = create_test_tracker();
+        let mut other = FinanceTracker::new();
+        for transaction in tracker.get_transactions().clone().into_iter().rev() {
+            other.add(NewTransaction::from(transaction)).unwrap();
+        }
+        other.insert(NewTransaction {
+            description: String::from("Cinema"),
+            amount: 24.0,
+            date: String::from("2024-01-15"),
+            category: String::from("Fun"),
+            ..Default::default()
+        });
+
+        let report = tracker.merge(other.get_transactions().clone()).unwrap();
+        assert_eq!(report.duplicates, 4);
+        assert_eq!(report.added, vec![5]);
+        assert!(report.renumbered.is_empty());
+        assert!(report.conflicts.is_empty());
+    }
+}
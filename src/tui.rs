@@ -0,0 +1,901 @@
+use std::io;
+
+use chrono::Local;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Clear, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::color;
+use crate::money::format_money;
+use crate::{
+    FinanceTracker, NewTransaction, SortKey, SortOrder, Transaction, TransactionType, dates,
+    parse_amount, parse_bool,
+};
+use expense_tracker::alerts;
+use expense_tracker::config::Config;
+use expense_tracker::report::{self, CategoryReport, MonthlyReport, Report, Summary};
+
+const FIELD_LABELS: [&str; 7] = [
+    "Description",
+    "Amount",
+    "Recurring (yes/no)",
+    "Date (YYYY-MM-DD)",
+    "Type (income/expense)",
+    "Category",
+    "Account",
+];
+
+#[derive(Debug)]
+struct Form {
+    editing: Option<u32>,
+    values: [String; 7],
+    focus: usize,
+    error: Option<String>,
+}
+
+impl Form {
+    fn empty(config: &Config) -> Self {
+        let mut values: [String; 7] = Default::default();
+        values[4] = config.default_type.to_string().to_lowercase();
+        values[6] = config.default_account.clone();
+
+        Form {
+            editing: None,
+            values,
+            focus: 0,
+            error: None,
+        }
+    }
+
+    fn from_transaction(transaction: &Transaction) -> Self {
+        let recurring = if transaction.is_recurring {
+            "yes"
+        } else {
+            "no"
+        };
+        Form {
+            editing: Some(transaction.id),
+            values: [
+                transaction.description.clone(),
+                format!("{:.2}", transaction.amount),
+                recurring.to_string(),
+                transaction.date.clone(),
+                transaction.transaction_type.to_string().to_lowercase(),
+                transaction.category.to_string(),
+                transaction.account.clone(),
+            ],
+            focus: 0,
+            error: None,
+        }
+    }
+
+    fn next_field(&mut self) {
+        self.focus = (self.focus + 1) % self.values.len();
+    }
+
+    fn previous_field(&mut self) {
+        self.focus = (self.focus + self.values.len() - 1) % self.values.len();
+    }
+
+    fn submit(&self, tracker: &mut FinanceTracker, date_format: &str) -> Result<(), String> {
+        let [
+            description,
+            amount,
+            recurring,
+            date,
+            transaction_type,
+            category,
+            account,
+        ] = &self.values;
+
+        if description.trim().is_empty() {
+            return Err(String::from("Description is required"));
+        }
+        let amount = parse_amount(amount.trim())
+            .map_err(|_| String::from("Invalid amount. Please enter a number."))?;
+
+        let transaction_type: TransactionType = transaction_type
+            .parse()
+            .map_err(|_| String::from("Type must be income or expense"))?;
+
+        let date = match date.trim() {
+            "" => String::new(),
+            date => dates::normalize_in(date, date_format, Local::now().date_naive())
+                .ok_or_else(|| format!("Date must be {}", dates::format_hint(date_format)))?,
+        };
+
+        let fields = |existing: NewTransaction| NewTransaction {
+            description: description.trim().to_string(),
+            amount,
+            is_recurring: parse_bool(recurring.trim()),
+            date: date.clone(),
+            transaction_type,
+            category: category.trim().to_string(),
+            account: account.trim().to_string(),
+            ..existing
+        };
+        let result = match self.editing {
+            Some(id) => match tracker.get_transaction(id) {
+                Some(existing) => tracker.edit(id, fields(existing.clone().into())),
+                None => return Err(format!("Transaction {} no longer exists", id)),
+            },
+            None => tracker.add(fields(NewTransaction::default())),
+        };
+        result.map(|_| ()).map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Debug)]
+enum Mode {
+    Browse,
+    Form(Box<Form>),
+    /// The changes made to a transaction.
+    History(u32),
+    /// Typing the text transactions are filtered by.
+    Filter,
+}
+
+struct App<'a> {
+    tracker: &'a mut FinanceTracker,
+    config: &'a Config,
+    table_state: TableState,
+    sort_key: SortKey,
+    sort_order: SortOrder,
+    /// Only transactions matching this are listed, summed up and charted.
+    filter: String,
+    /// Charts in place of the category totals.
+    charts: bool,
+    mode: Mode,
+    /// Shown in place of the key help until the next key press.
+    message: Option<String>,
+    should_quit: bool,
+}
+
+impl<'a> App<'a> {
+    fn new(tracker: &'a mut FinanceTracker, config: &'a Config) -> Self {
+        let mut table_state = TableState::default();
+        if !tracker.get_transactions().is_empty() {
+            table_state.select(Some(0));
+        }
+
+        App {
+            tracker,
+            config,
+            table_state,
+            sort_key: SortKey::Id,
+            sort_order: SortOrder::Ascending,
+            filter: String::new(),
+            charts: false,
+            mode: Mode::Browse,
+            message: None,
+            should_quit: false,
+        }
+    }
+
+    fn visible(&self) -> Vec<&Transaction> {
+        let mut visible = self
+            .tracker
+            .sorted_transactions(self.sort_key, self.sort_order);
+        if !self.filter.is_empty() {
+            visible.retain(|t| t.matches(&self.filter));
+        }
+        visible
+    }
+
+    fn selected_transaction(&self) -> Option<&Transaction> {
+        let index = self.table_state.selected()?;
+        self.visible().get(index).copied()
+    }
+
+    fn select_id(&mut self, id: u32) {
+        let index = self.visible().iter().position(|t| t.id == id);
+        self.table_state.select(index);
+    }
+
+    fn change_sort(&mut self, key: SortKey, order: SortOrder) {
+        let selected = self.selected_transaction().map(|t| t.id);
+        self.sort_key = key;
+        self.sort_order = order;
+        if let Some(id) = selected {
+            self.select_id(id);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.should_quit = true;
+            return;
+        }
+
+        self.message = None;
+        match &mut self.mode {
+            Mode::Browse => self.handle_browse_key(key.code),
+            Mode::Form(_) => self.handle_form_key(key.code),
+            Mode::History(_) => self.mode = Mode::Browse,
+            Mode::Filter => self.handle_filter_key(key.code),
+        }
+    }
+
+    fn handle_browse_key(&mut self, code: KeyCode) {
+        let count = self.visible().len();
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+                let next = self
+                    .table_state
+                    .selected()
+                    .map_or(0, |i| (i + 1).min(count - 1));
+                self.table_state.select(Some(next));
+            }
+            KeyCode::Up | KeyCode::Char('k') if count > 0 => {
+                let previous = self
+                    .table_state
+                    .selected()
+                    .map_or(0, |i| i.saturating_sub(1));
+                self.table_state.select(Some(previous));
+            }
+            KeyCode::Home | KeyCode::Char('g') if count > 0 => self.table_state.select(Some(0)),
+            KeyCode::End | KeyCode::Char('G') if count > 0 => {
+                self.table_state.select(Some(count - 1))
+            }
+            KeyCode::Char('s') => {
+                let next = match self.sort_key {
+                    SortKey::Id => SortKey::Date,
+                    SortKey::Date => SortKey::Amount,
+                    SortKey::Amount => SortKey::Category,
+                    SortKey::Category => SortKey::Id,
+                };
+                self.change_sort(next, self.sort_order);
+            }
+            KeyCode::Char('r') => {
+                let reversed = match self.sort_order {
+                    SortOrder::Ascending => SortOrder::Descending,
+                    SortOrder::Descending => SortOrder::Ascending,
+                };
+                self.change_sort(self.sort_key, reversed);
+            }
+            KeyCode::Char('a') => self.mode = Mode::Form(Box::new(Form::empty(self.config))),
+            KeyCode::Char('o') => self.load_older_year(),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('c') => self.charts = !self.charts,
+            KeyCode::Char('h') => {
+                if let Some(id) = self.selected_transaction().map(|t| t.id) {
+                    if let Err(err) = self.tracker.load_history() {
+                        self.message = Some(format!("Could not load older changes: {}", err));
+                    }
+                    self.mode = Mode::History(id);
+                }
+            }
+            KeyCode::Char('e') | KeyCode::Enter => {
+                if let Some(transaction) = self.selected_transaction() {
+                    self.mode = Mode::Form(Box::new(Form::from_transaction(transaction)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Loads the archived year before the earliest one shown.
+    fn load_older_year(&mut self) {
+        let Some(since) = self.tracker.loaded_since() else {
+            self.message = Some(String::from("All transactions are loaded"));
+            return;
+        };
+        let year: i32 = since
+            .get(..4)
+            .and_then(|y| y.parse().ok())
+            .unwrap_or_default();
+        let from = format!("{}-01-01", year - 1);
+        let selected = self.selected_transaction().map(|t| t.id);
+        self.message = Some(match self.tracker.load_since(Some(&from)) {
+            Ok(count) => format!("Loaded {} transaction(s) from {}", count, year - 1),
+            Err(err) => format!("Could not load older transactions: {}", err),
+        });
+        if let Some(id) = selected {
+            self.select_id(id);
+        } else if !self.tracker.get_transactions().is_empty() {
+            self.table_state.select(Some(0));
+        }
+    }
+
+    /// Filters as the text is typed; Esc clears it.
+    fn handle_filter_key(&mut self, code: KeyCode) {
+        let selected = self.selected_transaction().map(|t| t.id);
+        match code {
+            KeyCode::Enter => self.mode = Mode::Browse,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.mode = Mode::Browse;
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        match selected {
+            Some(id) if self.visible().iter().any(|t| t.id == id) => self.select_id(id),
+            _ if self.visible().is_empty() => self.table_state.select(None),
+            _ => self.table_state.select(Some(0)),
+        }
+    }
+
+    fn handle_form_key(&mut self, code: KeyCode) {
+        let Mode::Form(form) = &mut self.mode else {
+            return;
+        };
+
+        match code {
+            KeyCode::Esc => self.mode = Mode::Browse,
+            KeyCode::Tab | KeyCode::Down => form.next_field(),
+            KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+            KeyCode::Backspace => {
+                form.values[form.focus].pop();
+            }
+            KeyCode::Char(c) => form.values[form.focus].push(c),
+            KeyCode::Enter => match form.submit(self.tracker, &self.config.date_format) {
+                Ok(()) => {
+                    let added = form.editing.is_none();
+                    self.mode = Mode::Browse;
+                    if added {
+                        let id = self.tracker.next_id() - 1;
+                        self.select_id(id);
+                    }
+                }
+                Err(message) => form.error = Some(message),
+            },
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [list, side] =
+            Layout::horizontal([Constraint::Percentage(65), Constraint::Percentage(35)])
+                .areas(main);
+        let [summary, categories] =
+            Layout::vertical([Constraint::Length(6), Constraint::Min(0)]).areas(side);
+
+        self.draw_transactions(frame, list);
+        self.draw_summary(frame, summary);
+        if self.charts {
+            let [trend, shares] =
+                Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .areas(categories);
+            self.draw_trend(frame, trend);
+            self.draw_shares(frame, shares);
+        } else {
+            self.draw_categories(frame, categories);
+        }
+
+        let help = match self.mode {
+            Mode::Browse => {
+                "↑/↓ move  s sort  r reverse  / filter  c charts  a add  e/Enter edit  h history  o older  q quit"
+            }
+            Mode::Form(_) => "Tab/↓ next field  Shift-Tab/↑ previous  Enter save  Esc cancel",
+            Mode::History(_) => "Any key to close",
+            Mode::Filter => "",
+        };
+        let filtering = format!("Filter: {}▏  Enter keep  Esc clear", self.filter);
+        let status_line = match (&self.message, &self.mode) {
+            (Some(message), _) => message.as_str(),
+            (None, Mode::Filter) => filtering.as_str(),
+            (None, _) => help,
+        };
+        frame.render_widget(Paragraph::new(status_line), status);
+
+        match &self.mode {
+            Mode::Form(form) => draw_form(frame, form),
+            Mode::History(id) => self.draw_history(frame, *id),
+            Mode::Browse | Mode::Filter => {}
+        }
+    }
+
+    fn draw_transactions(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(["ID", "Date", "Description", "Type", "Category", "Amount"])
+            .style(Style::new().add_modifier(Modifier::BOLD));
+        let mut title = format!(
+            " Transactions (by {:?}, {:?}) ",
+            self.sort_key, self.sort_order
+        );
+        if let Some(since) = self.tracker.loaded_since() {
+            title = format!("{}since {} ", title, since);
+        }
+        if !self.filter.is_empty() {
+            title = format!("{}matching \"{}\" ", title, self.filter);
+        }
+        let archived_before = self.config.recent_start(Local::now().date_naive());
+        let rows = self.visible().into_iter().map(|t| {
+            let archived = archived_before
+                .as_deref()
+                .is_some_and(|start| t.date.as_str() < start);
+            Row::new([
+                t.id.to_string(),
+                t.date.clone(),
+                t.description.clone(),
+                t.transaction_type.to_string(),
+                t.category.to_string(),
+                format_money(t.amount),
+            ])
+            .style(amount_style(if archived {
+                color::Color::Dim
+            } else {
+                color::type_color(&t.transaction_type)
+            }))
+        });
+        let widths = [
+            Constraint::Length(4),
+            Constraint::Length(10),
+            Constraint::Fill(1),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Length(12),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::bordered().title(title))
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(table, area, &mut self.table_state);
+    }
+
+    fn draw_summary(&self, frame: &mut Frame, area: Rect) {
+        let summary = if self.filter.is_empty() {
+            Summary::from_tracker(self.tracker)
+        } else {
+            Summary::compute(&self.visible())
+        };
+        let lines = vec![
+            Line::from(format!(
+                "Total Income: {}",
+                format_money(summary.total_income)
+            ))
+            .style(amount_style(color::Color::Green)),
+            Line::from(format!(
+                "Total Expense: {}",
+                format_money(summary.total_expense)
+            ))
+            .style(amount_style(color::Color::Red)),
+            Line::from(format!(
+                "Net Balance: {}",
+                format_money(summary.net_balance)
+            ))
+            .style(amount_style(color::balance_color(summary.net_balance))),
+            Line::from(format!(
+                "Average Transaction {}",
+                format_money(summary.average_transaction)
+            )),
+        ];
+        let title = match self.tracker.loaded_since() {
+            Some(since) => format!(" Summary since {} ", since),
+            None => String::from(" Summary "),
+        };
+        let summary = Paragraph::new(lines).block(Block::bordered().title(title));
+        frame.render_widget(summary, area);
+    }
+
+    fn draw_categories(&self, frame: &mut Frame, area: Rect) {
+        let report = if self.filter.is_empty() {
+            CategoryReport::from_tracker(self.tracker)
+        } else {
+            CategoryReport::compute(&self.visible())
+        };
+        let all: Vec<&Transaction> = self.tracker.get_transactions().iter().collect();
+        let over = alerts::over_budget(&all, self.config, Local::now().date_naive());
+        let lines: Vec<Line> = report
+            .ordered(self.config.category_order)
+            .mark_over_budget(&over)
+            .categories
+            .iter()
+            .map(|c| {
+                let line = Line::from(format!("{} {}", c.category, format_money(c.total)));
+                if c.over_budget {
+                    line.style(amount_style(color::Color::Alert))
+                } else {
+                    line
+                }
+            })
+            .collect();
+        let categories = Paragraph::new(lines).block(Block::bordered().title(" Categories "));
+        frame.render_widget(categories, area);
+    }
+
+    /// Income and expense bars for the latest months that fit.
+    fn draw_trend(&self, frame: &mut Frame, area: Rect) {
+        // Two bars of 3 columns and a gap per month, inside the border.
+        let fit = usize::from(area.width.saturating_sub(2) / 7).max(1);
+        let months = MonthlyReport::compute(&self.visible()).months;
+        let groups: Vec<BarGroup> = months[months.len().saturating_sub(fit)..]
+            .iter()
+            .map(|m| {
+                let bar = |amount: f64, color| {
+                    Bar::new(amount.round() as u64)
+                        .text_value("")
+                        .style(amount_style(color))
+                };
+                BarGroup::new([
+                    bar(m.income, color::Color::Green),
+                    bar(m.expense, color::Color::Red),
+                ])
+                .label(Line::from(m.month.get(2..).unwrap_or(&m.month).to_string()))
+            })
+            .collect();
+        let chart = BarChart::grouped(groups)
+            .bar_width(3)
+            .bar_gap(0)
+            .group_gap(1)
+            .block(Block::bordered().title(" Monthly income / expense "));
+        frame.render_widget(chart, area);
+    }
+
+    /// Each category's share of the expenses, largest first.
+    fn draw_shares(&self, frame: &mut Frame, area: Rect) {
+        let bars: Vec<Bar> = expense_shares(&self.visible())
+            .into_iter()
+            .map(|(category, total, share)| {
+                Bar::new((share * 1000.0).round() as u64)
+                    .label(Line::from(category))
+                    .text_value(format!("{:.0}% {}", share * 100.0, format_money(total)))
+                    .style(amount_style(color::Color::Red))
+            })
+            .collect();
+        let chart = BarChart::horizontal(bars)
+            .bar_gap(0)
+            .max(1000)
+            .block(Block::bordered().title(" Expenses by category "));
+        frame.render_widget(chart, area);
+    }
+
+    fn draw_history(&self, frame: &mut Frame, id: u32) {
+        let mut lines: Vec<Line> = Vec::new();
+        for entry in self.tracker.history_of(id) {
+            let by = entry
+                .by
+                .as_deref()
+                .map(|by| format!(" by {}", by))
+                .unwrap_or_default();
+            lines.push(
+                Line::from(format!("{}{}", entry.local_time(), by))
+                    .style(Style::new().add_modifier(Modifier::BOLD)),
+            );
+            lines.extend(
+                entry
+                    .describe()
+                    .into_iter()
+                    .map(|line| Line::from(format!("  {}", line))),
+            );
+        }
+        if lines.is_empty() {
+            lines.push(Line::from("No changes recorded."));
+        }
+
+        let area = centered(frame.area(), 70, lines.len() as u16 + 2);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(format!(" History of {} ", id))),
+            area,
+        );
+    }
+}
+
+fn draw_form(frame: &mut Frame, form: &Form) {
+    let area = centered(frame.area(), 50, FIELD_LABELS.len() as u16 + 4);
+    let title = match form.editing {
+        Some(id) => format!(" Edit Transaction {} ", id),
+        None => String::from(" Add Transaction "),
+    };
+
+    let mut lines: Vec<Line> = FIELD_LABELS
+        .iter()
+        .zip(form.values.iter())
+        .enumerate()
+        .map(|(index, (label, value))| {
+            let line = Line::from(format!("{:<22} {}", label, value));
+            if index == form.focus {
+                line.style(Style::new().add_modifier(Modifier::REVERSED))
+            } else {
+                line
+            }
+        })
+        .collect();
+    if let Some(error) = &form.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(error.as_str()));
+    }
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(title)),
+        area,
+    );
+}
+
+/// Each expense category with its total and share of all the expenses in
+/// `transactions`, largest first.
+fn expense_shares(transactions: &[&Transaction]) -> Vec<(String, f64, f64)> {
+    let expenses: Vec<&Transaction> = transactions
+        .iter()
+        .copied()
+        .filter(|t| t.transaction_type == TransactionType::Expense)
+        .collect();
+    let total: f64 = expenses.iter().map(|t| t.amount).sum();
+    let mut shares: Vec<(String, f64, f64)> = report::category_totals(&expenses)
+        .into_iter()
+        .map(|(category, spent)| (category.to_string(), spent.abs(), spent.abs() / total))
+        .collect();
+    shares.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    shares
+}
+
+fn amount_style(color: color::Color) -> Style {
+    if !color::enabled() {
+        return Style::new();
+    }
+    match color {
+        color::Color::Green => Style::new().fg(Color::Green),
+        color::Color::Red => Style::new().fg(Color::Red),
+        color::Color::Yellow => Style::new().fg(Color::Yellow),
+        color::Color::Highlight => Style::new().add_modifier(Modifier::REVERSED),
+        color::Color::Alert => Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+        color::Color::Dim => Style::new().add_modifier(Modifier::DIM),
+    }
+}
+
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn run(tracker: &mut FinanceTracker, config: &Config) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, App::new(tracker, config));
+    ratatui::restore();
+    result
+}
+
+fn run_app(terminal: &mut DefaultTerminal, mut app: App) -> io::Result<()> {
+    while !app.should_quit {
+        terminal.draw(|frame| app.draw(frame))?;
+
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Symbol;
+
+    fn press(app: &mut App, code: KeyCode) {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c));
+        }
+    }
+
+    #[test]
+    fn test_add_form_creates_transaction() {
+        let mut tracker = FinanceTracker::new();
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+
+        press(&mut app, KeyCode::Char('a'));
+        for value in ["Coffee", "4.50", "no", "2024-02-01", "", "Food"] {
+            type_text(&mut app, value);
+            press(&mut app, KeyCode::Tab);
+        }
+        press(&mut app, KeyCode::Enter);
+
+        assert!(matches!(app.mode, Mode::Browse));
+        assert_eq!(app.table_state.selected(), Some(0));
+        assert_eq!(tracker.total_expense(), 4.5);
+        assert_eq!(
+            tracker.category_breakdown().get(&Symbol::new("Food")),
+            Some(&4.5)
+        );
+        assert_eq!(
+            tracker.get_transactions()[0].account,
+            config.default_account
+        );
+    }
+
+    #[test]
+    fn test_form_rejects_invalid_amount() {
+        let mut tracker = FinanceTracker::new();
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+
+        press(&mut app, KeyCode::Char('a'));
+        type_text(&mut app, "Coffee");
+        press(&mut app, KeyCode::Tab);
+        type_text(&mut app, "abc");
+        press(&mut app, KeyCode::Enter);
+
+        let Mode::Form(form) = &app.mode else {
+            panic!("form should stay open");
+        };
+        assert!(form.error.is_some());
+        assert!(tracker.get_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_edit_form_updates_selected_transaction() {
+        let mut tracker = FinanceTracker::new();
+        tracker.add_transaction(
+            String::from("Rent"),
+            2000.0,
+            true,
+            String::from("2024-01-01"),
+            TransactionType::Expense,
+            String::from("Housing"),
+        );
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+
+        press(&mut app, KeyCode::Char('e'));
+        press(&mut app, KeyCode::Tab);
+        for _ in 0..7 {
+            press(&mut app, KeyCode::Backspace);
+        }
+        type_text(&mut app, "2100");
+        press(&mut app, KeyCode::Enter);
+
+        assert!(matches!(app.mode, Mode::Browse));
+        assert_eq!(tracker.get_transactions()[0].amount, 2100.0);
+        assert_eq!(
+            tracker.category_breakdown().get(&Symbol::new("Housing")),
+            Some(&2100.0)
+        );
+
+        let mut app = App::new(&mut tracker, &config);
+        press(&mut app, KeyCode::Char('h'));
+        assert!(matches!(app.mode, Mode::History(1)));
+        press(&mut app, KeyCode::Esc);
+        assert!(matches!(app.mode, Mode::Browse));
+    }
+
+    #[test]
+    fn test_sort_keeps_selected_transaction() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount) in [("Rent", 2000.0), ("Coffee", 4.5), ("Books", 30.0)] {
+            tracker.add_transaction(
+                String::from(description),
+                amount,
+                false,
+                String::from("2024-01-01"),
+                TransactionType::Expense,
+                String::from("Misc"),
+            );
+        }
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Char('s'));
+        assert_eq!(app.sort_key, SortKey::Amount);
+        assert_eq!(app.selected_transaction().unwrap().id, 1);
+        assert_eq!(app.table_state.selected(), Some(2));
+
+        press(&mut app, KeyCode::Char('r'));
+        assert_eq!(app.table_state.selected(), Some(0));
+        let order: Vec<u32> = app.visible().iter().map(|t| t.id).collect();
+        assert_eq!(order, vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn test_ctrl_c_quits_from_form() {
+        let mut tracker = FinanceTracker::new();
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+
+        press(&mut app, KeyCode::Char('a'));
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_navigation_stays_in_bounds() {
+        let mut tracker = FinanceTracker::new();
+        for description in ["A", "B"] {
+            tracker.add_transaction(
+                String::from(description),
+                1.0,
+                false,
+                String::from("2024-01-01"),
+                TransactionType::Expense,
+                String::from("Misc"),
+            );
+        }
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.table_state.selected(), Some(1));
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Up);
+        assert_eq!(app.table_state.selected(), Some(0));
+        press(&mut app, KeyCode::Char('q'));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_filter_narrows_list_and_charts() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, category) in [
+            ("Rent", 1500.0, "2024-01-01", "Housing"),
+            ("Groceries", 300.0, "2024-01-05", "Food"),
+            ("Restaurant", 200.0, "2024-02-10", "Food"),
+        ] {
+            tracker.add_transaction(
+                String::from(description),
+                amount,
+                false,
+                String::from(date),
+                TransactionType::Expense,
+                String::from(category),
+            );
+        }
+        let config = Config::default();
+        let mut app = App::new(&mut tracker, &config);
+        assert_eq!(
+            expense_shares(&app.visible()),
+            vec![
+                (String::from("Housing"), 1500.0, 0.75),
+                (String::from("Food"), 500.0, 0.25),
+            ]
+        );
+
+        press(&mut app, KeyCode::Char('/'));
+        type_text(&mut app, "food");
+        assert!(matches!(app.mode, Mode::Filter));
+        press(&mut app, KeyCode::Enter);
+        let ids: Vec<u32> = app.visible().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(app.table_state.selected(), Some(0));
+        assert_eq!(
+            expense_shares(&app.visible()),
+            vec![(String::from("Food"), 500.0, 1.0)]
+        );
+
+        press(&mut app, KeyCode::Char('c'));
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("Monthly income / expense"));
+        assert!(screen.contains("100% $500.00"));
+        assert!(screen.contains("Total Expense: $500.00"));
+
+        press(&mut app, KeyCode::Char('/'));
+        press(&mut app, KeyCode::Esc);
+        assert_eq!(app.visible().len(), 3);
+    }
+}
@@ -0,0 +1,100 @@
+//! wasm-bindgen wrappers for running the tracker in a browser.
+//!
+//! Build with `wasm-pack build --target web -- --no-default-features --features wasm`.
+//! Nothing here touches the filesystem: the page keeps the data file contents
+//! (e.g. in `localStorage`) and passes them to [`Tracker::from_json`].
+//! Transactions, reports and the data file all cross the boundary as JSON.
+
+use wasm_bindgen::prelude::*;
+
+use crate::report::{CategoryReport, MonthlyReport, Report, Summary};
+use crate::storage::{self, Ledger};
+use crate::{ExpensoError, FinanceTracker, NewTransaction};
+
+#[wasm_bindgen]
+#[derive(Debug, Default)]
+pub struct Tracker {
+    inner: FinanceTracker,
+}
+
+#[wasm_bindgen]
+impl Tracker {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Tracker {
+        Tracker::default()
+    }
+
+    /// Loads the contents of an expenso data file.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(data: &str) -> Result<Tracker, JsError> {
+        Ok(Tracker {
+            inner: storage::parse(data)?.into(),
+        })
+    }
+
+    /// The data file contents, readable by the CLI.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(storage::to_json(&Ledger::from(&self.inner))?)
+    }
+
+    /// Adds a transaction given as JSON and returns its id.
+    pub fn add(&mut self, json: &str) -> Result<u32, JsError> {
+        let new: NewTransaction = serde_json::from_str(json)?;
+        Ok(self.inner.add(new)?.id)
+    }
+
+    /// Returns false when there is no transaction with `id`, and fails for
+    /// one that is reconciled or in a closed period.
+    pub fn delete(&mut self, id: u32) -> Result<bool, JsError> {
+        match self.inner.remove(id) {
+            Ok(_) => Ok(true),
+            Err(ExpensoError::NotFound(_)) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn transactions(&self) -> Result<String, JsError> {
+        Ok(serde_json::to_string(self.inner.get_transactions())?)
+    }
+
+    pub fn summary(&self) -> Result<String, JsError> {
+        Ok(self.report::<Summary>()?)
+    }
+
+    pub fn categories(&self) -> Result<String, JsError> {
+        Ok(self.report::<CategoryReport>()?)
+    }
+
+    pub fn monthly(&self) -> Result<String, JsError> {
+        Ok(self.report::<MonthlyReport>()?)
+    }
+}
+
+impl Tracker {
+    fn report<R: Report>(&self) -> serde_json::Result<String> {
+        R::from_tracker(&self.inner).json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip_through_json() {
+        let mut tracker = Tracker::new();
+        let id = tracker
+            .add(r#"{"description":"Lunch","amount":12.5,"date":"2024-03-01","category":"Food"}"#)
+            .unwrap();
+        assert_eq!(id, 1);
+
+        let reloaded = Tracker::from_json(&tracker.to_json().unwrap()).unwrap();
+        let categories: serde_json::Value =
+            serde_json::from_str(&reloaded.categories().unwrap()).unwrap();
+        assert_eq!(categories[0]["category"], "Food");
+        assert_eq!(categories[0]["total"], 12.5);
+        assert!(!Tracker::new().delete(1).unwrap());
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+use serde::Serialize;
+
+use crate::error::{ExpensoError, Result};
+use crate::{Transaction, dates, money};
+
+/// Full months of past spending the baseline averages whatever isn't
+/// recurring over.
+pub const AVERAGE_MONTHS: u32 = 3;
+
+/// Money coming in or going out every month, negative when it goes out.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Flow {
+    pub description: String,
+    pub category: String,
+    pub amount: f64,
+    /// Set for recurring transactions, unset for a category's average
+    /// other spending.
+    pub recurring: bool,
+}
+
+/// A hypothetical change to the monthly flows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A new monthly expense.
+    Add { description: String, amount: f64 },
+    /// New monthly income.
+    Income { description: String, amount: f64 },
+    /// Recurring amounts whose description contains `description` going
+    /// up by `by`, or down when it is negative.
+    Adjust { description: String, by: f64 },
+    /// Recurring amounts whose description contains `description` stopping.
+    Drop { description: String },
+    /// Spending `amount` a month on a category instead of its average.
+    Budget { category: String, amount: f64 },
+}
+
+impl Change {
+    fn apply(&self, flows: &mut Vec<Flow>) -> Result<()> {
+        match self {
+            Change::Add {
+                description,
+                amount,
+            } => flows.push(Flow {
+                description: description.clone(),
+                category: String::from("What if"),
+                amount: -amount,
+                recurring: true,
+            }),
+            Change::Income {
+                description,
+                amount,
+            } => flows.push(Flow {
+                description: description.clone(),
+                category: String::from("What if"),
+                amount: *amount,
+                recurring: true,
+            }),
+            Change::Adjust { description, by } => {
+                for flow in matching(flows, description)? {
+                    flow.amount = money::round(flow.amount + by * flow.amount.signum());
+                }
+            }
+            Change::Drop { description } => {
+                matching(flows, description)?;
+                let description = description.to_lowercase();
+                flows.retain(|flow| {
+                    !(flow.recurring && flow.description.to_lowercase().contains(&description))
+                });
+            }
+            Change::Budget { category, amount } => {
+                flows
+                    .retain(|flow| flow.recurring || !flow.category.eq_ignore_ascii_case(category));
+                flows.push(Flow {
+                    description: category.clone(),
+                    category: category.clone(),
+                    amount: -amount,
+                    recurring: false,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn matching<'a>(flows: &'a mut [Flow], description: &str) -> Result<Vec<&'a mut Flow>> {
+    let needle = description.to_lowercase();
+    let found: Vec<&mut Flow> = flows
+        .iter_mut()
+        .filter(|flow| flow.recurring && flow.description.to_lowercase().contains(&needle))
+        .collect();
+    if found.is_empty() {
+        return Err(ExpensoError::InvalidInput(format!(
+            "nothing recurring matches '{}'",
+            description
+        )));
+    }
+    Ok(found)
+}
+
+/// What a month is expected to bring in and pay out: the latest of each
+/// recurring transaction, plus every category's average other spending
+/// over the last [`AVERAGE_MONTHS`] full months.
+pub fn baseline(transactions: &[&Transaction], today: NaiveDate) -> Vec<Flow> {
+    let mut latest: BTreeMap<(String, String, String), &Transaction> = BTreeMap::new();
+    let mut other: BTreeMap<String, f64> = BTreeMap::new();
+    let this_month = today.with_day(1).unwrap_or(today);
+    let from = (this_month - Months::new(AVERAGE_MONTHS))
+        .format(dates::ISO_FORMAT)
+        .to_string();
+    let until = this_month.format(dates::ISO_FORMAT).to_string();
+
+    for t in transactions {
+        if t.is_recurring {
+            let key = (
+                t.description.to_lowercase(),
+                t.category.to_lowercase(),
+                t.account.to_lowercase(),
+            );
+            if latest.get(&key).is_none_or(|seen| t.date >= seen.date) {
+                latest.insert(key, t);
+            }
+        } else if t.date >= from && t.date < until {
+            let total = other.entry(t.category.to_string()).or_default();
+            *total = money::sum([*total, t.signed_amount()]);
+        }
+    }
+
+    let mut flows: Vec<Flow> = latest
+        .into_values()
+        .map(|t| Flow {
+            description: t.description.clone(),
+            category: t.category.to_string(),
+            amount: t.signed_amount(),
+            recurring: true,
+        })
+        .collect();
+    flows.extend(
+        other
+            .into_iter()
+            .filter(|(_, total)| *total != 0.0)
+            .map(|(category, total)| Flow {
+                description: category.clone(),
+                category,
+                amount: money::round(total / f64::from(AVERAGE_MONTHS)),
+                recurring: false,
+            }),
+    );
+    flows
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProjectedMonth {
+    /// `YYYY-MM`.
+    pub month: String,
+    /// Balance at the end of the month as things are, and with the changes.
+    pub baseline: f64,
+    pub scenario: f64,
+}
+
+/// Balances projected for the months after this one, from today's, as
+/// things are and with the changes. Nothing is written; the transactions are only read.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Projection {
+    pub balance: f64,
+    pub baseline: Vec<Flow>,
+    pub scenario: Vec<Flow>,
+    pub months: Vec<ProjectedMonth>,
+}
+
+impl Projection {
+    pub fn compute(
+        transactions: &[&Transaction],
+        changes: &[Change],
+        months: u32,
+        today: NaiveDate,
+    ) -> Result<Self> {
+        let baseline = baseline(transactions, today);
+        let mut scenario = baseline.clone();
+        for change in changes {
+            change.apply(&mut scenario)?;
+        }
+
+        let balance = money::sum(transactions.iter().map(|t| t.signed_amount()));
+        let (per_month, per_month_scenario) = (monthly(&baseline), monthly(&scenario));
+        let this_month = today.with_day(1).unwrap_or(today);
+        let months = (1..=months)
+            .map(|n| ProjectedMonth {
+                month: (this_month + Months::new(n)).format("%Y-%m").to_string(),
+                baseline: money::round(balance + per_month * f64::from(n)),
+                scenario: money::round(balance + per_month_scenario * f64::from(n)),
+            })
+            .collect();
+        Ok(Projection {
+            balance,
+            baseline,
+            scenario,
+            months,
+        })
+    }
+
+    /// Adds `opening`, what there was before the first transaction, to
+    /// every balance.
+    pub fn with_opening_balance(mut self, opening: f64) -> Self {
+        self.balance = money::sum([self.balance, opening]);
+        for month in &mut self.months {
+            month.baseline = money::sum([month.baseline, opening]);
+            month.scenario = money::sum([month.scenario, opening]);
+        }
+        self
+    }
+}
+
+/// What the flows add to the balance each month.
+pub fn monthly(flows: &[Flow]) -> f64 {
+    money::sum(flows.iter().map(|flow| flow.amount))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{FinanceTracker, NewTransaction, TransactionType};
+
+    #[test]
+    fn test_what_if_projects_balances() {
+        let mut tracker = FinanceTracker::new();
+        for (description, amount, date, transaction_type, category, is_recurring) in [
+            (
+                "Salary",
+                3000.0,
+                "2024-03-01",
+                TransactionType::Income,
+                "Work",
+                true,
+            ),
+            (
+                "Rent",
+                1200.0,
+                "2024-02-01",
+                TransactionType::Expense,
+                "Housing",
+                true,
+            ),
+            (
+                "Rent",
+                1250.0,
+                "2024-03-01",
+                TransactionType::Expense,
+                "Housing",
+                true,
+            ),
+            (
+                "Netflix",
+                15.0,
+                "2024-03-05",
+                TransactionType::Expense,
+                "Fun",
+                true,
+            ),
+            (
+                "Groceries",
+                450.0,
+                "2024-02-10",
+                TransactionType::Expense,
+                "Food",
+                false,
+            ),
+            (
+                "Groceries",
+                300.0,
+                "2024-03-10",
+                TransactionType::Expense,
+                "Food",
+                false,
+            ),
+            // This month's spending isn't over yet, so it isn't averaged.
+            (
+                "Groceries",
+                80.0,
+                "2024-04-02",
+                TransactionType::Expense,
+                "Food",
+                false,
+            ),
+        ] {
+            tracker.insert(NewTransaction {
+                description: String::from(description),
+                amount,
+                date: String::from(date),
+                transaction_type,
+                category: String::from(category),
+                is_recurring,
+                ..Default::default()
+            });
+        }
+        let all: Vec<&Transaction> = tracker.get_transactions().iter().collect();
+        let today = NaiveDate::from_ymd_opt(2024, 4, 15).unwrap();
+
+        let changes = [
+            Change::Adjust {
+                description: String::from("rent"),
+                by: 300.0,
+            },
+            Change::Drop {
+                description: String::from("netflix"),
+            },
+            Change::Budget {
+                category: String::from("Food"),
+                amount: 200.0,
+            },
+        ];
+        let projection = Projection::compute(&all, &changes, 3, today).unwrap();
+        assert_eq!(projection.balance, -295.0);
+        // 3000 - 1250 - 15 - 750 / 3
+        assert_eq!(monthly(&projection.baseline), 1485.0);
+        // 3000 - 1550 - 200
+        assert_eq!(monthly(&projection.scenario), 1250.0);
+        assert_eq!(
+            projection.months[2],
+            ProjectedMonth {
+                month: String::from("2024-07"),
+                baseline: 4160.0,
+                scenario: 3455.0,
+            }
+        );
+
+        let unknown = [Change::Drop {
+            description: String::from("gym"),
+        }];
+        assert!(Projection::compute(&all, &unknown, 3, today).is_err());
+    }
+}
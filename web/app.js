@@ -0,0 +1,164 @@
+"use strict";
+
+let settings = { currency: "USD", locale: "en-US", default_account: "Cash" };
+
+function money(amount) {
+  try {
+    return new Intl.NumberFormat(settings.locale, {
+      style: "currency",
+      currency: settings.currency,
+    }).format(amount);
+  } catch {
+    return amount.toFixed(2);
+  }
+}
+
+// Servers started with a token are opened as http://host:port/#token=...
+const tokenMatch = location.hash.match(/token=([^&]+)/);
+if (tokenMatch) {
+  sessionStorage.setItem("expenso-token", decodeURIComponent(tokenMatch[1]));
+  history.replaceState(null, "", location.pathname);
+}
+
+async function api(path, options = {}) {
+  const headers = { "content-type": "application/json" };
+  const token = sessionStorage.getItem("expenso-token");
+  if (token) headers.authorization = `Bearer ${token}`;
+  const response = await fetch(path, { headers, ...options });
+  if (!response.ok) {
+    const body = await response.json().catch(() => ({}));
+    throw new Error(body.error || response.statusText);
+  }
+  return response.status === 204 ? null : response.json();
+}
+
+function cell(text, className) {
+  const td = document.createElement("td");
+  td.textContent = text;
+  if (className) td.className = className;
+  return td;
+}
+
+function renderSummary(summary) {
+  document.getElementById("income").textContent = money(summary.total_income);
+  document.getElementById("expense").textContent = money(summary.total_expense);
+  document.getElementById("balance").textContent = money(summary.net_balance);
+}
+
+function renderTransactions(transactions) {
+  const body = document.getElementById("transactions");
+  body.replaceChildren();
+
+  for (const t of transactions.slice().reverse()) {
+    const row = document.createElement("tr");
+    const kind = t.transaction_type.toLowerCase();
+    row.append(
+      cell(t.id),
+      cell(t.date),
+      cell(t.description),
+      cell(t.category),
+      cell(t.account),
+      cell(money(t.amount), `amount ${kind}`),
+    );
+
+    const remove = document.createElement("button");
+    remove.textContent = "✕";
+    remove.title = "Delete";
+    remove.addEventListener("click", async () => {
+      if (confirm(`Delete "${t.description}"?`)) {
+        await api(`/api/transactions/${t.id}`, { method: "DELETE" });
+        refresh();
+      }
+    });
+    const actions = document.createElement("td");
+    actions.append(remove);
+    row.append(actions);
+    body.append(row);
+  }
+
+  const categories = [...new Set(transactions.map((t) => t.category))].sort();
+  document.getElementById("category-list").replaceChildren(
+    ...categories.map((name) => {
+      const option = document.createElement("option");
+      option.value = name;
+      return option;
+    }),
+  );
+}
+
+function renderChart(categories) {
+  const svg = document.getElementById("chart");
+  const ns = "http://www.w3.org/2000/svg";
+  const rows = categories.slice().sort((a, b) => b.total - a.total);
+  const max = Math.max(1, ...rows.map((row) => row.total));
+  const barHeight = 22;
+  const labelWidth = 110;
+  const width = 480;
+
+  svg.setAttribute("viewBox", `0 0 ${width} ${Math.max(1, rows.length) * barHeight}`);
+  svg.replaceChildren();
+
+  rows.forEach((row, index) => {
+    const y = index * barHeight;
+    const barWidth = ((width - labelWidth - 90) * row.total) / max;
+
+    const label = document.createElementNS(ns, "text");
+    label.setAttribute("x", 0);
+    label.setAttribute("y", y + 15);
+    label.textContent = row.category;
+
+    const bar = document.createElementNS(ns, "rect");
+    bar.setAttribute("x", labelWidth);
+    bar.setAttribute("y", y + 4);
+    bar.setAttribute("width", Math.max(1, barWidth));
+    bar.setAttribute("height", barHeight - 8);
+
+    const value = document.createElementNS(ns, "text");
+    value.setAttribute("x", labelWidth + barWidth + 6);
+    value.setAttribute("y", y + 15);
+    value.textContent = money(row.total);
+
+    svg.append(label, bar, value);
+  });
+}
+
+async function refresh() {
+  const [summary, transactions, categories] = await Promise.all([
+    api("/api/summary"),
+    api("/api/transactions"),
+    api("/api/categories?type=expense"),
+  ]);
+  renderSummary(summary);
+  renderTransactions(transactions);
+  renderChart(categories);
+}
+
+document.getElementById("add-form").addEventListener("submit", async (event) => {
+  event.preventDefault();
+  const form = event.target;
+  const error = document.getElementById("form-error");
+  const data = Object.fromEntries(new FormData(form));
+
+  try {
+    await api("/api/transactions", {
+      method: "POST",
+      body: JSON.stringify({
+        ...data,
+        amount: Number(data.amount),
+        account: data.account || settings.default_account,
+      }),
+    });
+    error.hidden = true;
+    form.reset();
+    refresh();
+  } catch (err) {
+    error.textContent = err.message;
+    error.hidden = false;
+  }
+});
+
+api("/api/settings")
+  .then((loaded) => {
+    settings = loaded;
+  })
+  .finally(refresh);